@@ -0,0 +1,62 @@
+//! Benchmarks for building request urls and batch payloads
+//!
+//! Run them with `cargo bench --bench url_building`
+
+use divan::{black_box, Bencher};
+use powerplatform_dataverse_service_client::{
+    batch::Batch,
+    query::{filter::Filter, order::Order, parameters::QueryParameters, Query},
+    reference::ReferenceStruct,
+};
+use uuid::Uuid;
+
+fn main() {
+    divan::main();
+}
+
+fn build_query() -> Query {
+    Query::new("contacts")
+        .filter(Filter::equal("lastname", "McTestface").and(Filter::greater_than("age", 30)))
+        .order(vec![Order::Ascending("lastname"), Order::Descending("createdon")])
+        .limit(50)
+}
+
+#[divan::bench]
+fn render_query(bencher: Bencher) {
+    let query = build_query();
+    bencher.bench_local(|| black_box(&query).to_string());
+}
+
+#[divan::bench]
+fn write_query_parameters(bencher: Bencher) {
+    let query = build_query();
+    let mut buffer = String::with_capacity(256);
+
+    bencher.bench_local(|| {
+        let mut parameters = QueryParameters::new();
+        parameters.push_select(&["contactid", "firstname", "lastname", "emailaddress1"]);
+        black_box(&query).append_stable_parameters(&mut parameters, "contactid");
+
+        buffer.clear();
+        buffer.push_str("https://instance.crm.dynamics.com/api/data/v9.2/contacts");
+        parameters.write_to(&mut buffer);
+        black_box(buffer.len());
+    });
+}
+
+#[divan::bench(args = [50, 1000])]
+fn batch_deletes(bencher: Bencher, count: usize) {
+    let references: Vec<ReferenceStruct> = (0..count)
+        .map(|_| ReferenceStruct::new("contacts", Uuid::new_v4()))
+        .collect();
+
+    bencher.bench_local(|| {
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+
+        for reference in references.iter() {
+            batch.delete(reference).unwrap();
+        }
+
+        black_box(batch.get_count())
+    });
+}
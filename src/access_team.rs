@@ -0,0 +1,195 @@
+/*!
+Module for managing the access teams of records
+
+Access teams are created by Dataverse for a record and an access team template
+(`teamtemplate`) when the first user is added. The users of the team get the
+access rights defined by the template on this record only
+
+The record is referenced with the logical name of its entity (e.g. `account`)
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let user_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+    let template_id = Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?;
+    let account = EntityReference::new(
+        "account",
+        Uuid::parse_str("12345678-1234-1234-1234-1234567890ab").into_dataverse_result()?
+    );
+
+    let team_id = client.add_user_to_record_team(user_id, &account, template_id).await?;
+
+    for team in client.retrieve_record_teams(account.entity_id).await? {
+        println!("{} is an access team of the account", team.name);
+    }
+
+    client.remove_user_from_record_team(user_id, &account, template_id).await?;
+    Ok(())
+}
+```
+*/
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    action::serialize_action_reference,
+    auth::Authenticate,
+    client::Client,
+    entity::ReadEntity,
+    query::{attribute::Attribute, filter::Filter, Query},
+    reference::EntityReference,
+    result::Result,
+    select::Select,
+};
+
+/// `teamtype` of access teams
+static ACCESS_TEAM_TYPE: i64 = 1;
+
+/// An access team of a record
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AccessTeam {
+    pub teamid: Uuid,
+    pub name: String,
+    #[serde(rename = "_teamtemplateid_value")]
+    pub team_template_id: Option<Uuid>,
+    #[serde(rename = "_regardingobjectid_value")]
+    pub regarding_object_id: Option<Uuid>,
+}
+
+impl ReadEntity for AccessTeam {}
+
+impl Select for AccessTeam {
+    fn get_columns() -> &'static [&'static str] {
+        &["teamid", "name", "_teamtemplateid_value", "_regardingobjectid_value"]
+    }
+}
+
+#[derive(Serialize)]
+struct RecordTeamRequest {
+    #[serde(rename = "Record", serialize_with = "serialize_action_reference")]
+    record: EntityReference,
+    #[serde(rename = "TeamTemplate", serialize_with = "serialize_action_reference")]
+    team_template: EntityReference,
+}
+
+#[derive(Deserialize)]
+struct RecordTeamResponse {
+    #[serde(rename = "AccessTeamId")]
+    access_team_id: Uuid,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    adds the user to the access team of the record that belongs to the given
+    access team template and returns the id of the access team
+
+    The access team is created if it doesn't exist yet
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The user, the record or the access team template doesn't exist
+    */
+    pub async fn add_user_to_record_team(
+        &self,
+        user_id: Uuid,
+        record: &EntityReference,
+        team_template_id: Uuid,
+    ) -> Result<Uuid> {
+        self.execute_record_team_action("AddUserToRecordTeam", user_id, record, team_template_id)
+            .await
+    }
+
+    /**
+    removes the user from the access team of the record that belongs to the given
+    access team template and returns the id of the access team
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The user, the record or the access team template doesn't exist
+    */
+    pub async fn remove_user_from_record_team(
+        &self,
+        user_id: Uuid,
+        record: &EntityReference,
+        team_template_id: Uuid,
+    ) -> Result<Uuid> {
+        self.execute_record_team_action("RemoveUserFromRecordTeam", user_id, record, team_template_id)
+            .await
+    }
+
+    /**
+    retrieves all access teams of the record with the given id
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_record_teams(&self, record_id: Uuid) -> Result<Vec<AccessTeam>> {
+        let query = Query::new("teams").filter(
+            Filter::Equal("teamtype", Attribute::Integer(ACCESS_TEAM_TYPE))
+                .and(Filter::Equal("_regardingobjectid_value", Attribute::Uuid(record_id))),
+        );
+
+        self.retrieve_all(&query).await
+    }
+
+    async fn execute_record_team_action(
+        &self,
+        action: &str,
+        user_id: Uuid,
+        record: &EntityReference,
+        team_template_id: Uuid,
+    ) -> Result<Uuid> {
+        let request = RecordTeamRequest {
+            record: record.clone(),
+            team_template: EntityReference::new("teamtemplate", team_template_id),
+        };
+
+        let response: RecordTeamResponse = self
+            .execute_action(
+                &format!(
+                    "systemusers({})/Microsoft.Dynamics.CRM.{}",
+                    user_id.as_hyphenated(),
+                    action
+                ),
+                &request,
+            )
+            .await?;
+
+        Ok(response.access_team_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::reference::EntityReference;
+
+    use super::RecordTeamRequest;
+
+    #[test]
+    fn record_team_request() {
+        let request = RecordTeamRequest {
+            record: EntityReference::new("account", Uuid::nil()),
+            team_template: EntityReference::new("teamtemplate", Uuid::nil()),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"Record":{"@odata.type":"Microsoft.Dynamics.CRM.account","accountid":"00000000-0000-0000-0000-000000000000"},"TeamTemplate":{"@odata.type":"Microsoft.Dynamics.CRM.teamtemplate","teamtemplateid":"00000000-0000-0000-0000-000000000000"}}"#
+        );
+    }
+}
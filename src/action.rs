@@ -1,45 +1,420 @@
-use serde::{Serialize, ser::SerializeMap};
+/*!
+Module for executing Dataverse actions
+
+Actions are called with their parameters as a JSON body. Entity typed parameters
+are references with the logical name of their entity (e.g. `account`) instead of
+the name of its entity set, because they are serialized with their `@odata.type`
+
+Typed wrappers for common actions are available as functions of `Client`.
+Other actions can be called with `Client::execute_action(...)`
+
+# Examples
+```rust
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+#[derive(Serialize)]
+struct CalculateRollupField {
+    #[serde(rename = "FieldName")]
+    field_name: &'static str,
+}
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let response: serde_json::Value = client
+        .execute_action("new_CustomAction", &CalculateRollupField { field_name: "new_total" })
+        .await?;
+    Ok(())
+}
+```
+*/
+
+use reqwest::{Method, Response};
+use serde::{de::DeserializeOwned, ser::SerializeMap, Serialize, Serializer};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    error::DataverseError,
+    query::attribute::Attribute,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result},
+};
+
+/// logical names of activity entities which share the primary key `activityid`
+static ACTIVITY_ENTITIES: &[&str] = &[
+    "activitypointer",
+    "appointment",
+    "email",
+    "fax",
+    "letter",
+    "phonecall",
+    "recurringappointmentmaster",
+    "serviceappointment",
+    "socialactivity",
+    "task",
+];
+
+/// logical names of the entities that support the Merge action
+pub static MERGE_ENTITIES: &[&str] = &["account", "contact", "incident", "lead"];
+
+/**
+Represents a request to execute the Merge action in Dataverse
+
+The entity names of the references are the logical names of the entities
+(e.g. `account`) as expected by the action. Dataverse only merges records of the
+same entity, which has to be one of `MERGE_ENTITIES`
+
+# Examples
+```rust
 use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    action::MergeRequest,
+    reference::EntityReference
+};
+
+let request = MergeRequest::from_references(
+    EntityReference::new("contact", Uuid::new_v4()),
+    EntityReference::new("contact", Uuid::new_v4()),
+)
+.check_parents(true)
+.set("telephone1", "+49 123 456789");
 
-/// Represents a request to execute the Merge action in Dataverse
-#[derive(Debug, Serialize)]
-pub struct MergeRequest<'a> {
-    #[serde(rename = "Target")]
-    pub target: EntityReference<'a>,
-    #[serde(rename = "Subordinate")]
-    pub subordinate: EntityReference<'a>,
-    #[serde(rename = "PerformParentingChecks")]
+assert!(request.validate().is_ok());
+```
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeRequest {
+    pub target: EntityReference,
+    pub subordinate: EntityReference,
     pub check_parents: bool,
+    /// the columns that are written to the target when the records are merged
+    pub update_content: Map<String, Value>,
 }
 
-impl<'a> MergeRequest<'a> {
-    pub fn new(entity_name: &'a str, target: Uuid, subordinate: Uuid, check_parents: bool) -> Self {
+impl MergeRequest {
+    pub fn new(entity_name: &str, target: Uuid, subordinate: Uuid, check_parents: bool) -> Self {
+        Self::from_references(
+            EntityReference::new(entity_name.to_string(), target),
+            EntityReference::new(entity_name.to_string(), subordinate),
+        )
+        .check_parents(check_parents)
+    }
+
+    /// creates a request that merges the subordinate record into the target record
+    pub fn from_references(target: EntityReference, subordinate: EntityReference) -> Self {
         Self {
-            target: EntityReference {
-                entity_name,
-                entity_id: target,
-            },
-            subordinate: EntityReference {
-                entity_name,
-                entity_id: subordinate,
-            },
-            check_parents
+            target,
+            subordinate,
+            check_parents: false,
+            update_content: Map::new(),
         }
     }
-}
 
-#[derive(Debug)]
-pub struct EntityReference<'a> {
-    pub entity_name: &'a str,
-    pub entity_id: Uuid,
+    /// sets if Dataverse checks that the parents of both records are the same before merging
+    pub fn check_parents(mut self, check_parents: bool) -> Self {
+        self.check_parents = check_parents;
+        self
+    }
+
+    /// writes the column of the target with the given value when the records are merged
+    pub fn set(mut self, column: &str, value: impl Into<Attribute>) -> Self {
+        self.update_content
+            .insert(column.to_string(), value.into().to_json_value());
+        self
+    }
+
+    /**
+    checks that Dataverse can merge the records of this request
+
+    This may fail for any of these reasons
+    - A reference uses the entity set name (e.g. `accounts`) instead of the logical name
+    - The entity of the target doesn't support merging
+    - The target and the subordinate are records of different entities
+    - The target and the subordinate are the same record
+    */
+    pub fn validate(&self) -> Result<()> {
+        for reference in [&self.target, &self.subordinate] {
+            let logical_name = reference.entity_name.strip_suffix('s').unwrap_or_default();
+
+            if MERGE_ENTITIES.contains(&logical_name) {
+                return Err(DataverseError::new(format!(
+                    "the Merge action expects logical names, please use {} instead of the entity set name {}",
+                    logical_name, reference.entity_name
+                )));
+            }
+        }
+
+        if !MERGE_ENTITIES.contains(&self.target.entity_name.as_ref()) {
+            return Err(DataverseError::new(format!(
+                "the entity {} doesn't support merging, only {} do",
+                self.target.entity_name,
+                MERGE_ENTITIES.join(", ")
+            )));
+        }
+
+        if self.target.entity_name != self.subordinate.entity_name {
+            return Err(DataverseError::new(format!(
+                "a {} can't be merged into a {}, both records have to be of the same entity",
+                self.subordinate.entity_name, self.target.entity_name
+            )));
+        }
+
+        if self.target.entity_id == self.subordinate.entity_id {
+            return Err(DataverseError::new(format!(
+                "the {} {} can't be merged into itself",
+                self.target.entity_name, self.target.entity_id
+            )));
+        }
+
+        Ok(())
+    }
 }
 
-impl<'a> Serialize for EntityReference<'a> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where S: serde::Serializer {
-        let mut map = serializer.serialize_map(Some(2))?;
-        map.serialize_entry("@odata.type", &format!("Microsoft.Dynamics.CRM.{}", self.entity_name))?;
-        map.serialize_entry(&format!("{}id", self.entity_id), self.entity_id.as_hyphenated())?;
+impl Serialize for MergeRequest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut update_content = self.update_content.clone();
+        update_content.insert(
+            String::from("@odata.type"),
+            Value::String(format!("Microsoft.Dynamics.CRM.{}", self.target.entity_name)),
+        );
+
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("Target", &ActionReference(&self.target))?;
+        map.serialize_entry("Subordinate", &ActionReference(&self.subordinate))?;
+        map.serialize_entry("UpdateContent", &update_content)?;
+        map.serialize_entry("PerformParentingChecks", &self.check_parents)?;
         map.end()
     }
-}
\ No newline at end of file
+}
+
+/// serializes the reference with `serialize_action_reference`
+struct ActionReference<'a>(&'a EntityReference);
+
+impl Serialize for ActionReference<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serialize_action_reference(self.0, serializer)
+    }
+}
+
+/// returns the name of the primary key attribute of the entity with the given logical name
+pub fn get_primary_id_attribute(logical_name: &str) -> String {
+    if ACTIVITY_ENTITIES.contains(&logical_name) {
+        String::from("activityid")
+    } else {
+        format!("{}id", logical_name)
+    }
+}
+
+/**
+serializes an entity reference as an entity typed action parameter
+
+The entity name of the reference has to be the logical name of the entity
+
+# Examples
+```rust
+use serde::Serialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    action::serialize_action_reference,
+    reference::EntityReference
+};
+
+#[derive(Serialize)]
+struct Parameters {
+    #[serde(rename = "Target", serialize_with = "serialize_action_reference")]
+    target: EntityReference,
+}
+
+let parameters = Parameters { target: EntityReference::new("account", Uuid::nil()) };
+assert_eq!(
+    serde_json::to_string(&parameters).unwrap(),
+    r#"{"Target":{"@odata.type":"Microsoft.Dynamics.CRM.account","accountid":"00000000-0000-0000-0000-000000000000"}}"#
+);
+```
+*/
+pub fn serialize_action_reference<S>(reference: &EntityReference, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_entry("@odata.type", &format!("Microsoft.Dynamics.CRM.{}", reference.entity_name))?;
+    map.serialize_entry(&get_primary_id_attribute(&reference.entity_name), reference.entity_id.as_hyphenated())?;
+    map.end()
+}
+
+/**
+serializes an entity as an entity typed action parameter by adding its `@odata.type`
+
+This fails if the entity does not serialize into a JSON object
+*/
+pub(crate) fn to_typed_entity(logical_name: &str, entity: &impl Serialize) -> Result<Value> {
+    let mut entity: Map<String, Value> = match serde_json::to_value(entity).into_dataverse_result()? {
+        Value::Object(entity) => entity,
+        _ => return Err(DataverseError::new(String::from("an entity has to serialize into a JSON object"))),
+    };
+
+    entity.insert(
+        String::from("@odata.type"),
+        Value::String(format!("Microsoft.Dynamics.CRM.{}", logical_name)),
+    );
+    Ok(Value::Object(entity))
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    executes the unbound action (or bound action path) with the given parameters
+
+    The response of the action is deserialized into `T`. Actions without a response
+    can be deserialized into `()`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    */
+    pub async fn execute_action<T: DeserializeOwned>(
+        &self,
+        action: &str,
+        parameters: &impl Serialize,
+    ) -> Result<T> {
+        let url_path = self.build_simple_url(action);
+        let body = serde_json::to_vec(parameters).into_dataverse_result()?;
+
+        self.request(
+            Method::POST,
+            &url_path,
+            move |request| {
+                Ok(request
+                    .header("Content-Type", "application/json")
+                    .body(body))
+            },
+            handle_action_response,
+        )
+        .await
+    }
+
+    /**
+    merges the subordinate record of the request into its target and deactivates the subordinate
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization error
+    - Any http client or server error
+    - The request is not valid, see `MergeRequest::validate()`
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use powerplatform_dataverse_service_client::{
+        action::MergeRequest,
+        client::Client,
+        reference::EntityReference,
+        result::{IntoDataverseResult, Result}
+    };
+
+    async fn test() -> Result<()> {
+        let request = MergeRequest::from_references(
+            EntityReference::new("account", Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?),
+            EntityReference::new("account", Uuid::parse_str("12345687-1234-1234-1234-123456879012").into_dataverse_result()?),
+        )
+        .check_parents(true);
+
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        client.execute_merge(&request).await
+    }
+    ```
+    */
+    pub async fn execute_merge(&self, request: &MergeRequest) -> Result<()> {
+        request.validate()?;
+        self.execute_action("Merge", request).await
+    }
+
+    /**
+    calls the function (including its parameters) and deserializes its response into `T`
+
+    The function is given as path relative to the Web API, e.g. `WhoAmI()` or
+    `calendars(<id>)/Microsoft.Dynamics.CRM.ExpandCalendar(Start=@p1,End=@p2)?@p1=...`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn execute_function<T: DeserializeOwned>(&self, function: &str) -> Result<T> {
+        let url_path = self.build_simple_url(function);
+        self.request(Method::GET, &url_path, Ok, handle_json_response).await
+    }
+}
+
+/// deserializes the response of an action, treating an empty body as `null`
+pub(crate) async fn handle_action_response<T: DeserializeOwned>(response: Response) -> Result<T> {
+    if response.status().is_client_error() || response.status().is_server_error() {
+        let error_message = response
+            .text()
+            .await
+            .unwrap_or_else(|_| String::from("no error details provided from server"));
+        return Err(DataverseError::new(error_message));
+    }
+
+    let content = response.bytes().await.into_dataverse_result()?;
+
+    if content.is_empty() {
+        serde_json::from_str("null").into_dataverse_result()
+    } else {
+        serde_json::from_slice(content.as_ref()).into_dataverse_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::reference::EntityReference;
+
+    use super::MergeRequest;
+
+    #[test]
+    fn merge_request_serialization() {
+        let request = MergeRequest::new("account", Uuid::nil(), Uuid::nil(), false);
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"Target":{"@odata.type":"Microsoft.Dynamics.CRM.account","accountid":"00000000-0000-0000-0000-000000000000"},"Subordinate":{"@odata.type":"Microsoft.Dynamics.CRM.account","accountid":"00000000-0000-0000-0000-000000000000"},"UpdateContent":{"@odata.type":"Microsoft.Dynamics.CRM.account"},"PerformParentingChecks":false}"#
+        );
+    }
+
+    #[test]
+    fn merge_request_validation() {
+        let lead = EntityReference::new("lead", Uuid::from_u128(1));
+        let request = MergeRequest::from_references(lead.clone(), EntityReference::new("lead", Uuid::from_u128(2)))
+            .check_parents(true)
+            .set("companyname", "Contoso");
+
+        assert!(request.validate().is_ok());
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"Target":{"@odata.type":"Microsoft.Dynamics.CRM.lead","leadid":"00000000-0000-0000-0000-000000000001"},"Subordinate":{"@odata.type":"Microsoft.Dynamics.CRM.lead","leadid":"00000000-0000-0000-0000-000000000002"},"UpdateContent":{"@odata.type":"Microsoft.Dynamics.CRM.lead","companyname":"Contoso"},"PerformParentingChecks":true}"#
+        );
+
+        let mixed = MergeRequest::from_references(lead.clone(), EntityReference::new("contact", Uuid::from_u128(2)));
+        assert_eq!(
+            mixed.validate().unwrap_err().message,
+            "a contact can't be merged into a lead, both records have to be of the same entity"
+        );
+
+        let entity_set = MergeRequest::from_references(lead.clone(), EntityReference::new("leads", Uuid::from_u128(2)));
+        assert_eq!(
+            entity_set.validate().unwrap_err().message,
+            "the Merge action expects logical names, please use lead instead of the entity set name leads"
+        );
+
+        assert!(MergeRequest::from_references(lead.clone(), lead).validate().is_err());
+        assert!(MergeRequest::new("opportunity", Uuid::from_u128(1), Uuid::from_u128(2), false).validate().is_err());
+    }
+}
@@ -0,0 +1,309 @@
+/*!
+Module for activities and their polymorphic `regardingobjectid` lookup
+
+The `regardingobjectid` of an activity can reference records of many tables. Dataverse
+returns it as `_regardingobjectid_value` with the logical name of the referenced table
+in an annotation, and it has to be written with a navigation property per table and
+activity type, e.g. `regardingobjectid_account_task@odata.bind`. `Regarding` reads the
+lookup with its table and `RegardingBind` writes it to the right navigation property
+
+`Activity` reads the common columns of all activities from the `activitypointers` table
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use powerplatform_dataverse_service_client::{
+    activity::{Regarding, RegardingBind},
+    client::Client,
+    entity::{ReadEntity, WriteEntity},
+    reference::{Reference, ReferenceStruct},
+    result::{IntoDataverseResult, Result},
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let account = ReferenceStruct::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let task = NewTask {
+        activityid: Uuid::new_v4(),
+        subject: String::from("Call back"),
+        regarding: client.resolve_regarding("task", &account).await?,
+    };
+    client.create(&task).await?;
+
+    let task: Task = client.retrieve(&task).await?;
+    if let Some(regarding) = task.regarding {
+        println!("the task is regarding the {} {}", regarding.logical_name, regarding.entity_id);
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct NewTask {
+    activityid: Uuid,
+    subject: String,
+    #[serde(flatten)]
+    regarding: RegardingBind,
+}
+
+impl WriteEntity for NewTask {}
+
+impl Reference for NewTask {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("tasks", self.activityid)
+    }
+}
+
+#[derive(Deserialize)]
+struct Task {
+    activityid: Uuid,
+    #[serde(flatten)]
+    regarding: Option<Regarding>,
+}
+
+impl ReadEntity for Task {
+    fn include_lookup_logical_names() -> bool {
+        true
+    }
+}
+
+impl Select for Task {
+    fn get_columns() -> &'static [&'static str] {
+        &["activityid", "_regardingobjectid_value"]
+    }
+}
+```
+*/
+
+use chrono::{DateTime, Utc};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::{EntityName, ReadEntity},
+    metadata::EntityNames,
+    reference::{EntityReference, Reference},
+    result::Result,
+    select::Select,
+};
+
+/// name of the polymorphic lookup of activities
+pub static REGARDING_OBJECT_ID: &str = "regardingobjectid";
+
+/// name of the column that contains the id of the record an activity is regarding
+pub static REGARDING_COLUMN: &str = "_regardingobjectid_value";
+
+/**
+The record an activity is regarding, read from `_regardingobjectid_value`
+
+The entity requires `ReadEntity::include_lookup_logical_names()` to return `true`. As the
+lookup can be empty, it is usually flattened into the entity as `Option<Regarding>`
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct Regarding {
+    #[serde(rename = "_regardingobjectid_value")]
+    pub entity_id: Uuid,
+    /// the logical name of the table of the record, e.g. `account`
+    #[serde(rename = "_regardingobjectid_value@Microsoft.Dynamics.CRM.lookuplogicalname")]
+    pub logical_name: String,
+}
+
+impl Reference for Regarding {
+    /// returns a reference with the logical name, see `Client::resolve_reference(...)`
+    fn get_reference(&self) -> EntityReference {
+        EntityReference::new(self.logical_name.clone(), self.entity_id)
+    }
+}
+
+/**
+Binds the `regardingobjectid` of an activity to a record when it is serialized
+
+It serializes into the bind of the navigation property of the table and the activity
+type, e.g. `"regardingobjectid_account_task@odata.bind": "/accounts(...)"`, and is
+usually flattened into the written entity
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RegardingBind {
+    /// the logical name of the table of the activity, e.g. `task`
+    pub activity: String,
+    pub names: EntityNames,
+    pub entity_id: Uuid,
+}
+
+impl RegardingBind {
+    /// binds an activity of the table with the logical name to the record of the table with the given names
+    pub fn new(activity: impl Into<String>, names: EntityNames, entity_id: Uuid) -> Self {
+        Self {
+            activity: activity.into(),
+            names,
+            entity_id,
+        }
+    }
+
+    /// binds an activity to the record with the given id where both tables are known at compile time
+    pub fn of<T: EntityName, E: EntityName>(entity_id: Uuid) -> Self {
+        Self::new(T::LOGICAL, EntityNames::of::<E>(), entity_id)
+    }
+
+    /// returns the name of the navigation property, e.g. `regardingobjectid_account_task`
+    pub fn get_navigation_property(&self) -> String {
+        format!(
+            "{}_{}_{}",
+            REGARDING_OBJECT_ID, self.names.logical_name, self.activity
+        )
+    }
+
+    /// returns the value of the bind, e.g. `/accounts(...)`
+    pub fn to_bind(&self) -> String {
+        EntityReference::new(self.names.collection_name.clone(), self.entity_id).to_bind()
+    }
+}
+
+impl Serialize for RegardingBind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(
+            &format!("{}@odata.bind", self.get_navigation_property()),
+            &self.to_bind(),
+        )?;
+        map.end()
+    }
+}
+
+/// The columns that all activities share, read from the `activitypointers` table
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Activity {
+    pub activityid: Uuid,
+    /// the logical name of the table of the activity, e.g. `task` or `email`
+    pub activitytypecode: String,
+    pub subject: Option<String>,
+    pub scheduledstart: Option<DateTime<Utc>>,
+    pub scheduledend: Option<DateTime<Utc>>,
+    pub statecode: i32,
+    #[serde(flatten)]
+    pub regarding: Option<Regarding>,
+}
+
+impl EntityName for Activity {
+    const LOGICAL: &'static str = "activitypointer";
+    const COLLECTION: &'static str = "activitypointers";
+}
+
+impl Select for Activity {
+    fn get_columns() -> &'static [&'static str] {
+        &[
+            "activityid",
+            "activitytypecode",
+            "subject",
+            "scheduledstart",
+            "scheduledend",
+            "statecode",
+            "_regardingobjectid_value",
+        ]
+    }
+}
+
+impl ReadEntity for Activity {
+    fn include_lookup_logical_names() -> bool {
+        true
+    }
+}
+
+impl Reference for Activity {
+    fn get_reference(&self) -> EntityReference {
+        EntityReference::of::<Activity>(self.activityid)
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    creates the bind of the `regardingobjectid` of an activity to the referenced record
+
+    The activity table and the reference may use the logical name or the entity set name
+    of their table
+
+    see `resolve_entity_names(...)` for the failure reasons
+    */
+    pub async fn resolve_regarding(&self, activity: &str, reference: &impl Reference) -> Result<RegardingBind> {
+        let activity = self.resolve_entity_names(activity).await?;
+        let reference = reference.get_reference();
+        let names = self.resolve_entity_names(&reference.entity_name).await?;
+        Ok(RegardingBind::new(activity.logical_name, names, reference.entity_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::metadata::EntityNames;
+
+    use super::{Activity, RegardingBind};
+
+    #[test]
+    fn reads_regarding_with_logical_name() {
+        let activity: Activity = serde_json::from_value(json!({
+            "activityid": "00000000-0000-0000-0000-000000000001",
+            "activitytypecode": "task",
+            "subject": "Call back",
+            "scheduledstart": null,
+            "scheduledend": "2024-03-01T12:00:00Z",
+            "statecode": 0,
+            "_regardingobjectid_value": "00000000-0000-0000-0000-000000000002",
+            "_regardingobjectid_value@Microsoft.Dynamics.CRM.lookuplogicalname": "account"
+        }))
+        .unwrap();
+
+        let regarding = activity.regarding.unwrap();
+        assert_eq!(regarding.logical_name, "account");
+        assert_eq!(regarding.entity_id, Uuid::from_u128(2));
+
+        let activity: Activity = serde_json::from_value(json!({
+            "activityid": "00000000-0000-0000-0000-000000000001",
+            "activitytypecode": "email",
+            "subject": null,
+            "scheduledstart": null,
+            "scheduledend": null,
+            "statecode": 1,
+            "_regardingobjectid_value": null
+        }))
+        .unwrap();
+        assert_eq!(activity.regarding, None);
+    }
+
+    #[test]
+    fn binds_navigation_property_of_table_and_activity() {
+        #[derive(Serialize)]
+        struct Task {
+            subject: &'static str,
+            #[serde(flatten)]
+            regarding: RegardingBind,
+        }
+
+        let names = EntityNames {
+            logical_name: String::from("account"),
+            collection_name: String::from("accounts"),
+        };
+        let task = Task {
+            subject: "Call back",
+            regarding: RegardingBind::new("task", names, Uuid::nil()),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&task).unwrap(),
+            json!({
+                "subject": "Call back",
+                "regardingobjectid_account_task@odata.bind": "/accounts(00000000-0000-0000-0000-000000000000)"
+            })
+        );
+    }
+}
@@ -0,0 +1,311 @@
+/*!
+Module for retrieving query results as Apache Arrow record batches
+
+This is only available with the `arrow` feature. The records of each page are
+decoded directly into a `RecordBatch` of the given schema, so data science workflows
+don't need a serde struct per row. The record batches can be handed to Polars,
+DataFusion or any other Arrow based library
+
+The schema can be derived from the attribute metadata of the table with
+`Client::retrieve_arrow_schema(...)`. Lookup columns are selected with their
+`_<name>_value` form and contain the id of the referenced record
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    query::Query,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let schema = client
+        .retrieve_arrow_schema("account", &["name", "revenue", "createdon", "_primarycontactid_value"])
+        .await?;
+
+    let batches = client.retrieve_all_arrow(&Query::new("accounts"), schema).await?;
+    let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    Ok(())
+}
+```
+*/
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    error::DataverseError,
+    query::{parameters::QueryParameters, Query},
+    result::{IntoDataverseResult, Result},
+};
+
+/// A page of query results decoded into a record batch
+#[derive(Clone, Debug)]
+pub struct ArrowPage {
+    pub batch: RecordBatch,
+    next_link: Option<String>,
+}
+
+impl ArrowPage {
+    /// Indicates if there are more records available in the query after this page
+    pub fn is_incomplete(&self) -> bool {
+        self.next_link.is_some()
+    }
+
+    /// returns the link to the next page of the query if there is one
+    pub fn get_next_link(&self) -> Option<&str> {
+        self.next_link.as_deref()
+    }
+}
+
+#[derive(Deserialize)]
+struct RawPage {
+    value: Vec<Map<String, Value>>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AttributeDefinitions {
+    value: Vec<AttributeDefinition>,
+}
+
+#[derive(Deserialize)]
+struct AttributeDefinition {
+    #[serde(rename = "LogicalName")]
+    logical_name: String,
+    #[serde(rename = "AttributeType")]
+    attribute_type: String,
+}
+
+/**
+returns the arrow data type of a column with the given `AttributeType`
+
+Lookups, ids and choices of multiple values are strings. Date and time columns are
+timestamps in UTC. Returns `None` for types that cannot be selected
+*/
+pub fn get_data_type(attribute_type: &str) -> Option<DataType> {
+    let data_type = match attribute_type {
+        "Boolean" => DataType::Boolean,
+        "Integer" | "Picklist" | "State" | "Status" => DataType::Int32,
+        "BigInt" => DataType::Int64,
+        "Decimal" | "Double" | "Money" => DataType::Float64,
+        "DateTime" => DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into())),
+        "String" | "Memo" | "EntityName" | "Uniqueidentifier" | "Lookup" | "Customer" | "Owner"
+        | "Virtual" => DataType::Utf8,
+        _ => return None,
+    };
+
+    Some(data_type)
+}
+
+/// returns the attribute name of a selected column (`_<name>_value` for lookups)
+fn get_attribute_name(column: &str) -> &str {
+    column
+        .strip_prefix('_')
+        .and_then(|column| column.strip_suffix("_value"))
+        .unwrap_or(column)
+}
+
+/// decodes the records into a record batch of the schema, ignoring columns outside of it
+fn decode(schema: &SchemaRef, records: &[Map<String, Value>]) -> Result<RecordBatch> {
+    let mut decoder = ReaderBuilder::new(Arc::clone(schema))
+        .with_batch_size(records.len().max(1))
+        .build_decoder()
+        .into_dataverse_result()?;
+
+    decoder.serialize(records).into_dataverse_result()?;
+
+    Ok(decoder
+        .flush()
+        .into_dataverse_result()?
+        .unwrap_or_else(|| RecordBatch::new_empty(Arc::clone(schema))))
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    derives the schema of the given columns from the attribute metadata of the entity
+    with the given logical name
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - A column doesn't exist or has a type that cannot be selected
+    */
+    pub async fn retrieve_arrow_schema(&self, logical_name: &str, columns: &[&str]) -> Result<SchemaRef> {
+        let mut parameters = QueryParameters::new();
+        parameters.push_select(&["LogicalName", "AttributeType"]);
+
+        let url_path = format!(
+            "{}{}",
+            self.build_simple_url(format_args!("EntityDefinitions(LogicalName='{}')/Attributes", logical_name)),
+            parameters
+        );
+
+        let definitions: AttributeDefinitions = self
+            .request(Method::GET, &url_path, Ok, handle_json_response)
+            .await?;
+
+        let fields = columns
+            .iter()
+            .map(|column| {
+                let attribute_name = get_attribute_name(column);
+                let definition = definitions
+                    .value
+                    .iter()
+                    .find(|definition| definition.logical_name == attribute_name)
+                    .ok_or_else(|| {
+                        DataverseError::new(format!("the entity {} has no column {}", logical_name, column))
+                    })?;
+
+                let data_type = get_data_type(&definition.attribute_type).ok_or_else(|| {
+                    DataverseError::new(format!(
+                        "the column {} of type {} cannot be retrieved as arrow data",
+                        column, definition.attribute_type
+                    ))
+                })?;
+
+                Ok(Field::new(*column, data_type, true))
+            })
+            .collect::<Result<Vec<Field>>>()?;
+
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
+    /**
+    retrieves the first page of the query with the columns of the schema as record batch
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - A value doesn't match the data type of its column in the schema
+    */
+    pub async fn retrieve_arrow_page(&self, query: &Query, schema: SchemaRef) -> Result<ArrowPage> {
+        let columns: Vec<&str> = schema.fields().iter().map(|field| field.name().as_str()).collect();
+        let mut parameters = QueryParameters::new();
+        parameters.push_select(&columns);
+        query.append_parameters(&mut parameters);
+
+        let mut url_path = self.build_simple_url(query.logical_name);
+        parameters.write_to(&mut url_path);
+        self.retrieve_arrow_page_from(&url_path, schema).await
+    }
+
+    /**
+    retrieves the page after the given page of a query as record batch
+
+    see `retrieve_arrow_page(...)` for the failure reasons. This also fails if the
+    given page is the last page of the query
+    */
+    pub async fn retrieve_next_arrow_page(&self, previous_page: &ArrowPage) -> Result<ArrowPage> {
+        let next_link = previous_page.get_next_link().ok_or_else(|| {
+            DataverseError::new(String::from("the query already finished with the last page"))
+        })?;
+
+        self.retrieve_arrow_page_from(next_link, previous_page.batch.schema())
+            .await
+    }
+
+    /**
+    retrieves all pages of the query as record batches, one per page
+
+    see `retrieve_arrow_page(...)` for the failure reasons
+    */
+    pub async fn retrieve_all_arrow(&self, query: &Query, schema: SchemaRef) -> Result<Vec<RecordBatch>> {
+        let mut page = self.retrieve_arrow_page(query, schema).await?;
+        let mut batches = Vec::new();
+
+        while page.is_incomplete() {
+            let next_page = self.retrieve_next_arrow_page(&page).await?;
+            batches.push(page.batch);
+            page = next_page;
+        }
+
+        batches.push(page.batch);
+        Ok(batches)
+    }
+
+    async fn retrieve_arrow_page_from(&self, url: &str, schema: SchemaRef) -> Result<ArrowPage> {
+        let page: RawPage = self
+            .request(Method::GET, url, Ok, handle_json_response)
+            .await?;
+
+        Ok(ArrowPage {
+            batch: decode(&schema, &page.value)?,
+            next_link: page.next_link,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Array, Float64Array, Int32Array, StringArray, TimestampMillisecondArray};
+    use arrow_schema::{Field, Schema};
+    use serde_json::{json, Map, Value};
+
+    use super::{decode, get_attribute_name, get_data_type};
+
+    #[test]
+    fn decodes_records_into_batch() {
+        let schema = Arc::new(Schema::new(
+            [
+                ("name", "String"),
+                ("revenue", "Money"),
+                ("statecode", "State"),
+                ("createdon", "DateTime"),
+                ("_primarycontactid_value", "Lookup"),
+            ]
+            .iter()
+            .map(|(column, attribute_type)| Field::new(*column, get_data_type(attribute_type).unwrap(), true))
+            .collect::<Vec<Field>>(),
+        ));
+
+        let records: Vec<Map<String, Value>> = serde_json::from_value(json!([
+            {
+                "@odata.etag": "W/\"1\"",
+                "name": "Contoso",
+                "revenue": 1000.5,
+                "statecode": 0,
+                "createdon": "2024-05-17T08:30:00Z",
+                "_primarycontactid_value": "00000000-0000-0000-0000-000000000000"
+            },
+            { "name": "Fabrikam", "revenue": null, "statecode": 1, "createdon": null, "_primarycontactid_value": null }
+        ]))
+        .unwrap();
+
+        let batch = decode(&schema, &records).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+
+        let names = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(names.value(1), "Fabrikam");
+        let revenue = batch.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(revenue.value(0), 1000.5);
+        assert!(revenue.is_null(1));
+        let states = batch.column(2).as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(states.value(1), 1);
+        let created = batch.column(3).as_any().downcast_ref::<TimestampMillisecondArray>().unwrap();
+        assert_eq!(created.value(0), 1_715_934_600_000);
+
+        assert_eq!(decode(&schema, &[]).unwrap().num_rows(), 0);
+    }
+
+    #[test]
+    fn lookup_attribute_names() {
+        assert_eq!(get_attribute_name("_primarycontactid_value"), "primarycontactid");
+        assert_eq!(get_attribute_name("name"), "name");
+        assert_eq!(get_data_type("PartyList"), None);
+    }
+}
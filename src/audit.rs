@@ -0,0 +1,653 @@
+/*!
+Module for the audit history of records
+
+`Client::retrieve_change_history(...)` returns the audited changes of a record.
+`Client::retrieve_as_of(...)` reconstructs the values a record had at a point in time
+by reverting the audited changes after that time on the current values of the record.
+This requires auditing to be enabled for the table and its columns
+
+Please note that the reconstruction can only be as complete as the audit history.
+Columns that are not audited, changes made while auditing was disabled and audit records
+that were deleted by the retention policy are missing in the reconstructed snapshot
+
+The `audit` table itself is partitioned by `createdon`, and queries without a range on this
+column scan every partition until they time out. An `AuditQuery` splits a time range into
+windows that stay within one partition (a calendar quarter), newest first, and leaves out
+the time before the retention boundary of the environment where no audit records are kept.
+`Client::retrieve_audit(...)` retrieves the `AuditEntry` records of all windows, and
+`AuditQuery::build_queries(...)` returns the query of each window to page through it
+
+# Examples
+```rust
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result},
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let reference = ReferenceStruct::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+    let timestamp = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let contact: Contact = client.retrieve_as_of(&reference, timestamp).await?;
+    println!("the email address on March 1st was {:?}", contact.emailaddress1);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    emailaddress1: Option<String>,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "emailaddress1"]
+    }
+}
+```
+
+```rust
+use chrono::{Duration, Utc};
+use powerplatform_dataverse_service_client::{
+    audit::AuditQuery,
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let now = Utc::now();
+    let query = AuditQuery::new(now - Duration::days(400), now).for_table("contact");
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let history = client.retrieve_audit(&query).await?;
+
+    if history.is_truncated() {
+        println!("audit records before {:?} were deleted by the retention policy", history.retention_boundary);
+    }
+
+    for entry in history.entries.iter() {
+        println!("{} {:?} {:?}", entry.createdon, entry.get_operation(), entry.object_id);
+    }
+    Ok(())
+}
+```
+*/
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::ReadEntity,
+    error::DataverseError,
+    query::{attribute::Attribute, filter::Filter, order::Order, Query},
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+    select::Select,
+};
+
+/// number of audit details retrieved per page
+static PAGE_SIZE: usize = 5000;
+
+/// entity set name of the audit table
+pub static AUDIT_TABLE: &str = "audits";
+
+/// column the audit table is partitioned by
+static PARTITION_COLUMN: &str = "createdon";
+
+/// The operation of an audited change
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditOperation {
+    Create,
+    Update,
+    Delete,
+    /// any other operation with its option set value
+    Other(i32),
+}
+
+impl From<i32> for AuditOperation {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => AuditOperation::Create,
+            2 => AuditOperation::Update,
+            3 => AuditOperation::Delete,
+            value => AuditOperation::Other(value),
+        }
+    }
+}
+
+impl From<AuditOperation> for i32 {
+    fn from(value: AuditOperation) -> Self {
+        match value {
+            AuditOperation::Create => 1,
+            AuditOperation::Update => 2,
+            AuditOperation::Delete => 3,
+            AuditOperation::Other(value) => value,
+        }
+    }
+}
+
+/// An audited change of the columns of a record
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditChange {
+    pub changed_on: DateTime<Utc>,
+    pub operation: AuditOperation,
+    /// the values of the changed columns before the change
+    pub old_values: Map<String, Value>,
+    /// the values of the changed columns after the change
+    pub new_values: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct ChangeHistoryResponse {
+    #[serde(rename = "AuditDetailCollection")]
+    collection: AuditDetailCollection,
+}
+
+#[derive(Deserialize)]
+struct AuditDetailCollection {
+    #[serde(rename = "MoreRecords", default)]
+    more_records: bool,
+    #[serde(rename = "AuditDetails", default)]
+    details: Vec<AuditDetail>,
+}
+
+#[derive(Deserialize)]
+struct AuditDetail {
+    #[serde(rename = "AuditRecord")]
+    record: AuditRecord,
+    #[serde(rename = "OldValue")]
+    old_value: Option<Map<String, Value>>,
+    #[serde(rename = "NewValue")]
+    new_value: Option<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct AuditRecord {
+    createdon: DateTime<Utc>,
+    operation: i32,
+}
+
+impl AuditDetail {
+    /// returns the change of this detail, `None` for details without column values
+    fn into_change(self) -> Option<AuditChange> {
+        if self.old_value.is_none() && self.new_value.is_none() {
+            return None;
+        }
+
+        Some(AuditChange {
+            changed_on: self.record.createdon,
+            operation: AuditOperation::from(self.record.operation),
+            old_values: strip_annotations(self.old_value.unwrap_or_default()),
+            new_values: strip_annotations(self.new_value.unwrap_or_default()),
+        })
+    }
+}
+
+/// removes the OData annotations like `@odata.type` from the values
+fn strip_annotations(mut values: Map<String, Value>) -> Map<String, Value> {
+    values.retain(|key, _| !key.contains('@'));
+    values
+}
+
+/**
+reverts the changes after the timestamp on the current values of a record
+
+Changes are reverted from the newest to the oldest. A column that has no old value
+was empty before the change
+*/
+fn reconstruct(
+    mut values: Map<String, Value>,
+    changes: &[AuditChange],
+    timestamp: DateTime<Utc>,
+) -> Result<Map<String, Value>> {
+    let mut changes: Vec<&AuditChange> = changes
+        .iter()
+        .filter(|change| change.changed_on > timestamp)
+        .collect();
+    changes.sort_by_key(|change| std::cmp::Reverse(change.changed_on));
+
+    for change in changes {
+        if change.operation == AuditOperation::Create {
+            return Err(DataverseError::new(format!(
+                "the record was created on {} after the requested time {}",
+                change.changed_on, timestamp
+            )));
+        }
+
+        for column in change.new_values.keys().chain(change.old_values.keys()) {
+            let old_value = change.old_values.get(column).cloned().unwrap_or(Value::Null);
+            values.insert(column.clone(), old_value);
+        }
+    }
+
+    Ok(values)
+}
+
+/// A record of the `audit` table
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AuditEntry {
+    pub auditid: Uuid,
+    pub createdon: DateTime<Utc>,
+    pub operation: i32,
+    pub action: i32,
+    /// the logical name of the entity of the audited record
+    pub objecttypecode: String,
+    #[serde(rename = "_objectid_value")]
+    pub object_id: Option<Uuid>,
+    #[serde(rename = "_userid_value")]
+    pub user_id: Option<Uuid>,
+    /// the changed values as serialized by Dataverse, use `retrieve_change_history(...)` for parsed values
+    pub changedata: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn get_operation(&self) -> AuditOperation {
+        AuditOperation::from(self.operation)
+    }
+}
+
+impl ReadEntity for AuditEntry {}
+
+impl Select for AuditEntry {
+    fn get_columns() -> &'static [&'static str] {
+        &[
+            "auditid",
+            "createdon",
+            "operation",
+            "action",
+            "objecttypecode",
+            "_objectid_value",
+            "_userid_value",
+            "changedata",
+        ]
+    }
+}
+
+/**
+A query of the `audit` table within a time range
+
+The range starts inclusively at `from` and ends exclusively at `to`. It is split into
+windows at the start of every calendar quarter, which are the partitions of the audit
+table, and optionally into smaller windows of a maximum length
+*/
+#[derive(Clone, Debug)]
+pub struct AuditQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub filter: Option<Filter>,
+    pub max_window: Option<Duration>,
+}
+
+impl AuditQuery {
+    /// creates a query of all audit records created within the time range
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        Self {
+            from,
+            to,
+            filter: None,
+            max_window: None,
+        }
+    }
+
+    /// restricts the query to the audit records of the record with the given id
+    pub fn for_record(self, entity_id: Uuid) -> Self {
+        self.filter(Filter::Equal("_objectid_value", Attribute::Uuid(entity_id)))
+    }
+
+    /// restricts the query to the audit records of the entity with the given logical name
+    pub fn for_table(self, logical_name: &str) -> Self {
+        self.filter(Filter::Equal(
+            "objecttypecode",
+            Attribute::String(logical_name.to_string()),
+        ))
+    }
+
+    /// restricts the query to the audit records of the given operation
+    pub fn operation(self, operation: AuditOperation) -> Self {
+        self.filter(Filter::Equal(
+            "operation",
+            Attribute::Integer(i32::from(operation).into()),
+        ))
+    }
+
+    /// adds the filter to the filter of this query
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(match self.filter.take() {
+            Some(existing) => existing.and(filter),
+            None => filter,
+        });
+        self
+    }
+
+    /// splits the windows further so that none is longer than the given duration
+    pub fn with_max_window(mut self, max_window: Duration) -> Self {
+        self.max_window = Some(max_window).filter(|max_window| *max_window > Duration::zero());
+        self
+    }
+
+    /**
+    returns the time ranges of the windows of this query, newest first
+
+    The time before the retention boundary is left out, as there are no audit records
+    left to find in it
+    */
+    pub fn get_windows(
+        &self,
+        retention_boundary: Option<DateTime<Utc>>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut start = match retention_boundary {
+            Some(boundary) => self.from.max(boundary),
+            None => self.from,
+        };
+        let mut windows = Vec::new();
+
+        while start < self.to {
+            let mut end = get_next_quarter(start).min(self.to);
+
+            if let Some(max_window) = self.max_window {
+                end = end.min(start + max_window);
+            }
+
+            windows.push((start, end));
+            start = end;
+        }
+
+        windows.reverse();
+        windows
+    }
+
+    /// builds a query of the audit table for every window, newest first, see `get_windows(...)`
+    pub fn build_queries(&self, retention_boundary: Option<DateTime<Utc>>) -> Vec<Query> {
+        self.get_windows(retention_boundary)
+            .into_iter()
+            .map(|(start, end)| {
+                let window = Filter::GreaterOrEqual(PARTITION_COLUMN, Attribute::DateTime(start))
+                    .and(Filter::LessThan(PARTITION_COLUMN, Attribute::DateTime(end)));
+
+                Query::new(AUDIT_TABLE)
+                    .filter(match self.filter.clone() {
+                        Some(filter) => window.and(filter),
+                        None => window,
+                    })
+                    .order(vec![Order::Descending(PARTITION_COLUMN)])
+            })
+            .collect()
+    }
+}
+
+/// returns the start of the calendar quarter after the given time
+fn get_next_quarter(time: DateTime<Utc>) -> DateTime<Utc> {
+    let month = (time.month0() / 3 + 1) * 3;
+    let (year, month) = match month {
+        12 => (time.year() + 1, 0),
+        month => (time.year(), month),
+    };
+
+    Utc.with_ymd_and_hms(year, month + 1, 1, 0, 0, 0)
+        .single()
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// The audit records retrieved by `Client::retrieve_audit(...)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditHistory {
+    /// the audit records, newest first
+    pub entries: Vec<AuditEntry>,
+    /// the time before which audit records are deleted, `None` if they are kept forever
+    pub retention_boundary: Option<DateTime<Utc>>,
+    /// the start of the requested range
+    pub from: DateTime<Utc>,
+}
+
+impl AuditHistory {
+    /// Indicates if a part of the requested range lies before the retention boundary
+    pub fn is_truncated(&self) -> bool {
+        self.retention_boundary
+            .is_some_and(|boundary| self.from < boundary)
+    }
+}
+
+#[derive(Deserialize)]
+struct Organizations {
+    value: Vec<Organization>,
+}
+
+#[derive(Deserialize)]
+struct Organization {
+    auditretentionperiodv2: Option<i32>,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the number of days audit records are kept in this environment,
+    `None` if they are kept forever
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_audit_retention(&self) -> Result<Option<Duration>> {
+        let organizations: Organizations = self
+            .execute_function("organizations?$select=auditretentionperiodv2")
+            .await?;
+
+        Ok(organizations
+            .value
+            .into_iter()
+            .next()
+            .and_then(|organization| organization.auditretentionperiodv2)
+            .filter(|days| *days > 0)
+            .map(|days| Duration::days(days.into())))
+    }
+
+    /**
+    retrieves the records of the audit table matching the query, newest first
+
+    The windows of the query are retrieved one after another with all their pages.
+    The retention period of the environment is retrieved first, so the time before
+    its boundary is not queried
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The user lacks the privilege to view the audit history
+    */
+    pub async fn retrieve_audit(&self, query: &AuditQuery) -> Result<AuditHistory> {
+        let retention_boundary = self
+            .retrieve_audit_retention()
+            .await?
+            .map(|retention| Utc::now() - retention);
+
+        let mut entries = Vec::new();
+
+        for window in query.build_queries(retention_boundary) {
+            entries.extend(self.retrieve_all::<AuditEntry>(&window).await?);
+        }
+
+        Ok(AuditHistory {
+            entries,
+            retention_boundary,
+            from: query.from,
+        })
+    }
+
+    /**
+    retrieves the audited changes of the columns of the referenced record, oldest first
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The user lacks the privilege to view the audit history
+    */
+    pub async fn retrieve_change_history(&self, reference: &impl Reference) -> Result<Vec<AuditChange>> {
+        let reference = reference.get_reference();
+        let mut changes = Vec::new();
+
+        for page_number in 1.. {
+            let function = format!(
+                "RetrieveRecordChangeHistory(Target=@target,PagingInfo=@paging)?@target={{\"@odata.id\":\"{}({})\"}}&@paging={{\"PageNumber\":{},\"Count\":{}}}",
+                reference.entity_name,
+                reference.entity_id.as_hyphenated(),
+                page_number,
+                PAGE_SIZE
+            );
+
+            let response: ChangeHistoryResponse = self.execute_function(&function).await?;
+            changes.extend(
+                response
+                    .collection
+                    .details
+                    .into_iter()
+                    .filter_map(AuditDetail::into_change),
+            );
+
+            if !response.collection.more_records {
+                break;
+            }
+        }
+
+        changes.sort_by_key(|change| change.changed_on);
+        Ok(changes)
+    }
+
+    /**
+    retrieves the referenced record with the values it had at the given time
+
+    The current values of the columns selected by `E` are retrieved and all audited
+    changes after the given time are reverted on them. see the module documentation
+    for the limits of the reconstruction
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The user lacks the privilege to view the audit history
+    - The record was created after the given time
+    */
+    pub async fn retrieve_as_of<E: ReadEntity>(
+        &self,
+        reference: &impl Reference,
+        timestamp: DateTime<Utc>,
+    ) -> Result<E> {
+        let record = reference.get_reference();
+        let current: Map<String, Value> = self
+            .execute_function(&format!(
+                "{}({})?$select={}",
+                record.entity_name,
+                record.entity_id.as_hyphenated(),
+                E::get_columns().join(",")
+            ))
+            .await?;
+
+        let changes = self.retrieve_change_history(&record).await?;
+        let snapshot = reconstruct(strip_annotations(current), &changes, timestamp)?;
+        serde_json::from_value(Value::Object(snapshot)).into_dataverse_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::{json, Map, Value};
+
+    use super::{reconstruct, AuditChange, AuditOperation, AuditQuery, ChangeHistoryResponse};
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    fn change(day: u32, operation: AuditOperation, old: Value, new: Value) -> AuditChange {
+        AuditChange {
+            changed_on: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+            operation,
+            old_values: values(old),
+            new_values: values(new),
+        }
+    }
+
+    #[test]
+    fn reverts_changes_after_timestamp() {
+        let changes = vec![
+            change(1, AuditOperation::Create, json!({}), json!({ "firstname": "A" })),
+            change(5, AuditOperation::Update, json!({ "firstname": "A" }), json!({ "firstname": "B", "emailaddress1": "b@example.com" })),
+            change(9, AuditOperation::Update, json!({ "firstname": "B" }), json!({ "firstname": "C" })),
+        ];
+        let current = values(json!({ "firstname": "C", "emailaddress1": "b@example.com" }));
+
+        let snapshot = reconstruct(current.clone(), &changes, Utc.with_ymd_and_hms(2024, 3, 3, 0, 0, 0).unwrap()).unwrap();
+        assert_eq!(Value::Object(snapshot), json!({ "firstname": "A", "emailaddress1": null }));
+
+        let snapshot = reconstruct(current.clone(), &changes, Utc.with_ymd_and_hms(2024, 3, 7, 0, 0, 0).unwrap()).unwrap();
+        assert_eq!(Value::Object(snapshot), json!({ "firstname": "B", "emailaddress1": "b@example.com" }));
+
+        assert!(reconstruct(current, &changes, Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()).is_err());
+    }
+
+    #[test]
+    fn splits_audit_query_into_partitions() {
+        let from = Utc.with_ymd_and_hms(2023, 11, 15, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        let query = AuditQuery::new(from, to).for_table("contact");
+
+        let windows = query.get_windows(None);
+        assert_eq!(
+            windows,
+            vec![
+                (Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap(), to),
+                (
+                    Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()
+                ),
+                (from, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            ]
+        );
+
+        let boundary = Utc.with_ymd_and_hms(2024, 2, 10, 0, 0, 0).unwrap();
+        let queries = query.build_queries(Some(boundary));
+        assert_eq!(queries.len(), 2);
+        assert_eq!(
+            queries[1].to_string(),
+            "audits?$filter=createdon ge '2024-02-10 00:00:00 UTC' and createdon lt '2024-04-01 00:00:00 UTC' and objecttypecode eq 'contact'&$orderby=createdon desc"
+        );
+
+        assert!(query.get_windows(Some(to)).is_empty());
+        assert_eq!(
+            query
+                .with_max_window(chrono::Duration::days(30))
+                .get_windows(Some(boundary))
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn parses_change_history() {
+        let response: ChangeHistoryResponse = serde_json::from_str(
+            r##"{"AuditDetailCollection":{"MoreRecords":false,"AuditDetails":[{"@odata.type":"#Microsoft.Dynamics.CRM.AttributeAuditDetail","AuditRecord":{"createdon":"2024-03-05T12:00:00Z","operation":2},"OldValue":{"@odata.type":"#Microsoft.Dynamics.CRM.contact","firstname":"A"},"NewValue":{"@odata.type":"#Microsoft.Dynamics.CRM.contact","firstname":"B"}}]}}"##,
+        )
+        .unwrap();
+
+        let change = response.collection.details.into_iter().next().unwrap().into_change().unwrap();
+        assert_eq!(change.operation, AuditOperation::Update);
+        assert_eq!(Value::Object(change.old_values), json!({ "firstname": "A" }));
+    }
+}
@@ -0,0 +1,324 @@
+/*!
+Module for an audit trail of the write requests a client sends
+
+Integrations that need an immutable record of their writes (e.g. for financial
+compliance) can pass a sink to `Client::with_audit_trail(...)`. The sink receives
+an `AuditTrailEntry` for every request that writes data, including every request of
+a batch, before the request is sent. So the trail also covers writes that fail
+
+Every write is passed once, even if it is sent several times because it was throttled
+or failed over to another url. The requests of a batch are read from the batch itself
+instead of the http body, its spool file is read in chunks. Batches streamed from a reader
+pass their requests to the sink while the body is sent, so only the request that is
+currently read is held in memory
+
+The entry contains the exact payload of the request, which the sink can hash or
+sign with the keys of the organization before storing it in an append-only store.
+The caller is the user id of the connection, which is known once `Client::who_am_i()`
+was called
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy() // Please replace this with your preferred authentication method
+        .with_audit_trail(|entry| {
+            println!(
+                "{} {} {} {:?} by {:?} changed {:?}",
+                entry.timestamp, entry.method, entry.entity_name, entry.entity_id, entry.caller, entry.columns
+            );
+        });
+
+    client.who_am_i().await?;
+    Ok(())
+}
+```
+*/
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use reqwest::{Body, Method, Request};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+/// A write request sent by the client
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditTrailEntry {
+    pub timestamp: DateTime<Utc>,
+    /// the user id of the connection if it is known
+    pub caller: Option<Uuid>,
+    pub method: Method,
+    /// the entity set name of the written record or the name of an action
+    pub entity_name: String,
+    /// the id of the written record if it is addressed by its primary key
+    pub entity_id: Option<Uuid>,
+    /// the names of the written columns in alphabetical order
+    pub columns: Vec<String>,
+    /// the payload of the request, `None` for requests without a body or streamed bodies
+    pub payload: Option<String>,
+}
+
+/// callback that receives the write requests of a client
+pub type AuditTrailSink = Arc<dyn Fn(&AuditTrailEntry) + Send + Sync>;
+
+/**
+returns the entry of the request if it writes data
+
+Batches return no entries, their requests are collected from the batch payload
+with `collect_batch_entries(...)` or a `BatchAuditor` before they are turned into a request
+*/
+pub(crate) fn collect_entries(request: &Request, caller: Option<Uuid>) -> Vec<AuditTrailEntry> {
+    if request.method() == Method::GET {
+        return Vec::new();
+    }
+
+    let Some(path) = get_resource_path(request.url().as_str()) else {
+        return Vec::new();
+    };
+
+    if path == "$batch" {
+        return Vec::new();
+    }
+
+    let payload = request
+        .body()
+        .and_then(Body::as_bytes)
+        .map(|payload| String::from_utf8_lossy(payload).into_owned());
+
+    vec![new_entry(Utc::now(), caller, request.method().clone(), path, payload)]
+}
+
+/// returns one entry for every request of the batch payload that writes data
+#[cfg(feature = "batch")]
+pub(crate) fn collect_batch_entries(payload: &str, caller: Option<Uuid>) -> Vec<AuditTrailEntry> {
+    let timestamp = Utc::now();
+
+    payload
+        .split(PART_DELIMITER)
+        .filter_map(|part| new_batch_entry(timestamp, caller, part))
+        .collect()
+}
+
+/// the delimiter that precedes every boundary line of a batch payload
+#[cfg(feature = "batch")]
+const PART_DELIMITER: &str = "\r\n--";
+
+/// the size of the pieces a `BatchAuditor` scans for delimiters at once
+#[cfg(feature = "batch")]
+const SCAN_SIZE: usize = 8 * 1024;
+
+/**
+passes the requests of a batch payload that is read in chunks to the sink
+
+Only the part of the payload after the last complete request is buffered, so
+spooled and streamed batches are audited without holding their body in memory.
+A request is passed once the boundary after it was pushed, which the closing
+boundaries of every batch payload guarantee for the last request
+*/
+#[cfg(feature = "batch")]
+pub(crate) struct BatchAuditor {
+    sink: AuditTrailSink,
+    caller: Option<Uuid>,
+    timestamp: DateTime<Utc>,
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "batch")]
+impl BatchAuditor {
+    pub(crate) fn new(sink: AuditTrailSink, caller: Option<Uuid>) -> Self {
+        Self {
+            sink,
+            caller,
+            timestamp: Utc::now(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// passes every request that is completed by the chunk to the sink
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        for piece in chunk.chunks(SCAN_SIZE) {
+            self.push_piece(piece);
+        }
+    }
+
+    fn push_piece(&mut self, piece: &[u8]) {
+        let delimiter = PART_DELIMITER.as_bytes();
+        let mut searched = self.pending.len().saturating_sub(delimiter.len() - 1);
+        let mut consumed = 0;
+        self.pending.extend_from_slice(piece);
+
+        while let Some(index) = self.pending[searched..]
+            .windows(delimiter.len())
+            .position(|window| window == delimiter)
+        {
+            let end = searched + index;
+            let part = String::from_utf8_lossy(&self.pending[consumed..end]);
+
+            if let Some(entry) = new_batch_entry(self.timestamp, self.caller, &part) {
+                (self.sink)(&entry);
+            }
+
+            consumed = end + delimiter.len();
+            searched = consumed;
+        }
+
+        self.pending.drain(..consumed);
+    }
+}
+
+/// returns the entry of a single part of a batch payload if it is a request that writes data
+#[cfg(feature = "batch")]
+fn new_batch_entry(timestamp: DateTime<Utc>, caller: Option<Uuid>, part: &str) -> Option<AuditTrailEntry> {
+    let (method, url, payload) = parse_part(part)?;
+
+    if method == Method::GET {
+        return None;
+    }
+
+    let path = get_resource_path(url)?;
+    Some(new_entry(timestamp, caller, method, path, payload.map(String::from)))
+}
+
+fn new_entry(
+    timestamp: DateTime<Utc>,
+    caller: Option<Uuid>,
+    method: Method,
+    path: &str,
+    payload: Option<String>,
+) -> AuditTrailEntry {
+    let (target, column) = match path.split_once('/') {
+        Some((target, column)) => (target, Some(column)),
+        None => (path, None),
+    };
+
+    let (entity_name, entity_id) = match target.split_once('(') {
+        Some((entity_name, key)) => (entity_name, Uuid::parse_str(key.trim_end_matches(')')).ok()),
+        None => (target, None),
+    };
+
+    let mut columns: Vec<String> = match column {
+        Some(column) => vec![column.to_string()],
+        None => payload
+            .as_deref()
+            .and_then(|payload| serde_json::from_str::<Map<String, Value>>(payload).ok())
+            .map(|payload| payload.into_iter().map(|(column, _)| column).collect())
+            .unwrap_or_default(),
+    };
+    columns.sort();
+
+    AuditTrailEntry {
+        timestamp,
+        caller,
+        method,
+        entity_name: entity_name.to_string(),
+        entity_id,
+        columns,
+        payload,
+    }
+}
+
+/// returns the path of the url after the Web API version without query options
+fn get_resource_path(url: &str) -> Option<&str> {
+    let (_, path) = url.split_once("/api/data/v")?;
+    let (_, path) = path.split_once('/')?;
+    Some(path.split('?').next().unwrap_or(path))
+}
+
+/// returns the method, url and payload of a part of a batch payload if it contains a request
+#[cfg(feature = "batch")]
+fn parse_part(part: &str) -> Option<(Method, &str, Option<&str>)> {
+    let mut sections = part.splitn(3, "\r\n\r\n").skip(1);
+
+    let (method, url) = sections
+        .next()
+        .and_then(|request| request.split("\r\n").next())
+        .and_then(|line| line.strip_suffix(" HTTP/1.1"))
+        .and_then(|line| line.split_once(' '))?;
+
+    let method = Method::from_bytes(method.as_bytes()).ok()?;
+    let body = sections.next().map(str::trim).filter(|body| !body.is_empty());
+    Some((method, url, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Body, Method, Request, Url};
+    use uuid::Uuid;
+
+    #[cfg(feature = "batch")]
+    use std::sync::{Arc, Mutex};
+
+    #[cfg(feature = "batch")]
+    use super::{collect_batch_entries, BatchAuditor};
+    use super::collect_entries;
+
+    static BATCH_PAYLOAD: &str = "--batch_1\r\nContent-Type: multipart/mixed;boundary=changeset_1\r\n\r\n\
+        --changeset_1\r\nContent-Type: application/http\r\nContent-Id: 1\r\n\r\n\
+        POST https://contoso.crm4.dynamics.com/api/data/v9.2/contacts HTTP/1.1\r\nContent-Type: application/json;type=entry\r\n\r\n\
+        {\"lastname\":\"McTestface\"}\r\n\
+        --changeset_1\r\nContent-Type: application/http\r\nContent-Id: 2\r\n\r\n\
+        DELETE https://contoso.crm4.dynamics.com/api/data/v9.2/accounts(00000000-0000-0000-0000-000000000002) HTTP/1.1\r\n\r\n\r\n\
+        --changeset_1--\r\n--batch_1--\r\n";
+
+    #[test]
+    fn collects_writes_of_requests() {
+        let mut request = Request::new(
+            Method::PATCH,
+            Url::parse("https://contoso.crm4.dynamics.com/api/data/v9.2/contacts(00000000-0000-0000-0000-000000000001)").unwrap(),
+        );
+        *request.body_mut() = Some(Body::from(r#"{"lastname":"McTestface","firstname":"Testy"}"#));
+
+        let entries = collect_entries(&request, Some(Uuid::nil()));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].entity_name, "contacts");
+        assert_eq!(entries[0].entity_id, Some(Uuid::from_u128(1)));
+        assert_eq!(entries[0].columns, vec!["firstname", "lastname"]);
+        assert_eq!(entries[0].caller, Some(Uuid::nil()));
+
+        let mut batch = Request::new(
+            Method::POST,
+            Url::parse("https://contoso.crm4.dynamics.com/api/data/v9.2/$batch").unwrap(),
+        );
+        *batch.body_mut() = Some(Body::from(BATCH_PAYLOAD));
+        assert!(collect_entries(&batch, None).is_empty());
+
+        let query = Request::new(Method::GET, Url::parse("https://contoso.crm4.dynamics.com/api/data/v9.2/contacts").unwrap());
+        assert!(collect_entries(&query, None).is_empty());
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn collects_writes_of_batches() {
+        let entries = collect_batch_entries(BATCH_PAYLOAD, None);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].method, Method::POST);
+        assert_eq!(entries[0].payload.as_deref(), Some("{\"lastname\":\"McTestface\"}"));
+        assert_eq!(entries[0].columns, vec!["lastname"]);
+        assert_eq!(entries[1].entity_name, "accounts");
+        assert_eq!(entries[1].entity_id, Some(Uuid::from_u128(2)));
+        assert_eq!(entries[1].payload, None);
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn audits_batches_in_chunks() {
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&entries);
+        let mut auditor = BatchAuditor::new(Arc::new(move |entry| sink.lock().unwrap().push(entry.clone())), None);
+
+        for chunk in BATCH_PAYLOAD.as_bytes().chunks(3) {
+            auditor.push(chunk);
+        }
+
+        let entries = entries.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].payload.as_deref(), Some("{\"lastname\":\"McTestface\"}"));
+        assert_eq!(entries[1].entity_id, Some(Uuid::from_u128(2)));
+        assert_eq!(auditor.pending, b"batch_1--\r\n");
+    }
+}
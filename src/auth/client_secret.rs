@@ -1,12 +1,14 @@
 use std::{
     collections::HashMap,
     sync::Arc,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant},
 };
 
+use async_lock::Mutex;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::DATE;
 use serde::Deserialize;
-use tokio::sync::Mutex;
 
 use super::Authenticate;
 use crate::{
@@ -14,9 +16,23 @@ use crate::{
     result::{IntoDataverseResult, Result},
 };
 
+/// tokens are refreshed this long before they expire
+static REFRESH_MARGIN: Duration = Duration::from_secs(120);
+
+/// lifetime of tokens whose response doesn't state when they expire
+static DEFAULT_LIFETIME: Duration = Duration::from_secs(900);
+
 /**
 Implements the `Authenticate` trait by using OAuth client/secret authentication
 
+Tokens are cached per scope, so the same instance can authenticate requests against
+Dataverse and other endpoints like the discovery service
+
+The expiry of acquired tokens is tracked with a monotonic clock, starting when the
+token was requested. Absolute expiry times are converted with the `Date` header of
+the login server, so a local clock that is off by minutes doesn't cause premature
+or late refreshes
+
 It is unlikely you need to use this struct directly. just use the
 `Client::with_client_secret_auth(...)` function instead
 */
@@ -24,7 +40,8 @@ pub struct ClientSecretAuth {
     http_client: reqwest::Client,
     login_url: String,
     login_data: HashMap<&'static str, String>,
-    token_info: Mutex<Option<TokenInfo>>,
+    scope: String,
+    tokens: Mutex<HashMap<String, TokenInfo>>,
 }
 
 impl ClientSecretAuth {
@@ -44,8 +61,9 @@ impl ClientSecretAuth {
         Self {
             http_client,
             login_url,
-            login_data: build_login_data(client_id, client_secret, scope),
-            token_info: Mutex::new(None),
+            login_data: build_login_data(client_id, client_secret),
+            scope,
+            tokens: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -53,18 +71,30 @@ impl ClientSecretAuth {
 #[async_trait]
 impl Authenticate for ClientSecretAuth {
     async fn get_valid_token(&self) -> Result<Arc<String>> {
-        let mut token_info = self.token_info.lock().await;
+        self.get_valid_token_for(&self.scope).await
+    }
 
-        if let Some(info) = token_info.as_ref() {
-            if info.valid_until > SystemTime::now() {
+    async fn get_valid_token_for(&self, scope: &str) -> Result<Arc<String>> {
+        let mut tokens = self.tokens.lock().await;
+        let now = Instant::now();
+
+        if let Some(info) = tokens.get(scope) {
+            if info.valid_until > now {
                 return Ok(Arc::clone(&info.key));
             }
         }
 
+        // evicts the expired tokens of all scopes, so scopes that are no longer used don't pile up
+        tokens.retain(|_, info| info.valid_until > now);
+
+        let mut login_data = self.login_data.clone();
+        login_data.insert("scope", scope.to_string());
+
+        let requested_at = Instant::now();
         let response = self
             .http_client
             .post(&self.login_url)
-            .form(&self.login_data)
+            .form(&login_data)
             .send()
             .await
             .into_dataverse_result()?;
@@ -77,39 +107,126 @@ impl Authenticate for ClientSecretAuth {
             return Err(DataverseError::new(error_message));
         }
 
+        let server_date = response
+            .headers()
+            .get(DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+            .map(|date| date.with_timezone(&Utc));
+
         let content = response.bytes().await.into_dataverse_result()?;
         let mut result: TokenResult =
             serde_json::from_slice(content.as_ref()).into_dataverse_result()?;
-        let key = Arc::from(result.access_token.take().unwrap());
-
-        *token_info = Some(TokenInfo {
-            key: Arc::clone(&key),
-            valid_until: SystemTime::now() + Duration::from_secs(900),
-        });
+        let key = Arc::from(result.access_token.take().ok_or_else(|| {
+            DataverseError::new(String::from("the login server provided no access token"))
+        })?);
+
+        tokens.insert(
+            scope.to_string(),
+            TokenInfo {
+                key: Arc::clone(&key),
+                valid_until: requested_at + get_token_lifetime(&result, server_date).saturating_sub(REFRESH_MARGIN),
+            },
+        );
 
         Ok(key)
     }
 }
 
-fn build_login_data(
-    client_id: String,
-    client_secret: String,
-    scope: String,
-) -> HashMap<&'static str, String> {
+fn build_login_data(client_id: String, client_secret: String) -> HashMap<&'static str, String> {
     let mut form_data = HashMap::new();
     form_data.insert("grant_type", String::from("client_credentials"));
     form_data.insert("client_id", client_id);
     form_data.insert("client_secret", client_secret);
-    form_data.insert("scope", scope);
     form_data
 }
 
+/**
+returns how long the token is valid, measured from the time it was requested
+
+`expires_in` is relative and therefore independent of any clock. The absolute
+`expires_on` of older endpoints is compared to the `Date` of the login server
+instead of the local clock
+*/
+fn get_token_lifetime(result: &TokenResult, server_date: Option<DateTime<Utc>>) -> Duration {
+    let seconds = match (result.expires_in, result.expires_on, server_date) {
+        (Some(expires_in), _, _) => expires_in,
+        (None, Some(expires_on), Some(server_date)) => expires_on - server_date.timestamp(),
+        _ => return DEFAULT_LIFETIME,
+    };
+
+    Duration::from_secs(seconds.max(0) as u64)
+}
+
 struct TokenInfo {
     key: Arc<String>,
-    valid_until: SystemTime,
+    valid_until: Instant,
 }
 
 #[derive(Deserialize)]
 struct TokenResult {
     pub access_token: Option<String>,
+    #[serde(default, with = "crate::serde_helpers::int64_string::option")]
+    pub expires_in: Option<i64>,
+    #[serde(default, with = "crate::serde_helpers::int64_string::option")]
+    pub expires_on: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{get_token_lifetime, TokenResult, DEFAULT_LIFETIME};
+
+    fn parse(json: &str) -> TokenResult {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn token_lifetime() {
+        let result = parse(r#"{"access_token":"token","expires_in":3599}"#);
+        assert_eq!(get_token_lifetime(&result, None), Duration::from_secs(3599));
+
+        // the local clock doesn't matter, only the date of the login server
+        let server_date = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let result = parse(r#"{"access_token":"token","expires_on":"1700003600"}"#);
+        assert_eq!(get_token_lifetime(&result, Some(server_date)), Duration::from_secs(3600));
+        assert_eq!(get_token_lifetime(&result, None), DEFAULT_LIFETIME);
+    }
+
+    #[cfg(feature = "testkit")]
+    #[tokio::test]
+    async fn caches_tokens_per_scope() {
+        use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+        use crate::auth::Authenticate;
+
+        use super::ClientSecretAuth;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"access_token":"token","expires_in":3599}"#),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let auth = ClientSecretAuth::new(
+            reqwest::Client::new(),
+            format!("{}/token", server.uri()),
+            String::from("https://instance.crm.dynamics.com/.default"),
+            String::from("client"),
+            String::from("secret"),
+        );
+
+        auth.get_valid_token().await.unwrap();
+        auth.get_valid_token().await.unwrap();
+        auth.get_valid_token_for("https://globaldisco.crm.dynamics.com/.default")
+            .await
+            .unwrap();
+    }
 }
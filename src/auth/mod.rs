@@ -12,15 +12,21 @@ use powerplatform_dataverse_service_client::result::Result;
 #[async_trait]
 pub trait Authenticate {
     async fn get_valid_token(&self) -> Result<Arc<String>>;
+
+    async fn get_valid_token_for(&self, scope: &str) -> Result<Arc<String>>;
 }
 ```
+
+`get_valid_token_for(...)` acquires tokens for other scopes than the one of the
+Dataverse environment (e.g. the discovery service), so other endpoints can share
+the credentials and the token cache of a client
 */
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::result::Result;
+use crate::{error::DataverseError, result::Result};
 
 pub mod client_secret;
 pub mod no_auth;
@@ -44,4 +50,18 @@ pub trait Authenticate {
     handle soft errors with their own strategies like retries
     */
     async fn get_valid_token(&self) -> Result<Arc<String>>;
+
+    /**
+    Authenticates the current instance and returns a Bearer token for the given scope,
+    e.g. `https://globaldisco.crm.dynamics.com/.default`
+
+    Tokens should be cached per scope like in `get_valid_token(...)`. The default
+    implementation fails for authentication methods that only support a single scope
+    */
+    async fn get_valid_token_for(&self, scope: &str) -> Result<Arc<String>> {
+        Err(DataverseError::new(format!(
+            "this authentication method doesn't support tokens for the scope {}",
+            scope
+        )))
+    }
 }
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    action::get_primary_id_attribute,
+    currency::{apply_default_currency, has_currency, CurrencyEntityCache},
+    error::DataverseError,
+    id_generator::{apply_generated_id, IdGenerator},
+    identity::apply_default_owner,
+    metadata::EntityNameCache,
+    reference::EntityReference,
+    result::Result,
+};
+
+/**
+The defaults of a client that are applied to the payloads of creates added to a `Batch`
+
+They are attached to the batches of `Client::new_batch()`, `Client::new_batch_set()`
+and `Client::new_transaction()` next to the write hook and applied after it. Defaults
+that depend on the metadata of an entity read it from the caches of the client, which
+are filled by `Client::prepare_creates(...)`
+*/
+#[derive(Default)]
+pub(crate) struct CreateDefaults {
+    /// the owner that is bound unless the payload sets an owner
+    pub(crate) owner: Option<EntityReference>,
+    /// the currency that is bound to entities with a currency lookup and the cache of these entities
+    pub(crate) currency: Option<(Uuid, Arc<CurrencyEntityCache>)>,
+    /// the generator of primary keys and the cache of the entity names to resolve them
+    pub(crate) id_generator: Option<(Arc<dyn IdGenerator>, Arc<EntityNameCache>)>,
+}
+
+impl CreateDefaults {
+    /// applies the defaults to the payload of a create of the given entity (entity set name)
+    pub(crate) fn apply(&self, entity_name: &str, payload: &mut Map<String, Value>) -> Result<()> {
+        if let Some(owner) = self.owner.as_ref() {
+            apply_default_owner(payload, owner);
+        }
+
+        if let Some((currency_id, entities)) = self.currency.as_ref() {
+            if !has_currency(payload) {
+                match entities.get(entity_name) {
+                    Some(true) => apply_default_currency(payload, *currency_id),
+                    Some(false) => {}
+                    None => return Err(not_prepared(entity_name)),
+                }
+            }
+        }
+
+        if let Some((generator, entity_names)) = self.id_generator.as_ref() {
+            let names = entity_names.get(entity_name).ok_or_else(|| not_prepared(entity_name))?;
+            let primary_key = get_primary_id_attribute(&names.logical_name);
+            apply_generated_id(payload, &primary_key, generator.generate(entity_name))?;
+        }
+
+        Ok(())
+    }
+
+    /// indicates if there is no default to apply
+    pub(crate) fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.currency.is_none() && self.id_generator.is_none()
+    }
+}
+
+fn not_prepared(entity_name: &str) -> DataverseError {
+    DataverseError::new(format!(
+        "The metadata of {} is not resolved for the defaults of its creates, please call `Client::prepare_creates(...)` before adding them to a batch",
+        entity_name
+    ))
+}
@@ -0,0 +1,1224 @@
+use std::{
+    borrow::Cow,
+    fmt::{Arguments, Display, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use reqwest::Body;
+use serde::Serialize;
+use serde_json::Value;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::{
+    audit_trail::BatchAuditor,
+    client::VERSION,
+    entity::{ReadEntity, WriteEntity},
+    error::DataverseError,
+    hook::{to_hooked_string, to_hooked_value, WriteContext, WriteHook, WriteOperation},
+    reference::{EntityReference, Reference},
+    result::{IntoDataverseResult, Result},
+};
+
+use self::{
+    defaults::CreateDefaults,
+    response::ContentId,
+    spool::{Spool, SPOOL_THRESHOLD},
+};
+
+pub(crate) mod defaults;
+pub mod response;
+pub mod set;
+mod spool;
+
+/**
+Represents a batch of Microsoft Dataverse Requests
+
+Some restrictions apply for creating batches:
+- the batch size may not exceed 1000 calls
+- the batch execution time may not exceed 2 minutes
+
+the second restriction is especially tricky to handle because the execution time
+depends on the complexity of the entity in dataverse.
+So it is possible to create 300 records of an entity with low complexity
+but only 50 records of an entity with high complexity in that timeframe.
+
+Based on experience a batch size of 50 should be safe for all entities though.
+The bulk functions of `Client` can size their batches by the measured execution
+time of each entity instead, see the `planner` module
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    batch::Batch,
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::{Result, IntoDataverseResult}
+};
+
+async fn test() -> Result<()> {
+    let testy_contact = Contact {
+        contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+        firstname: String::from("Testy"),
+        lastname: String::from("McTestface"),
+    };
+
+    let marianne_contact = Contact {
+        contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?,
+        firstname: String::from("Marianne"),
+        lastname: String::from("McTestface"),
+    };
+
+    // this batch creates both contacts in one call
+    let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+    batch.create(&testy_contact)?;
+    batch.create(&marianne_contact)?;
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    client.execute(&batch).await?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+    lastname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new(
+            "contacts",
+            self.contactid,
+        )
+    }
+}
+```
+*/
+pub struct Batch {
+    url: Cow<'static, str>,
+    batch_id: Uuid,
+    dataset_id: Uuid,
+    payload: String,
+    next_content_id: u16,
+    default_headers: Vec<(String, String)>,
+    write_hook: Option<WriteHook>,
+    create_defaults: Option<Arc<CreateDefaults>>,
+    spool: Option<Spool>,
+}
+
+impl Batch {
+    /// Creates a new empty batch with its own batch id and dataset id
+    pub fn new(url: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            url: url.into(),
+            batch_id: Uuid::new_v4(),
+            dataset_id: Uuid::new_v4(),
+            payload: String::new(),
+            next_content_id: 1,
+            default_headers: Vec::new(),
+            write_hook: None,
+            create_defaults: None,
+            spool: None,
+        }
+    }
+
+    /**
+    Creates a new empty batch that spools its payload into the file at the given path
+
+    Only a small buffer of the payload is kept in memory, the rest is written to
+    the file and streamed from there when the batch is executed. This keeps the
+    memory usage bounded regardless of the batch size
+
+    The file is created (or truncated if it exists) and removed again when the
+    batch is dropped
+
+    # Examples
+    ```rust
+    use powerplatform_dataverse_service_client::{batch::Batch, result::Result};
+
+    fn test() -> Result<()> {
+        let path = std::env::temp_dir().join("contacts.batch");
+        let mut batch = Batch::with_spool_file("https://instance.crm.dynamics.com/", path)?;
+        Ok(())
+    }
+    ```
+    */
+    pub fn with_spool_file(url: impl Into<Cow<'static, str>>, path: impl Into<PathBuf>) -> Result<Self> {
+        let mut batch = Self::new(url);
+        batch.spool = Some(Spool::create(path.into())?);
+        Ok(batch)
+    }
+
+    /**
+    Creates a new empty batch that spools its payload into a file in the
+    temporary directory of the system
+
+    see `with_spool_file(...)` for more details
+    */
+    pub fn spooled(url: impl Into<Cow<'static, str>>) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("dataverse_batch_{}", Uuid::new_v4().as_simple()));
+        Self::with_spool_file(url, path)
+    }
+
+    /// returns the path of the spool file if this batch spools its payload
+    pub fn get_spool_path(&self) -> Option<&Path> {
+        self.spool.as_ref().map(Spool::get_path)
+    }
+
+    /**
+    Clears the batch of its contents and generates a new batch id and
+    a new dataset id
+
+    Note that this can be used to prevent frequent allocations by reusing
+    the `Batch` instance and its buffer
+
+    Default headers added with `add_default_header(...)` are kept. The spool file
+    of a spooled batch is truncated
+
+    Please note that this function can fail if the spool file cannot be truncated
+    */
+    pub fn reset(&mut self) -> Result<()> {
+        self.batch_id = Uuid::new_v4();
+        self.dataset_id = Uuid::new_v4();
+        self.payload.clear();
+        self.next_content_id = 1;
+
+        if let Some(spool) = self.spool.as_mut() {
+            spool.truncate()?;
+        }
+
+        Ok(())
+    }
+
+    /// returns the current batch id (This will change after a call to `reset()`)
+    pub fn get_batch_id(&self) -> Uuid {
+        self.batch_id
+    }
+
+    /// returns the current dataset id (This will change after a call to `reset()`)
+    pub fn get_dataset_id(&self) -> Uuid {
+        self.dataset_id
+    }
+
+    /// returns the current count of requests in this batch
+    pub fn get_count(&self) -> u16 {
+        self.next_content_id - 1
+    }
+
+    /// returns the size of the payload in memory in bytes
+    pub(crate) fn get_payload_size(&self) -> usize {
+        self.payload.len()
+    }
+
+    /**
+    Adds a header that is attached to every request added to this batch afterwards
+
+    This is useful for headers like `CallerObjectId` (impersonation),
+    `MSCRM.BypassCustomPluginExecution` or `Prefer` that should apply to the
+    whole batch. Requests that were already added to the batch are not affected
+
+    # Examples
+    ```rust
+    use powerplatform_dataverse_service_client::batch::Batch;
+
+    let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+    batch.add_default_header("MSCRM.BypassCustomPluginExecution", "true");
+    ```
+    */
+    pub fn add_default_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.default_headers.push((name.into(), value.into()));
+    }
+
+    /// returns the headers that are attached to every request added to this batch
+    pub fn get_default_headers(&self) -> &[(String, String)] {
+        &self.default_headers
+    }
+
+    /**
+    Adds a Create Request for the given entity to this batch
+
+    Please note that this function can fail if a serde serialization error occurs
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Serialize;
+    use powerplatform_dataverse_service_client::{
+        batch::Batch,
+        client::Client,
+        entity::WriteEntity,
+        reference::{Reference, ReferenceStruct},
+        result::{Result, IntoDataverseResult}
+    };
+
+    async fn test() -> Result<()> {
+        let testy_contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+            firstname: String::from("Testy"),
+            lastname: String::from("McTestface"),
+        };
+
+        let marianne_contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?,
+            firstname: String::from("Marianne"),
+            lastname: String::from("McTestface"),
+        };
+
+        // this batch creates both contacts in one call
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+        batch.create(&testy_contact)?;
+        batch.create(&marianne_contact)?;
+
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        client.execute(&batch).await?;
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+        lastname: String,
+    }
+
+    impl WriteEntity for Contact {}
+
+    impl Reference for Contact {
+        fn get_reference(&self) -> ReferenceStruct {
+            ReferenceStruct::new(
+                "contacts",
+                self.contactid,
+            )
+        }
+    }
+    ```
+    */
+    pub fn create(&mut self, entity: &impl WriteEntity) -> Result<()> {
+        self.create_with_headers(entity, &[])
+    }
+
+    /**
+    Adds a Create Request for the given entity to this batch with additional
+    headers for this request only
+
+    The given headers are written after the default headers of this batch
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Serialize;
+    use powerplatform_dataverse_service_client::{
+        batch::Batch,
+        entity::WriteEntity,
+        reference::{Reference, ReferenceStruct},
+        result::{Result, IntoDataverseResult}
+    };
+
+    fn test() -> Result<()> {
+        let contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+            firstname: String::from("Testy"),
+            lastname: String::from("McTestface"),
+        };
+
+        // this contact is created on behalf of another user
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+        batch.create_with_headers(
+            &contact,
+            &[("CallerObjectId", "12345678-1234-1234-1234-123456789abc")]
+        )?;
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+        lastname: String,
+    }
+
+    impl WriteEntity for Contact {}
+
+    impl Reference for Contact {
+        fn get_reference(&self) -> ReferenceStruct {
+            ReferenceStruct::new(
+                "contacts",
+                self.contactid,
+            )
+        }
+    }
+    ```
+    */
+    pub fn create_with_headers(
+        &mut self,
+        entity: &impl WriteEntity,
+        headers: &[(&str, &str)],
+    ) -> Result<()> {
+        let reference = entity.get_reference();
+        let entity = self.serialize_entity(WriteOperation::Create, &reference, entity)?;
+
+        self.write_request(
+            "POST",
+            format_args!("{}", reference.entity_name),
+            &[("Content-Type", "application/json;type=entry")],
+            headers,
+            Some(&entity),
+        )
+    }
+
+    /**
+    Adds a Create Request with a pre-serialized JSON payload for the table
+    (entity set name) to this batch
+
+    The payload is passed through as it is, so it is neither validated nor
+    modified by the write hook of this batch
+
+    # Examples
+    ```rust
+    use powerplatform_dataverse_service_client::{batch::Batch, result::Result};
+
+    fn test() -> Result<()> {
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+        batch.create_raw("contacts", r#"{"firstname":"Testy","lastname":"McTestface"}"#)?;
+        Ok(())
+    }
+    ```
+    */
+    pub fn create_raw(&mut self, entity_set_name: &str, json: &str) -> Result<()> {
+        self.write_request(
+            "POST",
+            format_args!("{}", entity_set_name),
+            &[("Content-Type", "application/json;type=entry")],
+            &[],
+            Some(json),
+        )
+    }
+
+    /**
+    Adds a Create Request for the given entity to this batch that asks dataverse
+    to return the created record
+
+    The returned record contains the columns selected by `R` including values
+    calculated by the server. It can be extracted from the response of
+    `Client::execute(...)` with the returned `ContentId`
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn create_returning<R: ReadEntity>(
+        &mut self,
+        entity: &impl WriteEntity,
+    ) -> Result<ContentId<R>> {
+        let reference = entity.get_reference();
+        let entity = self.serialize_entity(WriteOperation::Create, &reference, entity)?;
+        let content_id = ContentId::new(self.next_content_id);
+
+        self.write_request(
+            "POST",
+            format_args!("{}?$select={}", reference.entity_name, R::get_columns().join(",")),
+            &[
+                ("Content-Type", "application/json;type=entry"),
+                ("Prefer", "return=representation"),
+            ],
+            &[],
+            Some(&entity),
+        )?;
+
+        Ok(content_id)
+    }
+
+    /**
+    Adds an Update Request for the given entity to this batch
+
+    Please note that this function can fail if a serde serialization error occurs
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Serialize;
+    use powerplatform_dataverse_service_client::{
+        batch::Batch,
+        client::Client,
+        entity::WriteEntity,
+        reference::{Reference, ReferenceStruct},
+        result::{Result, IntoDataverseResult}
+    };
+
+    async fn test() -> Result<()> {
+        let testy_contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+            firstname: String::from("Testy"),
+            lastname: String::from("McTestface"),
+        };
+
+        let marianne_contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?,
+            firstname: String::from("Marianne"),
+            lastname: String::from("McTestface"),
+        };
+
+        // this batch creates both contacts in one call
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+        batch.update(&testy_contact)?;
+        batch.update(&marianne_contact)?;
+
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        client.execute(&batch).await?;
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+        lastname: String,
+    }
+
+    impl WriteEntity for Contact {}
+
+    impl Reference for Contact {
+        fn get_reference(&self) -> ReferenceStruct {
+            ReferenceStruct::new(
+                "contacts",
+                self.contactid,
+            )
+        }
+    }
+    ```
+    */
+    pub fn update(&mut self, entity: &impl WriteEntity) -> Result<()> {
+        self.update_with_headers(entity, &[])
+    }
+
+    /**
+    Adds an Update Request for the given entity to this batch with additional
+    headers for this request only
+
+    The given headers are written after the default headers of this batch
+    */
+    pub fn update_with_headers(
+        &mut self,
+        entity: &impl WriteEntity,
+        headers: &[(&str, &str)],
+    ) -> Result<()> {
+        let reference = entity.get_reference();
+        let entity = self.serialize_entity(WriteOperation::Update, &reference, entity)?;
+
+        self.write_request(
+            "PATCH",
+            format_args!("{}({})", reference.entity_name, reference.entity_id),
+            &[
+                ("Content-Type", "application/json;type=entry"),
+                ("If-Match", "*"),
+            ],
+            headers,
+            Some(&entity),
+        )
+    }
+
+    /**
+    Adds an Update Request with a pre-serialized JSON payload for the referenced
+    record to this batch
+
+    The payload is passed through as it is, so it is neither validated nor
+    modified by the write hook of this batch
+    */
+    pub fn update_raw(&mut self, reference: &impl Reference, json: &str) -> Result<()> {
+        let reference = reference.get_reference();
+
+        self.write_request(
+            "PATCH",
+            format_args!("{}({})", reference.entity_name, reference.entity_id),
+            &[
+                ("Content-Type", "application/json;type=entry"),
+                ("If-Match", "*"),
+            ],
+            &[],
+            Some(json),
+        )
+    }
+
+    /**
+    Adds an Update Request for the given entity to this batch that asks dataverse
+    to return the updated record
+
+    The returned record contains the columns selected by `R`. It can be extracted
+    from the response of `Client::execute(...)` with the returned `ContentId`
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn update_returning<R: ReadEntity>(
+        &mut self,
+        entity: &impl WriteEntity,
+    ) -> Result<ContentId<R>> {
+        let reference = entity.get_reference();
+        let entity = self.serialize_entity(WriteOperation::Update, &reference, entity)?;
+        let content_id = ContentId::new(self.next_content_id);
+
+        self.write_request(
+            "PATCH",
+            format_args!(
+                "{}({})?$select={}",
+                reference.entity_name,
+                reference.entity_id,
+                R::get_columns().join(",")
+            ),
+            &[
+                ("Content-Type", "application/json;type=entry"),
+                ("If-Match", "*"),
+                ("Prefer", "return=representation"),
+            ],
+            &[],
+            Some(&entity),
+        )?;
+
+        Ok(content_id)
+    }
+
+    /**
+    Adds an Upsert Request for the given entity to this batch
+
+    Please note that this function can fail if a serde serialization error occurs
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Serialize;
+    use powerplatform_dataverse_service_client::{
+        batch::Batch,
+        client::Client,
+        entity::WriteEntity,
+        reference::{Reference, ReferenceStruct},
+        result::{Result, IntoDataverseResult}
+    };
+
+    async fn test() -> Result<()> {
+        let testy_contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+            firstname: String::from("Testy"),
+            lastname: String::from("McTestface"),
+        };
+
+        let marianne_contact = Contact {
+            contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?,
+            firstname: String::from("Marianne"),
+            lastname: String::from("McTestface"),
+        };
+
+        // this batch creates both contacts in one call
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+        batch.upsert(&testy_contact)?;
+        batch.upsert(&marianne_contact)?;
+
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        client.execute(&batch).await?;
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+        lastname: String,
+    }
+
+    impl WriteEntity for Contact {}
+
+    impl Reference for Contact {
+        fn get_reference(&self) -> ReferenceStruct {
+            ReferenceStruct::new(
+                "contacts",
+                self.contactid,
+            )
+        }
+    }
+    ```
+    */
+    pub fn upsert(&mut self, entity: &impl WriteEntity) -> Result<()> {
+        self.upsert_with_headers(entity, &[])
+    }
+
+    /**
+    Adds an Upsert Request for the given entity to this batch with additional
+    headers for this request only
+
+    The given headers are written after the default headers of this batch
+    */
+    pub fn upsert_with_headers(
+        &mut self,
+        entity: &impl WriteEntity,
+        headers: &[(&str, &str)],
+    ) -> Result<()> {
+        let reference = entity.get_reference();
+        let entity = self.serialize_entity(WriteOperation::Upsert, &reference, entity)?;
+
+        self.write_request(
+            "PATCH",
+            format_args!("{}({})", reference.entity_name, reference.entity_id),
+            &[("Content-Type", "application/json;type=entry")],
+            headers,
+            Some(&entity),
+        )
+    }
+
+    /**
+    Adds an Upsert Request for the given entity to this batch that asks dataverse
+    to return the created or updated record
+
+    The returned record contains the columns selected by `R`. It can be extracted
+    from the response of `Client::execute(...)` with the returned `ContentId`
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn upsert_returning<R: ReadEntity>(
+        &mut self,
+        entity: &impl WriteEntity,
+    ) -> Result<ContentId<R>> {
+        let reference = entity.get_reference();
+        let entity = self.serialize_entity(WriteOperation::Upsert, &reference, entity)?;
+        let content_id = ContentId::new(self.next_content_id);
+
+        self.write_request(
+            "PATCH",
+            format_args!(
+                "{}({})?$select={}",
+                reference.entity_name,
+                reference.entity_id,
+                R::get_columns().join(",")
+            ),
+            &[
+                ("Content-Type", "application/json;type=entry"),
+                ("Prefer", "return=representation"),
+            ],
+            &[],
+            Some(&entity),
+        )?;
+
+        Ok(content_id)
+    }
+
+    /**
+    Adds an Upsert Request for the given entity to this batch that addresses the
+    record by an alternate key instead of its primary key
+
+    The values of the key columns are taken from the serialized entity, so the
+    entity has to contain all of them. Dataverse creates the record if no record
+    with these key values exists and updates it otherwise
+
+    This can fail for any of these reasons
+    - A serde serialization error
+    - The entity doesn't serialize into a JSON object
+    - A key column is missing or has no string, number or boolean value
+    */
+    pub fn upsert_by_key(&mut self, entity: &impl WriteEntity, key_columns: &[&str]) -> Result<()> {
+        let reference = entity.get_reference();
+        let context = WriteContext {
+            operation: WriteOperation::Upsert,
+            entity_name: &reference.entity_name,
+        };
+        let value = to_hooked_value(self.write_hook.as_ref(), &context, entity)?;
+        let key = build_key_segment(&value, key_columns)?;
+        let entity = value.to_string();
+
+        self.write_request(
+            "PATCH",
+            format_args!("{}({})", reference.entity_name, key),
+            &[("Content-Type", "application/json;type=entry")],
+            &[],
+            Some(&entity),
+        )
+    }
+
+    /**
+    Adds a Delete Request for the given entity reference to this batch
+
+    Please note that this function can fail if a serde serialization error occurs
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Serialize;
+    use powerplatform_dataverse_service_client::{
+        batch::Batch,
+        client::Client,
+        entity::WriteEntity,
+        reference::{Reference, ReferenceStruct},
+        result::{Result, IntoDataverseResult}
+    };
+
+    async fn test() -> Result<()> {
+        let testy_contact = ReferenceStruct::new(
+            "contacts",
+            Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+        );
+
+        let marianne_contact = ReferenceStruct::new(
+            "contacts",
+            Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?
+        );
+
+        // this batch creates both contacts in one call
+        let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+        batch.delete(&testy_contact)?;
+        batch.delete(&marianne_contact)?;
+
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        client.execute(&batch).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub fn delete(&mut self, entity: &impl Reference) -> Result<()> {
+        self.delete_with_headers(entity, &[])
+    }
+
+    /**
+    Adds a Delete Request for the given entity reference to this batch with
+    additional headers for this request only
+
+    The given headers are written after the default headers of this batch
+    */
+    pub fn delete_with_headers(
+        &mut self,
+        entity: &impl Reference,
+        headers: &[(&str, &str)],
+    ) -> Result<()> {
+        let reference = entity.get_reference();
+
+        self.write_request(
+            "DELETE",
+            format_args!("{}({})", reference.entity_name, reference.entity_id),
+            &[],
+            headers,
+            None,
+        )
+    }
+
+    /**
+    Adds a Request for the unbound action (or bound action path) with the given parameters
+    to this batch
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn action(&mut self, action: &str, parameters: &impl Serialize) -> Result<()> {
+        let parameters = serde_json::to_string(parameters).into_dataverse_result()?;
+
+        self.write_request(
+            "POST",
+            format_args!("{}", action),
+            &[("Content-Type", "application/json")],
+            &[],
+            Some(&parameters),
+        )
+    }
+
+    /**
+    Applies the given hook to the payloads of all creates, updates and upserts
+    added to this batch afterwards
+
+    see the `hook` module for details
+    */
+    pub fn set_write_hook(&mut self, hook: WriteHook) {
+        self.write_hook = Some(hook);
+    }
+
+    /// applies the defaults of a client to all creates added to this batch afterwards
+    pub(crate) fn set_create_defaults(&mut self, defaults: Arc<CreateDefaults>) {
+        self.create_defaults = Some(defaults);
+    }
+
+    fn serialize_entity(
+        &self,
+        operation: WriteOperation,
+        reference: &EntityReference,
+        entity: &impl WriteEntity,
+    ) -> Result<String> {
+        let context = WriteContext {
+            operation,
+            entity_name: &reference.entity_name,
+        };
+
+        let defaults = match (operation, self.create_defaults.as_ref()) {
+            (WriteOperation::Create, Some(defaults)) => defaults,
+            _ => return to_hooked_string(self.write_hook.as_ref(), &context, entity),
+        };
+
+        let mut value = to_hooked_value(self.write_hook.as_ref(), &context, entity)?;
+
+        if let Some(payload) = value.as_object_mut() {
+            defaults.apply(&reference.entity_name, payload)?;
+        }
+
+        Ok(value.to_string())
+    }
+
+    fn write_request(
+        &mut self,
+        method: &str,
+        path: Arguments<'_>,
+        request_headers: &[(&str, &str)],
+        item_headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> Result<()> {
+        if self.next_content_id == 1 {
+            let head = self.get_head();
+            self.payload.push_str(&head);
+        }
+
+        write!(
+            self.payload,
+            "--changeset_{}\r\nContent-Type: application/http\r\nContent-Transfer-Encoding:binary\r\nContent-Id: {}\r\n\r\n{} {}api/data/v{}/{} HTTP/1.1\r\n",
+            self.dataset_id.as_simple(),
+            self.next_content_id,
+            method,
+            self.url,
+            VERSION,
+            path,
+        ).into_dataverse_result()?;
+
+        let default_headers = self
+            .default_headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+
+        for (name, value) in request_headers
+            .iter()
+            .copied()
+            .chain(default_headers)
+            .chain(item_headers.iter().copied())
+        {
+            write!(self.payload, "{}: {}\r\n", name, value).into_dataverse_result()?;
+        }
+
+        match body {
+            Some(body) => write!(self.payload, "\r\n{}\r\n", body),
+            None => write!(self.payload, "\r\n"),
+        }
+        .into_dataverse_result()?;
+
+        if let Some(spool) = self.spool.as_mut() {
+            if self.payload.len() >= SPOOL_THRESHOLD {
+                spool.append(&self.payload)?;
+                self.payload.clear();
+            }
+        }
+
+        self.next_content_id += 1;
+        Ok(())
+    }
+
+    /**
+    Consumes the batch and returns its complete body
+
+    The payload is built incrementally while requests are added, so the body of an
+    in-memory batch reuses its buffer without copying or formatting it again.
+    Please note that this reads the whole spool file into memory for spooled batches
+    */
+    pub fn into_body(mut self) -> Result<Bytes> {
+        if self.next_content_id == 1 {
+            let head = self.get_head();
+            self.payload.push_str(&head);
+        }
+
+        let mut body = match self.spool.as_ref() {
+            Some(spool) => {
+                let mut body = spool.read_to_string()?;
+                body.push_str(&self.payload);
+                body
+            }
+            None => std::mem::take(&mut self.payload),
+        };
+
+        body.push_str(&self.get_tail());
+        Ok(Bytes::from(body))
+    }
+
+    /// passes the requests of this batch to the auditor, the spool file is read in chunks
+    pub(crate) async fn audit(&self, auditor: &mut BatchAuditor) -> Result<()> {
+        if let Some(spool_path) = self.get_spool_path() {
+            let file = tokio::fs::File::open(spool_path).await.into_dataverse_result()?;
+            let mut chunks = ReaderStream::new(file);
+
+            while let Some(chunk) = chunks.try_next().await.into_dataverse_result()? {
+                auditor.push(&chunk);
+            }
+        }
+
+        auditor.push(self.payload.as_bytes());
+        auditor.push(self.get_tail().as_bytes());
+        Ok(())
+    }
+
+    /**
+    Creates the http body for this batch
+
+    The payload of an in-memory batch is copied once into the body, the payload of
+    a spooled batch is streamed from its spool file
+    */
+    pub(crate) async fn to_body(&self) -> Result<Body> {
+        let tail = self.get_tail();
+        let head = match self.next_content_id {
+            1 => self.get_head(),
+            _ => String::new(),
+        };
+
+        let spool_path = match self.get_spool_path() {
+            Some(path) => path,
+            None => {
+                let mut body = Vec::with_capacity(head.len() + self.payload.len() + tail.len());
+                body.extend_from_slice(head.as_bytes());
+                body.extend_from_slice(self.payload.as_bytes());
+                body.extend_from_slice(tail.as_bytes());
+                return Ok(Body::from(body));
+            }
+        };
+
+        let file = tokio::fs::File::open(spool_path).await.into_dataverse_result()?;
+        let head = Bytes::from(head);
+        let tail = Bytes::from(format!("{}{}", self.payload, tail));
+
+        let body = stream::once(async { Ok(head) })
+            .chain(ReaderStream::new(file))
+            .chain(stream::once(async { Ok::<_, std::io::Error>(tail) }));
+
+        Ok(Body::wrap_stream(body))
+    }
+
+    fn get_head(&self) -> String {
+        format!(
+            "--batch_{}\r\nContent-Type: multipart/mixed; boundary=changeset_{}\r\n\r\n",
+            self.batch_id.as_simple(),
+            self.dataset_id.as_simple(),
+        )
+    }
+
+    fn get_tail(&self) -> String {
+        format!(
+            "--changeset_{}--\r\n--batch_{}--\r\n",
+            self.dataset_id.as_simple(),
+            self.batch_id.as_simple(),
+        )
+    }
+}
+
+/// builds the alternate key segment (e.g. `accountnumber='A-1',region=3`) from the key columns of an entity
+fn build_key_segment(entity: &Value, key_columns: &[&str]) -> Result<String> {
+    if key_columns.is_empty() {
+        return Err(DataverseError::new(String::from("an alternate key needs at least one column")));
+    }
+
+    let mut segment = String::new();
+
+    for (index, column) in key_columns.iter().enumerate() {
+        let literal = match entity.get(column) {
+            Some(Value::String(value)) => format!("'{}'", encode_key_value(&value.replace('\'', "''"))),
+            Some(Value::Number(value)) => value.to_string(),
+            Some(Value::Bool(value)) => value.to_string(),
+            _ => {
+                return Err(DataverseError::new(format!(
+                    "alternate key column {} is missing or has no string, number or boolean value",
+                    column
+                )))
+            }
+        };
+
+        if index > 0 {
+            segment.push(',');
+        }
+
+        write!(segment, "{}={}", column, literal).into_dataverse_result()?;
+    }
+
+    Ok(segment)
+}
+
+/// percent-encodes all characters of a key value that are not allowed in a request line
+fn encode_key_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'\'' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/**
+Formats the whole batch body
+
+Please note that this reads the whole spool file into memory for spooled batches
+*/
+impl Display for Batch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.next_content_id == 1 {
+            f.write_str(&self.get_head())?;
+        }
+
+        if let Some(spool) = self.spool.as_ref() {
+            let spooled = spool.read_to_string().map_err(|_| std::fmt::Error)?;
+            f.write_str(&spooled)?;
+        }
+
+        f.write_str(&self.payload)?;
+        f.write_str(&self.get_tail())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::{batch::Batch, reference::ReferenceStruct, update::RecordChanges};
+
+    use super::build_key_segment;
+
+    #[test]
+    fn delete_request() {
+        let mut batch = Batch::new("https://instance/");
+        batch.delete(&ReferenceStruct::new("contacts", Uuid::nil())).unwrap();
+        assert_eq!(
+            batch.payload,
+            format!(
+                "{}--changeset_{}\r\nContent-Type: application/http\r\nContent-Transfer-Encoding:binary\r\nContent-Id: 1\r\n\r\nDELETE https://instance/api/data/v9.2/contacts({}) HTTP/1.1\r\n\r\n",
+                batch.get_head(),
+                batch.get_dataset_id().as_simple(),
+                Uuid::nil()
+            )
+        );
+    }
+
+    #[test]
+    fn action_request() {
+        let mut batch = Batch::new("https://instance/");
+        batch.action("WinOpportunity", &serde_json::json!({ "Status": 3 })).unwrap();
+        assert!(batch.payload.ends_with(
+            "POST https://instance/api/data/v9.2/WinOpportunity HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{\"Status\":3}\r\n"
+        ));
+    }
+
+    #[test]
+    fn raw_requests() {
+        let mut batch = Batch::new("https://instance/");
+        batch.set_write_hook(std::sync::Arc::new(|_, payload| {
+            payload.insert(String::from("hooked"), serde_json::Value::Bool(true));
+        }));
+        batch.create_raw("contacts", r#"{"firstname":"Testy"}"#).unwrap();
+        assert!(batch.payload.ends_with(
+            "POST https://instance/api/data/v9.2/contacts HTTP/1.1\r\nContent-Type: application/json;type=entry\r\n\r\n{\"firstname\":\"Testy\"}\r\n"
+        ));
+
+        batch
+            .update_raw(&ReferenceStruct::new("contacts", Uuid::nil()), r#"{"lastname":"McTestface"}"#)
+            .unwrap();
+        assert!(batch.payload.ends_with(&format!(
+            "PATCH https://instance/api/data/v9.2/contacts({}) HTTP/1.1\r\nContent-Type: application/json;type=entry\r\nIf-Match: *\r\n\r\n{{\"lastname\":\"McTestface\"}}\r\n",
+            Uuid::nil()
+        )));
+        assert_eq!(batch.get_count(), 2);
+    }
+
+    #[test]
+    fn write_hook_applies_to_creates() {
+        let mut batch = Batch::new("https://instance/");
+        batch.set_write_hook(std::sync::Arc::new(|context, payload| {
+            payload.insert(String::from("entity"), serde_json::Value::from(context.entity_name));
+        }));
+        batch
+            .create(&RecordChanges::new(ReferenceStruct::new("contacts", Uuid::nil())).set("firstname", "Testy"))
+            .unwrap();
+        assert!(batch.payload.ends_with("{\"entity\":\"contacts\",\"firstname\":\"Testy\"}\r\n"));
+    }
+
+    #[test]
+    fn default_and_item_headers() {
+        let mut batch = Batch::new("https://instance/");
+        batch.add_default_header("MSCRM.BypassCustomPluginExecution", "true");
+        batch
+            .delete_with_headers(
+                &ReferenceStruct::new("contacts", Uuid::nil()),
+                &[("CallerObjectId", "abc")],
+            )
+            .unwrap();
+        assert!(batch.payload.ends_with(
+            "HTTP/1.1\r\nMSCRM.BypassCustomPluginExecution: true\r\nCallerObjectId: abc\r\n\r\n"
+        ));
+    }
+
+    #[test]
+    fn reset_keeps_default_headers() {
+        let mut batch = Batch::new("https://instance/");
+        batch.add_default_header("Prefer", "return=representation");
+        batch.reset().unwrap();
+        assert_eq!(batch.get_default_headers().len(), 1);
+        assert_eq!(batch.get_count(), 0);
+    }
+
+    #[test]
+    fn spooled_batch_matches_memory_batch() {
+        let mut memory = Batch::new("https://instance/");
+        let mut spooled = Batch::spooled("https://instance/").unwrap();
+        spooled.batch_id = memory.batch_id;
+        spooled.dataset_id = memory.dataset_id;
+
+        for _ in 0..1000 {
+            let reference = ReferenceStruct::new("contacts", Uuid::new_v4());
+            memory.delete(&reference).unwrap();
+            spooled.delete(&reference).unwrap();
+        }
+
+        let spool_path = spooled.get_spool_path().unwrap().to_path_buf();
+        assert!(spooled.payload.len() < memory.payload.len());
+        assert_eq!(spooled.to_string(), memory.to_string());
+
+        drop(spooled);
+        assert!(!spool_path.exists());
+    }
+
+    #[test]
+    fn body_matches_display() {
+        let empty = Batch::new("https://instance/");
+        assert_eq!(empty.to_string().into_bytes(), empty.into_body().unwrap());
+
+        let mut batch = Batch::new("https://instance/");
+        batch.delete(&ReferenceStruct::new("contacts", Uuid::nil())).unwrap();
+        assert_eq!(batch.to_string().into_bytes(), batch.into_body().unwrap());
+    }
+
+    #[test]
+    fn uses_crlf_line_endings() {
+        let mut batch = Batch::new("https://instance/");
+        batch.add_default_header("Prefer", "return=representation");
+        batch.action("WinOpportunity", &serde_json::json!({ "Status": 3 })).unwrap();
+        batch.delete(&ReferenceStruct::new("contacts", Uuid::nil())).unwrap();
+
+        let body = batch.to_string();
+        assert!(body.ends_with("--\r\n"));
+        assert_eq!(body.matches('\n').count(), body.matches("\r\n").count());
+    }
+
+    #[test]
+    fn alternate_key_segment() {
+        let entity = json!({ "accountnumber": "O'Neil & Sons", "region": 3, "name": "Contoso" });
+        assert_eq!(
+            build_key_segment(&entity, &["accountnumber", "region"]).unwrap(),
+            "accountnumber='O''Neil%20%26%20Sons',region=3"
+        );
+        assert!(build_key_segment(&entity, &["missing"]).is_err());
+        assert!(build_key_segment(&entity, &[]).is_err());
+    }
+}
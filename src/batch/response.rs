@@ -0,0 +1,408 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    error::{DataverseError, ErrorKind, ServiceError},
+    result::{IntoDataverseResult, Result},
+};
+
+/**
+A typed handle to a request inside a `Batch`
+
+It is returned by the `*_returning(...)` functions of `Batch` and can be used
+to extract the deserialized entity from the `BatchResponse` after the batch
+was executed
+*/
+#[derive(Debug)]
+pub struct ContentId<E> {
+    id: u16,
+    entity_type: PhantomData<fn() -> E>,
+}
+
+impl<E> ContentId<E> {
+    pub(crate) fn new(id: u16) -> Self {
+        Self {
+            id,
+            entity_type: PhantomData,
+        }
+    }
+
+    /// returns the Content-Id of the request inside its batch
+    pub fn get_id(&self) -> u16 {
+        self.id
+    }
+}
+
+impl<E> Clone for ContentId<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for ContentId<E> {}
+
+/**
+The parsed response of an executed `Batch`
+
+Each request in the batch results in one `BatchResponseItem`. Responses of
+requests that were added with one of the `*_returning(...)` functions can be
+deserialized with `get_entity(...)`
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use powerplatform_dataverse_service_client::{
+    batch::Batch,
+    client::Client,
+    entity::{ReadEntity, WriteEntity},
+    reference::{Reference, ReferenceStruct},
+    result::{IntoDataverseResult, Result},
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let contact = Contact {
+        contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+        firstname: String::from("Testy"),
+        lastname: String::from("McTestface"),
+    };
+
+    let mut batch = Batch::new("https://instance.crm.dynamics.com/");
+    let created = batch.create_returning::<CreatedContact>(&contact)?;
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let response = client.execute(&batch).await?;
+    let created_contact = response.get_entity(created)?;
+    println!("created on {}", created_contact.createdon);
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+    lastname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+
+#[derive(Deserialize)]
+struct CreatedContact {
+    contactid: Uuid,
+    createdon: String,
+}
+
+impl ReadEntity for CreatedContact {}
+
+impl Select for CreatedContact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "createdon"]
+    }
+}
+```
+*/
+#[derive(Clone, Debug, Default)]
+pub struct BatchResponse {
+    pub items: Vec<BatchResponseItem>,
+}
+
+/**
+The request that failed inside a batch
+
+Dataverse stops the execution of a changeset at the first failed request and rolls
+back all requests of the changeset that were executed before it
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchFailure {
+    /// Content-Id of the failed request
+    pub content_id: Option<u16>,
+    /// index of the failed request in the batch, derived from its Content-Id
+    pub index: Option<usize>,
+    pub status: u16,
+    /// the parsed error details if the server returned them
+    pub error: Option<ServiceError>,
+    pub body: String,
+}
+
+impl BatchFailure {
+    /// returns the error message of the server or the raw body if it couldn't be parsed
+    pub fn get_message(&self) -> &str {
+        self.error
+            .as_ref()
+            .map(|error| error.message.as_str())
+            .unwrap_or(&self.body)
+    }
+
+    /// converts this failure into an error of the kind `ErrorKind::BatchItemFailed`
+    pub fn into_error(self) -> DataverseError {
+        let request = match self.index {
+            Some(index) => format!("request {} (Content-Id {})", index, index + 1),
+            None => String::from("a request"),
+        };
+
+        DataverseError::with_kind(
+            ErrorKind::BatchItemFailed {
+                content_id: self.content_id,
+                status: self.status,
+            },
+            format!(
+                "{} of the batch failed with status {}: {}",
+                request,
+                self.status,
+                self.get_message()
+            ),
+        )
+    }
+}
+
+/// The response to a single request inside a batch
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchResponseItem {
+    pub content_id: Option<u16>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl BatchResponse {
+    /**
+    Parses the multipart body of a batch response
+
+    The boundary is taken from the `Content-Type` header of the response
+    */
+    pub fn parse(content_type: &str, body: &str) -> Result<Self> {
+        let boundary = get_boundary(content_type).ok_or_else(|| {
+            DataverseError::new(format!(
+                "batch response has no multipart boundary: {}",
+                content_type
+            ))
+        })?;
+
+        let body = body.replace("\r\n", "\n");
+        let mut items = Vec::new();
+        parse_multipart(boundary, &body, &mut items)?;
+        Ok(Self { items })
+    }
+
+    /// returns the response item of the request with the given Content-Id
+    pub fn get(&self, content_id: u16) -> Option<&BatchResponseItem> {
+        self.items
+            .iter()
+            .find(|item| item.content_id == Some(content_id))
+    }
+
+    /**
+    returns the first failed request of this batch with its parsed error
+
+    The index of the failed request refers to the order in which the requests were
+    added to the `Batch`, so the offending record can be fixed or excluded on retry
+    */
+    pub fn get_failure(&self) -> Option<BatchFailure> {
+        self.items
+            .iter()
+            .find(|item| !item.is_success())
+            .map(|item| BatchFailure {
+                content_id: item.content_id,
+                index: item
+                    .content_id
+                    .and_then(|content_id| (content_id as usize).checked_sub(1)),
+                status: item.status,
+                error: item.get_error(),
+                body: item.body.clone(),
+            })
+    }
+
+    /**
+    Deserializes the entity that was returned for the request behind the given handle
+
+    This fails if there is no response for the request, the request did not
+    succeed or the response body cannot be deserialized into `E`
+    */
+    pub fn get_entity<E: DeserializeOwned>(&self, content_id: ContentId<E>) -> Result<E> {
+        let item = self.get(content_id.get_id()).ok_or_else(|| {
+            DataverseError::new(format!(
+                "batch response contains no item for Content-Id {}",
+                content_id.get_id()
+            ))
+        })?;
+
+        if !item.is_success() {
+            return Err(DataverseError::new(item.body.clone()));
+        }
+
+        serde_json::from_str(&item.body).into_dataverse_result()
+    }
+}
+
+impl BatchResponseItem {
+    /// Indicates if the request was successful
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// returns the parsed error details of a failed request
+    pub fn get_error(&self) -> Option<ServiceError> {
+        match self.is_success() {
+            true => None,
+            false => ServiceError::parse(&self.body),
+        }
+    }
+
+    /// returns the value of the header with the given name (case-insensitive)
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn get_boundary(content_type: &str) -> Option<&str> {
+    content_type
+        .split(';')
+        .map(str::trim)
+        .find_map(|parameter| parameter.strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+}
+
+fn parse_multipart(boundary: &str, body: &str, items: &mut Vec<BatchResponseItem>) -> Result<()> {
+    let delimiter = format!("--{}", boundary);
+
+    for part in body.split(delimiter.as_str()).skip(1) {
+        if part.starts_with("--") {
+            break;
+        }
+
+        let part = part.trim_start_matches('\n');
+        let (part_headers, content) = split_head(part);
+        let part_headers = parse_headers(part_headers);
+        let content_type = find_header(&part_headers, "Content-Type").unwrap_or_default();
+
+        if content_type.starts_with("multipart/mixed") {
+            let inner_boundary = get_boundary(content_type).ok_or_else(|| {
+                DataverseError::new(format!(
+                    "batch response part has no multipart boundary: {}",
+                    content_type
+                ))
+            })?;
+            parse_multipart(inner_boundary, content, items)?;
+        } else {
+            let content_id = find_header(&part_headers, "Content-ID")
+                .and_then(|value| value.parse::<u16>().ok());
+            items.push(parse_http_response(content_id, content)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_http_response(content_id: Option<u16>, content: &str) -> Result<BatchResponseItem> {
+    let (head, body) = split_head(content);
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| {
+            DataverseError::new(format!(
+                "batch response part has an invalid status line: {}",
+                status_line
+            ))
+        })?;
+
+    Ok(BatchResponseItem {
+        content_id,
+        status,
+        headers: parse_headers(lines.collect::<Vec<_>>().join("\n").as_str())
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        body: body.trim_end_matches('\n').to_string(),
+    })
+}
+
+fn split_head(content: &str) -> (&str, &str) {
+    content.split_once("\n\n").unwrap_or((content, ""))
+}
+
+fn parse_headers(head: &str) -> Vec<(&str, &str)> {
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect()
+}
+
+fn find_header<'a>(headers: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::{BatchResponse, ContentId};
+
+    static RESPONSE: &str = "--batchresponse_1\r\nContent-Type: multipart/mixed; boundary=changesetresponse_2\r\n\r\n--changesetresponse_2\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: 1\r\n\r\nHTTP/1.1 201 Created\r\nContent-Type: application/json; odata.metadata=minimal\r\nOData-Version: 4.0\r\n\r\n{\"name\":\"Testy\",\"rank\":3}\r\n--changesetresponse_2\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: 2\r\n\r\nHTTP/1.1 204 No Content\r\nOData-Version: 4.0\r\n\r\n\r\n--changesetresponse_2--\r\n--batchresponse_1--\r\n";
+
+    #[derive(Deserialize)]
+    struct Testy {
+        name: String,
+        rank: i32,
+    }
+
+    #[test]
+    fn parse_changeset_response() {
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", RESPONSE).unwrap();
+        assert_eq!(response.items.len(), 2);
+
+        let created = response.get(1).unwrap();
+        assert_eq!(created.status, 201);
+        assert_eq!(created.get_header("odata-version"), Some("4.0"));
+
+        let updated = response.get(2).unwrap();
+        assert_eq!(updated.status, 204);
+        assert_eq!(updated.body, "");
+    }
+
+    #[test]
+    fn deserialize_returned_entity() {
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", RESPONSE).unwrap();
+        let testy: Testy = response.get_entity(ContentId::new(1)).unwrap();
+        assert_eq!(testy.name, "Testy");
+        assert_eq!(testy.rank, 3);
+        assert!(response.get_entity::<Testy>(ContentId::new(3)).is_err());
+    }
+
+    #[test]
+    fn failure_names_request() {
+        let content = "--batchresponse_1\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: 3\r\n\r\nHTTP/1.1 412 Precondition Failed\r\n\r\n{\"error\":{\"code\":\"0x80060882\",\"message\":\"The version of the existing record doesn't match.\"}}\r\n--batchresponse_1--\r\n";
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", content).unwrap();
+
+        let failure = response.get_failure().unwrap();
+        assert_eq!(failure.index, Some(2));
+        assert_eq!(failure.error.as_ref().unwrap().code, "0x80060882");
+        assert_eq!(
+            failure.into_error().message,
+            "request 2 (Content-Id 3) of the batch failed with status 412: The version of the existing record doesn't match."
+        );
+
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", RESPONSE).unwrap();
+        assert!(response.get_failure().is_none());
+    }
+}
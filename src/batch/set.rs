@@ -0,0 +1,276 @@
+/*!
+Module for logical batches with any number of operations
+
+Dataverse accepts at most 1000 requests per batch and cancels batches that run
+longer than two minutes. A `BatchSet` accepts any number of operations and splits
+them into batches of a configurable size (and optionally payload size) while they
+are added. `Client::execute_batch_set(...)` executes these batches sequentially or
+in parallel like the bulk functions and aggregates their results
+
+Each batch is its own changeset, so a failing operation only rolls back the
+operations of its batch
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    bulk::BulkOptions,
+    client::Client,
+    reference::ReferenceStruct,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let mut set = client.new_batch_set().with_batch_size(200);
+
+    for _ in 0..5000 {
+        set.delete(&ReferenceStruct::new("contacts", Uuid::new_v4()))?;
+    }
+
+    assert_eq!(set.get_batch_count(), 25);
+
+    let result = client.execute_batch_set(set, &BulkOptions::new().concurrency(1)).await?;
+
+    for failure in result.failures.iter() {
+        println!("operation {} failed: {}", failure.index, failure.message);
+    }
+    Ok(())
+}
+```
+*/
+
+use std::{borrow::Cow, sync::Arc};
+
+use serde::Serialize;
+
+use crate::{
+    auth::Authenticate,
+    bulk::{BulkOptions, BulkResult, DEFAULT_BATCH_SIZE, MAX_BATCH_SIZE},
+    client::Client,
+    entity::WriteEntity,
+    hook::WriteHook,
+    reference::Reference,
+    result::Result,
+};
+
+use super::{defaults::CreateDefaults, Batch};
+
+/// A logical batch that is split into batches the server accepts
+pub struct BatchSet {
+    url: Cow<'static, str>,
+    batch_size: u16,
+    max_payload_size: Option<usize>,
+    default_headers: Vec<(String, String)>,
+    write_hook: Option<WriteHook>,
+    create_defaults: Option<Arc<CreateDefaults>>,
+    batches: Vec<Batch>,
+}
+
+impl BatchSet {
+    /// creates an empty set that splits its operations into batches of 50 operations
+    pub fn new(url: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            url: url.into(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_payload_size: None,
+            default_headers: Vec::new(),
+            write_hook: None,
+            create_defaults: None,
+            batches: Vec::new(),
+        }
+    }
+
+    /**
+    sets the number of operations per batch (between 1 and 1000)
+
+    Batches of complex tables (many plugins or workflows) need to be smaller to
+    finish within the execution time limit of two minutes
+    */
+    pub fn with_batch_size(mut self, batch_size: u16) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_BATCH_SIZE);
+        self
+    }
+
+    /// starts a new batch once the payload of the current batch reaches the given size in bytes
+    pub fn with_max_payload_size(mut self, max_payload_size: usize) -> Self {
+        self.max_payload_size = Some(max_payload_size);
+        self
+    }
+
+    /// adds a header that is attached to every operation added to this set afterwards
+    pub fn add_default_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.default_headers.push((name.into(), value.into()));
+    }
+
+    /// applies the given hook to all creates, updates and upserts added to this set afterwards
+    pub fn set_write_hook(&mut self, hook: WriteHook) {
+        self.write_hook = Some(hook);
+    }
+
+    /// applies the defaults of a client to all creates added to this set afterwards
+    pub(crate) fn set_create_defaults(&mut self, defaults: Arc<CreateDefaults>) {
+        self.create_defaults = Some(defaults);
+    }
+
+    /// returns the number of operations in this set
+    pub fn get_count(&self) -> usize {
+        self.batches.iter().map(|batch| batch.get_count() as usize).sum()
+    }
+
+    /// returns the number of batches the operations are split into
+    pub fn get_batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// returns the batches the operations are split into
+    pub fn into_batches(self) -> Vec<Batch> {
+        self.batches
+    }
+
+    /**
+    Adds a Create Request for the given entity to this set
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn create(&mut self, entity: &impl WriteEntity) -> Result<()> {
+        self.get_current_batch().create(entity)
+    }
+
+    /**
+    Adds an Update Request for the given entity to this set
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn update(&mut self, entity: &impl WriteEntity) -> Result<()> {
+        self.get_current_batch().update(entity)
+    }
+
+    /**
+    Adds an Upsert Request for the given entity to this set
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn upsert(&mut self, entity: &impl WriteEntity) -> Result<()> {
+        self.get_current_batch().upsert(entity)
+    }
+
+    /// Adds a Create Request with a pre-serialized JSON payload for the table (entity set name) to this set
+    pub fn create_raw(&mut self, entity_set_name: &str, json: &str) -> Result<()> {
+        self.get_current_batch().create_raw(entity_set_name, json)
+    }
+
+    /// Adds an Update Request with a pre-serialized JSON payload for the referenced record to this set
+    pub fn update_raw(&mut self, reference: &impl Reference, json: &str) -> Result<()> {
+        self.get_current_batch().update_raw(reference, json)
+    }
+
+    /// Adds a Delete Request for the given entity reference to this set
+    pub fn delete(&mut self, entity: &impl Reference) -> Result<()> {
+        self.get_current_batch().delete(entity)
+    }
+
+    /**
+    Adds a Request for the unbound action (or bound action path) with the given parameters
+    to this set
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn action(&mut self, action: &str, parameters: &impl Serialize) -> Result<()> {
+        self.get_current_batch().action(action, parameters)
+    }
+
+    /// returns the batch the next operation is added to and starts a new one if the current one is full
+    fn get_current_batch(&mut self) -> &mut Batch {
+        let is_full = match self.batches.last() {
+            Some(batch) => {
+                batch.get_count() >= self.batch_size
+                    || self
+                        .max_payload_size
+                        .is_some_and(|max_payload_size| batch.get_payload_size() >= max_payload_size)
+            }
+            None => true,
+        };
+
+        if is_full {
+            let mut batch = Batch::new(self.url.clone());
+
+            for (name, value) in self.default_headers.iter() {
+                batch.add_default_header(name.clone(), value.clone());
+            }
+
+            if let Some(hook) = self.write_hook.as_ref() {
+                batch.set_write_hook(Arc::clone(hook));
+            }
+
+            if let Some(defaults) = self.create_defaults.as_ref() {
+                batch.set_create_defaults(Arc::clone(defaults));
+            }
+
+            self.batches.push(batch);
+        }
+
+        // a batch was pushed above if there was none
+        self.batches.last_mut().unwrap()
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    executes all batches of the set and aggregates their results
+
+    The batches are executed like the batches of the bulk functions, so the concurrency
+    of the options applies and throttled batches are retried. The batch size of the
+    options is ignored in favor of the batch size of the set. Failed operations are
+    reported by their index in the set in the returned `BulkResult`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    */
+    pub async fn execute_batch_set(&self, set: BatchSet, options: &BulkOptions) -> Result<BulkResult> {
+        let count = set.get_count();
+        let mut start = 0;
+        // a batch stays empty if adding an operation to it failed
+        let batches = set.into_batches().into_iter().filter(|batch| batch.get_count() > 0).map(|batch| {
+            let chunk = start..start + batch.get_count() as usize;
+            start = chunk.end;
+            (chunk, Ok(batch), None)
+        });
+
+        self.execute_batches(count, options, batches).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::reference::ReferenceStruct;
+
+    use super::BatchSet;
+
+    #[test]
+    fn splits_into_batches() {
+        let mut set = BatchSet::new("https://instance/").with_batch_size(3);
+        set.add_default_header("MSCRM.BypassCustomPluginExecution", "true");
+
+        for _ in 0..7 {
+            set.delete(&ReferenceStruct::new("contacts", Uuid::new_v4())).unwrap();
+        }
+
+        assert_eq!(set.get_count(), 7);
+        let counts: Vec<u16> = set.into_batches().iter().map(|batch| batch.get_count()).collect();
+        assert_eq!(counts, vec![3, 3, 1]);
+    }
+
+    #[test]
+    fn splits_by_payload_size() {
+        let mut set = BatchSet::new("https://instance/").with_max_payload_size(1);
+
+        for _ in 0..2 {
+            set.action("WinOpportunity", &serde_json::json!({ "Status": 3 })).unwrap();
+        }
+
+        assert_eq!(set.get_batch_count(), 2);
+    }
+}
@@ -0,0 +1,52 @@
+use std::{
+    fs::{self, File},
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::result::{IntoDataverseResult, Result};
+
+/// Size of the in-memory buffer after which the payload is written to the spool file
+pub(crate) const SPOOL_THRESHOLD: usize = 64 * 1024;
+
+/**
+A file that holds the payload of a spooled `Batch`
+
+The file is truncated on reset and removed when the spool is dropped
+*/
+pub(crate) struct Spool {
+    path: PathBuf,
+    file: File,
+}
+
+impl Spool {
+    pub(crate) fn create(path: PathBuf) -> Result<Self> {
+        let file = File::create(&path).into_dataverse_result()?;
+        Ok(Self { path, file })
+    }
+
+    pub(crate) fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn append(&mut self, content: &str) -> Result<()> {
+        self.file
+            .write_all(content.as_bytes())
+            .into_dataverse_result()
+    }
+
+    pub(crate) fn truncate(&mut self) -> Result<()> {
+        self.file.set_len(0).into_dataverse_result()?;
+        self.file.rewind().into_dataverse_result()
+    }
+
+    pub(crate) fn read_to_string(&self) -> Result<String> {
+        fs::read_to_string(&self.path).into_dataverse_result()
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,153 @@
+/*!
+Module for using the client from synchronous code
+
+`BlockingClient` owns a `Client` and a single-threaded tokio runtime and blocks the
+calling thread until an operation completes. This suits command line tools and
+functions that don't run on an async runtime themselves. Operations without a blocking
+counterpart can be run with `BlockingClient::block_on(...)`
+
+Please note that the blocking functions must not be called from within an async
+runtime, as blocking its threads would stall the other tasks
+
+This is only available with the `blocking` feature
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    blocking::BlockingClient,
+    client::Client,
+    entity::ReadEntity,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result},
+    select::Select
+};
+
+fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let client = BlockingClient::new(client)?;
+
+    let contact: Contact = client.retrieve(&ReferenceStruct::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    ))?;
+    println!("retrieved {}", contact.firstname);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname"]
+    }
+}
+```
+*/
+
+use std::future::Future;
+
+use tokio::runtime::{Builder, Runtime};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::{Client, Page},
+    entity::{ReadEntity, WriteEntity},
+    query::Query,
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+/// A `Client` whose operations block the calling thread until they complete
+pub struct BlockingClient<'url, A: Authenticate> {
+    client: Client<'url, A>,
+    runtime: Runtime,
+}
+
+impl<'url, A: Authenticate> BlockingClient<'url, A> {
+    /**
+    wraps the client with a new single-threaded runtime
+
+    This may fail if the runtime can't be created
+    */
+    pub fn new(client: Client<'url, A>) -> Result<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .into_dataverse_result()?;
+
+        Ok(Self { client, runtime })
+    }
+
+    /// returns the wrapped client
+    pub fn get_client(&self) -> &Client<'url, A> {
+        &self.client
+    }
+
+    /// runs the future to completion on the runtime of this client, e.g. an operation of `get_client()`
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// see `Client::create(...)`
+    pub fn create(&self, entity: &impl WriteEntity) -> Result<Uuid> {
+        self.block_on(self.client.create(entity))
+    }
+
+    /// see `Client::retrieve(...)`
+    pub fn retrieve<E: ReadEntity>(&self, reference: &impl Reference) -> Result<E> {
+        self.block_on(self.client.retrieve(reference))
+    }
+
+    /// see `Client::retrieve_multiple(...)`
+    pub fn retrieve_multiple<E: ReadEntity>(&self, query: &Query) -> Result<Page<E>> {
+        self.block_on(self.client.retrieve_multiple(query))
+    }
+
+    /// see `Client::retrieve_all(...)`
+    pub fn retrieve_all<E: ReadEntity>(&self, query: &Query) -> Result<Vec<E>> {
+        self.block_on(self.client.retrieve_all(query))
+    }
+
+    /// see `Client::update(...)`
+    pub fn update(&self, entity: &impl WriteEntity) -> Result<()> {
+        self.block_on(self.client.update(entity))
+    }
+
+    /// see `Client::upsert(...)`
+    pub fn upsert(&self, entity: &impl WriteEntity) -> Result<()> {
+        self.block_on(self.client.upsert(entity))
+    }
+
+    /// see `Client::delete(...)`
+    pub fn delete(&self, reference: &impl Reference) -> Result<()> {
+        self.block_on(self.client.delete(reference))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{client::Client, reference::ReferenceStruct};
+
+    use super::BlockingClient;
+
+    #[test]
+    fn blocks_until_completion() {
+        let client = BlockingClient::new(Client::new_dummy()).unwrap();
+
+        assert_eq!(client.block_on(async { 42 }), 42);
+        assert!(client
+            .delete(&ReferenceStruct::new("contacts", Uuid::new_v4()))
+            .is_err());
+    }
+}
@@ -0,0 +1,801 @@
+/*!
+Module for writing large numbers of records in parallel batches
+
+The bulk functions of `Client` split the given records into batches of
+`batch_size` requests and execute up to `concurrency` batches at the same time.
+Each batch is a single changeset, so a failing request rolls back the other
+requests of its batch, but not those of other batches. A batch that fails in
+transport (e.g. because the connection dropped) is reported as failure of each of
+its records, only a failed authentication fails the whole bulk function
+
+Batches that are throttled by the service protection limits of dataverse are
+retried after the period the server asks for. With adaptive concurrency the
+number of parallel batches is lowered when requests are throttled or the
+remaining execution time of the current window runs low and is raised again
+while there is enough headroom
+
+The returned `BulkResult` contains the outcome of every record in the order of the
+given records, no matter in which order the parallel batches finished
+
+The progress is reported after each batch to the receiver configured with
+`Client::with_progress(...)`
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    bulk::BulkOptions,
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let contacts: Vec<Contact> = (0..10_000)
+        .map(|number| Contact {
+            contactid: Uuid::new_v4(),
+            lastname: format!("Contact {}", number),
+        })
+        .collect();
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let options = BulkOptions::new().batch_size(100).adaptive(1, 16);
+    let result = client.bulk_create(&contacts, &options).await?;
+
+    for failure in result.failures.iter() {
+        println!("contact {} failed: {}", failure.index, failure.message);
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    lastname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use reqwest::{Method, Response};
+
+use crate::{
+    auth::Authenticate,
+    batch::{
+        response::{BatchResponse, BatchResponseItem},
+        Batch,
+    },
+    client::Client,
+    entity::WriteEntity,
+    error::{DataverseError, ErrorKind, ServiceError},
+    metrics::RetryMetric,
+    progress::Operation,
+    rate_limit::RateLimit,
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+/// number of requests per batch that is used if no other size is configured
+pub static DEFAULT_BATCH_SIZE: u16 = 50;
+
+/// maximum number of requests dataverse accepts in a single batch
+pub static MAX_BATCH_SIZE: u16 = 1000;
+
+/// number of times a throttled batch is retried before it fails
+static MAX_THROTTLE_RETRIES: usize = 5;
+
+/// period to wait for a throttled batch if the server doesn't send a `Retry-After` header
+static DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// How many batches are executed at the same time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Concurrency {
+    /// Always executes the given number of batches at the same time
+    Fixed(usize),
+
+    /// Adjusts the number of batches between `min` and `max` based on the
+    /// service protection headers of the responses
+    Adaptive { min: usize, max: usize },
+}
+
+/// Options for the bulk functions of `Client`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulkOptions {
+    batch_size: u16,
+    concurrency: Concurrency,
+    create_key: Option<Vec<String>>,
+    time_boxed: bool,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            concurrency: Concurrency::Fixed(4),
+            create_key: None,
+            time_boxed: false,
+        }
+    }
+}
+
+impl BulkOptions {
+    /// creates options for batches of 50 requests with 4 batches at the same time
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the number of requests per batch (between 1 and 1000)
+    pub fn batch_size(mut self, batch_size: u16) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_BATCH_SIZE);
+        self
+    }
+
+    /// executes the given number of batches at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Concurrency::Fixed(concurrency.max(1));
+        self
+    }
+
+    /// adjusts the number of batches executed at the same time between `min` and `max`
+    pub fn adaptive(mut self, min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        self.concurrency = Concurrency::Adaptive {
+            min,
+            max: max.max(min),
+        };
+        self
+    }
+
+    /**
+    turns the creates of `Client::bulk_create(...)` into upserts by the alternate key
+    with the given columns
+
+    Records that were already created by a previous (failed) run are updated instead
+    of being created again, so a bulk load can simply be repeated. Please make sure
+    the records don't serialize a primary key that changes between runs
+    */
+    pub fn upsert_by_key(mut self, key_columns: &[&str]) -> Self {
+        self.create_key = Some(key_columns.iter().map(|column| column.to_string()).collect());
+        self
+    }
+
+    /**
+    sizes the batches with the `BatchPlanner` of the client, so they stay within its time budget
+
+    The batch size of these options is used for entities that were not measured yet,
+    see the `planner` module for details
+    */
+    pub fn time_boxed(mut self) -> Self {
+        self.time_boxed = true;
+        self
+    }
+
+    /// Indicates if the batches are sized by the `BatchPlanner` of the client
+    pub fn is_time_boxed(&self) -> bool {
+        self.time_boxed
+    }
+
+    /// returns the number of requests per batch
+    pub fn get_batch_size(&self) -> u16 {
+        self.batch_size
+    }
+
+    /// returns the configured concurrency
+    pub fn get_concurrency(&self) -> Concurrency {
+        self.concurrency
+    }
+
+    /// returns the alternate key columns creates are turned into upserts by
+    pub fn get_create_key(&self) -> Option<Vec<&str>> {
+        self.create_key
+            .as_ref()
+            .map(|columns| columns.iter().map(String::as_str).collect())
+    }
+}
+
+/// A record that could not be written by a bulk function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulkFailure {
+    /// index of the record in the given slice
+    pub index: usize,
+    /// http status of the failed request if the server responded
+    pub status: Option<u16>,
+    /// error code of Dataverse (e.g. `0x80040237`) if the server sent one
+    pub code: Option<String>,
+    pub message: String,
+}
+
+/**
+The outcome of a single record of a bulk function
+
+Dataverse doesn't report timings per request, so the duration is the one of the
+batch the record was written in, including the retries of throttled attempts
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulkItem {
+    /// index of the record in the given slice
+    pub index: usize,
+    /// http status of the request if the server responded
+    pub status: Option<u16>,
+    /// the error message if the record could not be written
+    pub error: Option<String>,
+    /// time it took to execute the batch of the record
+    pub duration: Duration,
+}
+
+impl BulkItem {
+    /// Indicates if the record was written successfully
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The outcome of a bulk function
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BulkResult {
+    /// number of records that were written successfully
+    pub succeeded: usize,
+    /// records that could not be written, ordered by their index
+    pub failures: Vec<BulkFailure>,
+    /**
+    the outcome of every record, ordered by their index
+
+    The order is stable regardless of the order in which the parallel batches finished,
+    so the items line up with the given records
+    */
+    pub items: Vec<BulkItem>,
+    /// number of times a batch was throttled and retried
+    pub throttled: usize,
+}
+
+impl BulkResult {
+    /// Indicates if all records were written successfully
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn record(&mut self, chunk: Range<usize>, outcome: Result<BatchResponse>, duration: Duration) {
+        let response = match outcome {
+            Ok(response) => response,
+            Err(error) => {
+                let error = ServiceError::parse(&error.message).unwrap_or(ServiceError {
+                    code: String::new(),
+                    message: error.message,
+                });
+
+                for index in chunk {
+                    self.push(index, None, Some(error.clone()), duration);
+                }
+                return;
+            }
+        };
+
+        let failed: Vec<&BatchResponseItem> = response
+            .items
+            .iter()
+            .filter(|item| !item.is_success())
+            .collect();
+
+        for index in chunk.clone() {
+            let content_id = (index - chunk.start + 1) as u16;
+
+            if failed.is_empty() {
+                let status = response
+                    .get(content_id)
+                    .map(|item| item.status);
+                self.push(index, status, None, duration);
+                continue;
+            }
+
+            let item = failed
+                .iter()
+                .find(|item| item.content_id == Some(content_id))
+                .or_else(|| failed.iter().find(|item| item.content_id.is_none()));
+
+            match item {
+                Some(item) => {
+                    let error = item.get_error().unwrap_or_else(|| ServiceError {
+                        code: String::new(),
+                        message: item.body.clone(),
+                    });
+                    self.push(index, Some(item.status), Some(error), duration);
+                }
+                None => self.push(
+                    index,
+                    None,
+                    Some(ServiceError {
+                        code: String::new(),
+                        message: String::from("rolled back because another request of its batch failed"),
+                    }),
+                    duration,
+                ),
+            }
+        }
+    }
+
+    fn push(&mut self, index: usize, status: Option<u16>, error: Option<ServiceError>, duration: Duration) {
+        let error = match error {
+            Some(error) => {
+                self.failures.push(BulkFailure {
+                    index,
+                    status,
+                    code: Some(error.code).filter(|code| !code.is_empty()),
+                    message: error.message.clone(),
+                });
+                Some(error.message)
+            }
+            None => {
+                self.succeeded += 1;
+                None
+            }
+        };
+
+        self.items.push(BulkItem {
+            index,
+            status,
+            error,
+            duration,
+        });
+    }
+
+    /// orders the failures and items by their index after all batches finished
+    fn sort(&mut self) {
+        self.failures.sort_by_key(|failure| failure.index);
+        self.items.sort_by_key(|item| item.index);
+    }
+}
+
+/// limits the number of running batches and adapts it to the service protection state
+struct ConcurrencyController {
+    limit: AtomicUsize,
+    min: usize,
+    max: usize,
+    adaptive: bool,
+}
+
+impl ConcurrencyController {
+    fn new(concurrency: Concurrency) -> Self {
+        match concurrency {
+            Concurrency::Fixed(limit) => Self {
+                limit: AtomicUsize::new(limit),
+                min: limit,
+                max: limit,
+                adaptive: false,
+            },
+            Concurrency::Adaptive { min, max } => Self {
+                limit: AtomicUsize::new(min),
+                min,
+                max,
+                adaptive: true,
+            },
+        }
+    }
+
+    fn get_limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    fn observe(&self, rate_limit: &RateLimit) {
+        if !self.adaptive {
+            return;
+        }
+
+        let limit = self.get_limit();
+        let time_ratio = rate_limit.get_time_remaining_ratio().unwrap_or(1.0);
+        let burst_remaining = rate_limit.burst_remaining.unwrap_or(u64::MAX);
+
+        let next = if rate_limit.throttled {
+            limit / 2
+        } else if time_ratio < 0.2 || burst_remaining < 100 {
+            limit.saturating_sub(1)
+        } else if time_ratio > 0.5 {
+            limit + 1
+        } else {
+            limit
+        };
+
+        self.limit
+            .store(next.clamp(self.min, self.max), Ordering::Relaxed);
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    creates all given records in parallel batches
+
+    Failed records are reported in the returned `BulkResult` instead of failing
+    the whole operation. If the options define an alternate key with
+    `BulkOptions::upsert_by_key(...)` the records are upserted by this key instead.
+    Otherwise the id generator of `Client::with_id_generator(...)` generates the
+    primary keys of the records that don't set one
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - The metadata of an entity can't be resolved for the id generator or the default currency
+    */
+    pub async fn bulk_create<E: WriteEntity + Sync>(&self, entities: &[E], options: &BulkOptions) -> Result<BulkResult> {
+        let create_key = options.get_create_key();
+
+        if create_key.is_none() {
+            let mut entity_names: Vec<_> = entities.iter().map(|entity| entity.get_reference().entity_name).collect();
+            entity_names.sort();
+            entity_names.dedup();
+
+            let entity_names: Vec<&str> = entity_names.iter().map(AsRef::as_ref).collect();
+            self.prepare_creates(&entity_names).await?;
+        }
+
+        let entity_name = |index: usize| entities[index].get_reference().entity_name.to_string();
+
+        self.execute_bulk(entities.len(), options, entity_name, |batch, index| match &create_key {
+            Some(key_columns) => batch.upsert_by_key(&entities[index], key_columns),
+            None => batch.create(&entities[index]),
+        })
+        .await
+    }
+
+    /**
+    updates all given records in parallel batches
+
+    Failed records are reported in the returned `BulkResult` instead of failing
+    the whole operation
+
+    This may fail for any of these reasons
+    - An authentication failure
+    */
+    pub async fn bulk_update<E: WriteEntity + Sync>(&self, entities: &[E], options: &BulkOptions) -> Result<BulkResult> {
+        let entity_name = |index: usize| entities[index].get_reference().entity_name.to_string();
+
+        self.execute_bulk(entities.len(), options, entity_name, |batch, index| {
+            batch.update(&entities[index])
+        })
+        .await
+    }
+
+    /**
+    upserts all given records in parallel batches
+
+    Failed records are reported in the returned `BulkResult` instead of failing
+    the whole operation
+
+    This may fail for any of these reasons
+    - An authentication failure
+    */
+    pub async fn bulk_upsert<E: WriteEntity + Sync>(&self, entities: &[E], options: &BulkOptions) -> Result<BulkResult> {
+        let entity_name = |index: usize| entities[index].get_reference().entity_name.to_string();
+
+        self.execute_bulk(entities.len(), options, entity_name, |batch, index| {
+            batch.upsert(&entities[index])
+        })
+        .await
+    }
+
+    /**
+    deletes all given records in parallel batches
+
+    Failed records are reported in the returned `BulkResult` instead of failing
+    the whole operation
+
+    This may fail for any of these reasons
+    - An authentication failure
+    */
+    pub async fn bulk_delete<R: Reference + Sync>(&self, references: &[R], options: &BulkOptions) -> Result<BulkResult> {
+        let entity_name = |index: usize| references[index].get_reference().entity_name.to_string();
+
+        self.execute_bulk(references.len(), options, entity_name, |batch, index| {
+            batch.delete(&references[index])
+        })
+        .await
+    }
+
+    /**
+    executes `count` requests in parallel batches which are filled by `build` per index
+
+    The entity name of the first request of a batch is used to measure and plan it
+    */
+    pub(crate) async fn execute_bulk(
+        &self,
+        count: usize,
+        options: &BulkOptions,
+        entity_name: impl Fn(usize) -> String + Sync,
+        build: impl Fn(&mut Batch, usize) -> Result<()> + Sync,
+    ) -> Result<BulkResult> {
+        let mut start = 0;
+        // batches are taken when they are executed, so they are sized by the latest measurements
+        let batches = std::iter::from_fn(|| {
+            if start >= count {
+                return None;
+            }
+
+            let entity_name = entity_name(start);
+            let batch_size = match options.time_boxed {
+                true => self.get_batch_planner().plan_batch_size(&entity_name, options.batch_size),
+                false => options.batch_size,
+            };
+
+            let chunk = start..(start + batch_size as usize).min(count);
+            start = chunk.end;
+
+            let mut batch = self.new_batch();
+            let built = chunk
+                .clone()
+                .try_for_each(|index| build(&mut batch, index))
+                .map(|_| batch);
+
+            Some((chunk, built, Some(entity_name)))
+        });
+
+        self.execute_batches(count, options, batches).await
+    }
+
+    /**
+    executes the given batches in parallel and aggregates their outcome
+
+    Each batch is given with the range of the indices of its requests and the entity
+    name its execution time is measured for. The batches are only taken from the
+    iterator when they are executed
+    */
+    pub(crate) async fn execute_batches(
+        &self,
+        count: usize,
+        options: &BulkOptions,
+        mut batches: impl Iterator<Item = (Range<usize>, Result<Batch>, Option<String>)>,
+    ) -> Result<BulkResult> {
+        let tracker = self.track_progress(Operation::Bulk, Some(count));
+        let controller = ConcurrencyController::new(options.concurrency);
+        let mut running = FuturesUnordered::new();
+        let mut result = BulkResult::default();
+
+        loop {
+            while running.len() < controller.get_limit() {
+                match batches.next() {
+                    Some((chunk, batch, entity_name)) => {
+                        running.push(self.execute_chunk(chunk, batch, entity_name, &controller))
+                    }
+                    None => break,
+                }
+            }
+
+            match running.next().await {
+                Some((chunk, outcome, throttled, duration)) => {
+                    result.throttled += throttled;
+                    let outcome = match outcome {
+                        Err(error) if error.kind == ErrorKind::Authentication => return Err(error),
+                        Err(error) => Err(error),
+                        Ok(outcome) => outcome,
+                    };
+                    result.record(chunk, outcome, duration);
+                    tracker.report(result.succeeded + result.failures.len());
+                }
+                None => break,
+            }
+        }
+
+        result.sort();
+        Ok(result)
+    }
+
+    /**
+    executes one batch and retries it while it is throttled
+
+    The execution time of a successful attempt is passed to the batch planner
+    */
+    async fn execute_chunk(
+        &self,
+        chunk: Range<usize>,
+        batch: Result<Batch>,
+        entity_name: Option<String>,
+        controller: &ConcurrencyController,
+    ) -> (Range<usize>, Result<Result<BatchResponse>>, usize, Duration) {
+        let started = Instant::now();
+        let batch = match batch {
+            Ok(batch) => batch,
+            Err(error) => return (chunk, Ok(Err(error)), 0, started.elapsed()),
+        };
+
+        if let Some(metrics) = self.get_metrics() {
+            metrics.record_batch(batch.get_count() as usize);
+        }
+
+        let boundary = format!("batch_{}", batch.get_batch_id().as_simple());
+        let body = match batch.into_body() {
+            Ok(body) => body,
+            Err(error) => return (chunk, Ok(Err(error)), 0, started.elapsed()),
+        };
+        self.audit_batch(&body);
+        let mut throttled = 0;
+
+        loop {
+            let attempt_started = Instant::now();
+            let (rate_limit, outcome) = match self.execute_observed(&boundary, body.clone()).await {
+                Ok(observed) => observed,
+                Err(error) => return (chunk, Err(error), throttled, started.elapsed()),
+            };
+
+            controller.observe(&rate_limit);
+
+            if rate_limit.throttled && throttled < MAX_THROTTLE_RETRIES {
+                throttled += 1;
+                let delay = rate_limit.retry_after.unwrap_or(DEFAULT_RETRY_AFTER);
+
+                if let Some(metrics) = self.get_metrics() {
+                    metrics.record_retry(&RetryMetric {
+                        attempt: throttled,
+                        delay,
+                    });
+                }
+
+                self.sleep(delay).await;
+                continue;
+            }
+
+            if let (Some(entity_name), Ok(response)) = (entity_name.as_deref(), outcome.as_ref()) {
+                if response.items.iter().all(|item| item.is_success()) {
+                    self.get_batch_planner()
+                        .observe(entity_name, chunk.len(), attempt_started.elapsed());
+                }
+            }
+
+            return (chunk, Ok(outcome), throttled, started.elapsed());
+        }
+    }
+
+    /**
+    executes the batch body and returns the service protection state with its outcome
+
+    The body is shared between retries without copying it.
+    Only authentication and transport failures fail the outer result. The caller
+    records transport failures for every request of the batch and only aborts the
+    bulk function on authentication failures
+    */
+    async fn execute_observed(&self, boundary: &str, body: Bytes) -> Result<(RateLimit, Result<BatchResponse>)> {
+        let url_path = self.build_simple_url("$batch");
+
+        async fn handle_response(response: Response) -> Result<(RateLimit, Result<BatchResponse>)> {
+            let rate_limit = RateLimit::from_response(response.status(), response.headers());
+
+            if response.status().is_client_error() || response.status().is_server_error() {
+                let error_message = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("no error details provided from server"));
+                return Ok((rate_limit, Err(DataverseError::new(error_message))));
+            }
+
+            let content_type = response
+                .headers()
+                .get("Content-Type")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+
+            let content = response.text().await.into_dataverse_result()?;
+            Ok((rate_limit, BatchResponse::parse(&content_type, &content)))
+        }
+
+        self.request(
+            Method::POST,
+            &url_path,
+            move |request| {
+                Ok(request
+                    .header("Content-Type", format!("multipart/mixed; boundary={}", boundary))
+                    .body(body))
+            },
+            handle_response,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        batch::response::{BatchResponse, BatchResponseItem},
+        client::Client,
+        error::DataverseError,
+        rate_limit::RateLimit,
+        reference::EntityReference,
+    };
+
+    use super::{BulkOptions, BulkResult, Concurrency, ConcurrencyController};
+
+    fn item(content_id: u16, status: u16) -> BatchResponseItem {
+        BatchResponseItem {
+            content_id: Some(content_id),
+            status,
+            headers: Vec::new(),
+            body: format!("status {}", status),
+        }
+    }
+
+    #[test]
+    fn record_failed_changeset() {
+        let mut result = BulkResult::default();
+        result.record(0..2, Ok(BatchResponse { items: vec![item(1, 204), item(2, 204)] }), Duration::ZERO);
+        result.record(2..5, Ok(BatchResponse { items: vec![item(2, 400)] }), Duration::ZERO);
+        result.record(5..6, Err(DataverseError::new(String::from("offline"))), Duration::ZERO);
+
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.failures.len(), 4);
+        assert_eq!(result.failures[0].index, 2);
+        assert_eq!(result.failures[0].status, None);
+        assert_eq!(result.failures[1].index, 3);
+        assert_eq!(result.failures[1].status, Some(400));
+        assert_eq!(result.failures[3].message, "offline");
+    }
+
+    #[test]
+    fn items_in_input_order() {
+        let mut result = BulkResult::default();
+        result.record(2..3, Err(DataverseError::new(String::from("offline"))), Duration::from_secs(2));
+        result.record(0..2, Ok(BatchResponse { items: vec![item(1, 204), item(2, 204)] }), Duration::from_secs(1));
+        result.sort();
+
+        let indices: Vec<usize> = result.items.iter().map(|item| item.index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert_eq!(result.items[1].status, Some(204));
+        assert!(result.items[1].is_success());
+        assert_eq!(result.items[2].error.as_deref(), Some("offline"));
+        assert_eq!(result.items[2].duration, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn adaptive_concurrency() {
+        let controller = ConcurrencyController::new(Concurrency::Adaptive { min: 2, max: 8 });
+        assert_eq!(controller.get_limit(), 2);
+
+        controller.observe(&RateLimit::default());
+        controller.observe(&RateLimit::default());
+        assert_eq!(controller.get_limit(), 4);
+
+        controller.observe(&RateLimit {
+            time_remaining: Some(100_000.0),
+            ..RateLimit::default()
+        });
+        assert_eq!(controller.get_limit(), 3);
+
+        controller.observe(&RateLimit {
+            throttled: true,
+            ..RateLimit::default()
+        });
+        assert_eq!(controller.get_limit(), 2);
+    }
+
+    #[test]
+    fn bulk_future_is_send() {
+        fn assert_send<T: Send>(_: T) {}
+
+        let client = Client::new_dummy();
+        let references: Vec<EntityReference> = Vec::new();
+        assert_send(client.bulk_delete(&references, &BulkOptions::new()));
+    }
+
+    #[test]
+    fn fixed_concurrency() {
+        let controller = ConcurrencyController::new(Concurrency::Fixed(4));
+        controller.observe(&RateLimit {
+            throttled: true,
+            ..RateLimit::default()
+        });
+        assert_eq!(controller.get_limit(), 4);
+    }
+}
@@ -0,0 +1,210 @@
+/*!
+Module for caching retrieved records on the client side
+
+Cached records are revalidated with their ETag on every retrieve. Dataverse only
+sends records that changed since they were cached, so read-heavy applications
+save a lot of bandwidth and API consumption while always reading current data
+
+The storage of cached records is pluggable through the `CacheStore` trait.
+`MemoryCacheStore` is a simple in-memory implementation
+
+# Examples
+```rust
+use serde::Deserialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    cache::MemoryCacheStore,
+    client::Client,
+    entity::ReadEntity,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result},
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy().with_cache(MemoryCacheStore::new()); // Please replace this with your preferred authentication method
+    let reference = ReferenceStruct::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    // the first call downloads the record, the second one only revalidates it
+    let contact: Contact = client.retrieve(&reference).await?;
+    let contact: Contact = client.retrieve(&reference).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname"]
+    }
+}
+```
+*/
+
+use std::{collections::HashMap, sync::Mutex};
+
+use bytes::Bytes;
+
+use crate::{
+    auth::Authenticate,
+    client::{Client, Conditional},
+    entity::{get_annotations_preference, ReadEntity},
+    error::DataverseError,
+    json,
+    reference::EntityReference,
+    result::Result,
+};
+
+/// A cached record as it was returned by Dataverse
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedRecord {
+    pub etag: String,
+    pub content: Bytes,
+}
+
+/**
+trait for stores that hold cached records
+
+Keys are built from the reference of the record and the retrieved columns.
+Implementations are free to evict records at any time
+*/
+pub trait CacheStore: Send + Sync {
+    /// returns the cached record for the given key
+    fn get(&self, key: &str) -> Option<CachedRecord>;
+
+    /// stores the record under the given key
+    fn put(&self, key: String, record: CachedRecord);
+}
+
+/// A `CacheStore` that keeps all cached records in memory
+#[derive(Debug, Default)]
+pub struct MemoryCacheStore {
+    records: Mutex<HashMap<String, CachedRecord>>,
+}
+
+impl MemoryCacheStore {
+    /// creates a new empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// returns the current count of cached records
+    pub fn get_count(&self) -> usize {
+        self.records
+            .lock()
+            .map(|records| records.len())
+            .unwrap_or(0)
+    }
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CachedRecord> {
+        self.records.lock().ok()?.get(key).cloned()
+    }
+
+    fn put(&self, key: String, record: CachedRecord) {
+        if let Ok(mut records) = self.records.lock() {
+            records.insert(key, record);
+        }
+    }
+}
+
+/// builds the cache key of a record retrieved with the given columns
+pub fn build_cache_key(reference: &EntityReference, columns: &[&str]) -> String {
+    format!(
+        "{}({})?$select={}",
+        reference.entity_name,
+        reference.entity_id.as_hyphenated(),
+        columns.join(",")
+    )
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    pub(crate) async fn retrieve_cached<E: ReadEntity>(
+        &self,
+        store: &dyn CacheStore,
+        reference: &EntityReference,
+    ) -> Result<E> {
+        let columns = E::get_columns();
+        let annotations = get_annotations_preference::<E>();
+        let mut key = build_cache_key(reference, columns);
+
+        if E::include_formatted_values() {
+            key.push_str("&formatted");
+        }
+
+        if E::include_lookup_logical_names() {
+            key.push_str("&lookups");
+        }
+
+        let cached = store.get(&key);
+        let etag = cached.as_ref().map(|record| record.etag.as_str());
+
+        let content = match self
+            .retrieve_raw_if_modified(reference, columns, annotations, etag)
+            .await?
+        {
+            Conditional::Modified { entity, etag } => {
+                if let Some(etag) = etag {
+                    store.put(
+                        key,
+                        CachedRecord {
+                            etag,
+                            content: entity.clone(),
+                        },
+                    );
+                }
+
+                entity
+            }
+            // an ETag is only sent for cached records
+            Conditional::NotModified => cached
+                .map(|record| record.content)
+                .ok_or_else(|| DataverseError::new(String::from("record is not cached")))?,
+        };
+
+        json::from_slice(content.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use uuid::Uuid;
+
+    use crate::reference::EntityReference;
+
+    use super::{build_cache_key, CacheStore, CachedRecord, MemoryCacheStore};
+
+    #[test]
+    fn cache_key_contains_columns() {
+        let reference = EntityReference::new("contacts", Uuid::nil());
+        assert_eq!(
+            build_cache_key(&reference, &["firstname", "lastname"]),
+            "contacts(00000000-0000-0000-0000-000000000000)?$select=firstname,lastname"
+        );
+    }
+
+    #[test]
+    fn memory_store_roundtrip() {
+        let store = MemoryCacheStore::new();
+        let record = CachedRecord {
+            etag: String::from("W/\"123\""),
+            content: Bytes::from_static(b"{}"),
+        };
+
+        store.put(String::from("key"), record.clone());
+        assert_eq!(store.get("key"), Some(record));
+        assert_eq!(store.get("other"), None);
+        assert_eq!(store.get_count(), 1);
+    }
+}
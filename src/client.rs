@@ -21,34 +21,73 @@ let client = Client::with_client_secret_auth(
 */
 
 use std::future::Future;
-use std::{borrow::Cow, fmt::Display};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::{borrow::Cow, fmt::{Display, Write}};
+use std::time::{Duration, Instant};
 
+use async_lock::OnceCell;
+use bytes::Bytes;
 use lazy_static::lazy_static;
 use regex::Regex;
-use reqwest::{RequestBuilder, Response, Method};
-use serde::Deserialize;
+use reqwest::{Body, RequestBuilder, Response, Method, StatusCode};
+#[cfg(feature = "batch")]
+use tokio::io::AsyncRead;
+#[cfg(feature = "batch")]
+use tokio_util::io::ReaderStream;
+#[cfg(feature = "batch")]
+use futures_util::TryStreamExt;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
-use crate::action::MergeRequest;
+use crate::action::{get_primary_id_attribute, MergeRequest};
+#[cfg(feature = "batch")]
+use crate::audit_trail::{collect_batch_entries, BatchAuditor};
+#[cfg(feature = "batch")]
+use crate::batch::{defaults::CreateDefaults, response::BatchResponse, set::BatchSet, Batch};
+#[cfg(feature = "batch")]
+use crate::planner::BatchPlanner;
+#[cfg(feature = "batch")]
+use crate::transaction::Transaction;
+#[cfg(feature = "fault-injection")]
+use crate::fault::FaultPolicy;
 use crate::{
+    audit_trail::{collect_entries, AuditTrailEntry, AuditTrailSink},
     auth::{client_secret::ClientSecretAuth, Authenticate, no_auth::NoAuth},
-    batch::Batch,
-    entity::{ReadEntity, WriteEntity},
-    error::DataverseError,
-    query::Query,
-    reference::Reference,
+    cache::CacheStore,
+    consistency::{ReadYourWrites, RecentWrites},
+    currency::{apply_default_currency, has_currency, CurrencyEntityCache},
+    diagnostics::{collect_warnings, FailureCapture, ResponseMeta, ServerWarning},
+    failover::FailoverUrls,
+    naming::{check_payload, check_query, NameWarning, NameWarningHandler},
+    entity::{get_annotations_preference, ReadEntity, WriteEntity},
+    error::{DataverseError, ErrorCode, ErrorKind},
+    hook::{to_hooked_string, to_hooked_value, WriteContext, WriteHook, WriteOperation},
+    id_generator::{apply_generated_id, IdGenerator},
+    identity::{apply_default_owner, DefaultOwner, WhoAmI},
+    json::{self, RetrieveMultipleResult},
+    metadata::EntityNameCache,
+    metrics::{Metrics, RequestMetric},
+    progress::{Operation, Progress, ProgressTracker},
+    rate_limit::QuotaInfo,
+    query::{parameters::{write_select, QueryParameters}, Query},
+    reference::{EntityReference, Reference},
     result::{IntoDataverseResult, Result},
+    runtime::{Timer, TokioTimer},
+    transport::HttpTransport,
 };
 
 lazy_static! {
-    static ref UUID_REGEX: Regex =
+    pub(crate) static ref UUID_REGEX: Regex =
         Regex::new("[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}")
             .unwrap();
 }
 
 /// Microsoft Dataverse Web-API Version this client uses
 pub static VERSION: &str = "9.2";
+
+/// capacity reserved for the path and query string when building urls
+static URL_CAPACITY: usize = 128;
 /**
 A client capable of connecting to a dataverse environment
 
@@ -73,9 +112,40 @@ let client = Client::with_client_secret_auth(
 pub struct Client<'url, A: Authenticate> {
     pub url: Cow<'url, str>,
     backend: reqwest::Client,
+    transport: Arc<dyn HttpTransport>,
     auth: A,
+    pub(crate) entity_names: Arc<EntityNameCache>,
+    cache: Option<Arc<dyn CacheStore>>,
+    warning_handler: Option<WarningHandler>,
+    name_validation: Option<NameWarningHandler>,
+    pub(crate) default_owner: Option<DefaultOwner>,
+    default_currency: Option<Uuid>,
+    id_generator: Option<Arc<dyn IdGenerator>>,
+    read_your_writes: Option<ReadYourWrites>,
+    recent_writes: RecentWrites,
+    pub(crate) currency_entities: Arc<CurrencyEntityCache>,
+    pub(crate) identity: OnceCell<WhoAmI>,
+    stable_paging: bool,
+    query_via_post: bool,
+    max_url_length: Option<usize>,
+    max_payload_size: Option<usize>,
+    metrics: Option<Arc<dyn Metrics>>,
+    progress: Option<Arc<dyn Progress>>,
+    failure_capture: Option<FailureCapture>,
+    failover: Option<FailoverUrls>,
+    audit_trail: Option<AuditTrailSink>,
+    last_response: Mutex<Option<ResponseMeta>>,
+    write_hook: Option<WriteHook>,
+    timer: Arc<dyn Timer>,
+    #[cfg(feature = "batch")]
+    batch_planner: BatchPlanner,
+    #[cfg(feature = "fault-injection")]
+    fault_policy: Option<FaultPolicy>,
 }
 
+/// callback that receives warnings returned by Dataverse
+pub type WarningHandler = Arc<dyn Fn(&ServerWarning) + Send + Sync>;
+
 impl<'url> Client<'url, ClientSecretAuth> {
     /**
     Creates a dataverse client that uses client/secret authentication
@@ -160,7 +230,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
     - tokens should be acquired lazily
     - tokens should be cached and reused where possible
     - each call to the `get_valid_token()` function should give a token that is valid
-    for at least the next 2 minutes
+      for at least the next 2 minutes
 
     # Examples
     ```rust
@@ -199,7 +269,483 @@ impl<'url, A: Authenticate> Client<'url, A> {
     */
     pub fn new(url: impl Into<Cow<'url, str>>, backend: reqwest::Client, auth: A) -> Self {
         let url = url.into();
-        Self { url, backend, auth }
+        Self {
+            url,
+            transport: Arc::new(backend.clone()),
+            backend,
+            auth,
+            entity_names: Arc::default(),
+            cache: None,
+            warning_handler: None,
+            name_validation: None,
+            default_owner: None,
+            default_currency: None,
+            id_generator: None,
+            read_your_writes: None,
+            recent_writes: RecentWrites::default(),
+            currency_entities: Arc::default(),
+            identity: OnceCell::new(),
+            stable_paging: false,
+            query_via_post: false,
+            max_url_length: None,
+            max_payload_size: None,
+            metrics: None,
+            progress: None,
+            failure_capture: None,
+            failover: None,
+            audit_trail: None,
+            last_response: Mutex::new(None),
+            write_hook: None,
+            timer: Arc::new(TokioTimer),
+            #[cfg(feature = "batch")]
+            batch_planner: BatchPlanner::new(),
+            #[cfg(feature = "fault-injection")]
+            fault_policy: None,
+        }
+    }
+
+    /**
+    Configures an owner that is assigned to every record created with `create(...)`
+    that doesn't set an `ownerid` itself
+
+    The owner also applies to `bulk_create(...)` and to the batches, batch sets and
+    transactions created with `new_batch()`, `new_batch_set()` and `new_transaction()`.
+    Creates in these batches leave `DefaultOwner::CurrentUser` to Dataverse until
+    `who_am_i()` was called, which assigns the calling user as the owner anyway
+    */
+    pub fn with_default_owner(mut self, owner: DefaultOwner) -> Self {
+        self.default_owner = Some(owner);
+        self
+    }
+
+    /**
+    Configures a transaction currency that is bound to every record created with `create(...)`
+    whose entity has money columns and that doesn't set a `transactioncurrencyid` itself
+
+    The currency also applies to `bulk_create(...)` and to the batches, batch sets and
+    transactions created with `new_batch()`, `new_batch_set()` and `new_transaction()`,
+    whose entities have to be prepared with `prepare_creates(...)` first
+    */
+    pub fn with_default_currency(mut self, currency_id: Uuid) -> Self {
+        self.default_currency = Some(currency_id);
+        self
+    }
+
+    /**
+    Configures a strategy that generates the primary key of every record created with
+    `create(...)` or `bulk_create(...)` that doesn't set a primary key itself
+
+    The name of the primary key is resolved with `resolve_entity_names(...)`, so creates
+    may additionally fail if the entity name can't be resolved. The keys also apply to
+    the batches, batch sets and transactions created with `new_batch()`, `new_batch_set()`
+    and `new_transaction()`, whose entities have to be prepared with `prepare_creates(...)`
+    first, see the `id_generator` module for details
+    */
+    pub fn with_id_generator(mut self, generator: impl IdGenerator + 'static) -> Self {
+        self.id_generator = Some(Arc::new(generator));
+        self
+    }
+
+    /**
+    Retries retrieves of records created by this client that are not found yet
+
+    This applies to retrieves through the cache of `with_cache(...)` as well,
+    see the `consistency` module for details
+    */
+    pub fn with_read_your_writes(mut self, policy: ReadYourWrites) -> Self {
+        self.read_your_writes = Some(policy);
+        self
+    }
+
+    /**
+    Registers a handler that receives the warnings Dataverse returns in response headers
+
+    Dataverse uses these headers to announce deprecated usages and preview features,
+    see the `diagnostics` module for details
+    */
+    pub fn with_warning_handler(
+        mut self,
+        handler: impl Fn(&ServerWarning) + Send + Sync + 'static,
+    ) -> Self {
+        self.warning_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /**
+    Registers a handler that receives suspicious column names of outgoing requests
+
+    The keys of write payloads and the columns of queries are checked for schema
+    names and other common mistakes, see the `naming` module for details
+    */
+    pub fn with_name_validation(
+        mut self,
+        handler: impl Fn(&NameWarning) + Send + Sync + 'static,
+    ) -> Self {
+        self.name_validation = Some(Arc::new(handler));
+        self
+    }
+
+    /**
+    Enables caching of retrieved records in the given store
+
+    Once enabled, `retrieve(...)` revalidates cached records with their ETag and only
+    downloads records that changed. Records are cached by their reference and the
+    retrieved columns
+
+    # Examples
+    ```rust
+    use powerplatform_dataverse_service_client::{
+        cache::MemoryCacheStore,
+        client::Client
+    };
+
+    let client = Client::new_dummy().with_cache(MemoryCacheStore::new());
+    ```
+    */
+    pub fn with_cache(mut self, store: impl CacheStore + 'static) -> Self {
+        self.cache = Some(Arc::new(store));
+        self
+    }
+
+    /**
+    Orders the results of `retrieve_multiple(...)` by the primary key of the table last
+
+    Dataverse can skip or repeat records between pages if the order of a query is not
+    unique. With stable paging the primary key is appended to the order of every query,
+    which makes long exports reliable. The primary key is resolved from the entity
+    metadata once per table
+    */
+    pub fn with_stable_paging(mut self) -> Self {
+        self.stable_paging = true;
+        self
+    }
+
+    /**
+    Retries queries of `retrieve_multiple(...)` whose url is too long for the server
+    by sending their query options in the body of a `POST <table>/$query` request
+
+    Without this option such queries fail with `ErrorKind::RequestTooLarge`
+    */
+    pub fn with_query_via_post(mut self) -> Self {
+        self.query_via_post = true;
+        self
+    }
+
+    /**
+    Sends queries of `retrieve_multiple(...)` whose url would exceed the given length
+    in the body of a `POST <table>/$query` request right away
+
+    This avoids the failed round trip of `with_query_via_post()` for queries that are
+    known to be long, e.g. filters for thousands of ids. Dataverse rejects urls longer
+    than 32 KB, so a value below that (like 16000) is recommended. This also enables
+    `with_query_via_post()`
+    */
+    pub fn with_max_url_length(mut self, max_url_length: usize) -> Self {
+        self.query_via_post = true;
+        self.max_url_length = Some(max_url_length);
+        self
+    }
+
+    /// Indicates if a query with the given url has to be sent in the request body
+    pub(crate) fn is_url_too_long(&self, url: &str) -> bool {
+        self.max_url_length
+            .is_some_and(|max_url_length| url.len() > max_url_length)
+    }
+
+    /**
+    Rejects creates, updates and upserts whose serialized payload exceeds the given size in bytes
+
+    The payload is checked locally before it is sent, so accidentally oversized
+    payloads (e.g. embedded files) fail fast with `ErrorKind::PayloadTooLarge`
+    instead of being rejected by the server after a slow upload
+    */
+    pub fn with_max_payload_size(mut self, max_payload_size: usize) -> Self {
+        self.max_payload_size = Some(max_payload_size);
+        self
+    }
+
+    /**
+    Registers a receiver for the metrics of this client
+
+    see the `metrics` module for details
+    */
+    pub fn with_metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /**
+    Registers a receiver for the progress of bulk functions, `retrieve_all(...)` and
+    system job pollers of this client
+
+    see the `progress` module for details
+    */
+    pub fn with_progress(mut self, progress: impl Progress + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /**
+    Captures requests that fail with a client or server error with their redacted payload
+
+    see the `diagnostics` module for details
+    */
+    pub fn with_failure_capture(mut self, capture: FailureCapture) -> Self {
+        self.failure_capture = Some(capture);
+        self
+    }
+
+    /**
+    Passes every write request of this client to the sink before it is sent
+
+    see the `audit_trail` module for details
+    */
+    pub fn with_audit_trail(mut self, sink: impl Fn(&AuditTrailEntry) + Send + Sync + 'static) -> Self {
+        self.audit_trail = Some(Arc::new(sink));
+        self
+    }
+
+    /**
+    returns the diagnostic headers of the last response that Dataverse returned to this client
+
+    Please note that concurrent requests of the same client replace each other's response,
+    so the method and url of the response should be checked
+
+    see the `diagnostics` module for details
+    */
+    pub fn get_last_response(&self) -> Option<ResponseMeta> {
+        self.last_response
+            .lock()
+            .ok()
+            .and_then(|response| response.clone())
+    }
+
+    /**
+    returns the remaining service protection quota reported with the last response
+
+    see the `rate_limit` module for details
+    */
+    pub fn get_quota(&self) -> Option<QuotaInfo> {
+        self.get_last_response().map(|response| response.quota)
+    }
+
+    /**
+    Registers a hook that modifies the payload of every created, updated or upserted record
+
+    see the `hook` module for details
+    */
+    pub fn with_write_hook(
+        mut self,
+        hook: impl Fn(&WriteContext, &mut Map<String, Value>) + Send + Sync + 'static,
+    ) -> Self {
+        self.write_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /**
+    Injects artificial faults and latency into the requests of this client
+
+    This is only available with the `fault-injection` feature, see the `fault` module
+    */
+    #[cfg(feature = "fault-injection")]
+    pub fn with_fault_policy(mut self, policy: FaultPolicy) -> Self {
+        self.fault_policy = Some(policy);
+        self
+    }
+
+    /// creates a new empty batch for this client that applies its write hook and the defaults for creates
+    #[cfg(feature = "batch")]
+    pub fn new_batch(&self) -> Batch {
+        let mut batch = Batch::new(self.url.to_string());
+
+        if let Some(hook) = self.write_hook.as_ref() {
+            batch.set_write_hook(Arc::clone(hook));
+        }
+
+        if let Some(defaults) = self.get_create_defaults() {
+            batch.set_create_defaults(defaults);
+        }
+
+        batch
+    }
+
+    /// creates a new empty batch set for this client that applies its write hook and the defaults for creates
+    #[cfg(feature = "batch")]
+    pub fn new_batch_set(&self) -> BatchSet {
+        let mut set = BatchSet::new(self.url.to_string());
+
+        if let Some(hook) = self.write_hook.as_ref() {
+            set.set_write_hook(Arc::clone(hook));
+        }
+
+        if let Some(defaults) = self.get_create_defaults() {
+            set.set_create_defaults(defaults);
+        }
+
+        set
+    }
+
+    /// returns the defaults that are applied to creates in the batches of this client if there are any
+    #[cfg(feature = "batch")]
+    fn get_create_defaults(&self) -> Option<Arc<CreateDefaults>> {
+        let defaults = CreateDefaults {
+            owner: self.get_known_default_owner(),
+            currency: self
+                .default_currency
+                .map(|currency_id| (currency_id, Arc::clone(&self.currency_entities))),
+            id_generator: self
+                .id_generator
+                .as_ref()
+                .map(|generator| (Arc::clone(generator), Arc::clone(&self.entity_names))),
+        };
+
+        match defaults.is_empty() {
+            true => None,
+            false => Some(Arc::new(defaults)),
+        }
+    }
+
+    /**
+    resolves the metadata that the defaults for creates need for the given entities (logical or entity set names)
+
+    Requests are added to batches synchronously, so the default currency and generated
+    primary keys can only be applied to creates in the batches of `new_batch()`,
+    `new_batch_set()` and `new_transaction()` once the metadata of their entities is
+    cached in this client. Adding a create of an entity that wasn't prepared fails in
+    this case. `bulk_create(...)` prepares its entities itself
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no entity with one of the given names
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use powerplatform_dataverse_service_client::{
+        client::Client,
+        reference::ReferenceStruct,
+        result::{IntoDataverseResult, Result},
+        update::RecordChanges
+    };
+
+    async fn test() -> Result<()> {
+        let euro_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+        let client = Client::new_dummy() // Please replace this with your preferred authentication method
+            .with_default_currency(euro_id);
+
+        client.prepare_creates(&["opportunities"]).await?;
+
+        let mut batch = client.new_batch();
+        batch.create(&RecordChanges::new(ReferenceStruct::new("opportunities", Uuid::new_v4())).set("name", "Test"))?;
+        client.execute(&batch).await?;
+        Ok(())
+    }
+    ```
+    */
+    #[cfg(feature = "batch")]
+    pub async fn prepare_creates(&self, entity_names: &[&str]) -> Result<()> {
+        for entity_name in entity_names {
+            if self.default_currency.is_some() {
+                self.has_currency_column(entity_name).await?;
+            }
+
+            if self.id_generator.is_some() {
+                self.resolve_entity_names(entity_name).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// creates a new empty transaction for this client that applies its write hook and the defaults for creates
+    #[cfg(feature = "batch")]
+    pub fn new_transaction(&self) -> Transaction {
+        Transaction::from_batch(self.new_batch())
+    }
+
+    /**
+    Replaces the batch planner of this client, e.g. to use another time budget
+
+    see the `planner` module for details
+    */
+    #[cfg(feature = "batch")]
+    pub fn with_batch_planner(mut self, planner: BatchPlanner) -> Self {
+        self.batch_planner = planner;
+        self
+    }
+
+    /// returns the batch planner with the execution times measured by this client
+    #[cfg(feature = "batch")]
+    pub fn get_batch_planner(&self) -> &BatchPlanner {
+        &self.batch_planner
+    }
+
+    /**
+    Configures alternative urls of the environment that are used when it is unreachable
+
+    Requests that fail without a response of the server are repeated with the next url,
+    which is used for all following requests until it fails itself. The urls have to
+    serve the same environment, so the token of the client is valid for them
+
+    Please note that requests with streamed payloads are not repeated
+
+    # Examples
+    ```rust
+    use powerplatform_dataverse_service_client::client::Client;
+
+    let client = Client::new_dummy()
+        .with_failover_urls(&["https://contoso-failover.crm4.dynamics.com/"]);
+    ```
+    */
+    pub fn with_failover_urls(mut self, urls: &[&str]) -> Self {
+        self.failover = Some(FailoverUrls::new(&self.url, urls));
+        self
+    }
+
+    /// returns the url of the environment that requests are currently sent to
+    pub fn get_active_url(&self) -> &str {
+        match self.failover.as_ref() {
+            Some(failover) => failover.get_url(failover.get_active()),
+            None => &self.url,
+        }
+    }
+
+    /**
+    Replaces the transport that sends the requests of this client
+
+    The `reqwest::Client` given to `new(...)` is still used to build the requests.
+    see the `transport` module for details
+    */
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /**
+    Replaces the timer used to wait for throttled batches, pollers and subscriptions
+
+    see the `runtime` module for details
+    */
+    pub fn with_timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// waits for the given period with the configured timer
+    pub(crate) async fn sleep(&self, duration: Duration) {
+        self.timer.sleep(duration).await
+    }
+
+    /// returns the configured metrics receiver
+    pub(crate) fn get_metrics(&self) -> Option<&dyn Metrics> {
+        self.metrics.as_deref()
+    }
+
+    /// starts tracking the progress of an operation for the configured receiver
+    pub(crate) fn track_progress(&self, operation: Operation, total: Option<usize>) -> ProgressTracker<'_> {
+        ProgressTracker::new(self.progress.as_deref(), operation, total)
     }
 
     /**
@@ -210,6 +756,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
     - A serde serialization error
     - Any http client or server error
     - there is already a record with this Uuid in the table
+    - The payload exceeds the maximum payload size of this client
 
     # Examples
     ```rust
@@ -251,8 +798,20 @@ impl<'url, A: Authenticate> Client<'url, A> {
     ```
     */
     pub async fn create(&self, entity: &impl WriteEntity) -> Result<Uuid> {
+        self.create_with_headers(entity, &[]).await
+    }
+
+    /// writes the given entity like `create(...)` with additional request headers
+    pub(crate) async fn create_with_headers(
+        &self,
+        entity: &impl WriteEntity,
+        headers: &[(&str, String)],
+    ) -> Result<Uuid> {
         let reference = entity.get_reference();
         let url_path = self.build_simple_url(reference.entity_name);
+        let payload = self.serialize_create(entity).await?;
+        self.check_payload_size(&payload)?;
+        self.validate_payload_names(&payload);
 
         async fn handle_response(response: Response) -> Result<Uuid> {
             if response.status().is_client_error() || response.status().is_server_error() {
@@ -275,17 +834,104 @@ impl<'url, A: Authenticate> Client<'url, A> {
             Uuid::parse_str(uuid_segment.as_str()).into_dataverse_result()
         }
 
-        self.request(
+        let entity_id = self.request(
             Method::POST, 
             &url_path, 
             move |request| {
-                Ok(request
+                Ok(headers
+                    .iter()
+                    .fold(request, |request, (name, value)| request.header(*name, value.as_str()))
                     .header("Content-Type", "application/json")
-                    .body(serde_json::to_vec(entity).into_dataverse_result()?)
+                    .body(payload)
                 )
             }, 
             handle_response
-        ).await
+        ).await?;
+
+        if let Some(policy) = self.read_your_writes.as_ref() {
+            self.recent_writes.insert(entity_id, policy.window);
+        }
+
+        Ok(entity_id)
+    }
+
+    /// passes suspicious names of a write payload to the name validation handler
+    fn validate_payload_names(&self, payload: &[u8]) {
+        if let Some(handler) = self.name_validation.as_ref() {
+            check_payload(payload).iter().for_each(|warning| handler(warning));
+        }
+    }
+
+    /// passes suspicious column names of a retrieve or query to the name validation handler
+    fn validate_query_names(&self, columns: &[&str], query: Option<&Query>) {
+        if let Some(handler) = self.name_validation.as_ref() {
+            check_query(columns, query).iter().for_each(|warning| handler(warning));
+        }
+    }
+
+    /// fails with `ErrorKind::PayloadTooLarge` if the payload exceeds the configured maximum size
+    fn check_payload_size(&self, payload: &[u8]) -> Result<()> {
+        match self.max_payload_size {
+            Some(limit) if payload.len() > limit => Err(DataverseError::with_kind(
+                ErrorKind::PayloadTooLarge {
+                    size: payload.len(),
+                    limit,
+                },
+                format!(
+                    "payload of {} bytes exceeds the maximum payload size of {} bytes",
+                    payload.len(),
+                    limit
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// serializes the entity for a create request and applies the configured defaults
+    async fn serialize_create(&self, entity: &impl WriteEntity) -> Result<Vec<u8>> {
+        let owner = self.get_default_owner().await?;
+
+        if owner.is_none() && self.default_currency.is_none() && self.id_generator.is_none() {
+            return self.serialize_write(WriteOperation::Create, entity);
+        }
+
+        let reference = entity.get_reference();
+        let context = WriteContext {
+            operation: WriteOperation::Create,
+            entity_name: &reference.entity_name,
+        };
+        let mut payload = to_hooked_value(self.write_hook.as_ref(), &context, entity)?;
+
+        if let Some(payload) = payload.as_object_mut() {
+            if let Some(owner) = owner.as_ref() {
+                apply_default_owner(payload, owner);
+            }
+
+            if let Some(currency_id) = self.default_currency {
+                if !has_currency(payload) && self.has_currency_column(&reference.entity_name).await? {
+                    apply_default_currency(payload, currency_id);
+                }
+            }
+
+            if let Some(generator) = self.id_generator.as_ref() {
+                let names = self.resolve_entity_names(&reference.entity_name).await?;
+                let primary_key = get_primary_id_attribute(&names.logical_name);
+                apply_generated_id(payload, &primary_key, generator.generate(&reference.entity_name))?;
+            }
+        }
+
+        serde_json::to_vec(&payload).into_dataverse_result()
+    }
+
+    /// serializes the entity for a write request and applies the configured write hook
+    fn serialize_write(&self, operation: WriteOperation, entity: &impl WriteEntity) -> Result<Vec<u8>> {
+        let reference = entity.get_reference();
+        let context = WriteContext {
+            operation,
+            entity_name: &reference.entity_name,
+        };
+
+        to_hooked_string(self.write_hook.as_ref(), &context, entity).map(String::into_bytes)
     }
 
     /**
@@ -299,6 +945,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
     - A serde serialization error
     - Any http client or server error
     - there is no record with this Uuid in the table
+    - The payload exceeds the maximum payload size of this client
 
     # Examples
     ```rust
@@ -342,6 +989,9 @@ impl<'url, A: Authenticate> Client<'url, A> {
     pub async fn update(&self, entity: &impl WriteEntity) -> Result<()> {
         let reference = entity.get_reference();
         let url_path = self.build_targeted_url(reference.entity_name, reference.entity_id);
+        let payload = self.serialize_write(WriteOperation::Update, entity)?;
+        self.check_payload_size(&payload)?;
+        self.validate_payload_names(&payload);
 
         self.request(
             Method::PATCH,
@@ -350,7 +1000,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
                 Ok(request
                     .header("Content-Type", "application/json")
                     .header("If-Match", "*")
-                    .body(serde_json::to_vec(entity).into_dataverse_result()?)
+                    .body(payload)
                 )
             }, 
             handle_empty_response
@@ -367,6 +1017,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
     - An authentication failure
     - A serde serialization error
     - Any http client or server error
+    - The payload exceeds the maximum payload size of this client
 
     # Examples
     ```rust
@@ -410,6 +1061,9 @@ impl<'url, A: Authenticate> Client<'url, A> {
     pub async fn upsert(&self, entity: &impl WriteEntity) -> Result<()> {
         let reference = entity.get_reference();
         let url_path = self.build_targeted_url(reference.entity_name, reference.entity_id);
+        let payload = self.serialize_write(WriteOperation::Upsert, entity)?;
+        self.check_payload_size(&payload)?;
+        self.validate_payload_names(&payload);
 
         self.request(
             Method::PATCH, 
@@ -417,7 +1071,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
             move |request| {
                 Ok(request
                     .header("Content-Type", "application/json")
-                    .body(serde_json::to_vec(entity).into_dataverse_result()?)
+                    .body(payload)
                 )
             }, 
             handle_empty_response
@@ -463,7 +1117,7 @@ impl<'url, A: Authenticate> Client<'url, A> {
         self.request(
             Method::DELETE, 
             &url_path, 
-            move |request| Ok(request), 
+            Ok, 
             handle_empty_response
         ).await
     }
@@ -526,7 +1180,8 @@ impl<'url, A: Authenticate> Client<'url, A> {
     pub async fn retrieve<E: ReadEntity>(&self, reference: &impl Reference) -> Result<E> {
         let reference = reference.get_reference();
         let columns = E::get_columns();
-        let url_path = self.build_retrieve_url(reference.entity_name, reference.entity_id, columns);
+        self.validate_query_names(columns, None);
+        let url_path = self.build_retrieve_url(&reference.entity_name, reference.entity_id, columns);
 
         async fn handle_response<E: ReadEntity>(response: Response) -> Result<E> {
             if response.status().is_client_error() || response.status().is_server_error() {
@@ -538,26 +1193,274 @@ impl<'url, A: Authenticate> Client<'url, A> {
             }
     
             let content = response.bytes().await.into_dataverse_result()?;
-            serde_json::from_slice(content.as_ref()).into_dataverse_result()
+            json::from_slice(content.as_ref())
         }
 
-        self.request(
-            Method::GET, 
-            &url_path, 
-            move |request| Ok(request), 
-            handle_response
-        ).await
+        let mut retry = 0;
+
+        loop {
+            let result = match self.cache.as_ref() {
+                Some(cache) => self.retrieve_cached(cache.as_ref(), &reference).await,
+                None => self.request(
+                    Method::GET, 
+                    &url_path, 
+                    |request| Ok(prefer_annotations::<E>(request)), 
+                    handle_response
+                ).await,
+            };
+
+            match self.read_your_writes.as_ref() {
+                Some(policy)
+                    if retry < policy.max_retries
+                        && result.as_ref().is_err_and(|error| error.get_error_code() == Some(ErrorCode::RecordNotFound))
+                        && self.recent_writes.contains(&reference.entity_id, policy.window) =>
+                {
+                    self.sleep(policy.get_delay(retry)).await;
+                    retry += 1;
+                }
+                _ => return result,
+            }
+        }
     }
 
     /**
-    Executes the query and retrieves the entities from dataverse
+    retrieves the entity record that the reference points to only if it changed
 
-    This function uses the implementation of the `Select` trait to only retrieve
-    those attributes relevant to the struct defined. It is an Anti-Pattern to
-    retrieve all attributes when they are not needed, so this library does not
-    give the option to do that
+    The given ETag is sent in an `If-None-Match` header. If the record didn't change
+    since the ETag was issued, dataverse responds with `304 Not Modified` and this
+    function returns `Conditional::NotModified` without downloading the record.
+    Without an ETag the record is always retrieved
 
-    Please note that if you don't specify a limit then the client will try to retrieve
+    The ETag of the retrieved record is returned with it, so it can be used in
+    subsequent calls
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The entity record referenced doesn't exist
+
+    # Examples
+    ```rust
+    use serde::Deserialize;
+    use uuid::Uuid;
+    use powerplatform_dataverse_service_client::{
+        client::{Client, Conditional},
+        entity::ReadEntity,
+        reference::ReferenceStruct,
+        result::{IntoDataverseResult, Result},
+        select::Select
+    };
+
+    async fn test() -> Result<()> {
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        let reference = ReferenceStruct::new(
+            "contacts",
+            Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+        );
+
+        let etag = match client.retrieve_if_modified::<Contact>(&reference, None).await? {
+            Conditional::Modified { entity, etag } => etag,
+            Conditional::NotModified => None,
+        };
+
+        if let Some(etag) = etag {
+            let result = client.retrieve_if_modified::<Contact>(&reference, Some(&etag)).await?;
+            assert!(result.is_not_modified());
+        }
+
+        Ok(())
+    }
+
+    #[derive(Deserialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+        lastname: String,
+    }
+
+    impl ReadEntity for Contact {}
+
+    impl Select for Contact {
+        fn get_columns() -> &'static [&'static str] {
+            &["contactid", "firstname", "lastname"]
+        }
+    }
+    ```
+    */
+    pub async fn retrieve_if_modified<E: ReadEntity>(
+        &self,
+        reference: &impl Reference,
+        etag: Option<&str>,
+    ) -> Result<Conditional<E>> {
+        let reference = reference.get_reference();
+        let result = self
+            .retrieve_raw_if_modified(&reference, E::get_columns(), get_annotations_preference::<E>(), etag)
+            .await?;
+
+        match result {
+            Conditional::Modified { entity, etag } => Ok(Conditional::Modified {
+                entity: json::from_slice(entity.as_ref())?,
+                etag,
+            }),
+            Conditional::NotModified => Ok(Conditional::NotModified),
+        }
+    }
+
+    pub(crate) async fn retrieve_raw_if_modified(
+        &self,
+        reference: &EntityReference,
+        columns: &[&str],
+        annotations: Option<&'static str>,
+        etag: Option<&str>,
+    ) -> Result<Conditional<Bytes>> {
+        let url_path = self.build_retrieve_url(&reference.entity_name, reference.entity_id, columns);
+
+        async fn handle_response(response: Response) -> Result<Conditional<Bytes>> {
+            if response.status() == StatusCode::NOT_MODIFIED {
+                return Ok(Conditional::NotModified);
+            }
+
+            if response.status().is_client_error() || response.status().is_server_error() {
+                let error_message = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("no error details provided from server"));
+                return Err(DataverseError::new(error_message));
+            }
+
+            let etag = response
+                .headers()
+                .get("ETag")
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+
+            let entity = response.bytes().await.into_dataverse_result()?;
+            Ok(Conditional::Modified { entity, etag })
+        }
+
+        self.request(
+            Method::GET,
+            &url_path,
+            move |request| {
+                let request = match annotations {
+                    Some(preference) => request.header("Prefer", preference),
+                    None => request,
+                };
+
+                match etag {
+                    Some(etag) => Ok(request.header("If-None-Match", etag)),
+                    None => Ok(request),
+                }
+            },
+            handle_response
+        ).await
+    }
+
+    /**
+    retrieves only the primary key of the record to confirm that it exists
+
+    The table can be given by its logical name or its entity set name. The returned
+    reference always uses the entity set name as it is known to dataverse. This is
+    much cheaper than retrieving the record itself when only its existence matters
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - There is no table with the given name
+    - The entity record referenced doesn't exist
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use powerplatform_dataverse_service_client::{
+        client::Client,
+        result::{IntoDataverseResult, Result}
+    };
+
+    async fn test() -> Result<()> {
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        let contact_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+
+        // resolves to the reference contacts(12345678-1234-1234-1234-123456789012)
+        let reference = client.retrieve_reference("contact", contact_id).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn retrieve_reference(&self, table: &str, id: Uuid) -> Result<EntityReference> {
+        self.find_reference(table, id).await?.ok_or_else(|| {
+            DataverseError::new(format!("There is no record {}({})", table, id.as_hyphenated()))
+        })
+    }
+
+    /**
+    Indicates if the entity record that the reference points to exists
+
+    Only the primary key of the record is retrieved. See `retrieve_reference(...)`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - There is no table with the given name
+    */
+    pub async fn exists(&self, reference: &impl Reference) -> Result<bool> {
+        let reference = reference.get_reference();
+        let found = self
+            .find_reference(&reference.entity_name, reference.entity_id)
+            .await?;
+
+        Ok(found.is_some())
+    }
+
+    async fn find_reference(&self, table: &str, id: Uuid) -> Result<Option<EntityReference>> {
+        let names = self.resolve_entity_names(table).await?;
+        let primary_key = get_primary_id_attribute(&names.logical_name);
+        let url_path = self.build_retrieve_url(&names.collection_name, id, &[primary_key.as_str()]);
+
+        async fn handle_response(response: Response, primary_key: String) -> Result<Option<Uuid>> {
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            if response.status().is_client_error() || response.status().is_server_error() {
+                let error_message = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("no error details provided from server"));
+                return Err(DataverseError::new(error_message));
+            }
+
+            let content = response.bytes().await.into_dataverse_result()?;
+            let record: serde_json::Map<String, serde_json::Value> = json::from_slice(content.as_ref())?;
+
+            let id = record
+                .get(&primary_key)
+                .and_then(|value| value.as_str())
+                .ok_or_else(|| DataverseError::new(format!("Dataverse provided no {}", primary_key)))?;
+
+            Uuid::parse_str(id).map(Some).into_dataverse_result()
+        }
+
+        let found = self.request(
+            Method::GET,
+            &url_path,
+            Ok,
+            move |response| handle_response(response, primary_key)
+        ).await?;
+
+        Ok(found.map(|id| EntityReference::new(names.collection_name, id)))
+    }
+
+    /**
+    Executes the query and retrieves the entities from dataverse
+
+    This function uses the implementation of the `Select` trait to only retrieve
+    those attributes relevant to the struct defined. It is an Anti-Pattern to
+    retrieve all attributes when they are not needed, so this library does not
+    give the option to do that
+
+    Please note that if you don't specify a limit then the client will try to retrieve
     up to 5000 records. Further records can then be retrieved with the `retrieve_next_page()`
     function
 
@@ -604,34 +1507,63 @@ impl<'url, A: Authenticate> Client<'url, A> {
     ```
     */
     pub async fn retrieve_multiple<E: ReadEntity>(&self, query: &Query) -> Result<Page<E>> {
+        let query = &query.with_default_filter(E::get_default_filter());
         let columns = E::get_columns();
-        let url_path = self.build_query_url(query.logical_name, columns, query);
+        let primary_key = self.get_stable_primary_key(query).await?;
+        self.validate_query_names(columns, Some(query));
+        let url_path = self.build_query_url(columns, query, primary_key.as_deref());
 
-        async fn handle_response<E: ReadEntity>(response: Response) -> Result<Page<E>> {
-            if response.status().is_client_error() || response.status().is_server_error() {
-                let error_message = response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| String::from("no error details provided from server"));
-                return Err(DataverseError::new(error_message));
-            }
-    
-            let content = response.bytes().await.into_dataverse_result()?;
-            let result = serde_json::from_slice(content.as_ref()).into_dataverse_result()?;
-    
-            match result {
-                RetrieveMultipleResult {entities, next_link} => {
-                    Ok(Page::new(entities, next_link))
-                }
-            }
+        if self.is_url_too_long(&url_path) {
+            let parameters = build_query_parameters(columns, query, primary_key.as_deref());
+            return self.retrieve_multiple_via_post(query.logical_name, &parameters).await;
         }
 
-        self.request(
+        let result = self.request(
             Method::GET, 
             &url_path, 
-            move |request| Ok(request),
-            handle_response
-        ).await
+            |request| Ok(prefer_annotations::<E>(request)),
+            handle_page_response
+        ).await;
+
+        match result {
+            Err(error) if self.query_via_post && is_url_too_long(&error) => {
+                let parameters = build_query_parameters(columns, query, primary_key.as_deref());
+                self.retrieve_multiple_via_post(query.logical_name, &parameters).await
+            }
+            result => result,
+        }
+    }
+
+    /// returns the primary key to order the pages of the query by if stable paging is enabled
+    pub(crate) async fn get_stable_primary_key(&self, query: &Query) -> Result<Option<String>> {
+        if !self.stable_paging {
+            return Ok(None);
+        }
+
+        let names = self.resolve_entity_names(query.logical_name).await?;
+        Ok(Some(get_primary_id_attribute(&names.logical_name)))
+    }
+
+    /// sends the query options in the body of a `$query` request instead of the url
+    pub(crate) async fn retrieve_multiple_via_post<E: ReadEntity>(
+        &self,
+        table_name: &str,
+        parameters: &QueryParameters,
+    ) -> Result<Page<E>> {
+        let url_path = self.build_simple_url(format_args!("{}/$query", table_name));
+        let body = parameters.to_string().trim_start_matches('?').to_string();
+
+        self.request(
+            Method::POST,
+            &url_path,
+            move |request| {
+                Ok(prefer_annotations::<E>(request)
+                    .header("Content-Type", "text/plain")
+                    .body(body))
+            },
+            handle_page_response,
+        )
+        .await
     }
 
     /**
@@ -690,34 +1622,168 @@ impl<'url, A: Authenticate> Client<'url, A> {
         if previous_page.next_link.is_none() {
             return Err(DataverseError::new(String::from("There is no next page to retrieve")))
         }
-        
-        async fn handle_response<E: ReadEntity>(response: Response) -> Result<Page<E>> {
-            if response.status().is_client_error() || response.status().is_server_error() {
-                let error_message = response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| String::from("no error details provided from server"));
-                return Err(DataverseError::new(error_message));
-            }
-    
-            let content = response.bytes().await.into_dataverse_result()?;
-            let result = serde_json::from_slice(content.as_ref()).into_dataverse_result()?;
-    
-            match result {
-                RetrieveMultipleResult {entities, next_link} => {
-                    Ok(Page::new( entities, next_link))
-                }
-            }
-        }
 
         self.request(
             Method::GET, 
             previous_page.next_link.as_ref().unwrap(), 
-            move |request| Ok(request),
-            handle_response
+            |request| Ok(prefer_annotations::<E>(request)),
+            handle_page_response
         ).await
     }
 
+    /**
+    Continues a query from a next link that was saved from a previous `Page`
+
+    This allows checkpointing long running exports and resuming them after a
+    restart. The next link has to point to the same environment as this client,
+    otherwise this function fails before sending any request. The `$select`
+    option of the link is replaced with the columns of `E`, and the token of this
+    client is used for the request
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The next link is invalid or points to another environment
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Deserialize;
+    use powerplatform_dataverse_service_client::{
+        client::{Client, Page},
+        entity::ReadEntity,
+        result::Result,
+        select::Select,
+        query::Query
+    };
+
+    async fn test() -> Result<()> {
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        let page: Page<Contact> = client.retrieve_multiple(&Query::new("contacts")).await?;
+
+        // store the next link as a checkpoint
+        let checkpoint = page.get_next_link().map(String::from);
+
+        // ... and resume from it later on
+        if let Some(checkpoint) = checkpoint {
+            let next_page: Page<Contact> = client.resume_from_next_link(&checkpoint).await?;
+        }
+
+        Ok(())
+    }
+
+    #[derive(Deserialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+    }
+
+    impl ReadEntity for Contact {}
+
+    impl Select for Contact {
+        fn get_columns() -> &'static [&'static str] {
+            &["contactid", "firstname"]
+        }
+    }
+    ```
+    */
+    pub async fn resume_from_next_link<E: ReadEntity>(&self, next_link: &str) -> Result<Page<E>> {
+        let next_link = rebuild_next_link(&self.url, next_link, E::get_columns())?;
+        self.retrieve_next_page(&Page::new(Vec::new(), Some(next_link)))
+            .await
+    }
+
+    /**
+    retrieves all records matching the query by following the next links of all pages
+
+    Please note that a limit of the query applies to each page, so all records
+    matching the query are retrieved
+
+    Use `Query::no_follow_next_link()` to retrieve only the records of the first page
+
+    If the first order of the query is `Order::AscendingNullsLast(...)` or
+    `Order::DescendingNullsFirst(...)`, the records with and without a value in this
+    column are retrieved separately to place the `null` values as requested
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+
+    # Examples
+    ```rust
+    use uuid::Uuid;
+    use serde::Deserialize;
+    use powerplatform_dataverse_service_client::{
+        client::Client,
+        entity::ReadEntity,
+        query::Query,
+        result::Result,
+        select::Select
+    };
+
+    async fn test() -> Result<()> {
+        let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+        let contacts: Vec<Contact> = client.retrieve_all(&Query::new("contacts")).await?;
+        Ok(())
+    }
+
+    #[derive(Deserialize)]
+    struct Contact {
+        contactid: Uuid,
+        firstname: String,
+    }
+
+    impl ReadEntity for Contact {}
+
+    impl Select for Contact {
+        fn get_columns() -> &'static [&'static str] {
+            &["contactid", "firstname"]
+        }
+    }
+    ```
+    */
+    pub async fn retrieve_all<E: ReadEntity>(&self, query: &Query) -> Result<Vec<E>> {
+        let Some((first, second)) = query.split_by_nulls() else {
+            return self.retrieve_all_pages(query).await;
+        };
+
+        let mut entities = self.retrieve_all_pages::<E>(&first).await?;
+
+        if !query.follow_next_link {
+            return Ok(entities);
+        }
+
+        entities.append(&mut self.retrieve_all_pages(&second).await?);
+        Ok(entities)
+    }
+
+    async fn retrieve_all_pages<E: ReadEntity>(&self, query: &Query) -> Result<Vec<E>> {
+        let tracker = self.track_progress(Operation::Retrieve, None);
+        let mut page = self.retrieve_multiple::<E>(query).await?;
+        let mut entities = Vec::new();
+
+        loop {
+            tracker.report(entities.len() + page.entities.len());
+
+            let next_page = if page.is_incomplete() && query.follow_next_link {
+                Some(self.retrieve_next_page(&page).await?)
+            } else {
+                None
+            };
+
+            entities.append(&mut page.entities);
+
+            match next_page {
+                Some(next_page) => page = next_page,
+                None => break,
+            }
+        }
+
+        Ok(entities)
+    }
+
     /**
     executes the batch against the dataverse environment
 
@@ -732,6 +1798,12 @@ impl<'url, A: Authenticate> Client<'url, A> {
 
     Based on experience a batch size of 50 should be safe for all entities though
 
+    The returned `BatchResponse` contains the response of each request in the batch.
+    Entities returned by requests added with the `*_returning(...)` functions of
+    `Batch` can be extracted from it
+
+    This is only available with the `batch` feature, which is enabled by default
+
     # Examples
     ```rust
     use uuid::Uuid;
@@ -785,7 +1857,87 @@ impl<'url, A: Authenticate> Client<'url, A> {
     }
     ```
     */
-    pub async fn execute(&self, batch: &Batch) -> Result<()> {
+    #[cfg(feature = "batch")]
+    pub async fn execute(&self, batch: &Batch) -> Result<BatchResponse> {
+        if let Some(metrics) = self.get_metrics() {
+            metrics.record_batch(batch.get_count() as usize);
+        }
+
+        if let Some(mut auditor) = self.new_batch_auditor() {
+            batch.audit(&mut auditor).await?;
+        }
+
+        let body = batch.to_body().await?;
+        let boundary = format!("batch_{}", batch.get_batch_id().as_simple());
+        self.execute_body(&boundary, body).await
+    }
+
+    /**
+    executes the batch like `execute(...)` but consumes it
+
+    The payload of the batch becomes the request body without being copied,
+    which halves the memory needed for large in-memory batches
+
+    see `execute(...)` for the restrictions that apply to batches
+    */
+    #[cfg(feature = "batch")]
+    pub async fn execute_owned(&self, batch: Batch) -> Result<BatchResponse> {
+        if let Some(metrics) = self.get_metrics() {
+            metrics.record_batch(batch.get_count() as usize);
+        }
+
+        let boundary = format!("batch_{}", batch.get_batch_id().as_simple());
+        let body = batch.into_body()?;
+        self.audit_batch(&body);
+        self.execute_body(&boundary, Body::from(body)).await
+    }
+
+    /**
+    executes a pre-built batch body that is streamed from the given reader
+
+    The reader has to provide a complete multipart batch body with the given
+    boundary (e.g. `batch_<batch id>`). This is useful for batches that were
+    written to a file or are produced by another system
+
+    see `execute(...)` for the restrictions that apply to batches
+    */
+    #[cfg(feature = "batch")]
+    pub async fn execute_reader(
+        &self,
+        boundary: &str,
+        reader: impl AsyncRead + Send + Sync + 'static,
+    ) -> Result<BatchResponse> {
+        let Some(mut auditor) = self.new_batch_auditor() else {
+            let body = Body::wrap_stream(ReaderStream::new(reader));
+            return self.execute_body(boundary, body).await;
+        };
+
+        // the requests are passed to the audit trail while the body is streamed
+        let body = ReaderStream::new(reader).inspect_ok(move |chunk| auditor.push(chunk));
+        self.execute_body(boundary, Body::wrap_stream(body)).await
+    }
+
+    /// returns an auditor for batches that are read in chunks if there is an audit trail
+    #[cfg(feature = "batch")]
+    fn new_batch_auditor(&self) -> Option<BatchAuditor> {
+        let caller = self.identity.get().map(|identity| identity.user_id);
+        self.audit_trail
+            .as_ref()
+            .map(|sink| BatchAuditor::new(Arc::clone(sink), caller))
+    }
+
+    /// passes the writing requests of the batch payload to the audit trail if there is one
+    #[cfg(feature = "batch")]
+    pub(crate) fn audit_batch(&self, payload: &[u8]) {
+        if let Some(sink) = self.audit_trail.as_ref() {
+            let caller = self.identity.get().map(|identity| identity.user_id);
+            let payload = String::from_utf8_lossy(payload);
+            collect_batch_entries(&payload, caller).iter().for_each(|entry| sink(entry));
+        }
+    }
+
+    #[cfg(feature = "batch")]
+    async fn execute_body(&self, boundary: &str, body: Body) -> Result<BatchResponse> {
         let url_path = self.build_simple_url("$batch");
 
         self.request(
@@ -793,11 +1945,11 @@ impl<'url, A: Authenticate> Client<'url, A> {
             &url_path, 
             move |request| {
                 Ok(request
-                    .header("Content-Type", format!("multipart/mixed; boundary=batch_{}", batch.get_batch_id()))
-                    .body(batch.to_string())
+                    .header("Content-Type", format!("multipart/mixed; boundary={}", boundary))
+                    .body(body)
                 )
             }, 
-            handle_empty_response
+            handle_batch_response
         ).await
     }
 
@@ -810,6 +1962,8 @@ impl<'url, A: Authenticate> Client<'url, A> {
     - lead
     - incident
 
+    see `execute_merge(...)` for merges with parenting checks or updated columns
+
     # Examples
     ```rust
     use uuid::Uuid;
@@ -826,25 +1980,11 @@ impl<'url, A: Authenticate> Client<'url, A> {
     ```
     */
     pub async fn merge(&self, entity_name: impl Display, target: Uuid, subordinate: Uuid) -> Result<()> {
-        let url_path = self.build_simple_url("Merge");
-
-        self.request(
-            Method::POST,
-            &url_path, 
-            move |request| {
-                let entity_name = entity_name.to_string();
-                let merge_request = MergeRequest::new(&entity_name, target, subordinate, false);
-
-                Ok(request
-                    .header("Content-Type", "application/json")
-                    .body(serde_json::to_vec(&merge_request).into_dataverse_result()?)
-                )
-            }, 
-            handle_empty_response,
-        ).await
+        let entity_name = entity_name.to_string();
+        self.execute_merge(&MergeRequest::new(&entity_name, target, subordinate, false)).await
     }
 
-    async fn request<E, Fut>(
+    pub(crate) async fn request<E, Fut>(
         &self,
         method: Method,
         url: &str, 
@@ -852,76 +1992,257 @@ impl<'url, A: Authenticate> Client<'url, A> {
         response_consumer: impl FnOnce(Response) -> Fut,
     ) -> Result<E> 
     where Fut: Future<Output = Result<E>>{
-        let token = self.auth.get_valid_token().await?;
-
-        let response = request_preparer(self.backend.request(method, url))?
+        let token = self
+            .auth
+            .get_valid_token()
+            .await
+            .map_err(|error| DataverseError::with_kind(ErrorKind::Authentication, error.message))?;
+
+        let request = self
+            .prepare_request(method.clone(), url, request_preparer)?
             .bearer_auth(token)
-            .header("OData-MaxVersion", "4.0")
-            .header("OData-Version", "4.0")
-            .header("Accept", "application/json")
-            .send().await.into_dataverse_result()?;
+            .build();
+
+        let captured_body = match (self.failure_capture.as_ref(), request.as_ref()) {
+            (Some(_), Ok(request)) => request
+                .body()
+                .and_then(Body::as_bytes)
+                .map(Bytes::copy_from_slice),
+            _ => None,
+        };
+
+        if let (Some(sink), Ok(request)) = (self.audit_trail.as_ref(), request.as_ref()) {
+            let caller = self.identity.get().map(|identity| identity.user_id);
+            collect_entries(request, caller).iter().for_each(|entry| sink(entry));
+        }
+
+        let started = Instant::now();
+        let response = match request {
+            Ok(request) => self.send(request).await,
+            Err(error) => Err(error).into_dataverse_result(),
+        };
+
+        if let Some(metrics) = self.get_metrics() {
+            metrics.record_request(&RequestMetric {
+                method: &method,
+                url,
+                status: response.as_ref().ok().map(|response| response.status().as_u16()),
+                duration: started.elapsed(),
+            });
+        }
+
+        let response = response?;
+
+        if let Ok(mut last_response) = self.last_response.lock() {
+            *last_response = Some(ResponseMeta::new(&method, url, response.status().as_u16(), response.headers()));
+        }
+
+        if let Some(capture) = self.failure_capture.as_ref() {
+            if response.status().is_client_error() || response.status().is_server_error() {
+                capture.capture(&method, url, response.status().as_u16(), captured_body.as_deref());
+            }
+        }
+
+        if let Some(handler) = self.warning_handler.as_ref() {
+            for warning in collect_warnings(&method, url, response.headers()) {
+                handler(&warning);
+            }
+        }
+
+        if let Some(error) = check_request_size(response.status()) {
+            return Err(error);
+        }
 
         response_consumer(response).await
     }
 
-    fn build_simple_url(&self, table_name: impl Display) -> String {
-        format!("{}api/data/v{}/{}", self.url, VERSION, table_name)
+    async fn send(&self, request: reqwest::Request) -> Result<Response> {
+        #[cfg(feature = "fault-injection")]
+        if let Some(policy) = self.fault_policy.as_ref() {
+            if let Some(response) = policy.inject(self.timer.as_ref()).await {
+                return Ok(response);
+            }
+        }
+
+        let Some(failover) = self.failover.as_ref() else {
+            return self.transport.execute(request).await;
+        };
+
+        let mut request = request;
+        let mut remaining = failover.len();
+
+        loop {
+            let index = failover.get_active();
+            remaining -= 1;
+
+            let retry = match remaining > 0 {
+                true => request.try_clone(),
+                false => None,
+            };
+
+            failover.redirect(&mut request, index)?;
+
+            match self.transport.execute(request).await {
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    failover.advance(index);
+
+                    match retry {
+                        Some(retry) => request = retry,
+                        None => return Err(error),
+                    }
+                }
+            }
+        }
+    }
+
+    /// applies the preparer and the headers of the Web API to a new request without authenticating it
+    pub(crate) fn prepare_request(
+        &self,
+        method: Method,
+        url: &str,
+        request_preparer: impl FnOnce(RequestBuilder) -> Result<RequestBuilder>,
+    ) -> Result<RequestBuilder> {
+        Ok(request_preparer(self.backend.request(method, url))?
+            .header("OData-MaxVersion", "4.0")
+            .header("OData-Version", "4.0")
+            .header("Accept", "application/json"))
     }
 
-    fn build_targeted_url(&self, table_name: impl Display, target_id: Uuid) -> String {
-        format!(
-            "{}api/data/v{}/{}({})",
-            self.url,
-            VERSION,
-            table_name,
-            target_id.as_hyphenated()
-        )
+    pub(crate) fn build_simple_url(&self, table_name: impl Display) -> String {
+        let mut url = self.build_api_url();
+        // writing into a String cannot fail
+        let _ = write!(url, "{}", table_name);
+        url
+    }
+
+    pub(crate) fn build_targeted_url(&self, table_name: impl Display, target_id: Uuid) -> String {
+        let mut url = self.build_api_url();
+        let _ = write!(url, "{}({})", table_name, target_id.as_hyphenated());
+        url
     }
 
     fn build_retrieve_url(&self, table_name: impl Display, target_id: Uuid, columns: &[&str]) -> String {
-        let mut select = String::new();
-        let mut comma_required = false;
+        let mut url = self.build_targeted_url(table_name, target_id);
+        write_select(&mut url, columns);
+        url
+    }
 
-        for column in columns {
-            if comma_required {
-                select.push(',');
-            }
+    pub(crate) fn build_query_url(&self, columns: &[&str], query: &Query, primary_key: Option<&str>) -> String {
+        let parameters = build_query_parameters(columns, query, primary_key);
+        let mut url = self.build_simple_url(query.logical_name);
+        parameters.write_to(&mut url);
+        url
+    }
 
-            select.push_str(column);
-            comma_required = true;
-        }
+    /// creates the buffer for an url with the base path of the Web API and room for the rest of the url
+    fn build_api_url(&self) -> String {
+        let mut url = String::with_capacity(self.url.len() + URL_CAPACITY);
+        url.push_str(&self.url);
+        url.push_str("api/data/v");
+        url.push_str(VERSION);
+        url.push('/');
+        url
+    }
+}
 
-        format!(
-            "{}api/data/v{}/{}({})?$select={}",
-            self.url,
-            VERSION,
-            table_name,
-            target_id.as_hyphenated(),
-            select
-        )
+/// builds the query options for the selected columns and the query
+pub(crate) fn build_query_parameters(columns: &[&str], query: &Query, primary_key: Option<&str>) -> QueryParameters {
+    let mut parameters = QueryParameters::new();
+    parameters.push_select(columns);
+
+    match primary_key {
+        Some(primary_key) => query.append_stable_parameters(&mut parameters, primary_key),
+        None => query.append_parameters(&mut parameters),
     }
 
-    fn build_query_url(&self, table_name: impl Display, columns: &[&str], query: &Query) -> String {
-        let mut select = String::new();
-        let mut comma_required = false;
+    parameters
+}
 
-        for column in columns {
-            if comma_required {
-                select.push(',');
-            }
+pub(crate) async fn handle_page_response<E: ReadEntity>(response: Response) -> Result<Page<E>> {
+    if response.status().is_client_error() || response.status().is_server_error() {
+        let error_message = response
+            .text()
+            .await
+            .unwrap_or_else(|_| String::from("no error details provided from server"));
+        return Err(DataverseError::new(error_message));
+    }
 
-            select.push_str(column);
-            comma_required = true;
+    let content = response.bytes().await.into_dataverse_result()?;
+    let RetrieveMultipleResult { entities, next_link, delta_link } = json::page_from_slice(content.as_ref())?;
+    let mut page = Page::new(entities, next_link);
+    page.delta_link = delta_link;
+    Ok(page)
+}
+
+/// creates the error for requests the server rejected because of their size
+fn check_request_size(status: StatusCode) -> Option<DataverseError> {
+    let guidance = match status {
+        StatusCode::PAYLOAD_TOO_LARGE => "its payload is too large",
+        StatusCode::URI_TOO_LONG | StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE => {
+            "its url is too long. Queries with long filters can be sent in the request body by enabling `Client::with_query_via_post()`"
         }
+        _ => return None,
+    };
 
-        format!(
-            "{}api/data/v{}/{}{}&$select={}",
-            self.url, VERSION, table_name, query, select
-        )
+    Some(DataverseError::with_kind(
+        ErrorKind::RequestTooLarge {
+            status: status.as_u16(),
+        },
+        format!("request was rejected with status {} because {}", status.as_u16(), guidance),
+    ))
+}
+
+/// Indicates if the server rejected a request because of the length of its url
+fn is_url_too_long(error: &DataverseError) -> bool {
+    matches!(error.kind, ErrorKind::RequestTooLarge { status: 414 | 431 })
+}
+
+/// validates that the next link points to the environment of the base url and restores its `$select`
+fn rebuild_next_link(base_url: &str, next_link: &str, columns: &[&str]) -> Result<String> {
+    let base_url = reqwest::Url::parse(base_url).into_dataverse_result()?;
+    let mut next_link = reqwest::Url::parse(next_link).into_dataverse_result()?;
+
+    if next_link.scheme() != base_url.scheme()
+        || next_link.host_str() != base_url.host_str()
+        || next_link.port_or_known_default() != base_url.port_or_known_default()
+        || !next_link.path().starts_with(&format!("{}api/data/", base_url.path()))
+    {
+        return Err(DataverseError::new(format!(
+            "next link {} does not belong to the environment {}",
+            next_link, base_url
+        )));
+    }
+
+    let mut options = Vec::new();
+
+    if !columns.is_empty() {
+        options.push(format!("$select={}", columns.join(",")));
     }
+
+    options.extend(
+        next_link
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|option| !option.is_empty())
+            .filter(|option| !option.starts_with("$select=") && !option.starts_with("%24select="))
+            .map(String::from),
+    );
+
+    next_link.set_query(Some(&options.join("&")));
+    Ok(next_link.to_string())
 }
 
-async fn handle_empty_response(response: Response) -> Result<()> {
+/// adds the preference for formatted value annotations if the entity needs them
+pub(crate) fn prefer_annotations<E: ReadEntity>(request: RequestBuilder) -> RequestBuilder {
+    match get_annotations_preference::<E>() {
+        Some(preference) => request.header("Prefer", preference),
+        None => request,
+    }
+}
+
+pub(crate) async fn handle_empty_response(response: Response) -> Result<()> {
     if response.status().is_client_error() || response.status().is_server_error() {
         let error_message = response.text().await.unwrap_or_else(|_| String::from("no error details provided from server"));
         return Err(DataverseError::new(error_message));
@@ -930,6 +2251,61 @@ async fn handle_empty_response(response: Response) -> Result<()> {
     Ok(())
 }
 
+pub(crate) async fn handle_json_response<T: DeserializeOwned>(response: Response) -> Result<T> {
+    if response.status().is_client_error() || response.status().is_server_error() {
+        let error_message = response.text().await.unwrap_or_else(|_| String::from("no error details provided from server"));
+        return Err(DataverseError::new(error_message));
+    }
+
+    let content = response.bytes().await.into_dataverse_result()?;
+    json::from_slice(content.as_ref())
+}
+
+#[cfg(feature = "batch")]
+async fn handle_batch_response(response: Response) -> Result<BatchResponse> {
+    if response.status().is_client_error() || response.status().is_server_error() {
+        let error_message = response.text().await.unwrap_or_else(|_| String::from("no error details provided from server"));
+        return Err(DataverseError::new(error_message));
+    }
+
+    let content_type = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let content = response.text().await.into_dataverse_result()?;
+    BatchResponse::parse(&content_type, &content)
+}
+
+/**
+The result of a conditional retrieve by `retrieve_if_modified()`
+*/
+#[derive(Debug)]
+pub enum Conditional<E> {
+    /// The record changed (or no ETag was given) and was retrieved with its current ETag
+    Modified { entity: E, etag: Option<String> },
+
+    /// The record didn't change since the given ETag was issued
+    NotModified,
+}
+
+impl<E> Conditional<E> {
+    /// Indicates if the record didn't change since the given ETag was issued
+    pub fn is_not_modified(&self) -> bool {
+        matches!(self, Conditional::NotModified)
+    }
+
+    /// returns the retrieved entity if it was modified
+    pub fn into_modified(self) -> Option<E> {
+        match self {
+            Conditional::Modified { entity, .. } => Some(entity),
+            Conditional::NotModified => None,
+        }
+    }
+}
+
 /**
 A page of retrieved entites by the `retrieve_multiple()` and `retrieve_next_page()`
 by a client instance 
@@ -938,6 +2314,7 @@ by a client instance
 pub struct Page<E> {
     pub entities: Vec<E>,
     next_link: Option<String>,
+    delta_link: Option<String>,
 }
 
 impl<E> Page<E> {
@@ -945,6 +2322,7 @@ impl<E> Page<E> {
         Self {
             entities,
             next_link,
+            delta_link: None,
         }
     }
 
@@ -953,16 +2331,188 @@ impl<E> Page<E> {
         self.next_link.is_some()
     }
 
+    /**
+    returns the link to the next page if there are more records available
+
+    The link can be stored to resume the query later on with
+    `Client::resume_from_next_link(...)`
+    */
+    pub fn get_next_link(&self) -> Option<&str> {
+        self.next_link.as_deref()
+    }
+
+    /**
+    returns the delta link of the last page of a query that tracks changes
+
+    The link retrieves the records that changed since the query, see `ExportJob::track_changes()`
+    */
+    pub fn get_delta_link(&self) -> Option<&str> {
+        self.delta_link.as_deref()
+    }
+
     /// Transforms the page into its content as a `Vec`
     pub fn into_inner(self) -> Vec<E> {
         self.entities
     }
 }
 
-#[derive(Deserialize)]
-struct RetrieveMultipleResult<E> {
-    #[serde(rename = "value")]
-    entities: Vec<E>,
-    #[serde(rename = "@odata.nextLink")]
-    next_link: Option<String>,
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        client::{check_request_size, is_url_too_long, rebuild_next_link, Client},
+        error::ErrorKind,
+        query::{attribute::Attribute, filter::Filter, order::Order, Query},
+    };
+
+    #[test]
+    fn request_size_errors() {
+        let error = check_request_size(reqwest::StatusCode::URI_TOO_LONG).unwrap();
+        assert_eq!(error.kind, ErrorKind::RequestTooLarge { status: 414 });
+        assert!(is_url_too_long(&error));
+
+        let error = check_request_size(reqwest::StatusCode::PAYLOAD_TOO_LARGE).unwrap();
+        assert!(!is_url_too_long(&error));
+        assert!(check_request_size(reqwest::StatusCode::BAD_REQUEST).is_none());
+    }
+
+    #[test]
+    fn url_length_threshold() {
+        let client = Client::new_dummy();
+        assert!(!client.is_url_too_long(&"a".repeat(100_000)));
+
+        let client = client.with_max_url_length(16);
+        assert!(client.query_via_post);
+        assert!(!client.is_url_too_long("contacts?$top=5"));
+        assert!(client.is_url_too_long("contacts?$top=5&$select=firstname"));
+    }
+
+    #[test]
+    fn payload_size_guard() {
+        let client = Client::new_dummy();
+        assert!(client.check_payload_size(&[0; 1024]).is_ok());
+
+        let client = client.with_max_payload_size(512);
+        assert!(client.check_payload_size(&[0; 512]).is_ok());
+        assert_eq!(
+            client.check_payload_size(&[0; 1024]).unwrap_err().kind,
+            ErrorKind::PayloadTooLarge {
+                size: 1024,
+                limit: 512
+            }
+        );
+    }
+
+    #[test]
+    fn retrieve_url() {
+        let client = Client::new_dummy();
+        assert_eq!(
+            client.build_retrieve_url("contacts", Uuid::nil(), &["firstname", "lastname"]),
+            "api/data/v9.2/contacts(00000000-0000-0000-0000-000000000000)?$select=firstname,lastname"
+        );
+    }
+
+    #[test]
+    fn retrieve_url_without_columns() {
+        let client = Client::new_dummy();
+        assert_eq!(
+            client.build_retrieve_url("contacts", Uuid::nil(), &[]),
+            "api/data/v9.2/contacts(00000000-0000-0000-0000-000000000000)"
+        );
+    }
+
+    #[test]
+    fn query_url_without_options() {
+        let client = Client::new_dummy();
+        assert_eq!(
+            client.build_query_url(&[], &Query::new("contacts"), None),
+            "api/data/v9.2/contacts"
+        );
+    }
+
+    #[test]
+    fn query_url_with_select_only() {
+        let client = Client::new_dummy();
+        assert_eq!(
+            client.build_query_url(&["firstname"], &Query::new("contacts"), None),
+            "api/data/v9.2/contacts?$select=firstname"
+        );
+    }
+
+    #[test]
+    fn query_url_with_options_only() {
+        let client = Client::new_dummy();
+        assert_eq!(
+            client.build_query_url(&[], &Query::new("contacts").limit(3), None),
+            "api/data/v9.2/contacts?$top=3"
+        );
+    }
+
+    #[test]
+    fn query_url_with_select_and_options() {
+        let client = Client::new_dummy();
+        let query = Query::new("contacts")
+            .limit(3)
+            .filter(Filter::Equal("firstname", Attribute::String(String::from("Testy"))))
+            .order(vec![Order::Ascending("lastname")]);
+
+        assert_eq!(
+            client.build_query_url(&["firstname", "lastname"], &query, None),
+            "api/data/v9.2/contacts?$select=firstname,lastname&$top=3&$filter=firstname eq 'Testy'&$orderby=lastname asc"
+        );
+    }
+
+    #[test]
+    fn query_url_with_stable_order() {
+        let client = Client::new_dummy();
+        let query = Query::new("contacts").order(vec![Order::Ascending("lastname")]);
+
+        assert_eq!(
+            client.build_query_url(&[], &query, Some("contactid")),
+            "api/data/v9.2/contacts?$orderby=lastname asc,contactid asc"
+        );
+        assert_eq!(
+            client.build_query_url(&[], &Query::new("contacts"), Some("contactid")),
+            "api/data/v9.2/contacts?$orderby=contactid asc"
+        );
+
+        let query = Query::new("contacts").order(vec![Order::Descending("contactid")]);
+        assert_eq!(
+            client.build_query_url(&[], &query, Some("contactid")),
+            "api/data/v9.2/contacts?$orderby=contactid desc"
+        );
+    }
+
+    #[test]
+    fn next_link_restores_select() {
+        let next_link = rebuild_next_link(
+            "https://instance.crm.dynamics.com/",
+            "https://instance.crm.dynamics.com/api/data/v9.2/contacts?$select=fullname&$skiptoken=%3Ccookie%20pagenumber=%222%22%20/%3E",
+            &["contactid", "firstname"],
+        )
+        .unwrap();
+
+        assert_eq!(
+            next_link,
+            "https://instance.crm.dynamics.com/api/data/v9.2/contacts?$select=contactid,firstname&$skiptoken=%3Ccookie%20pagenumber=%222%22%20/%3E"
+        );
+    }
+
+    #[test]
+    fn next_link_of_other_environment() {
+        assert!(rebuild_next_link(
+            "https://instance.crm.dynamics.com/",
+            "https://attacker.example.com/api/data/v9.2/contacts?$skiptoken=1",
+            &[],
+        )
+        .is_err());
+
+        assert!(rebuild_next_link(
+            "https://instance.crm.dynamics.com/",
+            "https://instance.crm.dynamics.com/other/contacts?$skiptoken=1",
+            &[],
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,484 @@
+/*!
+Module for coercing the values of write payloads into the column types of an environment
+
+When the same data is synchronized into several environments, the types of their columns
+can differ slightly, e.g. a whole number in one environment is a decimal number in another,
+or a text column is shorter. A `CoercionSchema` contains the column types of the target
+environment, read from its metadata with `Client::retrieve_coercion_schema(...)`, and adapts
+the values of outgoing payloads to them
+
+Conversions that change a value, e.g. rounding a decimal number or truncating a text,
+are reported as `LossyConversion` instead of letting the write fail. Values that cannot
+be converted at all are left unchanged and reported as well. The schema is usually
+registered as the write hook of the client, so it applies to direct writes, bulk
+functions and batches alike
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let schema = client.retrieve_coercion_schema(&["contacts", "accounts"]).await?;
+
+    let client = client.with_write_hook(schema.into_write_hook(|conversion| {
+        println!(
+            "{} of {} was written as {} instead of {}",
+            conversion.column, conversion.entity_name, conversion.coerced, conversion.original
+        );
+    }));
+    Ok(())
+}
+```
+*/
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+
+use crate::{auth::Authenticate, client::Client, hook::WriteContext, result::Result};
+
+/// The type of a column that values are coerced into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    Boolean,
+    /// a 32-bit whole number, including choices and status columns
+    Integer,
+    /// a 64-bit whole number
+    BigInt,
+    /// a decimal, floating point or currency number
+    Decimal,
+    /// a single or multiple lines of text with their maximum number of characters
+    Text {
+        max_length: Option<usize>,
+    },
+    /// a column whose values are not coerced
+    Other,
+}
+
+impl ColumnType {
+    /// returns the type of a column with the given `AttributeType`
+    pub fn from_attribute_type(attribute_type: &str, max_length: Option<usize>) -> Self {
+        match attribute_type {
+            "Boolean" => Self::Boolean,
+            "Integer" | "Picklist" | "State" | "Status" => Self::Integer,
+            "BigInt" => Self::BigInt,
+            "Decimal" | "Double" | "Money" => Self::Decimal,
+            "String" | "Memo" => Self::Text { max_length },
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A value of a payload that was changed or could not be converted into the type of its column
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossyConversion {
+    /// the entity set name of the written record
+    pub entity_name: String,
+    pub column: String,
+    pub column_type: ColumnType,
+    pub original: Value,
+    /// the written value, which equals the original value if it could not be converted
+    pub coerced: Value,
+}
+
+/// The column types of the entities of an environment, keyed by their entity set names
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoercionSchema {
+    entities: HashMap<String, HashMap<String, ColumnType>>,
+}
+
+impl CoercionSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the column types of the entity with the given entity set name
+    pub fn insert(&mut self, entity_name: impl Into<String>, columns: HashMap<String, ColumnType>) {
+        self.entities.insert(entity_name.into(), columns);
+    }
+
+    /// returns the type of the column of the entity with the given entity set name
+    pub fn get_column_type(&self, entity_name: &str, column: &str) -> Option<ColumnType> {
+        self.entities.get(entity_name)?.get(column).copied()
+    }
+
+    /**
+    coerces the values of the payload into the column types of the entity and returns
+    the lossy conversions
+
+    Columns and entities that are not part of this schema are left unchanged
+    */
+    pub fn coerce(
+        &self,
+        entity_name: &str,
+        payload: &mut Map<String, Value>,
+    ) -> Vec<LossyConversion> {
+        let Some(columns) = self.entities.get(entity_name) else {
+            return Vec::new();
+        };
+
+        let mut conversions = Vec::new();
+
+        for (column, value) in payload.iter_mut() {
+            let Some(column_type) = columns.get(column).copied() else {
+                continue;
+            };
+
+            let (coerced, is_lossy) = coerce_value(value, column_type);
+
+            if is_lossy {
+                conversions.push(LossyConversion {
+                    entity_name: entity_name.to_string(),
+                    column: column.clone(),
+                    column_type,
+                    original: value.clone(),
+                    coerced: coerced.clone(),
+                });
+            }
+
+            *value = coerced;
+        }
+
+        conversions
+    }
+
+    /// turns this schema into a write hook that passes the lossy conversions to the handler
+    pub fn into_write_hook(
+        self,
+        handler: impl Fn(&LossyConversion) + Send + Sync + 'static,
+    ) -> impl Fn(&WriteContext, &mut Map<String, Value>) + Send + Sync + 'static {
+        move |context, payload| {
+            self.coerce(context.entity_name, payload)
+                .iter()
+                .for_each(&handler);
+        }
+    }
+}
+
+/// returns the value converted into the column type and whether the conversion is lossy
+fn coerce_value(value: &Value, column_type: ColumnType) -> (Value, bool) {
+    let coerced = match (column_type, value) {
+        (_, Value::Null) | (ColumnType::Other, _) => return (value.clone(), false),
+        (ColumnType::Boolean, value) => coerce_boolean(value),
+        (ColumnType::Integer, value) => {
+            coerce_whole_number(value, i64::from(i32::MIN), i64::from(i32::MAX))
+        }
+        (ColumnType::BigInt, value) => coerce_whole_number(value, i64::MIN, i64::MAX),
+        (ColumnType::Decimal, value) => coerce_decimal(value),
+        (ColumnType::Text { max_length }, value) => return coerce_text(value, max_length),
+    };
+
+    match coerced {
+        Some((coerced, is_lossy)) => (coerced, is_lossy),
+        None => (value.clone(), true),
+    }
+}
+
+fn coerce_boolean(value: &Value) -> Option<(Value, bool)> {
+    match value {
+        Value::Bool(_) => Some((value.clone(), false)),
+        Value::Number(number) => match number.as_f64()? {
+            0.0 => Some((Value::Bool(false), false)),
+            1.0 => Some((Value::Bool(true), false)),
+            _ => None,
+        },
+        Value::String(text) => match text.trim().to_lowercase().as_str() {
+            "true" | "1" => Some((Value::Bool(true), false)),
+            "false" | "0" => Some((Value::Bool(false), false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn coerce_whole_number(value: &Value, min: i64, max: i64) -> Option<(Value, bool)> {
+    let whole = match value {
+        Value::Number(number) if number.is_i64() || number.is_u64() => number.as_i64(),
+        Value::String(text) => text.trim().parse::<i64>().ok(),
+        _ => None,
+    };
+
+    if let Some(whole) = whole {
+        return (min..=max).contains(&whole).then(|| (Value::from(whole), false));
+    }
+
+    let number = match value {
+        Value::Number(number) if number.is_f64() => number.as_f64()?,
+        Value::String(text) => text.trim().parse::<f64>().ok()?,
+        Value::Bool(flag) => return Some((Value::from(i64::from(*flag)), false)),
+        _ => return None,
+    };
+
+    let rounded = number.round();
+
+    // `i64::MAX as f64` rounds up to 2^63, so the upper bound is the exclusive `max + 1`
+    if !(min as f64..max as f64 + 1.0).contains(&rounded) {
+        return None;
+    }
+
+    Some((Value::from(rounded as i64), rounded != number))
+}
+
+fn coerce_decimal(value: &Value) -> Option<(Value, bool)> {
+    match value {
+        Value::Number(_) => Some((value.clone(), false)),
+        Value::String(text) => {
+            let text = text.trim();
+            let parsed = text.parse::<f64>().ok()?;
+            let number = Number::from_f64(parsed)?;
+            let is_lossy = normalize_decimal(text) != normalize_decimal(&parsed.to_string());
+            Some((Value::Number(number), is_lossy))
+        }
+        _ => None,
+    }
+}
+
+/**
+returns the sign, the significant digits and the position of the decimal point of a number
+
+Numbers that only differ in their notation, e.g. `1000.50`, `+1000.5` and `1.0005e3`,
+return the same result, so they can be compared by their value
+*/
+fn normalize_decimal(text: &str) -> Option<(bool, String, i64)> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction);
+
+    if !digits.chars().all(|digit| digit.is_ascii_digit()) {
+        return None;
+    }
+
+    let significant = digits.trim_matches('0');
+
+    if significant.is_empty() {
+        return Some((false, String::new(), 0));
+    }
+
+    let leading_zeros = digits.len() - digits.trim_start_matches('0').len();
+    let point = integer.len() as i64 - leading_zeros as i64 + exponent;
+    Some((negative, significant.to_string(), point))
+}
+
+fn coerce_text(value: &Value, max_length: Option<usize>) -> (Value, bool) {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => return (value.clone(), true),
+    };
+
+    match max_length {
+        Some(max_length) if text.chars().count() > max_length => {
+            (Value::String(text.chars().take(max_length).collect()), true)
+        }
+        _ => (Value::String(text), false),
+    }
+}
+
+#[derive(Deserialize)]
+struct AttributeDefinitions<T> {
+    value: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct AttributeDefinition {
+    #[serde(rename = "LogicalName")]
+    logical_name: String,
+    #[serde(rename = "AttributeType")]
+    attribute_type: String,
+}
+
+#[derive(Deserialize)]
+struct TextAttributeDefinition {
+    #[serde(rename = "LogicalName")]
+    logical_name: String,
+    #[serde(rename = "MaxLength")]
+    max_length: Option<usize>,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the column types of the entities (logical or entity set names) from their metadata
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no entity with one of the given names
+    */
+    pub async fn retrieve_coercion_schema(&self, entity_names: &[&str]) -> Result<CoercionSchema> {
+        let mut schema = CoercionSchema::new();
+
+        for entity_name in entity_names {
+            let names = self.resolve_entity_names(entity_name).await?;
+            let columns = self.retrieve_column_types(&names.logical_name).await?;
+            schema.insert(names.collection_name, columns);
+        }
+
+        Ok(schema)
+    }
+
+    /// retrieves the types of the columns of the entity with the given logical name
+    async fn retrieve_column_types(
+        &self,
+        logical_name: &str,
+    ) -> Result<HashMap<String, ColumnType>> {
+        let definitions: AttributeDefinitions<AttributeDefinition> = self
+            .execute_function(&format!(
+                "EntityDefinitions(LogicalName='{}')/Attributes?$select=LogicalName,AttributeType",
+                logical_name
+            ))
+            .await?;
+
+        let mut max_lengths = HashMap::new();
+
+        for metadata_type in ["StringAttributeMetadata", "MemoAttributeMetadata"] {
+            let text_definitions: AttributeDefinitions<TextAttributeDefinition> = self
+                .execute_function(&format!(
+                    "EntityDefinitions(LogicalName='{}')/Attributes/Microsoft.Dynamics.CRM.{}?$select=LogicalName,MaxLength",
+                    logical_name, metadata_type
+                ))
+                .await?;
+
+            max_lengths.extend(
+                text_definitions
+                    .value
+                    .into_iter()
+                    .map(|definition| (definition.logical_name, definition.max_length)),
+            );
+        }
+
+        Ok(definitions
+            .value
+            .into_iter()
+            .map(|definition| {
+                let max_length = max_lengths.get(&definition.logical_name).copied().flatten();
+                let column_type =
+                    ColumnType::from_attribute_type(&definition.attribute_type, max_length);
+                (definition.logical_name, column_type)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::{coerce_value, CoercionSchema, ColumnType};
+
+    #[test]
+    fn coerces_payload_and_reports_lossy_conversions() {
+        let mut schema = CoercionSchema::new();
+        schema.insert(
+            "contacts",
+            HashMap::from([
+                (String::from("numberofchildren"), ColumnType::Integer),
+                (String::from("creditlimit"), ColumnType::Decimal),
+                (
+                    String::from("jobtitle"),
+                    ColumnType::Text {
+                        max_length: Some(5),
+                    },
+                ),
+                (
+                    String::from("description"),
+                    ColumnType::Text { max_length: None },
+                ),
+                (String::from("donotemail"), ColumnType::Boolean),
+            ]),
+        );
+
+        let mut payload = json!({
+            "numberofchildren": 2.6,
+            "creditlimit": "1000.5",
+            "jobtitle": "Developer",
+            "description": 42,
+            "donotemail": "maybe",
+            "firstname": 7
+        });
+        let conversions = schema.coerce("contacts", payload.as_object_mut().unwrap());
+
+        assert_eq!(
+            payload,
+            json!({
+                "numberofchildren": 3,
+                "creditlimit": 1000.5,
+                "jobtitle": "Devel",
+                "description": "42",
+                "donotemail": "maybe",
+                "firstname": 7
+            })
+        );
+
+        let mut columns: Vec<&str> = conversions
+            .iter()
+            .map(|conversion| conversion.column.as_str())
+            .collect();
+        columns.sort();
+        assert_eq!(columns, vec!["donotemail", "jobtitle", "numberofchildren"]);
+
+        let mut payload = json!({ "numberofchildren": 3 });
+        assert!(schema
+            .coerce("accounts", payload.as_object_mut().unwrap())
+            .is_empty());
+        assert!(schema
+            .coerce("contacts", payload.as_object_mut().unwrap())
+            .is_empty());
+    }
+
+    #[test]
+    fn reports_lossy_numbers() {
+        assert_eq!(coerce_value(&json!("1000.50"), ColumnType::Decimal), (json!(1000.5), false));
+        assert_eq!(coerce_value(&json!("1.0005e3"), ColumnType::Decimal), (json!(1000.5), false));
+        assert_eq!(coerce_value(&json!("0.1"), ColumnType::Decimal), (json!(0.1), false));
+        assert!(coerce_value(&json!("12345678901234567890.123"), ColumnType::Decimal).1);
+        assert!(coerce_value(&json!("0.10000000000000000001"), ColumnType::Decimal).1);
+
+        assert_eq!(
+            coerce_value(&json!(i64::MAX), ColumnType::BigInt),
+            (json!(i64::MAX), false)
+        );
+        assert_eq!(
+            coerce_value(&json!("9223372036854775807"), ColumnType::BigInt),
+            (json!(i64::MAX), false)
+        );
+        assert_eq!(
+            coerce_value(&json!(9.3e18), ColumnType::BigInt),
+            (json!(9.3e18), true)
+        );
+        // i64::MAX as a float is 2^63, which doesn't fit into an i64
+        assert_eq!(
+            coerce_value(&json!(i64::MAX as f64), ColumnType::BigInt),
+            (json!(i64::MAX as f64), true)
+        );
+        assert_eq!(
+            coerce_value(&json!(9223372036854775808u64), ColumnType::BigInt),
+            (json!(9223372036854775808u64), true)
+        );
+        assert_eq!(
+            coerce_value(&json!(2147483647.4), ColumnType::Integer),
+            (json!(2147483647), true)
+        );
+        assert_eq!(
+            coerce_value(&json!(2147483647.6), ColumnType::Integer),
+            (json!(2147483647.6), true)
+        );
+    }
+}
@@ -0,0 +1,138 @@
+/*!
+Module for reading records right after they were created
+
+Dataverse occasionally answers a retrieve of a record that was created a moment ago
+with `404 Not Found`, because the read hits a replica that didn't receive the write yet.
+With `Client::with_read_your_writes(...)` the client remembers the records it created
+and retries a retrieve of such a record with an exponential backoff before it reports
+that the record doesn't exist. Retrieves of other records fail right away as before
+
+Only records created with `Client::create(...)` (or the functions built on it) are
+remembered, and only for the configured window after their creation
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    consistency::ReadYourWrites
+};
+
+let client = Client::new_dummy() // Please replace this with your preferred authentication method
+    .with_read_your_writes(ReadYourWrites::new().with_max_retries(3));
+```
+*/
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+/// period after a create in which retrieves of the record are retried by default
+pub static DEFAULT_WINDOW: Duration = Duration::from_secs(30);
+
+/// The retries of retrieves of records that were just created
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadYourWrites {
+    pub window: Duration,
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for ReadYourWrites {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_WINDOW,
+            max_retries: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl ReadYourWrites {
+    /// retries up to 5 times within 30 seconds after a create, starting with a delay of 100ms
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the period after a create in which retrieves of the record are retried
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// sets the number of retries before a missing record is reported
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// sets the delay before the first retry, which doubles with every further retry up to the maximum
+    pub fn with_delay(mut self, initial_delay: Duration, max_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self.max_delay = max_delay.max(initial_delay);
+        self
+    }
+
+    /// returns the delay before the given retry (starting at 0)
+    pub fn get_delay(&self, retry: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+/// The records a client created recently with the time of their creation
+#[derive(Debug, Default)]
+pub(crate) struct RecentWrites {
+    created: Mutex<HashMap<Uuid, Instant>>,
+}
+
+impl RecentWrites {
+    /// remembers the created record and forgets the records created before the window
+    pub(crate) fn insert(&self, entity_id: Uuid, window: Duration) {
+        if let Ok(mut created) = self.created.lock() {
+            let now = Instant::now();
+            created.retain(|_, created_at| now.duration_since(*created_at) < window);
+            created.insert(entity_id, now);
+        }
+    }
+
+    /// Indicates if the record was created within the window
+    pub(crate) fn contains(&self, entity_id: &Uuid, window: Duration) -> bool {
+        self.created
+            .lock()
+            .ok()
+            .and_then(|created| created.get(entity_id).copied())
+            .is_some_and(|created_at| created_at.elapsed() < window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use super::{ReadYourWrites, RecentWrites};
+
+    #[test]
+    fn backs_off_within_window() {
+        let policy = ReadYourWrites::new()
+            .with_delay(Duration::from_millis(100), Duration::from_millis(500));
+        assert_eq!(policy.get_delay(0), Duration::from_millis(100));
+        assert_eq!(policy.get_delay(2), Duration::from_millis(400));
+        assert_eq!(policy.get_delay(3), Duration::from_millis(500));
+        assert_eq!(policy.get_delay(40), Duration::from_millis(500));
+
+        let writes = RecentWrites::default();
+        writes.insert(Uuid::from_u128(1), policy.window);
+        assert!(writes.contains(&Uuid::from_u128(1), policy.window));
+        assert!(!writes.contains(&Uuid::from_u128(2), policy.window));
+        assert!(!writes.contains(&Uuid::from_u128(1), Duration::ZERO));
+    }
+}
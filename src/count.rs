@@ -0,0 +1,296 @@
+/*!
+Module for counting the records of tables
+
+`Client::retrieve_total_record_count(...)` wraps the `RetrieveTotalRecordCount`
+function. It returns the row counts of the last snapshot of the given tables which
+is refreshed every few hours. Unlike aggregate queries it is not limited to
+5000 records and returns immediately regardless of the table size
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let counts = client.retrieve_total_record_count(&["account", "contact"]).await?;
+
+    if counts.get("contact").copied().unwrap_or_default() > 100_000 {
+        println!("use bulk operations for contacts");
+    }
+    Ok(())
+}
+```
+
+`Client::count_records(...)` counts the records matching a query with `$count=true`.
+Dataverse stops counting at 5000 records, so the `CountMode` decides how larger counts
+are handled:
+
+- `CountMode::Exact` pages through the primary keys of all matching records. The count
+  is exact, but takes one request per 5000 records
+- `CountMode::Fast` sends a single request with the `ConsistencyLevel: eventual` header.
+  The header is not documented for Dataverse, so it is sent on a best-effort basis and
+  nothing relies on it being honored. If the response still contains a complete count,
+  it is reported as eventually consistent, because recent writes may be missing from it.
+  Otherwise the snapshot count is used for queries without a filter, and the count is
+  reported as lower bound for filtered queries
+
+The returned `RecordCount` tells which of these trade-offs applies to the count
+
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    count::{CountAccuracy, CountMode},
+    query::{attribute::Attribute, filter::Filter, Query},
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let query = Query::new("contacts").filter(Filter::Equal("statecode", Attribute::Integer(0)));
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let count = client.count_records(&query, CountMode::Fast).await?;
+
+    match count.accuracy {
+        CountAccuracy::AtLeast => println!("at least {} active contacts", count.count),
+        _ => println!("{} active contacts", count.count),
+    }
+    Ok(())
+}
+```
+*/
+
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde::{de::IgnoredAny, Deserialize};
+
+use crate::{
+    action::get_primary_id_attribute,
+    auth::Authenticate,
+    client::{build_query_parameters, handle_json_response, Client},
+    error::DataverseError,
+    query::Query,
+    result::{IntoDataverseResult, Result},
+};
+
+/// header that asks for a count from an eventually consistent replica, which Dataverse doesn't document
+pub static CONSISTENCY_LEVEL: &str = "ConsistencyLevel";
+
+/// How `Client::count_records(...)` counts beyond the count limit of Dataverse
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CountMode {
+    /// pages through all matching records to count them exactly
+    Exact,
+    /// sends a single request and accepts an eventually consistent count or a lower bound
+    Fast,
+}
+
+/// How a count of `Client::count_records(...)` relates to the actual number of records
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CountAccuracy {
+    /// the number of matching records at the time of the request
+    Exact,
+    /// the complete count of a fast request, which may lag behind recent writes
+    Eventual,
+    /// the number of records of the table in the last snapshot, refreshed every few hours
+    Snapshot,
+    /// a lower bound, as Dataverse stopped counting at its count limit
+    AtLeast,
+}
+
+/// A number of records with its accuracy
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RecordCount {
+    pub count: i64,
+    pub accuracy: CountAccuracy,
+}
+
+impl RecordCount {
+    pub fn new(count: i64, accuracy: CountAccuracy) -> Self {
+        Self { count, accuracy }
+    }
+
+    /// Indicates if the count is the number of matching records at the time of the request
+    pub fn is_exact(&self) -> bool {
+        self.accuracy == CountAccuracy::Exact
+    }
+}
+
+#[derive(Deserialize)]
+struct CountPage {
+    #[serde(rename = "@odata.count")]
+    count: Option<i64>,
+    #[serde(rename = "@Microsoft.Dynamics.CRM.totalrecordcountlimitexceeded", default)]
+    limit_exceeded: bool,
+    #[serde(default)]
+    value: Vec<IgnoredAny>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+}
+
+impl CountPage {
+    /// returns the count of the page if it is complete
+    fn get_complete_count(&self) -> Option<i64> {
+        self.count.filter(|_| !self.limit_exceeded)
+    }
+}
+
+#[derive(Deserialize)]
+struct RetrieveTotalRecordCountResponse {
+    #[serde(rename = "EntityRecordCountCollection")]
+    collection: EntityRecordCountCollection,
+}
+
+#[derive(Deserialize)]
+struct EntityRecordCountCollection {
+    #[serde(rename = "Keys")]
+    keys: Vec<String>,
+    #[serde(rename = "Values")]
+    values: Vec<i64>,
+}
+
+impl EntityRecordCountCollection {
+    fn into_map(self) -> Result<HashMap<String, i64>> {
+        if self.keys.len() != self.values.len() {
+            return Err(DataverseError::new(String::from(
+                "record count response contains a different number of keys and values",
+            )));
+        }
+
+        Ok(self.keys.into_iter().zip(self.values).collect())
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the snapshot record counts of the tables with the given logical names
+
+    The returned map is keyed by the logical names of the tables. Please note that
+    the counts are taken from a snapshot and may lag behind recent changes
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    - One of the tables doesn't exist
+    */
+    pub async fn retrieve_total_record_count(
+        &self,
+        logical_names: &[&str],
+    ) -> Result<HashMap<String, i64>> {
+        let function = format!(
+            "RetrieveTotalRecordCount(EntityNames=@names)?@names={}",
+            serde_json::to_string(logical_names).into_dataverse_result()?
+        );
+
+        let response: RetrieveTotalRecordCountResponse = self.execute_function(&function).await?;
+        response.collection.into_map()
+    }
+
+    /**
+    counts the records matching the query, see the module documentation for the modes
+
+    A limit of the query is ignored
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The table doesn't exist
+    */
+    pub async fn count_records(&self, query: &Query, mode: CountMode) -> Result<RecordCount> {
+        let names = self.resolve_entity_names(query.logical_name).await?;
+        let primary_key = get_primary_id_attribute(&names.logical_name);
+
+        let mut query = query.clone();
+        query.limit = match mode {
+            CountMode::Exact => None,
+            CountMode::Fast => Some(1),
+        };
+
+        let mut parameters = build_query_parameters(&[&primary_key], &query, None);
+        parameters.push("$count", "true");
+        let mut url_path = self.build_simple_url(query.logical_name);
+        parameters.write_to(&mut url_path);
+
+        let page: CountPage = self
+            .request(
+                Method::GET,
+                &url_path,
+                |request| match mode {
+                    CountMode::Exact => Ok(request),
+                    CountMode::Fast => Ok(request.header(CONSISTENCY_LEVEL, "eventual")),
+                },
+                handle_json_response,
+            )
+            .await?;
+
+        match (mode, page.get_complete_count()) {
+            (CountMode::Exact, Some(count)) => Ok(RecordCount::new(count, CountAccuracy::Exact)),
+            (CountMode::Exact, None) => self.count_pages(page).await,
+            (CountMode::Fast, Some(count)) => Ok(RecordCount::new(count, CountAccuracy::Eventual)),
+            (CountMode::Fast, None) if query.get_filter().is_none() => {
+                let counts = self
+                    .retrieve_total_record_count(&[&names.logical_name])
+                    .await?;
+                let snapshot = counts.get(&names.logical_name).copied().unwrap_or_default();
+                let count = snapshot.max(page.count.unwrap_or_default());
+                Ok(RecordCount::new(count, CountAccuracy::Snapshot))
+            }
+            (CountMode::Fast, None) => Ok(RecordCount::new(
+                page.count.unwrap_or(page.value.len() as i64),
+                CountAccuracy::AtLeast,
+            )),
+        }
+    }
+
+    /// counts the records of the page and all following pages
+    async fn count_pages(&self, mut page: CountPage) -> Result<RecordCount> {
+        let mut count = 0;
+
+        loop {
+            count += page.value.len() as i64;
+
+            let Some(next_link) = page.next_link.take() else {
+                return Ok(RecordCount::new(count, CountAccuracy::Exact));
+            };
+
+            page = self
+                .request(Method::GET, &next_link, Ok, handle_json_response)
+                .await?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CountPage, RetrieveTotalRecordCountResponse};
+
+    #[test]
+    fn deserialize_counts() {
+        let response: RetrieveTotalRecordCountResponse = serde_json::from_str(
+            r#"{"EntityRecordCountCollection":{"Count":2,"IsReadOnly":false,"Keys":["account","contact"],"Values":[12,345]}}"#,
+        )
+        .unwrap();
+
+        let counts = response.collection.into_map().unwrap();
+        assert_eq!(counts.get("account"), Some(&12));
+        assert_eq!(counts.get("contact"), Some(&345));
+    }
+
+    #[test]
+    fn deserialize_count_page() {
+        let page: CountPage = serde_json::from_str(
+            r#"{"@odata.count":5000,"@Microsoft.Dynamics.CRM.totalrecordcountlimitexceeded":true,"value":[{"contactid":"00000000-0000-0000-0000-000000000001"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(page.get_complete_count(), None);
+        assert_eq!(page.value.len(), 1);
+
+        let page: CountPage = serde_json::from_str(r#"{"@odata.count":12,"value":[]}"#).unwrap();
+        assert_eq!(page.get_complete_count(), Some(12));
+    }
+}
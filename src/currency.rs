@@ -0,0 +1,143 @@
+/*!
+Module for the transaction currency of created records
+
+Records of entities with money columns need a transaction currency. A default currency
+configured on the client is bound to every record created with `Client::create(...)`
+whose entity has a `transactioncurrencyid` column and that doesn't set a currency itself.
+Whether an entity has this column is looked up once in its metadata and then cached in
+the client. Creates in the batches of the client are bound to the currency as well once
+their entities were prepared with `Client::prepare_creates(...)`
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::{IntoDataverseResult, Result}
+};
+
+fn test() -> Result<()> {
+    let euro_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+
+    // every opportunity, quote, order, ... created by this client is bound to the euro
+    let client = Client::new_dummy().with_default_currency(euro_id);
+    Ok(())
+}
+```
+*/
+
+use std::{collections::HashMap, sync::Mutex};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{auth::Authenticate, client::Client, reference::EntityReference, result::Result};
+
+/// name of the currency lookup of entities with money columns
+pub static TRANSACTION_CURRENCY_ID: &str = "transactioncurrencyid";
+
+/// cache of the entities (logical or entity set name) and whether they have a currency lookup
+#[derive(Debug, Default)]
+pub(crate) struct CurrencyEntityCache {
+    entities: Mutex<HashMap<String, bool>>,
+}
+
+impl CurrencyEntityCache {
+    pub(crate) fn get(&self, name: &str) -> Option<bool> {
+        self.entities.lock().ok()?.get(name).copied()
+    }
+
+    fn insert(&self, name: &str, has_currency: bool) {
+        if let Ok(mut cache) = self.entities.lock() {
+            cache.insert(name.to_string(), has_currency);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Attributes {
+    value: Vec<Value>,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    Indicates if the entity (logical or entity set name) has a `transactioncurrencyid` column
+
+    The result is looked up in the entity metadata once and then cached in this client
+    */
+    pub(crate) async fn has_currency_column(&self, entity_name: &str) -> Result<bool> {
+        if let Some(has_currency) = self.currency_entities.get(entity_name) {
+            return Ok(has_currency);
+        }
+
+        let names = self.resolve_entity_names(entity_name).await?;
+        let attributes: Attributes = self
+            .execute_function(&format!(
+                "EntityDefinitions(LogicalName='{}')/Attributes?$select=LogicalName&$filter=LogicalName eq '{}'",
+                names.logical_name, TRANSACTION_CURRENCY_ID
+            ))
+            .await?;
+
+        let has_currency = !attributes.value.is_empty();
+        self.currency_entities.insert(&names.logical_name, has_currency);
+        self.currency_entities.insert(&names.collection_name, has_currency);
+        Ok(has_currency)
+    }
+}
+
+/// Indicates if the payload already sets a transaction currency
+pub(crate) fn has_currency(payload: &Map<String, Value>) -> bool {
+    payload.contains_key(TRANSACTION_CURRENCY_ID)
+        || payload.contains_key(&format!("{}@odata.bind", TRANSACTION_CURRENCY_ID))
+}
+
+/// binds the currency in the payload unless the payload already sets a currency
+pub(crate) fn apply_default_currency(payload: &mut Map<String, Value>, currency_id: Uuid) {
+    if !has_currency(payload) {
+        let currency = EntityReference::new("transactioncurrencies", currency_id);
+        payload.insert(
+            format!("{}@odata.bind", TRANSACTION_CURRENCY_ID),
+            Value::String(currency.to_bind()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use super::{apply_default_currency, CurrencyEntityCache};
+
+    #[test]
+    fn binds_missing_currency() {
+        let mut payload = json!({ "name": "Big Deal", "estimatedvalue": 1000.0 });
+        apply_default_currency(payload.as_object_mut().unwrap(), Uuid::nil());
+        assert_eq!(
+            payload["transactioncurrencyid@odata.bind"],
+            Value::from("/transactioncurrencies(00000000-0000-0000-0000-000000000000)")
+        );
+    }
+
+    #[test]
+    fn keeps_existing_currency() {
+        let mut payload = json!({
+            "transactioncurrencyid@odata.bind": "/transactioncurrencies(00000000-0000-0000-0000-000000000001)"
+        });
+        apply_default_currency(payload.as_object_mut().unwrap(), Uuid::nil());
+        assert_eq!(payload.as_object().unwrap().len(), 1);
+        assert_eq!(
+            payload["transactioncurrencyid@odata.bind"],
+            Value::from("/transactioncurrencies(00000000-0000-0000-0000-000000000001)")
+        );
+    }
+
+    #[test]
+    fn caches_entities() {
+        let cache = CurrencyEntityCache::default();
+        cache.insert("opportunity", true);
+        assert_eq!(cache.get("opportunity"), Some(true));
+        assert_eq!(cache.get("contact"), None);
+    }
+}
@@ -0,0 +1,399 @@
+/*!
+Module for finding and merging duplicate records
+
+`Client::deduplicate(...)` retrieves the records of a query, groups them by the values
+of match columns and merges every group into one surviving record with the Merge action.
+Values are matched case-insensitively without surrounding whitespace, and records with
+an empty match column are never treated as duplicates
+
+The `SurvivorshipPolicy` decides which record of a group survives and which columns
+of the merged records fill the empty columns of the survivor. A dry run only reports the
+planned merges, so they can be reviewed before anything is changed. Large data sets can be
+merged in controlled runs with a limit of merges per run. Only active records are
+retrieved, because the Merge action deactivates the merged records, so a later run
+doesn't merge them again
+
+Please note that only accounts, contacts, leads and incidents can be merged
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    dedupe::{DedupeOptions, Survivor, SurvivorshipPolicy},
+    query::Query,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let policy = SurvivorshipPolicy::new(Survivor::Oldest)
+        .fill(&["telephone1", "jobtitle"]);
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let report = client
+        .deduplicate(
+            &Query::new("contacts"),
+            &["emailaddress1"],
+            &DedupeOptions::new(policy).dry_run(),
+        )
+        .await?;
+
+    for merge in report.merges.iter() {
+        println!("{} would be merged into {}", merge.subordinate, merge.target);
+    }
+    Ok(())
+}
+```
+*/
+
+use std::collections::BTreeMap;
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    action::{get_primary_id_attribute, MergeRequest},
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    progress::Operation,
+    query::Query,
+    reference::EntityReference,
+    result::Result,
+};
+
+/// column that orders the records of a group by their age
+static CREATED_ON: &str = "createdon";
+
+/// The record of a group of duplicates that survives the merge
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Survivor {
+    /// the record that was created first
+    #[default]
+    Oldest,
+    /// the record that was created last
+    Newest,
+}
+
+/// Decides which record of a group survives and which values it takes from the others
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SurvivorshipPolicy {
+    pub survivor: Survivor,
+    /// columns that are filled from the merged records if they are empty in the survivor
+    pub fill_columns: Vec<String>,
+}
+
+impl SurvivorshipPolicy {
+    /// creates a policy that keeps the given survivor without filling columns
+    pub fn new(survivor: Survivor) -> Self {
+        Self {
+            survivor,
+            fill_columns: Vec::new(),
+        }
+    }
+
+    /**
+    fills the given columns of the survivor from the merged records if they are empty
+
+    The records are checked in the order of their age starting next to the survivor.
+    The columns have to be writable columns of the entity, lookups are not supported
+    */
+    pub fn fill(mut self, columns: &[&str]) -> Self {
+        self.fill_columns
+            .extend(columns.iter().map(|column| column.to_string()));
+        self
+    }
+}
+
+/// Options of `Client::deduplicate(...)`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DedupeOptions {
+    pub policy: SurvivorshipPolicy,
+    pub dry_run: bool,
+    pub limit: Option<usize>,
+    pub check_parents: bool,
+}
+
+impl DedupeOptions {
+    /// creates options that merge all duplicates with the given policy
+    pub fn new(policy: SurvivorshipPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// only reports the planned merges without executing them
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// executes at most the given number of merges in this run
+    pub fn limit(mut self, merges: usize) -> Self {
+        self.limit = Some(merges);
+        self
+    }
+
+    /// lets Dataverse check that the parents of the merged records are the same
+    pub fn check_parents(mut self) -> Self {
+        self.check_parents = true;
+        self
+    }
+}
+
+/// A merge of `Client::deduplicate(...)` that failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DedupeFailure {
+    /// index of the merge in `DedupeReport::merges`
+    pub index: usize,
+    pub message: String,
+}
+
+/// The planned and executed merges of `Client::deduplicate(...)`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DedupeReport {
+    /// every planned merge, including the ones that were not executed
+    pub merges: Vec<MergeRequest>,
+    /// number of merges that succeeded
+    pub merged: usize,
+    pub failures: Vec<DedupeFailure>,
+}
+
+impl DedupeReport {
+    /// returns the number of planned merges that were not executed because of the limit or a dry run
+    pub fn get_remaining(&self) -> usize {
+        self.merges.len() - self.merged - self.failures.len()
+    }
+}
+
+#[derive(Deserialize)]
+struct Records {
+    value: Vec<Map<String, Value>>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+}
+
+/// returns the normalized value of the column, `None` if it is empty
+fn get_match_value(record: &Map<String, Value>, column: &str) -> Option<String> {
+    match record.get(column)? {
+        Value::Null => None,
+        Value::String(value) if value.trim().is_empty() => None,
+        Value::String(value) => Some(value.trim().to_lowercase()),
+        value => Some(value.to_string()),
+    }
+}
+
+fn is_empty(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::String(value)) => value.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// groups the records by their match values and plans the merges of every group
+fn plan_merges(
+    records: Vec<Map<String, Value>>,
+    logical_name: &str,
+    match_columns: &[&str],
+    options: &DedupeOptions,
+) -> Vec<MergeRequest> {
+    let primary_key = get_primary_id_attribute(logical_name);
+    let mut groups: BTreeMap<Vec<String>, Vec<Map<String, Value>>> = BTreeMap::new();
+
+    for record in records {
+        let key: Option<Vec<String>> = match_columns
+            .iter()
+            .map(|column| get_match_value(&record, column))
+            .collect();
+
+        if let Some(key) = key {
+            groups.entry(key).or_default().push(record);
+        }
+    }
+
+    let mut merges = Vec::new();
+
+    for mut group in groups.into_values().filter(|group| group.len() > 1) {
+        group.sort_by(|left, right| {
+            let left = left
+                .get(CREATED_ON)
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let right = right
+                .get(CREATED_ON)
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            left.cmp(right)
+        });
+
+        if options.policy.survivor == Survivor::Newest {
+            group.reverse();
+        }
+
+        let mut records = group.into_iter().filter_map(|record| {
+            let id = record.get(&primary_key)?.as_str()?.parse().ok()?;
+            Some((EntityReference::new(logical_name.to_string(), id), record))
+        });
+
+        let Some((target, mut survivor)) = records.next() else {
+            continue;
+        };
+
+        for (subordinate, record) in records {
+            let mut merge = MergeRequest::from_references(target.clone(), subordinate)
+                .check_parents(options.check_parents);
+
+            for column in options.policy.fill_columns.iter() {
+                if is_empty(survivor.get(column)) && !is_empty(record.get(column)) {
+                    let value = record[column].clone();
+                    merge.update_content.insert(column.clone(), value.clone());
+                    survivor.insert(column.clone(), value);
+                }
+            }
+
+            merges.push(merge);
+        }
+    }
+
+    merges
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    merges the active records of the query that have the same values in all match columns
+
+    The merges are executed one after another and reported to the progress of this
+    client. Failed merges don't stop the others and are listed in the report
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error while retrieving the records
+    - There is no entity with the name of the query
+    */
+    pub async fn deduplicate(
+        &self,
+        query: &Query,
+        match_columns: &[&str],
+        options: &DedupeOptions,
+    ) -> Result<DedupeReport> {
+        let names = self.resolve_entity_names(query.logical_name).await?;
+        let primary_key = get_primary_id_attribute(&names.logical_name);
+
+        let mut columns = vec![primary_key.as_str(), CREATED_ON];
+        columns.extend_from_slice(match_columns);
+        columns.extend(options.policy.fill_columns.iter().map(String::as_str));
+        columns.sort_unstable();
+        columns.dedup();
+
+        let query = query.clone().active_only();
+        let mut records = Vec::new();
+        let mut url = Some(self.build_query_url(&columns, &query, None));
+
+        while let Some(next_url) = url {
+            let page: Records = self
+                .request(Method::GET, &next_url, Ok, handle_json_response)
+                .await?;
+            records.extend(page.value);
+            url = page.next_link;
+        }
+
+        let mut report = DedupeReport {
+            merges: plan_merges(records, &names.logical_name, match_columns, options),
+            ..DedupeReport::default()
+        };
+
+        if options.dry_run {
+            return Ok(report);
+        }
+
+        let count = options.limit.unwrap_or(usize::MAX).min(report.merges.len());
+        let tracker = self.track_progress(Operation::Merge, Some(count));
+
+        for index in 0..count {
+            match self.execute_merge(&report.merges[index]).await {
+                Ok(()) => report.merged += 1,
+                Err(error) => report.failures.push(DedupeFailure {
+                    index,
+                    message: error.message,
+                }),
+            }
+
+            tracker.report(index + 1);
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Map, Value};
+    use uuid::Uuid;
+
+    use super::{plan_merges, DedupeOptions, Survivor, SurvivorshipPolicy};
+
+    fn contact(id: u128, created_on: &str, email: Value, phone: Value) -> Map<String, Value> {
+        json!({
+            "contactid": Uuid::from_u128(id),
+            "createdon": created_on,
+            "emailaddress1": email,
+            "telephone1": phone,
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn plans_merges_by_survivorship() {
+        let records = vec![
+            contact(
+                1,
+                "2024-03-01T00:00:00Z",
+                json!("Testy@Example.com"),
+                Value::Null,
+            ),
+            contact(
+                2,
+                "2024-01-01T00:00:00Z",
+                json!(" testy@example.com"),
+                Value::Null,
+            ),
+            contact(
+                3,
+                "2024-02-01T00:00:00Z",
+                json!("testy@example.com"),
+                json!("123"),
+            ),
+            contact(
+                4,
+                "2024-02-01T00:00:00Z",
+                json!("other@example.com"),
+                Value::Null,
+            ),
+            contact(5, "2024-02-01T00:00:00Z", Value::Null, Value::Null),
+            contact(6, "2024-02-01T00:00:00Z", Value::Null, Value::Null),
+        ];
+
+        let options =
+            DedupeOptions::new(SurvivorshipPolicy::new(Survivor::Oldest).fill(&["telephone1"]));
+        let merges = plan_merges(records.clone(), "contact", &["emailaddress1"], &options);
+
+        assert_eq!(merges.len(), 2);
+        assert_eq!(merges[0].target.entity_id, Uuid::from_u128(2));
+        assert_eq!(merges[0].subordinate.entity_id, Uuid::from_u128(3));
+        assert_eq!(
+            merges[0].update_content.get("telephone1"),
+            Some(&json!("123"))
+        );
+        assert_eq!(merges[1].subordinate.entity_id, Uuid::from_u128(1));
+        assert!(merges[1].update_content.is_empty());
+
+        let options = DedupeOptions::new(SurvivorshipPolicy::new(Survivor::Newest));
+        let merges = plan_merges(records, "contact", &["emailaddress1"], &options);
+        assert_eq!(merges[0].target.entity_id, Uuid::from_u128(1));
+        assert_eq!(merges[0].target.entity_name, "contact");
+    }
+}
@@ -0,0 +1,290 @@
+/*!
+Module for diagnostic information that Dataverse returns alongside responses
+
+Dataverse announces deprecated usages and preview features with warning headers
+on otherwise successful responses. A warning handler configured on the client
+receives every such warning, so deprecated API usage can be found long before
+it is removed
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::client::Client;
+
+let client = Client::new_dummy().with_warning_handler(|warning| {
+    eprintln!("{} {} returned {}: {}", warning.method, warning.url, warning.header, warning.value);
+});
+```
+
+Failed requests can be captured with their payload for debugging. As payloads often
+contain personal data, the values of named fields are redacted before the payload
+is passed to the handler
+
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    diagnostics::FailureCapture
+};
+
+let capture = FailureCapture::new(|failed| {
+    eprintln!("{} {} failed with {}: {:?}", failed.method, failed.url, failed.status, failed.body);
+})
+.redact(&["emailaddress1", "telephone1", "birthdate"]);
+
+let client = Client::new_dummy().with_failure_capture(capture);
+```
+
+The service request id of the last response is kept by the client, so successful
+operations like high-value transactions can be audited and traced with Microsoft support
+
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let identity = client.who_am_i().await?;
+
+    if let Some(response) = client.get_last_response() {
+        println!("{} {} was served as {:?}", response.method, response.url, response.service_request_id);
+    }
+    Ok(())
+}
+```
+*/
+
+use std::sync::Arc;
+
+use reqwest::{header::HeaderMap, Method};
+use serde_json::Value;
+
+use crate::rate_limit::QuotaInfo;
+
+/// headers that carry warnings about the request
+pub static WARNING_HEADERS: &[&str] = &["Warning", "Deprecation", "Sunset"];
+
+/// A warning returned by Dataverse in a response header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerWarning {
+    pub method: Method,
+    pub url: String,
+    pub header: &'static str,
+    pub value: String,
+}
+
+/// extracts all warnings from the given response headers
+pub(crate) fn collect_warnings(method: &Method, url: &str, headers: &HeaderMap) -> Vec<ServerWarning> {
+    WARNING_HEADERS
+        .iter()
+        .flat_map(|header| {
+            headers.get_all(*header).iter().map(move |value| ServerWarning {
+                method: method.clone(),
+                url: url.to_string(),
+                header,
+                value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            })
+        })
+        .collect()
+}
+
+/// header with the id of the request in the service, required by Microsoft support
+pub static SERVICE_REQUEST_ID: &str = "x-ms-service-request-id";
+
+/// header with the id of the request in the organization service
+pub static REQUEST_ID: &str = "REQ_ID";
+
+/// The diagnostic headers of a response that Dataverse returned
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseMeta {
+    pub method: Method,
+    pub url: String,
+    pub status: u16,
+    pub service_request_id: Option<String>,
+    pub request_id: Option<String>,
+    pub quota: QuotaInfo,
+}
+
+impl ResponseMeta {
+    pub(crate) fn new(method: &Method, url: &str, status: u16, headers: &HeaderMap) -> Self {
+        Self {
+            method: method.clone(),
+            url: url.to_string(),
+            status,
+            service_request_id: get_header(headers, SERVICE_REQUEST_ID),
+            request_id: get_header(headers, REQUEST_ID),
+            quota: QuotaInfo::from_headers(headers),
+        }
+    }
+}
+
+/// returns the first value of the header, Dataverse may repeat the service request id
+fn get_header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+}
+
+/// value that replaces the values of redacted fields
+pub static REDACTED: &str = "***";
+
+/// A request that dataverse answered with a client or server error
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailedRequest {
+    pub method: Method,
+    pub url: String,
+    pub status: u16,
+    /// the redacted payload of the request, `None` for requests without a body or streamed bodies
+    pub body: Option<String>,
+}
+
+/// callback that receives failed requests
+pub type FailureHandler = Arc<dyn Fn(&FailedRequest) + Send + Sync>;
+
+/**
+Passes failed requests with their redacted payload to a handler
+
+Fields are redacted by name (case-insensitive) at any depth of JSON payloads.
+Batch payloads are redacted per JSON line
+*/
+#[derive(Clone)]
+pub struct FailureCapture {
+    handler: FailureHandler,
+    redactor: Redactor,
+}
+
+impl FailureCapture {
+    /// creates a capture that passes failed requests to the given handler
+    pub fn new(handler: impl Fn(&FailedRequest) + Send + Sync + 'static) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            redactor: Redactor::default(),
+        }
+    }
+
+    /// redacts the values of the given fields in captured payloads
+    pub fn redact(mut self, fields: &[&str]) -> Self {
+        self.redactor.add_fields(fields);
+        self
+    }
+
+    /// returns the payload with the values of all redacted fields replaced
+    pub fn redact_body(&self, body: &[u8]) -> String {
+        self.redactor.redact_body(body)
+    }
+
+    pub(crate) fn capture(&self, method: &Method, url: &str, status: u16, body: Option<&[u8]>) {
+        (self.handler)(&FailedRequest {
+            method: method.clone(),
+            url: url.to_string(),
+            status,
+            body: body.map(|body| self.redact_body(body)),
+        });
+    }
+}
+
+/// replaces the values of named fields in JSON payloads and JSON lines of batch payloads
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Redactor {
+    fields: Vec<String>,
+}
+
+impl Redactor {
+    /// redacts the given fields (case-insensitive) in addition to the current ones
+    pub(crate) fn add_fields(&mut self, fields: &[&str]) {
+        self.fields
+            .extend(fields.iter().map(|field| field.to_ascii_lowercase()));
+    }
+
+    pub(crate) fn redact_body(&self, body: &[u8]) -> String {
+        let body = String::from_utf8_lossy(body);
+
+        if let Some(redacted) = self.redact_json(&body) {
+            return redacted;
+        }
+
+        body.split('\n')
+            .map(|line| self.redact_json(line).unwrap_or_else(|| line.to_string()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn redact_json(&self, text: &str) -> Option<String> {
+        let trimmed = text.trim();
+
+        if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+            return None;
+        }
+
+        let mut value: Value = serde_json::from_str(trimmed).ok()?;
+        self.redact_value(&mut value);
+        Some(value.to_string())
+    }
+
+    fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    if self.fields.contains(&key.to_ascii_lowercase()) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_value(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_value(value)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        Method,
+    };
+
+    use super::{collect_warnings, FailureCapture, ResponseMeta};
+
+    #[test]
+    fn reads_request_ids() {
+        let mut headers = HeaderMap::new();
+        headers.append("x-ms-service-request-id", HeaderValue::from_static("2d6c8a66-47a5-4c2f-9a7b-1d6f0e0b5d3e"));
+        headers.append("x-ms-service-request-id", HeaderValue::from_static("9f1b3c42-0a7e-4c8d-b5e6-3f2a1d0c9b8a"));
+        headers.append("REQ_ID", HeaderValue::from_static("9f1b3c42-0a7e-4c8d-b5e6-3f2a1d0c9b8a"));
+
+        let response = ResponseMeta::new(&Method::POST, "contacts", 204, &headers);
+        assert_eq!(response.service_request_id.as_deref(), Some("2d6c8a66-47a5-4c2f-9a7b-1d6f0e0b5d3e"));
+        assert_eq!(response.request_id.as_deref(), Some("9f1b3c42-0a7e-4c8d-b5e6-3f2a1d0c9b8a"));
+        assert_eq!(response.status, 204);
+    }
+
+    #[test]
+    fn redacts_json_and_batch_payloads() {
+        let capture = FailureCapture::new(|_| {}).redact(&["EmailAddress1"]);
+
+        assert_eq!(
+            capture.redact_body(br#"{"lastname":"McTestface","emailaddress1":"testy@example.com"}"#),
+            r#"{"emailaddress1":"***","lastname":"McTestface"}"#
+        );
+        assert_eq!(
+            capture.redact_body(b"--batch_1\nPOST contacts HTTP/1.1\n\n{\"emailaddress1\":\"testy@example.com\"}\n--batch_1--"),
+            "--batch_1\nPOST contacts HTTP/1.1\n\n{\"emailaddress1\":\"***\"}\n--batch_1--"
+        );
+    }
+
+    #[test]
+    fn collects_all_warning_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append("Warning", HeaderValue::from_static("299 - \"deprecated\""));
+        headers.append("Warning", HeaderValue::from_static("299 - \"preview\""));
+        headers.append("Sunset", HeaderValue::from_static("Sat, 31 Dec 2025 23:59:59 GMT"));
+        headers.append("OData-Version", HeaderValue::from_static("4.0"));
+
+        let warnings = collect_warnings(&Method::GET, "contacts", &headers);
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(warnings[0].value, "299 - \"deprecated\"");
+        assert_eq!(warnings[2].header, "Sunset");
+    }
+}
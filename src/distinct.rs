@@ -0,0 +1,125 @@
+/*!
+Module for retrieving the distinct values of a column
+
+`Client::retrieve_distinct(...)` groups the records of a table by a single column
+with `$apply=groupby((column))`, so Dataverse returns every value only once. This
+is useful for building pick-lists from existing data
+
+Please note that aggregate queries are limited to 50000 records by Dataverse
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    query::filter::Filter,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let cities: Vec<Option<String>> = client
+        .retrieve_distinct("contacts", "address1_city", Some(&Filter::equal("statecode", 0)))
+        .await?;
+    Ok(())
+}
+```
+*/
+
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    error::DataverseError,
+    query::{filter::Filter, parameters::QueryParameters},
+    result::{IntoDataverseResult, Result},
+};
+
+#[derive(Deserialize)]
+struct GroupByResult {
+    value: Vec<Map<String, Value>>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the distinct values of the column in the table (entity set name)
+    for all records matching the optional filter
+
+    Records without a value in the column result in a `null` value, so the column
+    should be deserialized into an `Option` unless the filter excludes them
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The table or the column doesn't exist
+    */
+    pub async fn retrieve_distinct<T: DeserializeOwned>(
+        &self,
+        table: &str,
+        column: &str,
+        filter: Option<&Filter>,
+    ) -> Result<Vec<T>> {
+        let mut url_path = self.build_simple_url(table);
+        build_distinct_parameters(column, filter).write_to(&mut url_path);
+
+        let mut values = Vec::new();
+        let mut next_link = Some(url_path);
+
+        while let Some(url) = next_link {
+            let result: GroupByResult = self
+                .request(Method::GET, &url, Ok, handle_json_response)
+                .await?;
+
+            for mut record in result.value {
+                let value = record.remove(column).ok_or_else(|| {
+                    DataverseError::new(format!("grouped record contains no column {}", column))
+                })?;
+
+                values.push(serde_json::from_value(value).into_dataverse_result()?);
+            }
+
+            next_link = result.next_link;
+        }
+
+        Ok(values)
+    }
+}
+
+fn build_distinct_parameters(column: &str, filter: Option<&Filter>) -> QueryParameters {
+    let mut parameters = QueryParameters::new();
+
+    match filter {
+        Some(filter) => parameters.push(
+            "$apply",
+            format_args!("filter({})/groupby(({}))", filter, column),
+        ),
+        None => parameters.push("$apply", format_args!("groupby(({}))", column)),
+    }
+
+    parameters
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::filter::Filter;
+
+    use super::build_distinct_parameters;
+
+    #[test]
+    fn groupby_parameters() {
+        assert_eq!(
+            build_distinct_parameters("address1_city", None).to_string(),
+            "?$apply=groupby((address1_city))"
+        );
+        assert_eq!(
+            build_distinct_parameters("address1_city", Some(&Filter::equal("statecode", 0)))
+                .to_string(),
+            "?$apply=filter(statecode eq 0)/groupby((address1_city))"
+        );
+    }
+}
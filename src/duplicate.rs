@@ -0,0 +1,283 @@
+/*!
+Module for the duplicates found by the duplicate detection rules of Dataverse
+
+Creates through the Web API skip duplicate detection unless the request asks for it.
+`Client::create_unless_duplicate(...)` enables it and, if the create is blocked because
+of a duplicate, returns the existing records that match the new one as `DuplicateMatch`,
+so an application can ask its user whether one of them was meant instead
+
+The matches of a create are retrieved with the `RetrieveDuplicates` function, which
+doesn't name the rule that matched. The results of duplicate detection jobs are
+retrieved with `Client::retrieve_duplicate_matches(...)` including their rule
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    duplicate::CreateOutcome,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let contact = Contact {
+        contactid: Uuid::new_v4(),
+        emailaddress1: String::from("testy@example.com"),
+    };
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    match client.create_unless_duplicate(&contact).await? {
+        CreateOutcome::Created(id) => println!("created contact {}", id),
+        CreateOutcome::Duplicate(matches) => {
+            for duplicate in matches.iter() {
+                println!("did you mean the {} {}?", duplicate.record.entity_name, duplicate.record.entity_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    emailaddress1: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    action::get_primary_id_attribute,
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    entity::WriteEntity,
+    error::ServiceError,
+    reference::{EntityReference, Reference, LOOKUP_LOGICAL_NAMES_PREFERENCE},
+    result::{IntoDataverseResult, Result},
+};
+
+/// header that enables duplicate detection for a create or update if it is `false`
+pub static SUPPRESS_DUPLICATE_DETECTION: &str = "MSCRM.SuppressDuplicateDetection";
+
+/// error code of a write that was blocked by a duplicate detection rule
+pub static DUPLICATE_DETECTED: &str = "0x80040333";
+
+/// number of matches that are retrieved for a blocked create
+static MAX_MATCHES: usize = 50;
+
+/// An existing record that matches a record by a duplicate detection rule
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DuplicateMatch {
+    /// the matching record with the logical name of its entity
+    pub record: EntityReference,
+    /// the rule that matched, `None` if Dataverse didn't report it
+    pub rule_id: Option<Uuid>,
+}
+
+/// The outcome of `Client::create_unless_duplicate(...)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CreateOutcome {
+    /// the record was created with the given id
+    Created(Uuid),
+    /// the record was not created because it matches the given existing records
+    Duplicate(Vec<DuplicateMatch>),
+}
+
+#[derive(Deserialize)]
+struct Records {
+    value: Vec<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct DuplicateRecords {
+    value: Vec<DuplicateRecord>,
+}
+
+#[derive(Deserialize)]
+struct DuplicateRecord {
+    #[serde(rename = "_duplicaterecordid_value")]
+    record_id: Uuid,
+    #[serde(rename = "_duplicaterecordid_value@Microsoft.Dynamics.CRM.lookuplogicalname")]
+    logical_name: String,
+    #[serde(rename = "_duplicateruleid_value")]
+    rule_id: Option<Uuid>,
+}
+
+/// Indicates if the error message contains the error of a write blocked by duplicate detection
+pub fn is_duplicate_detected(message: &str) -> bool {
+    ServiceError::parse(message)
+        .is_some_and(|error| error.code.eq_ignore_ascii_case(DUPLICATE_DETECTED))
+}
+
+/// reads the matches from the records returned by `RetrieveDuplicates`
+fn parse_matches(records: Vec<Map<String, Value>>, logical_name: &str) -> Vec<DuplicateMatch> {
+    let primary_key = get_primary_id_attribute(logical_name);
+
+    records
+        .iter()
+        .filter_map(|record| record.get(&primary_key)?.as_str()?.parse().ok())
+        .map(|entity_id| DuplicateMatch {
+            record: EntityReference::new(logical_name.to_string(), entity_id),
+            rule_id: None,
+        })
+        .collect()
+}
+
+/// escapes the characters of a parameter value that would end or alter the query of the url
+fn escape_parameter(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('&', "%26")
+        .replace('#', "%23")
+        .replace('+', "%2B")
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    creates the record with duplicate detection and returns the matching records
+    instead if a duplicate detection rule blocks it
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error other than a detected duplicate
+    - The payload exceeds the maximum payload size of this client
+    */
+    pub async fn create_unless_duplicate(
+        &self,
+        entity: &impl WriteEntity,
+    ) -> Result<CreateOutcome> {
+        let headers = [(SUPPRESS_DUPLICATE_DETECTION, String::from("false"))];
+
+        match self.create_with_headers(entity, &headers).await {
+            Ok(entity_id) => Ok(CreateOutcome::Created(entity_id)),
+            Err(error) if is_duplicate_detected(&error.message) => Ok(CreateOutcome::Duplicate(
+                self.retrieve_duplicates(entity).await?,
+            )),
+            Err(error) => Err(error),
+        }
+    }
+
+    /**
+    returns the existing records that match the given record by the published duplicate
+    detection rules of its entity, without creating it
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_duplicates(
+        &self,
+        entity: &impl WriteEntity,
+    ) -> Result<Vec<DuplicateMatch>> {
+        let reference = entity.get_reference();
+        let names = self.resolve_entity_names(&reference.entity_name).await?;
+
+        let mut business_entity = serde_json::to_value(entity).into_dataverse_result()?;
+        if let Some(business_entity) = business_entity.as_object_mut() {
+            business_entity.insert(
+                String::from("@odata.type"),
+                Value::String(format!("Microsoft.Dynamics.CRM.{}", names.logical_name)),
+            );
+        }
+
+        let records: Records = self
+            .execute_function(&format!(
+                "RetrieveDuplicates(BusinessEntity=@p1,MatchingEntityName=@p2,PagingInfo=@p3)?@p1={}&@p2='{}'&@p3={}",
+                escape_parameter(&business_entity.to_string()),
+                names.logical_name,
+                serde_json::json!({ "PageNumber": 1, "Count": MAX_MATCHES }),
+            ))
+            .await?;
+
+        Ok(parse_matches(records.value, &names.logical_name))
+    }
+
+    /**
+    returns the duplicates of the referenced record that duplicate detection jobs found,
+    including the rule that matched
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_duplicate_matches(
+        &self,
+        reference: &impl Reference,
+    ) -> Result<Vec<DuplicateMatch>> {
+        let reference = reference.get_reference();
+        let url_path = self.build_simple_url(format_args!(
+            "duplicaterecords?$select=_duplicaterecordid_value,_duplicateruleid_value&$filter=_baserecordid_value eq {}",
+            reference.entity_id.as_hyphenated()
+        ));
+
+        let records: DuplicateRecords = self
+            .request(
+                Method::GET,
+                &url_path,
+                |request| Ok(request.header("Prefer", LOOKUP_LOGICAL_NAMES_PREFERENCE)),
+                handle_json_response,
+            )
+            .await?;
+
+        Ok(records
+            .value
+            .into_iter()
+            .map(|record| DuplicateMatch {
+                record: EntityReference::new(record.logical_name, record.record_id),
+                rule_id: record.rule_id,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Map, Value};
+    use uuid::Uuid;
+
+    use super::{escape_parameter, is_duplicate_detected, parse_matches};
+
+    #[test]
+    fn parses_duplicates() {
+        assert!(is_duplicate_detected(
+            r#"{"error":{"code":"0x80040333","message":"A record was not created or updated because a duplicate of the current record already exists."}}"#
+        ));
+        assert!(!is_duplicate_detected(
+            r#"{"error":{"code":"0x80040237","message":"A record with matching key values already exists."}}"#
+        ));
+
+        let records: Vec<Map<String, Value>> = serde_json::from_value(json!([
+            { "contactid": "00000000-0000-0000-0000-000000000001", "emailaddress1": "testy@example.com" },
+            { "emailaddress1": "testy@example.com" }
+        ]))
+        .unwrap();
+
+        let matches = parse_matches(records, "contact");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].record.entity_name, "contact");
+        assert_eq!(matches[0].record.entity_id, Uuid::from_u128(1));
+
+        assert_eq!(
+            escape_parameter(r#"{"name":"Tom & Jerry #1"}"#),
+            r#"{"name":"Tom %26 Jerry %231"}"#
+        );
+    }
+}
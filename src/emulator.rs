@@ -0,0 +1,766 @@
+/*!
+Module for running the client against an in-memory emulation of Dataverse
+
+This is only available with the `emulator` feature. `Emulator` is a transport that
+answers the requests of a client from tables kept in memory instead of sending them
+over the network. Business logic can be tested against the real `Client` API without
+any http server or credentials
+
+The emulation covers the basic record operations:
+- creates, retrieves, updates, upserts and deletes by primary key. Creates of a primary
+  key that already exists fail with a duplicate key error (`0x80040237`) like in Dataverse
+- queries with `$select`, `$filter`, `$orderby` and `$top`
+- filters with comparisons (`eq`, `ne`, `gt`, `ge`, `lt`, `le`), `contains`, `startswith`,
+  `endswith`, `and`, `or`, `not` and parentheses. Strings are compared case-insensitive
+  like Dataverse does
+
+Other requests (batches, actions, functions, `$expand`, alternate keys, ...) fail with
+`501 Not Implemented`. Lookups bound with `<name>@odata.bind` are stored as `_<name>_value`
+
+The primary key of a table is derived from its entity set name (`contacts` -> `contactid`,
+`opportunities` -> `opportunityid`). Other tables can be registered with `with_table(...)`
+
+# Examples
+```rust
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    emulator::Emulator,
+    entity::ReadEntity,
+    query::{filter::Filter, Query},
+    result::Result,
+    select::Select
+};
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname"]
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let emulator = Emulator::new();
+    emulator.insert("contacts", json!({ "firstname": "Testy" }))?;
+    emulator.insert("contacts", json!({ "firstname": "Marianne" }))?;
+
+    let client = emulator.client();
+    let query = Query::new("contacts").filter(Filter::starts_with("firstname", "test"));
+    let contacts: Vec<Contact> = client.retrieve_all(&query).await?;
+
+    assert_eq!(contacts.len(), 1);
+    Ok(())
+}
+```
+*/
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use reqwest::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::{Client, UUID_REGEX, VERSION},
+    error::DataverseError,
+    result::{IntoDataverseResult, Result},
+    transport::HttpTransport,
+};
+
+type Record = Map<String, Value>;
+
+/// status, headers and body of an emulated response
+type Reply = (StatusCode, Vec<(String, String)>, Value);
+
+#[derive(Default)]
+struct State {
+    primary_keys: HashMap<String, String>,
+    tables: HashMap<String, BTreeMap<Uuid, Record>>,
+}
+
+impl State {
+    fn get_primary_key(&self, table: &str) -> String {
+        if let Some(primary_key) = self.primary_keys.get(table) {
+            return primary_key.clone();
+        }
+
+        let singular = match table.strip_suffix("ies") {
+            Some(stem) => format!("{}y", stem),
+            None => table.strip_suffix('s').unwrap_or(table).to_string(),
+        };
+
+        format!("{}id", singular)
+    }
+
+    /// indicates if the table already has a record with the primary key that is set in the record
+    fn contains_key_of(&self, table: &str, record: &Record) -> bool {
+        let id = match record.get(&self.get_primary_key(table)) {
+            Some(Value::String(id)) => Uuid::parse_str(id).ok(),
+            _ => None,
+        };
+
+        id.is_some_and(|id| self.tables.get(table).is_some_and(|records| records.contains_key(&id)))
+    }
+
+    /// stores the record and returns its id, which is taken from the primary key if it is set
+    fn store(&mut self, table: &str, id: Option<Uuid>, mut record: Record) -> Result<Uuid> {
+        let primary_key = self.get_primary_key(table);
+        let id = match (id, record.get(&primary_key)) {
+            (Some(id), _) => id,
+            (None, Some(Value::String(id))) => Uuid::parse_str(id).into_dataverse_result()?,
+            (None, _) => Uuid::new_v4(),
+        };
+
+        bind_lookups(&mut record);
+        let stored = self
+            .tables
+            .entry(table.to_string())
+            .or_default()
+            .entry(id)
+            .or_default();
+
+        stored.extend(record);
+        stored.insert(primary_key, Value::String(id.as_hyphenated().to_string()));
+        Ok(id)
+    }
+}
+
+/// An authentication method that always provides the same token, used for clients of an `Emulator`
+pub struct EmulatorAuth;
+
+#[async_trait]
+impl Authenticate for EmulatorAuth {
+    async fn get_valid_token(&self) -> Result<Arc<String>> {
+        Ok(Arc::new(String::from("emulator-token")))
+    }
+}
+
+/// An in-memory emulation of the Dataverse Web API that is used as transport of a client
+#[derive(Clone, Default)]
+pub struct Emulator {
+    state: Arc<Mutex<State>>,
+}
+
+impl Emulator {
+    /// creates an emulator without any records
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// creates a client that sends all requests to this emulator
+    pub fn client(&self) -> Client<'static, EmulatorAuth> {
+        Client::new("https://emulator.crm.dynamics.com/", reqwest::Client::new(), EmulatorAuth)
+            .with_transport(self.clone())
+    }
+
+    /// registers the primary key of a table (entity set name) whose key cannot be derived
+    pub fn with_table(self, table: &str, primary_key: &str) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .primary_keys
+                .insert(table.to_string(), primary_key.to_string());
+        }
+        self
+    }
+
+    /**
+    inserts the record into the table (entity set name) and returns its id
+
+    This fails if the record is not a JSON object or has an invalid primary key
+    */
+    pub fn insert(&self, table: &str, record: Value) -> Result<Uuid> {
+        let record = match record {
+            Value::Object(record) => record,
+            _ => return Err(DataverseError::new(String::from("a record has to be a JSON object"))),
+        };
+
+        self.lock()?.store(table, None, record)
+    }
+
+    /// returns all records of the table (entity set name) ordered by their id
+    pub fn get_records(&self, table: &str) -> Vec<Value> {
+        self.lock()
+            .map(|state| {
+                state
+                    .tables
+                    .get(table)
+                    .map(|records| records.values().cloned().map(Value::Object).collect())
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, State>> {
+        self.state
+            .lock()
+            .map_err(|_| DataverseError::new(String::from("the emulator was poisoned by a panic")))
+    }
+
+    fn handle(&self, request: &Request) -> Result<Reply> {
+        let api_path = format!("/api/data/v{}/", VERSION);
+        let path = request
+            .url()
+            .path()
+            .split_once(&api_path)
+            .map(|(_, path)| path.to_string())
+            .unwrap_or_default();
+
+        let options: HashMap<String, String> = request.url().query_pairs().into_owned().collect();
+
+        if ["$expand", "$apply", "fetchXml"].iter().any(|option| options.contains_key(*option)) {
+            return Ok(not_implemented(request));
+        }
+
+        let body = match request.body().and_then(Body::as_bytes) {
+            Some(body) if !body.is_empty() => Some(serde_json::from_slice::<Value>(body).into_dataverse_result()?),
+            _ => None,
+        };
+
+        let (table, id) = match parse_path(&path) {
+            Some(target) => target,
+            None => return Ok(not_implemented(request)),
+        };
+
+        let mut state = self.lock()?;
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        match (request.method(), id, body) {
+            (&Method::POST, None, Some(Value::Object(record))) => {
+                if state.contains_key_of(&table, &record) {
+                    return Ok(duplicate_record());
+                }
+
+                let id = state.store(&table, None, record)?;
+                let entity_id = format!("{}({})", request.url().as_str().split('?').next().unwrap_or_default(), id);
+                Ok((StatusCode::NO_CONTENT, vec![(String::from("OData-EntityId"), entity_id)], Value::Null))
+            }
+            (&Method::GET, Some(id), None) => {
+                let record = state.tables.get(&table).and_then(|records| records.get(&id));
+
+                match record {
+                    Some(record) => Ok((StatusCode::OK, Vec::new(), Value::Object(select(record, options.get("$select"))))),
+                    None => Ok(not_found(&table, id)),
+                }
+            }
+            (&Method::GET, None, None) => query(&state, &table, &options),
+            (&Method::PATCH, Some(id), Some(Value::Object(record))) => {
+                let exists = state.tables.get(&table).is_some_and(|records| records.contains_key(&id));
+
+                if header("If-Match").as_deref() == Some("*") && !exists {
+                    return Ok(not_found(&table, id));
+                }
+
+                if header("If-None-Match").as_deref() == Some("*") && exists {
+                    return Ok(duplicate_record());
+                }
+
+                state.store(&table, Some(id), record)?;
+                Ok((StatusCode::NO_CONTENT, Vec::new(), Value::Null))
+            }
+            (&Method::DELETE, Some(id), None) => {
+                match state.tables.get_mut(&table).and_then(|records| records.remove(&id)) {
+                    Some(_) => Ok((StatusCode::NO_CONTENT, Vec::new(), Value::Null)),
+                    None => Ok(not_found(&table, id)),
+                }
+            }
+            _ => Ok(not_implemented(request)),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTransport for Emulator {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        let (status, headers, body) = self.handle(&request)?;
+        let mut builder = http::Response::builder().status(status);
+
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+
+        let body = match body {
+            Value::Null => String::new(),
+            body => {
+                builder = builder.header("Content-Type", "application/json; odata.metadata=minimal");
+                body.to_string()
+            }
+        };
+
+        Ok(Response::from(builder.body(body).into_dataverse_result()?))
+    }
+}
+
+/// splits a path like `contacts` or `contacts(<id>)` into the table and the id
+fn parse_path(path: &str) -> Option<(String, Option<Uuid>)> {
+    let path = path.trim_end_matches('/');
+
+    match path.split_once('(') {
+        None if !path.is_empty() && !path.contains('/') && !path.starts_with('$') => Some((path.to_string(), None)),
+        None => None,
+        Some((table, rest)) => {
+            let id = rest.strip_suffix(')').filter(|id| UUID_REGEX.is_match(id))?;
+            Some((table.to_string(), Some(Uuid::parse_str(id).ok()?)))
+        }
+    }
+}
+
+/// stores lookups bound with `<name>@odata.bind` as `_<name>_value`
+fn bind_lookups(record: &mut Record) {
+    let binds: Vec<String> = record
+        .keys()
+        .filter(|key| key.ends_with("@odata.bind"))
+        .cloned()
+        .collect();
+
+    for bind in binds {
+        let value = record.remove(&bind);
+        let name = bind.trim_end_matches("@odata.bind");
+        let id = value
+            .as_ref()
+            .and_then(Value::as_str)
+            .and_then(|value| UUID_REGEX.find(value))
+            .map(|id| Value::String(id.as_str().to_string()))
+            .unwrap_or(Value::Null);
+
+        record.insert(format!("_{}_value", name), id);
+    }
+}
+
+fn select(record: &Record, columns: Option<&String>) -> Record {
+    match columns {
+        Some(columns) => columns
+            .split(',')
+            .map(|column| (column.to_string(), record.get(column).cloned().unwrap_or(Value::Null)))
+            .collect(),
+        None => record.clone(),
+    }
+}
+
+fn query(state: &State, table: &str, options: &HashMap<String, String>) -> Result<Reply> {
+    let filter = match options.get("$filter") {
+        Some(filter) => match parse_filter(filter) {
+            Ok(filter) => Some(filter),
+            Err(message) => return Ok(error(StatusCode::NOT_IMPLEMENTED, "0x80060888", &message)),
+        },
+        None => None,
+    };
+
+    let mut records: Vec<&Record> = state
+        .tables
+        .get(table)
+        .map(|records| records.values().collect())
+        .unwrap_or_default();
+
+    if let Some(filter) = filter.as_ref() {
+        records.retain(|record| filter.matches(record));
+    }
+
+    if let Some(order) = options.get("$orderby") {
+        let order: Vec<(&str, bool)> = order
+            .split(',')
+            .map(|part| {
+                let mut words = part.split_whitespace();
+                let column = words.next().unwrap_or_default();
+                (column, words.next() == Some("desc"))
+            })
+            .collect();
+
+        records.sort_by(|left, right| {
+            order
+                .iter()
+                .map(|(column, descending)| {
+                    let ordering = compare_values(
+                        left.get(*column).unwrap_or(&Value::Null),
+                        right.get(*column).unwrap_or(&Value::Null),
+                    )
+                    .unwrap_or(Ordering::Equal);
+
+                    if *descending { ordering.reverse() } else { ordering }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+
+    if let Some(top) = options.get("$top").and_then(|top| top.parse::<usize>().ok()) {
+        records.truncate(top);
+    }
+
+    let value: Vec<Value> = records
+        .into_iter()
+        .map(|record| Value::Object(select(record, options.get("$select"))))
+        .collect();
+
+    Ok((StatusCode::OK, Vec::new(), json!({ "value": value })))
+}
+
+fn error(status: StatusCode, code: &str, message: &str) -> Reply {
+    (status, Vec::new(), json!({ "error": { "code": code, "message": message } }))
+}
+
+fn not_found(table: &str, id: Uuid) -> Reply {
+    error(
+        StatusCode::NOT_FOUND,
+        "0x80040217",
+        &format!("{} With Id = {} Does Not Exist", table, id.as_hyphenated()),
+    )
+}
+
+fn duplicate_record() -> Reply {
+    error(StatusCode::PRECONDITION_FAILED, "0x80040237", "A record with matching key values already exists.")
+}
+
+fn not_implemented(request: &Request) -> Reply {
+    error(
+        StatusCode::NOT_IMPLEMENTED,
+        "0x80060888",
+        &format!("{} {} is not supported by the emulator", request.method(), request.url()),
+    )
+}
+
+/// A parsed `$filter` expression
+#[derive(Debug, PartialEq)]
+enum Expression {
+    Compare(String, String, Value),
+    Function(String, String, String),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+}
+
+impl Expression {
+    fn matches(&self, record: &Record) -> bool {
+        match self {
+            Expression::Compare(column, operator, literal) => {
+                let value = record.get(column).unwrap_or(&Value::Null);
+                let ordering = compare_values(value, literal);
+
+                match operator.as_str() {
+                    "eq" => ordering == Some(Ordering::Equal),
+                    "ne" => ordering != Some(Ordering::Equal),
+                    "gt" => ordering == Some(Ordering::Greater),
+                    "ge" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    "lt" => ordering == Some(Ordering::Less),
+                    "le" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    _ => false,
+                }
+            }
+            Expression::Function(function, column, text) => {
+                let value = match record.get(column).and_then(Value::as_str) {
+                    Some(value) => value.to_lowercase(),
+                    None => return false,
+                };
+                let text = text.to_lowercase();
+
+                match function.as_str() {
+                    "contains" => value.contains(&text),
+                    "startswith" => value.starts_with(&text),
+                    "endswith" => value.ends_with(&text),
+                    _ => false,
+                }
+            }
+            Expression::And(left, right) => left.matches(record) && right.matches(record),
+            Expression::Or(left, right) => left.matches(record) || right.matches(record),
+            Expression::Not(expression) => !expression.matches(record),
+        }
+    }
+}
+
+/// compares two JSON values like Dataverse, returns `None` for values that cannot be compared
+fn compare_values(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Bool(left), Value::Bool(right)) => Some(left.cmp(right)),
+        (Value::Number(left), Value::Number(right)) => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        (Value::String(left), Value::String(right)) => Some(left.to_lowercase().cmp(&right.to_lowercase())),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    Word(String),
+    Literal(Value),
+}
+
+fn tokenize(filter: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = filter.chars().peekable();
+
+    while let Some(&char) = chars.peek() {
+        match char {
+            ' ' => {
+                chars.next();
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match char {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Comma,
+                });
+            }
+            '\'' => {
+                chars.next();
+                let mut text = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            text.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(char) => text.push(char),
+                        None => return Err(String::from("unterminated string in $filter")),
+                    }
+                }
+
+                tokens.push(Token::Literal(Value::String(text)));
+            }
+            _ => {
+                let mut word = String::new();
+
+                while let Some(&char) = chars.peek() {
+                    if char == ' ' || char == '(' || char == ')' || char == ',' || char == '\'' {
+                        break;
+                    }
+                    word.push(char);
+                    chars.next();
+                }
+
+                tokens.push(match word.as_str() {
+                    "null" => Token::Literal(Value::Null),
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    _ if UUID_REGEX.is_match(&word) && word.len() == 36 => Token::Literal(Value::String(word)),
+                    _ => match serde_json::from_str::<serde_json::Number>(&word) {
+                        Ok(number) => Token::Literal(Value::Number(number)),
+                        Err(_) => Token::Word(word),
+                    },
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_filter(filter: &str) -> std::result::Result<Expression, String> {
+    let tokens = tokenize(filter)?;
+    let mut parser = FilterParser { tokens, position: 0 };
+    let expression = parser.parse_or()?;
+
+    match parser.position == parser.tokens.len() {
+        true => Ok(expression),
+        false => Err(format!("unexpected token in $filter: {:?}", parser.tokens[parser.position])),
+    }
+}
+
+struct FilterParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl FilterParser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(next)) if next == word)
+    }
+
+    fn expect(&mut self, expected: Token) -> std::result::Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("expected {:?} in $filter but found {:?}", expected, token)),
+        }
+    }
+
+    fn parse_or(&mut self) -> std::result::Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+
+        while self.peek_word("or") {
+            self.position += 1;
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> std::result::Result<Expression, String> {
+        let mut expression = self.parse_unary()?;
+
+        while self.peek_word("and") {
+            self.position += 1;
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> std::result::Result<Expression, String> {
+        if self.peek_word("not") {
+            self.position += 1;
+            return Ok(Expression::Not(Box::new(self.parse_unary()?)));
+        }
+
+        match self.next() {
+            Some(Token::Open) => {
+                let expression = self.parse_or()?;
+                self.expect(Token::Close)?;
+                Ok(expression)
+            }
+            Some(Token::Word(function)) if ["contains", "startswith", "endswith"].contains(&function.as_str()) => {
+                self.expect(Token::Open)?;
+                let column = match self.next() {
+                    Some(Token::Word(column)) => column,
+                    token => return Err(format!("expected a column in {} but found {:?}", function, token)),
+                };
+                self.expect(Token::Comma)?;
+                let text = match self.next() {
+                    Some(Token::Literal(Value::String(text))) => text,
+                    token => return Err(format!("expected a string in {} but found {:?}", function, token)),
+                };
+                self.expect(Token::Close)?;
+                Ok(Expression::Function(function, column, text))
+            }
+            Some(Token::Word(column)) => {
+                let operator = match self.next() {
+                    Some(Token::Word(operator)) if ["eq", "ne", "gt", "ge", "lt", "le"].contains(&operator.as_str()) => operator,
+                    token => return Err(format!("unsupported operator in $filter: {:?}", token)),
+                };
+                let literal = match self.next() {
+                    Some(Token::Literal(literal)) => literal,
+                    token => return Err(format!("expected a value in $filter but found {:?}", token)),
+                };
+                Ok(Expression::Compare(column, operator, literal))
+            }
+            token => Err(format!("unsupported expression in $filter: {:?}", token)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::{
+        entity::ReadEntity,
+        error::ErrorCode,
+        query::{filter::Filter, order::Order, Query},
+        reference::ReferenceStruct,
+        select::Select,
+        update::RecordChanges,
+    };
+
+    use super::{parse_filter, Emulator};
+
+    #[derive(Debug, Deserialize)]
+    struct Contact {
+        firstname: String,
+        age: Option<i64>,
+    }
+
+    impl ReadEntity for Contact {}
+
+    impl Select for Contact {
+        fn get_columns() -> &'static [&'static str] {
+            &["firstname", "age"]
+        }
+    }
+
+    #[test]
+    fn filters_records() {
+        let record = json!({ "firstname": "Testy", "age": 42, "lastname": null, "contactid": "00000000-0000-0000-0000-000000000000" });
+        let record = record.as_object().unwrap();
+        let matches = |filter: &str| parse_filter(filter).unwrap().matches(record);
+
+        assert!(matches("firstname eq 'testy' and age gt 40"));
+        assert!(matches("(age lt 18 or age ge 42) and lastname eq null"));
+        assert!(matches("not startswith(firstname,'Mar') and contains(firstname,'ST')"));
+        assert!(matches("contactid eq 00000000-0000-0000-0000-000000000000"));
+        assert!(!matches("age ne 42"));
+        assert!(parse_filter("Microsoft.Dynamics.CRM.Today(PropertyName='createdon')").is_err());
+    }
+
+    #[tokio::test]
+    async fn client_roundtrip() {
+        let emulator = Emulator::new();
+        let client = emulator.client();
+
+        for (firstname, age) in [("Testy", 42), ("Marianne", 31), ("Tim", 18)] {
+            let id = Uuid::new_v4();
+            client
+                .upsert(
+                    &RecordChanges::new(ReferenceStruct::new("contacts", id))
+                        .set("firstname", firstname)
+                        .set("age", age),
+                )
+                .await
+                .unwrap();
+        }
+
+        let query = Query::new("contacts")
+            .filter(Filter::greater_than("age", 20))
+            .order(vec![Order::Descending("firstname")]);
+        let contacts: Vec<Contact> = client.retrieve_all(&query).await.unwrap();
+        let names: Vec<&str> = contacts.iter().map(|contact| contact.firstname.as_str()).collect();
+        assert_eq!(names, vec!["Testy", "Marianne"]);
+
+        let id = emulator.insert("contacts", json!({ "firstname": "Extra" })).unwrap();
+        let reference = ReferenceStruct::new("contacts", id);
+        let contact: Contact = client.retrieve(&reference).await.unwrap();
+        assert_eq!(contact.age, None);
+
+        client.delete(&reference).await.unwrap();
+        assert!(client.retrieve::<Contact>(&reference).await.is_err());
+        assert_eq!(emulator.get_records("contacts").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn rejects_creates_of_existing_records() {
+        let emulator = Emulator::new();
+        let client = emulator.client();
+        let id = emulator.insert("contacts", json!({ "firstname": "Testy" })).unwrap();
+
+        let duplicate = RecordChanges::new(ReferenceStruct::new("contacts", id))
+            .set("contactid", id.to_string().as_str())
+            .set("firstname", "Duplicate");
+        let error = client.create(&duplicate).await.unwrap_err();
+        assert_eq!(error.get_error_code(), Some(ErrorCode::DuplicateRecord));
+
+        let contact: Contact = client.retrieve(&ReferenceStruct::new("contacts", id)).await.unwrap();
+        assert_eq!(contact.firstname, "Testy");
+    }
+}
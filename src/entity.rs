@@ -1,6 +1,30 @@
+/*!
+Module for defining the entity types that are read from and written into
+Microsoft Dataverse
+
+Entities are modeled as plain rust structs that implement the traits of this module:
+- `ReadEntity` for structs that are deserialized from retrieved records
+- `WriteEntity` for structs that are serialized into created or updated records
+
+The `EntityName` trait provides the logical name and the entity set name of an
+entity at compile time. References to entity records are handled by the `reference` module.
+There is no separate dynamic entity model in this crate. Records with columns that are
+only known at runtime can be read into a struct wrapping a `serde_json::Value` map
+or with `unmapped::WithUnmapped`, which keeps the columns a struct doesn't contain.
+One-off updates can be written without a struct with `Client::update_builder(...)`
+*/
+
 use serde::{Serialize, de::DeserializeOwned};
 
-use crate::{reference::Reference, select::Select};
+use crate::{
+    formatted::FORMATTED_VALUES_PREFERENCE,
+    reference::{Reference, LOOKUP_LOGICAL_NAMES_PREFERENCE},
+    select::Select,
+};
+
+/// value of the `Prefer` header that requests formatted values and lookup logical names
+static ALL_ANNOTATIONS_PREFERENCE: &str =
+    "odata.include-annotations=\"OData.Community.Display.V1.FormattedValue,Microsoft.Dynamics.CRM.lookuplogicalname\"";
 
 /**
 Supertrait for entities that can be retrieved from a Microsoft
@@ -36,7 +60,37 @@ impl Select for Contact {
 }
 ```
 */
-pub trait ReadEntity: DeserializeOwned + Select {}
+pub trait ReadEntity: DeserializeOwned + Select {
+    /**
+    Indicates if the formatted values of the columns shall be included in the response
+
+    This is `false` by default. See `formatted::WithFormatted` for an entity that
+    deserializes them
+    */
+    fn include_formatted_values() -> bool {
+        false
+    }
+
+    /**
+    Indicates if the logical names of the entities referenced by lookups shall be included in the response
+
+    This is `false` by default. Polymorphic lookups like `regardingobjectid` need it,
+    see `activity::Regarding` for an example
+    */
+    fn include_lookup_logical_names() -> bool {
+        false
+    }
+}
+
+/// returns the value of the `Prefer` header that requests the annotations the entity includes
+pub(crate) fn get_annotations_preference<E: ReadEntity>() -> Option<&'static str> {
+    match (E::include_formatted_values(), E::include_lookup_logical_names()) {
+        (false, false) => None,
+        (true, false) => Some(FORMATTED_VALUES_PREFERENCE),
+        (false, true) => Some(LOOKUP_LOGICAL_NAMES_PREFERENCE),
+        (true, true) => Some(ALL_ANNOTATIONS_PREFERENCE),
+    }
+}
 
 /**
 Supertrait for entities that can be written into a Microsoft
@@ -77,3 +131,39 @@ impl Reference for Contact {
 ```
 */
 pub trait WriteEntity: Serialize + Reference {}
+
+/**
+trait for entities whose names are known at compile time
+
+Dataverse distinguishes between the logical name of an entity (e.g. `contact`)
+which is used in actions and metadata, and the name of its entity set (e.g. `contacts`)
+which is used in urls and binds
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    entity::EntityName,
+    reference::EntityReference
+};
+
+struct Contact {
+    contactid: Uuid,
+}
+
+impl EntityName for Contact {
+    const LOGICAL: &'static str = "contact";
+    const COLLECTION: &'static str = "contacts";
+}
+
+let reference = EntityReference::of::<Contact>(Uuid::nil());
+assert_eq!(reference.entity_name, "contacts");
+```
+*/
+pub trait EntityName {
+    /// the logical name of the entity (e.g. `contact`)
+    const LOGICAL: &'static str;
+
+    /// the name of the entity set of the entity (e.g. `contacts`)
+    const COLLECTION: &'static str;
+}
@@ -0,0 +1,232 @@
+/*!
+Module for reading and setting environment variables
+
+An environment variable consists of a definition with an optional default value
+and an optional value record that overrides the default in the current environment.
+These functions resolve both tables in one call
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+
+    client.set_environment_variable("contoso_ApiUrl", "https://api.contoso.com").await?;
+
+    let variable = client.retrieve_environment_variable("contoso_ApiUrl").await?;
+    println!("api url: {:?}", variable.get_effective_value());
+    Ok(())
+}
+```
+*/
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::{ReadEntity, WriteEntity},
+    error::DataverseError,
+    query::{attribute::Attribute, filter::Filter, Query},
+    reference::{Reference, ReferenceStruct},
+    result::Result,
+    select::Select,
+};
+
+/// An environment variable with its definition and its current value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvironmentVariable {
+    pub definition_id: Uuid,
+    pub schema_name: String,
+    pub default_value: Option<String>,
+    pub value_id: Option<Uuid>,
+    pub value: Option<String>,
+}
+
+impl EnvironmentVariable {
+    /// returns the value of the environment variable or its default value if no value is set
+    pub fn get_effective_value(&self) -> Option<&str> {
+        self.value.as_deref().or(self.default_value.as_deref())
+    }
+}
+
+#[derive(Deserialize)]
+struct DefinitionRecord {
+    environmentvariabledefinitionid: Uuid,
+    schemaname: String,
+    defaultvalue: Option<String>,
+}
+
+impl ReadEntity for DefinitionRecord {}
+
+impl Select for DefinitionRecord {
+    fn get_columns() -> &'static [&'static str] {
+        &["environmentvariabledefinitionid", "schemaname", "defaultvalue"]
+    }
+}
+
+#[derive(Deserialize)]
+struct ValueRecord {
+    environmentvariablevalueid: Uuid,
+    value: Option<String>,
+}
+
+impl ReadEntity for ValueRecord {}
+
+impl Select for ValueRecord {
+    fn get_columns() -> &'static [&'static str] {
+        &["environmentvariablevalueid", "value"]
+    }
+}
+
+#[derive(Serialize)]
+struct ValueWrite<'a> {
+    environmentvariablevalueid: Uuid,
+    value: &'a str,
+    #[serde(
+        rename = "EnvironmentVariableDefinitionId@odata.bind",
+        skip_serializing_if = "Option::is_none"
+    )]
+    definition: Option<String>,
+}
+
+impl WriteEntity for ValueWrite<'_> {}
+
+impl Reference for ValueWrite<'_> {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("environmentvariablevalues", self.environmentvariablevalueid)
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the definition and the current value of the environment variable
+    with the given schema name
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no environment variable with this schema name
+    */
+    pub async fn retrieve_environment_variable(&self, schema_name: &str) -> Result<EnvironmentVariable> {
+        let query = Query::new("environmentvariabledefinitions")
+            .limit(1)
+            .filter(Filter::Equal(
+                "schemaname",
+                Attribute::String(schema_name.to_string()),
+            ));
+
+        let definition = self
+            .retrieve_multiple::<DefinitionRecord>(&query)
+            .await?
+            .into_inner()
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                DataverseError::new(format!("environment variable {} does not exist", schema_name))
+            })?;
+
+        let query = Query::new("environmentvariablevalues")
+            .limit(1)
+            .filter(Filter::Equal(
+                "_environmentvariabledefinitionid_value",
+                Attribute::Uuid(definition.environmentvariabledefinitionid),
+            ));
+
+        let value = self
+            .retrieve_multiple::<ValueRecord>(&query)
+            .await?
+            .into_inner()
+            .into_iter()
+            .next();
+
+        Ok(EnvironmentVariable {
+            definition_id: definition.environmentvariabledefinitionid,
+            schema_name: definition.schemaname,
+            default_value: definition.defaultvalue,
+            value_id: value.as_ref().map(|value| value.environmentvariablevalueid),
+            value: value.and_then(|value| value.value),
+        })
+    }
+
+    /**
+    sets the value of the environment variable with the given schema name
+
+    The value record is updated if it exists and created otherwise.
+    The default value of the definition is not changed
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    - There is no environment variable with this schema name
+    */
+    pub async fn set_environment_variable(&self, schema_name: &str, value: &str) -> Result<()> {
+        let variable = self.retrieve_environment_variable(schema_name).await?;
+
+        match variable.value_id {
+            Some(value_id) => {
+                self.update(&ValueWrite {
+                    environmentvariablevalueid: value_id,
+                    value,
+                    definition: None,
+                })
+                .await
+            }
+            None => {
+                self.create(&ValueWrite {
+                    environmentvariablevalueid: Uuid::new_v4(),
+                    value,
+                    definition: Some(format!(
+                        "/environmentvariabledefinitions({})",
+                        variable.definition_id.as_hyphenated()
+                    )),
+                })
+                .await
+                .map(|_| ())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{EnvironmentVariable, ValueWrite};
+
+    #[test]
+    fn effective_value_falls_back_to_default() {
+        let mut variable = EnvironmentVariable {
+            definition_id: Uuid::nil(),
+            schema_name: String::from("contoso_ApiUrl"),
+            default_value: Some(String::from("https://default")),
+            value_id: None,
+            value: None,
+        };
+        assert_eq!(variable.get_effective_value(), Some("https://default"));
+
+        variable.value = Some(String::from("https://override"));
+        assert_eq!(variable.get_effective_value(), Some("https://override"));
+    }
+
+    #[test]
+    fn value_binds_definition() {
+        let value = ValueWrite {
+            environmentvariablevalueid: Uuid::nil(),
+            value: "42",
+            definition: Some(String::from("/environmentvariabledefinitions(00000000-0000-0000-0000-000000000001)")),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"environmentvariablevalueid":"00000000-0000-0000-0000-000000000000","value":"42","EnvironmentVariableDefinitionId@odata.bind":"/environmentvariabledefinitions(00000000-0000-0000-0000-000000000001)"}"#
+        );
+    }
+}
@@ -1,17 +1,171 @@
 use std::{error::Error, fmt::Display};
 
+use serde::Deserialize;
+
 /**
 The Error that is returned if any of the operations in this crate
 fails.
 */
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DataverseError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+/// The kind of a `DataverseError` for errors that callers may want to handle specifically
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Indicates an error without a more specific kind
+    #[default]
+    Other,
+
+    /// Indicates a request payload that exceeds the configured maximum size in bytes
+    PayloadTooLarge { size: usize, limit: usize },
+
+    /// Indicates a failed request inside a batch which rolled back its changeset
+    BatchItemFailed { content_id: Option<u16>, status: u16 },
+
+    /// Indicates a request the server rejected because its url, headers or payload are too large
+    /// (status 413, 414 or 431)
+    RequestTooLarge { status: u16 },
+
+    /// Indicates a table or user that doesn't allow overriding the creation of migrated records
+    MigrationNotSupported,
+
+    /// Indicates a user that lacks a privilege required for an operation
+    PrivilegeMissing,
+
+    /// Indicates that the authentication provider failed to acquire a token
+    Authentication,
+}
+
+/**
+Well-known error codes of the Dataverse Web API
+
+see `DataverseError::get_error_code()` and `ServiceError::get_error_code()`
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The referenced record does not exist (`0x80040217`)
+    RecordNotFound,
+
+    /// A record with the same primary or alternate key already exists (`0x80040237`, `0x80040333`)
+    DuplicateRecord,
+
+    /// The user lacks a privilege for the operation (`0x80040220`, `0x80042f09`)
+    PrivilegeDenied,
+
+    /// The request is invalid, e.g. because of an unknown column (`0x80040203`, `0x80048d19`)
+    InvalidArgument,
+
+    /// The record was changed since the given ETag was read (`0x80060882`)
+    ConcurrencyVersionMismatch,
+
+    /// The record is inactive or closed and can't be changed in its state (`0x80043b09`)
+    RecordInactive,
+
+    /// Service protection limit on the number of requests (`0x80072322`)
+    TooManyRequests,
+
+    /// Service protection limit on the combined execution time (`0x80072321`)
+    ExecutionTimeExceeded,
+
+    /// Service protection limit on the number of concurrent requests (`0x80072326`)
+    TooManyConcurrentRequests,
+
+    /// Any other error code as sent by Dataverse
+    Other(String),
+}
+
+impl ErrorCode {
+    /// maps an error code of Dataverse (e.g. `0x80040217`) to its typed representation
+    pub fn from_code(code: &str) -> Self {
+        match code.to_lowercase().as_str() {
+            "0x80040217" => Self::RecordNotFound,
+            "0x80040237" | "0x80040333" => Self::DuplicateRecord,
+            "0x80040220" | "0x80042f09" => Self::PrivilegeDenied,
+            "0x80040203" | "0x80048d19" => Self::InvalidArgument,
+            "0x80060882" => Self::ConcurrencyVersionMismatch,
+            "0x80043b09" => Self::RecordInactive,
+            "0x80072322" => Self::TooManyRequests,
+            "0x80072321" => Self::ExecutionTimeExceeded,
+            "0x80072326" => Self::TooManyConcurrentRequests,
+            _ => Self::Other(code.to_string()),
+        }
+    }
+
+    /// returns true for the error codes of the service protection limits
+    pub fn is_throttling(&self) -> bool {
+        matches!(
+            self,
+            Self::TooManyRequests | Self::ExecutionTimeExceeded | Self::TooManyConcurrentRequests
+        )
+    }
+}
+
+/**
+The error details Dataverse returns in the body of failed responses
+
+```json
+{"error":{"code":"0x80040237","message":"A record with matching key values already exists."}}
+```
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ServiceError {
+    #[serde(default)]
+    pub code: String,
     pub message: String,
 }
 
+impl ServiceError {
+    /// parses the error details from a response body, returns `None` for other bodies
+    pub fn parse(body: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct ErrorResponse {
+            error: ServiceError,
+        }
+
+        serde_json::from_str::<ErrorResponse>(body)
+            .ok()
+            .map(|response| response.error)
+    }
+
+    /// returns the typed error code of these details
+    pub fn get_error_code(&self) -> ErrorCode {
+        ErrorCode::from_code(&self.code)
+    }
+}
+
 impl DataverseError {
     pub fn new(message: String) -> Self {
-        Self { message }
+        Self::with_kind(ErrorKind::Other, message)
+    }
+
+    /// creates an error of the given kind
+    pub fn with_kind(kind: ErrorKind, message: String) -> Self {
+        Self { kind, message }
+    }
+
+    /**
+    returns the typed error code if the message contains the error details of Dataverse
+
+    Errors of failed requests carry the response body of Dataverse as message, so
+    application code can branch on the code instead of matching the message text
+
+    ```rust
+    use powerplatform_dataverse_service_client::error::{DataverseError, ErrorCode};
+
+    let error = DataverseError::new(String::from(
+        r#"{"error":{"code":"0x80040220","message":"Principal user is missing prvReadAccount privilege"}}"#
+    ));
+
+    assert_eq!(error.get_error_code(), Some(ErrorCode::PrivilegeDenied));
+    ```
+    */
+    pub fn get_error_code(&self) -> Option<ErrorCode> {
+        ServiceError::parse(&self.message).map(|error| error.get_error_code())
     }
 }
 
@@ -22,3 +176,34 @@ impl Display for DataverseError {
         f.write_str(&self.message)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DataverseError, ErrorCode, ServiceError};
+
+    #[test]
+    fn parse_service_error() {
+        let error = ServiceError::parse(
+            r#"{"error":{"code":"0x80040237","message":"A record with matching key values already exists."}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(error.code, "0x80040237");
+        assert_eq!(error.message, "A record with matching key values already exists.");
+        assert!(ServiceError::parse("Bad Request").is_none());
+    }
+
+    #[test]
+    fn typed_error_codes() {
+        assert_eq!(ErrorCode::from_code("0x80040333"), ErrorCode::DuplicateRecord);
+        assert_eq!(ErrorCode::from_code("0x80042F09"), ErrorCode::PrivilegeDenied);
+        assert!(ErrorCode::from_code("0x80072326").is_throttling());
+        assert_eq!(ErrorCode::from_code("0x8004431a"), ErrorCode::Other(String::from("0x8004431a")));
+
+        let error = DataverseError::new(String::from(
+            r#"{"error":{"code":"0x80040217","message":"contact With Id = 1 Does Not Exist"}}"#,
+        ));
+        assert_eq!(error.get_error_code(), Some(ErrorCode::RecordNotFound));
+        assert_eq!(DataverseError::new(String::from("timeout")).get_error_code(), None);
+    }
+}
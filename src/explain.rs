@@ -0,0 +1,197 @@
+/*!
+Module for inspecting the requests of queries without sending them
+
+`Client::explain_query(...)` builds the exact request the client would send for
+`retrieve_multiple(...)`, including the query options added by the client configuration
+(e.g. stable paging or `$query` requests for long urls). Its `Display` output can be
+pasted into a browser, Postman or a `.http` file when diagnosing filter issues.
+The authorization header is left out on purpose
+
+# Examples
+```rust
+use serde::Deserialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    query::{filter::Filter, Query},
+    result::Result,
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let query = Query::new("contacts").filter(Filter::equal("firstname", "Testy"));
+    let request = client.explain_query::<Contact>(&query).await?;
+
+    println!("{}", request);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname"]
+    }
+}
+```
+*/
+
+use std::fmt::Display;
+
+use reqwest::Method;
+
+use crate::{
+    auth::Authenticate,
+    client::{build_query_parameters, prefer_annotations, Client},
+    entity::ReadEntity,
+    query::Query,
+    result::{IntoDataverseResult, Result},
+};
+
+/// A request that the client would send, without its authorization
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExplainedRequest {
+    pub method: Method,
+    /// the percent-encoded url of the request
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl ExplainedRequest {
+    fn from_request(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().to_string(),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.to_string(),
+                        String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                    )
+                })
+                .collect(),
+            body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .map(|body| String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+}
+
+impl Display for ExplainedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} {}", self.method, self.url)?;
+
+        for (name, value) in &self.headers {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+
+        if let Some(body) = &self.body {
+            writeln!(f)?;
+            writeln!(f, "{}", body)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    returns the request that `retrieve_multiple(...)` would send for the query without sending it
+
+    This may fail for any of these reasons
+    - An authentication failure (only with stable paging)
+    - Any http client or server error (only with stable paging)
+    - The url cannot be parsed
+    */
+    pub async fn explain_query<E: ReadEntity>(&self, query: &Query) -> Result<ExplainedRequest> {
+        let query = &query.with_default_filter(E::get_default_filter());
+        let columns = E::get_columns();
+        let primary_key = self.get_stable_primary_key(query).await?;
+        let url_path = self.build_query_url(columns, query, primary_key.as_deref());
+
+        let request = match self.is_url_too_long(&url_path) {
+            true => {
+                let parameters = build_query_parameters(columns, query, primary_key.as_deref());
+                let url_path = self.build_simple_url(format_args!("{}/$query", query.logical_name));
+                let body = parameters.to_string().trim_start_matches('?').to_string();
+
+                self.prepare_request(Method::POST, &url_path, move |request| {
+                    Ok(prefer_annotations::<E>(request)
+                        .header("Content-Type", "text/plain")
+                        .body(body))
+                })?
+            }
+            false => self.prepare_request(Method::GET, &url_path, |request| {
+                Ok(prefer_annotations::<E>(request))
+            })?,
+        };
+
+        Ok(ExplainedRequest::from_request(
+            &request.build().into_dataverse_result()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::{
+        auth::no_auth::NoAuth,
+        client::Client,
+        entity::ReadEntity,
+        query::{filter::Filter, Query},
+        select::Select,
+    };
+
+    #[derive(Deserialize)]
+    struct Contact {}
+
+    impl ReadEntity for Contact {}
+
+    impl Select for Contact {
+        fn get_columns() -> &'static [&'static str] {
+            &["firstname"]
+        }
+    }
+
+    fn client() -> Client<'static, NoAuth> {
+        Client::new("https://instance.crm.dynamics.com/", reqwest::Client::new(), NoAuth {})
+    }
+
+    #[tokio::test]
+    async fn explains_query() {
+        let query = Query::new("contacts").filter(Filter::equal("firstname", "Testy"));
+
+        let request = client().explain_query::<Contact>(&query).await.unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(
+            request.url,
+            "https://instance.crm.dynamics.com/api/data/v9.2/contacts?$select=firstname&$filter=firstname%20eq%20%27Testy%27"
+        );
+        assert!(request.to_string().contains("odata-version: 4.0"));
+
+        let request = client()
+            .with_max_url_length(10)
+            .explain_query::<Contact>(&query)
+            .await
+            .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(
+            request.body.as_deref(),
+            Some("$select=firstname&$filter=firstname eq 'Testy'")
+        );
+    }
+}
@@ -0,0 +1,449 @@
+/*!
+Module for exports that survive restarts of the exporting process
+
+An `ExportJob` pages through the records of a query and hands every page to a handler.
+After each page a checkpoint with the next link and the number of exported records is
+saved into a `CheckpointStore`. Running the job again after a crash or restart loads the
+checkpoint and continues with the first page that wasn't saved, and a completed job
+doesn't send any request at all
+
+Pages are delivered at least once: the checkpoint is saved after the handler returns,
+so a page whose handler succeeded is handed over again if the process stops before the
+checkpoint was saved. The handler must therefore be idempotent, e.g. by upserting the
+records by their primary key. It receives the checkpoint that is saved next, so it can
+also store it together with the exported records (e.g. in the same database transaction)
+to rule out duplicates entirely
+
+A job with `ExportJob::track_changes()` requests the change tracking of Dataverse and
+saves the delta link of its last page into the final checkpoint, which retrieves the
+records that changed since the export
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    export::{ExportJob, FileCheckpointStore},
+    query::Query,
+    result::Result,
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let job = ExportJob::new(
+        "contacts-export",
+        Query::new("contacts"),
+        FileCheckpointStore::new("checkpoints"),
+    );
+
+    let checkpoint = client
+        .run_export(&job, |contacts: Vec<Contact>, checkpoint| {
+            println!("{} contacts exported so far", checkpoint.get_exported());
+            Ok(())
+        })
+        .await?;
+
+    assert!(checkpoint.is_completed());
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname"]
+    }
+}
+```
+*/
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_page_response, Client, Page},
+    entity::{get_annotations_preference, ReadEntity},
+    error::DataverseError,
+    progress::Operation,
+    query::Query,
+    result::{IntoDataverseResult, Result},
+};
+
+const TRACK_CHANGES_PREFERENCE: &str = "odata.track-changes";
+
+/**
+The state of an export job after its last saved page
+
+The state is serializable so it can be persisted between runs
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportCheckpoint {
+    query: String,
+    next_link: Option<String>,
+    #[serde(default)]
+    delta_link: Option<String>,
+    pages: usize,
+    exported: usize,
+    completed: bool,
+}
+
+impl ExportCheckpoint {
+    /// creates the checkpoint of a job that didn't export any page of the query yet
+    fn start(query: &Query) -> Self {
+        Self {
+            query: query.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// returns the link to the next page that will be exported
+    pub fn get_next_link(&self) -> Option<&str> {
+        self.next_link.as_deref()
+    }
+
+    /**
+    returns the delta link of a completed job that tracks changes
+
+    The link retrieves the records that changed since the export with
+    `Client::resume_from_next_link(...)`, see `ExportJob::track_changes()`
+    */
+    pub fn get_delta_link(&self) -> Option<&str> {
+        self.delta_link.as_deref()
+    }
+
+    /// returns the number of exported pages
+    pub fn get_pages(&self) -> usize {
+        self.pages
+    }
+
+    /// returns the number of exported records
+    pub fn get_exported(&self) -> usize {
+        self.exported
+    }
+
+    /// Indicates if all pages of the query were exported
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+
+    fn advance<E>(&self, page: &Page<E>, follow_next_link: bool) -> Self {
+        let next_link = page
+            .get_next_link()
+            .filter(|_| follow_next_link)
+            .map(String::from);
+
+        Self {
+            query: self.query.clone(),
+            completed: next_link.is_none(),
+            next_link,
+            delta_link: page.get_delta_link().map(String::from),
+            pages: self.pages + 1,
+            exported: self.exported + page.entities.len(),
+        }
+    }
+}
+
+/**
+trait for stores that persist the checkpoints of export jobs
+
+Checkpoints are keyed by the name of their job. A checkpoint has to be stored durably
+before `save` returns, otherwise a restart may export a page twice
+*/
+pub trait CheckpointStore: Send + Sync {
+    /// returns the checkpoint saved for the job or `None` if the job didn't run yet
+    fn load(&self, job_name: &str) -> Result<Option<ExportCheckpoint>>;
+
+    /// saves the checkpoint of the job, replacing the previous one
+    fn save(&self, job_name: &str, checkpoint: &ExportCheckpoint) -> Result<()>;
+}
+
+/// A `CheckpointStore` that keeps all checkpoints in memory, mainly for tests
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, ExportCheckpoint>>,
+}
+
+impl MemoryCheckpointStore {
+    /// creates a new empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MemoryCheckpointStore {
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, ExportCheckpoint>>> {
+        self.checkpoints.lock().map_err(|_| {
+            DataverseError::new(String::from("the checkpoint store was poisoned by a panic"))
+        })
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self, job_name: &str) -> Result<Option<ExportCheckpoint>> {
+        Ok(self.lock()?.get(job_name).cloned())
+    }
+
+    fn save(&self, job_name: &str, checkpoint: &ExportCheckpoint) -> Result<()> {
+        self.lock()?.insert(job_name.to_string(), checkpoint.clone());
+        Ok(())
+    }
+}
+
+/**
+A `CheckpointStore` that writes every checkpoint as JSON file into a directory
+
+The file of a job is named after the job. It is replaced atomically by writing a
+temporary file first, so a crash while saving keeps the previous checkpoint
+*/
+#[derive(Clone, Debug)]
+pub struct FileCheckpointStore {
+    directory: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// creates a store in the given directory, which is created on the first save
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// returns the path of the checkpoint file of the job
+    pub fn get_path(&self, job_name: &str) -> PathBuf {
+        self.directory.join(format!("{}.json", job_name))
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self, job_name: &str) -> Result<Option<ExportCheckpoint>> {
+        let content = match std::fs::read(self.get_path(job_name)) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error).into_dataverse_result(),
+        };
+
+        serde_json::from_slice(&content).into_dataverse_result()
+    }
+
+    fn save(&self, job_name: &str, checkpoint: &ExportCheckpoint) -> Result<()> {
+        std::fs::create_dir_all(&self.directory).into_dataverse_result()?;
+
+        let path = self.get_path(job_name);
+        let temporary_path = path.with_extension("json.tmp");
+        let content = serde_json::to_vec_pretty(checkpoint).into_dataverse_result()?;
+
+        std::fs::write(&temporary_path, content).into_dataverse_result()?;
+        std::fs::rename(&temporary_path, &path).into_dataverse_result()
+    }
+}
+
+/**
+An export of all records of a query that can be resumed from its last saved page
+
+The query must not change between runs of the same job, because the saved next link
+only continues the query it was created for
+*/
+#[derive(Debug)]
+pub struct ExportJob<S: CheckpointStore> {
+    name: String,
+    query: Query,
+    store: S,
+    track_changes: bool,
+}
+
+impl<S: CheckpointStore> ExportJob<S> {
+    /// creates a job that saves its checkpoints under the given name into the store
+    pub fn new(name: impl Into<String>, query: Query, store: S) -> Self {
+        Self {
+            name: name.into(),
+            query,
+            store,
+            track_changes: false,
+        }
+    }
+
+    /**
+    requests the change tracking of Dataverse for the query of this job
+
+    The final checkpoint then carries the delta link to the changes since the export.
+    Change tracking has to be enabled for the table and Dataverse rejects tracked queries
+    with a filter, an order or a limit. Stable paging is not applied to tracked queries
+    */
+    pub fn track_changes(mut self) -> Self {
+        self.track_changes = true;
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_query(&self) -> &Query {
+        &self.query
+    }
+
+    pub fn get_store(&self) -> &S {
+        &self.store
+    }
+
+    /**
+    returns the saved checkpoint of this job or the checkpoint of a new job
+
+    This may fail for any of these reasons
+    - The store failed to load the checkpoint
+    - The saved checkpoint belongs to another query
+    */
+    pub fn load_checkpoint(&self) -> Result<ExportCheckpoint> {
+        let Some(checkpoint) = self.store.load(&self.name)? else {
+            return Ok(ExportCheckpoint::start(&self.query));
+        };
+
+        let query = self.query.to_string();
+
+        if checkpoint.query != query {
+            return Err(DataverseError::new(format!(
+                "the checkpoint of the export job {} belongs to the query {} instead of {}",
+                self.name, checkpoint.query, query
+            )));
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// discards the progress of this job, so the next run starts with the first page
+    pub fn reset(&self) -> Result<()> {
+        self.store
+            .save(&self.name, &ExportCheckpoint::start(&self.query))
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    exports the pages of the job that were not exported yet and returns the final checkpoint
+
+    Every page is passed to the handler together with the checkpoint that is saved after
+    it. If the handler or a request fails, the job stops and the next run continues with
+    the failed page. `Query::no_follow_next_link()` limits the job to the first page
+
+    A page is handed over again if the process stops between the handler and the save
+    of its checkpoint, so the handler has to be idempotent (see the module documentation)
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The store failed to load or save a checkpoint
+    - The saved checkpoint belongs to another query
+    - The handler returned an error
+    */
+    pub async fn run_export<E: ReadEntity, S: CheckpointStore>(
+        &self,
+        job: &ExportJob<S>,
+        mut handler: impl FnMut(Vec<E>, &ExportCheckpoint) -> Result<()>,
+    ) -> Result<ExportCheckpoint> {
+        let mut checkpoint = job.load_checkpoint()?;
+        let tracker = self.track_progress(Operation::Retrieve, None);
+
+        while !checkpoint.completed {
+            let page: Page<E> = match checkpoint.next_link.as_deref() {
+                Some(next_link) => self.resume_from_next_link(next_link).await?,
+                None if job.track_changes => self.retrieve_tracked_changes(&job.query).await?,
+                None => self.retrieve_multiple(&job.query).await?,
+            };
+
+            let next_checkpoint = checkpoint.advance(&page, job.query.follow_next_link);
+            handler(page.into_inner(), &next_checkpoint)?;
+            job.store.save(&job.name, &next_checkpoint)?;
+
+            checkpoint = next_checkpoint;
+            tracker.report(checkpoint.exported);
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// retrieves the first page of the query with the preference to track its changes
+    async fn retrieve_tracked_changes<E: ReadEntity>(&self, query: &Query) -> Result<Page<E>> {
+        let query = &query.with_default_filter(E::get_default_filter());
+        let url_path = self.build_query_url(E::get_columns(), query, None);
+        let preference = match get_annotations_preference::<E>() {
+            Some(annotations) => format!("{},{}", TRACK_CHANGES_PREFERENCE, annotations),
+            None => String::from(TRACK_CHANGES_PREFERENCE),
+        };
+
+        self.request(
+            Method::GET,
+            &url_path,
+            |request| Ok(request.header("Prefer", preference)),
+            handle_page_response,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::Query;
+
+    use super::{
+        CheckpointStore, ExportCheckpoint, ExportJob, FileCheckpointStore, MemoryCheckpointStore,
+    };
+
+    #[test]
+    fn saves_checkpoints_into_files() {
+        let directory = std::env::temp_dir().join(format!("checkpoints-{}", uuid::Uuid::new_v4()));
+        let store = FileCheckpointStore::new(&directory);
+        let job = ExportJob::new("contacts", Query::new("contacts"), store.clone());
+        assert_eq!(
+            job.load_checkpoint().unwrap(),
+            ExportCheckpoint::start(&Query::new("contacts"))
+        );
+
+        let checkpoint = ExportCheckpoint {
+            next_link: Some(String::from(
+                "https://instance/api/data/v9.2/contacts?$skiptoken=1",
+            )),
+            pages: 1,
+            exported: 5000,
+            ..ExportCheckpoint::start(&Query::new("contacts"))
+        };
+        store.save("contacts", &checkpoint).unwrap();
+        assert_eq!(job.load_checkpoint().unwrap(), checkpoint);
+
+        let other_job = ExportJob::new("contacts", Query::new("contacts").limit(10), store);
+        assert!(other_job.load_checkpoint().is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn fails_to_load_from_poisoned_memory_store() {
+        let store = MemoryCheckpointStore::new();
+        let checkpoint = ExportCheckpoint::start(&Query::new("contacts"));
+        store.save("contacts", &checkpoint).unwrap();
+        assert_eq!(store.load("contacts").unwrap(), Some(checkpoint));
+
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = store.checkpoints.lock().unwrap();
+            panic!("poisons the store");
+        });
+
+        assert!(store.load("contacts").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+/*!
+Module for failing over to alternative urls of an environment
+
+The client resolves the host name of the environment for every new connection and
+doesn't reuse connections that failed, so a changed DNS record (e.g. after a geo
+disaster recovery of Dataverse) is picked up as soon as the old connections fail.
+If the environment is reachable under other host names, they can be configured as
+fallbacks with `Client::with_failover_urls(...)`. Requests that fail without a
+response of the server are then repeated with the next url, which stays active
+until it fails itself
+*/
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use reqwest::{header::CONTENT_TYPE, Body, Request, Url};
+
+use crate::result::{IntoDataverseResult, Result};
+
+/// The urls of an environment in the order they are tried, starting with the primary url
+#[derive(Debug)]
+pub(crate) struct FailoverUrls {
+    urls: Vec<String>,
+    active: AtomicUsize,
+}
+
+impl FailoverUrls {
+    pub(crate) fn new(primary: &str, fallbacks: &[&str]) -> Self {
+        let urls = std::iter::once(primary)
+            .chain(fallbacks.iter().copied())
+            .map(|url| match url.ends_with('/') {
+                true => url.to_string(),
+                false => format!("{}/", url),
+            })
+            .collect();
+
+        Self {
+            urls,
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    /// returns the index of the url that requests are sent to
+    pub(crate) fn get_active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn get_url(&self, index: usize) -> &str {
+        &self.urls[index]
+    }
+
+    /// switches from the failed url to the next one unless another request already switched
+    pub(crate) fn advance(&self, failed: usize) {
+        let _ = self.active.compare_exchange(
+            failed,
+            (failed + 1) % self.urls.len(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+
+    /**
+    points the request built for the primary url to the url with the given index
+
+    The urls of the requests in the payload of batches are replaced as well
+    */
+    pub(crate) fn redirect(&self, request: &mut Request, index: usize) -> Result<()> {
+        let primary = self.urls[0].as_str();
+        let target = self.urls[index].as_str();
+
+        if index == 0 {
+            return Ok(());
+        }
+
+        if let Some(path) = request.url().as_str().strip_prefix(primary) {
+            *request.url_mut() = Url::parse(&format!("{}{}", target, path)).into_dataverse_result()?;
+        }
+
+        let is_batch = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("multipart/mixed"));
+
+        if is_batch {
+            let payload = request
+                .body()
+                .and_then(Body::as_bytes)
+                .map(|payload| String::from_utf8_lossy(payload).replace(primary, target));
+
+            if let Some(payload) = payload {
+                *request.body_mut() = Some(Body::from(payload));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Body, Method, Request, Url};
+
+    use super::FailoverUrls;
+
+    #[test]
+    fn redirects_requests_and_batches() {
+        let urls = FailoverUrls::new("https://primary.crm4.dynamics.com/", &["https://fallback.crm4.dynamics.com"]);
+
+        let mut request = Request::new(Method::POST, Url::parse("https://primary.crm4.dynamics.com/api/data/v9.2/$batch").unwrap());
+        request.headers_mut().insert("Content-Type", "multipart/mixed; boundary=batch_1".parse().unwrap());
+        *request.body_mut() = Some(Body::from("POST https://primary.crm4.dynamics.com/api/data/v9.2/contacts HTTP/1.1"));
+
+        urls.redirect(&mut request, 1).unwrap();
+        assert_eq!(request.url().as_str(), "https://fallback.crm4.dynamics.com/api/data/v9.2/$batch");
+        assert_eq!(
+            request.body().and_then(Body::as_bytes),
+            Some("POST https://fallback.crm4.dynamics.com/api/data/v9.2/contacts HTTP/1.1".as_bytes())
+        );
+
+        urls.advance(0);
+        urls.advance(0);
+        assert_eq!(urls.get_active(), 1);
+        urls.advance(1);
+        assert_eq!(urls.get_active(), 0);
+    }
+}
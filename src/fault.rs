@@ -0,0 +1,146 @@
+/*!
+Module for injecting artificial faults into a client
+
+This module is only available with the `fault-injection` feature and is meant
+for testing. A client with a `FaultPolicy` answers a share of its requests with
+artificial `429 Too Many Requests` or `503 Service Unavailable` responses instead
+of sending them, and can delay every request. This allows verifying the retry
+logic of an application without putting load on a real environment
+
+# Examples
+```rust
+use std::time::Duration;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    fault::FaultPolicy
+};
+
+let policy = FaultPolicy::new()
+    .throttle_rate(0.1)
+    .unavailable_rate(0.05)
+    .latency(Duration::from_millis(200));
+
+let client = Client::new_dummy().with_fault_policy(policy);
+```
+*/
+
+use std::time::Duration;
+
+use reqwest::{Response, StatusCode};
+use uuid::Uuid;
+
+use crate::runtime::Timer;
+
+/// body of injected `429 Too Many Requests` responses
+static THROTTLED_BODY: &str = r#"{"error":{"code":"0x80072322","message":"Number of requests exceeded the limit (injected fault)"}}"#;
+
+/// body of injected `503 Service Unavailable` responses
+static UNAVAILABLE_BODY: &str = r#"{"error":{"code":"0x80040216","message":"Service unavailable (injected fault)"}}"#;
+
+/// Describes which faults are injected into the requests of a client
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FaultPolicy {
+    throttle_rate: f64,
+    unavailable_rate: f64,
+    latency: Option<Duration>,
+    retry_after: Duration,
+}
+
+impl FaultPolicy {
+    /// creates a policy that doesn't inject any faults
+    pub fn new() -> Self {
+        Self {
+            retry_after: Duration::from_secs(1),
+            ..Self::default()
+        }
+    }
+
+    /// answers the given share (between 0 and 1) of requests with `429 Too Many Requests`
+    pub fn throttle_rate(mut self, rate: f64) -> Self {
+        self.throttle_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// answers the given share (between 0 and 1) of requests with `503 Service Unavailable`
+    pub fn unavailable_rate(mut self, rate: f64) -> Self {
+        self.unavailable_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// delays every request by the given period
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// sets the `Retry-After` period of injected `429` responses (1 second by default)
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// applies the latency and returns an artificial response if a fault is injected
+    pub(crate) async fn inject(&self, timer: &dyn Timer) -> Option<Response> {
+        if let Some(latency) = self.latency {
+            timer.sleep(latency).await;
+        }
+
+        self.choose_fault(random_fraction())
+    }
+
+    fn choose_fault(&self, roll: f64) -> Option<Response> {
+        if roll < self.throttle_rate {
+            Some(build_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(self.retry_after),
+                THROTTLED_BODY,
+            ))
+        } else if roll < self.throttle_rate + self.unavailable_rate {
+            Some(build_response(StatusCode::SERVICE_UNAVAILABLE, None, UNAVAILABLE_BODY))
+        } else {
+            None
+        }
+    }
+}
+
+/// returns a random number in the range `[0, 1)`
+fn random_fraction() -> f64 {
+    // the lowest 53 bits of a v4 uuid are random, the version and variant bits are above them
+    let bits = Uuid::new_v4().as_u128() & ((1u128 << 53) - 1);
+    bits as f64 / (1u64 << 53) as f64
+}
+
+fn build_response(status: StatusCode, retry_after: Option<Duration>, body: &'static str) -> Response {
+    let mut response = http::Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json");
+
+    if let Some(retry_after) = retry_after {
+        response = response.header("Retry-After", retry_after.as_secs().max(1));
+    }
+
+    Response::from(response.body(body).expect("artificial responses are valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_fraction, FaultPolicy};
+
+    #[test]
+    fn chooses_faults_by_rate() {
+        let policy = FaultPolicy::new().throttle_rate(0.2).unavailable_rate(0.3);
+
+        assert_eq!(policy.choose_fault(0.1).unwrap().status(), 429);
+        assert_eq!(policy.choose_fault(0.1).unwrap().headers()["Retry-After"], "1");
+        assert_eq!(policy.choose_fault(0.4).unwrap().status(), 503);
+        assert!(policy.choose_fault(0.6).is_none());
+    }
+
+    #[test]
+    fn random_fraction_range() {
+        for _ in 0..1000 {
+            let fraction = random_fraction();
+            assert!((0.0..1.0).contains(&fraction));
+        }
+    }
+}
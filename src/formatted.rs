@@ -0,0 +1,152 @@
+/*!
+Module for deserializing the formatted values of columns
+
+Dataverse can return a display value next to the raw value of a column, e.g. the
+label of a choice, a formatted date or the name of a referenced record. These are
+returned as annotations named `<column>@OData.Community.Display.V1.FormattedValue`
+
+`WithFormatted<E, F>` deserializes the raw entity `E` and a struct `F` with the
+formatted values from the same record. The fields of `F` are named like the columns
+whose formatted values they contain. Retrieving a `WithFormatted` automatically
+requests the formatted values from dataverse
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    formatted::WithFormatted,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result},
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let reference = ReferenceStruct::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let account: WithFormatted<Account, AccountLabels> = client.retrieve(&reference).await?;
+    println!("{} ({})", account.entity.industrycode, account.formatted.industrycode);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Account {
+    accountid: Uuid,
+    industrycode: i32,
+}
+
+impl ReadEntity for Account {}
+
+impl Select for Account {
+    fn get_columns() -> &'static [&'static str] {
+        &["accountid", "industrycode"]
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountLabels {
+    industrycode: String,
+}
+```
+*/
+
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+use crate::{entity::ReadEntity, query::filter::Filter, select::Select};
+
+/// suffix of the annotations that contain the formatted value of a column
+pub static FORMATTED_VALUE_ANNOTATION: &str = "@OData.Community.Display.V1.FormattedValue";
+
+/// value of the `Prefer` header that requests formatted values
+pub(crate) static FORMATTED_VALUES_PREFERENCE: &str =
+    "odata.include-annotations=\"OData.Community.Display.V1.FormattedValue\"";
+
+/// A record deserialized into its raw entity and its formatted values
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithFormatted<E, F> {
+    pub entity: E,
+    pub formatted: F,
+}
+
+impl<'de, E: DeserializeOwned, F: DeserializeOwned> Deserialize<'de> for WithFormatted<E, F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = Map::<String, Value>::deserialize(deserializer)?;
+
+        let formatted: Map<String, Value> = record
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_suffix(FORMATTED_VALUE_ANNOTATION)
+                    .map(|column| (column.to_string(), value.clone()))
+            })
+            .collect();
+
+        Ok(Self {
+            entity: E::deserialize(Value::Object(record)).map_err(D::Error::custom)?,
+            formatted: F::deserialize(Value::Object(formatted)).map_err(D::Error::custom)?,
+        })
+    }
+}
+
+impl<E: Select, F> Select for WithFormatted<E, F> {
+    fn get_columns() -> &'static [&'static str] {
+        E::get_columns()
+    }
+
+    fn get_default_filter() -> Option<Filter> {
+        E::get_default_filter()
+    }
+}
+
+impl<E: ReadEntity, F: DeserializeOwned> ReadEntity for WithFormatted<E, F> {
+    fn include_formatted_values() -> bool {
+        true
+    }
+
+    fn include_lookup_logical_names() -> bool {
+        E::include_lookup_logical_names()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::WithFormatted;
+
+    #[derive(Debug, Deserialize)]
+    struct Account {
+        industrycode: i32,
+        #[serde(rename = "_primarycontactid_value")]
+        primary_contact: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct AccountLabels {
+        industrycode: String,
+        #[serde(rename = "_primarycontactid_value")]
+        primary_contact: Option<String>,
+    }
+
+    #[test]
+    fn deserialize_raw_and_formatted() {
+        let account: WithFormatted<Account, AccountLabels> = serde_json::from_str(
+            r#"{"industrycode":7,"industrycode@OData.Community.Display.V1.FormattedValue":"Consulting","_primarycontactid_value":"00000000-0000-0000-0000-000000000001","_primarycontactid_value@OData.Community.Display.V1.FormattedValue":"Testy McTestface"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(account.entity.industrycode, 7);
+        assert_eq!(account.formatted.industrycode, "Consulting");
+        assert_eq!(
+            account.entity.primary_contact.as_deref(),
+            Some("00000000-0000-0000-0000-000000000001")
+        );
+        assert_eq!(account.formatted.primary_contact.as_deref(), Some("Testy McTestface"));
+    }
+}
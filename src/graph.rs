@@ -0,0 +1,343 @@
+/*!
+Module for retrieving records together with their related records
+
+A `GraphSpec` declares the columns of a record and the navigation properties that
+shall be followed from it, each with its own spec. `Client::retrieve_graph(...)`
+retrieves the whole graph with a single `$expand` query and completes expanded
+collections that dataverse returned only partially by following their next links.
+The result is deserialized into a nested type of your choice
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    graph::GraphSpec,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let reference = ReferenceStruct::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let spec = GraphSpec::new(&["accountid", "name"])
+        .expand("primarycontactid", GraphSpec::new(&["fullname"]))
+        .expand(
+            "contact_customer_accounts",
+            GraphSpec::new(&["fullname"])
+                .expand("owninguser", GraphSpec::new(&["fullname"])),
+        );
+
+    let account: Account = client.retrieve_graph(&reference, &spec).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Account {
+    accountid: Uuid,
+    name: String,
+    primarycontactid: Option<Person>,
+    contact_customer_accounts: Vec<Contact>,
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    fullname: String,
+    owninguser: Option<Person>,
+}
+
+#[derive(Deserialize)]
+struct Person {
+    fullname: String,
+}
+```
+*/
+
+use std::fmt::Display;
+
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    error::DataverseError,
+    query::parameters::QueryParameters,
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+/// maximum depth of nested navigation properties dataverse supports in `$expand`
+pub static MAX_GRAPH_DEPTH: usize = 10;
+
+/// suffix of the annotations that link to the remaining records of a collection
+static NEXT_LINK_ANNOTATION: &str = "@odata.nextLink";
+
+/// Declares the columns and the related records to retrieve for a record
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphSpec {
+    columns: Vec<String>,
+    expansions: Vec<(String, GraphSpec)>,
+}
+
+impl GraphSpec {
+    /// creates a spec that retrieves the given columns of a record
+    pub fn new(columns: &[&str]) -> Self {
+        Self {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            expansions: Vec::new(),
+        }
+    }
+
+    /// follows the lookup or collection with the given navigation property name
+    pub fn expand(mut self, navigation_property: &str, spec: GraphSpec) -> Self {
+        self.expansions.push((navigation_property.to_string(), spec));
+        self
+    }
+
+    /// returns the number of nested levels of navigation properties
+    pub fn get_depth(&self) -> usize {
+        self.expansions
+            .iter()
+            .map(|(_, spec)| spec.get_depth() + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// adds the `$select` and `$expand` options of this spec to the given list
+    pub fn append_parameters(&self, parameters: &mut QueryParameters) {
+        let columns: Vec<&str> = self.columns.iter().map(String::as_str).collect();
+        parameters.push_select(&columns);
+
+        if !self.expansions.is_empty() {
+            parameters.push("$expand", Expansions(&self.expansions));
+        }
+    }
+}
+
+/// renders the expansions of a spec with their nested options
+struct Expansions<'a>(&'a [(String, GraphSpec)]);
+
+impl Display for Expansions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, (navigation_property, spec)) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+
+            f.write_str(navigation_property)?;
+
+            let mut options = Vec::new();
+
+            if !spec.columns.is_empty() {
+                options.push(format!("$select={}", spec.columns.join(",")));
+            }
+
+            if !spec.expansions.is_empty() {
+                options.push(format!("$expand={}", Expansions(&spec.expansions)));
+            }
+
+            if !options.is_empty() {
+                f.write_fmt(format_args!("({})", options.join(";")))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the record with the related records declared in the spec
+
+    The spec may be nested up to 10 levels deep. Expanded collections are
+    completed by following their next links, so they contain all related records
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The spec is nested too deep
+    - The entity record referenced doesn't exist
+    */
+    pub async fn retrieve_graph<E: DeserializeOwned>(
+        &self,
+        reference: &impl Reference,
+        spec: &GraphSpec,
+    ) -> Result<E> {
+        if spec.get_depth() > MAX_GRAPH_DEPTH {
+            return Err(DataverseError::new(format!(
+                "graph spec is nested {} levels deep, but at most {} levels are supported",
+                spec.get_depth(),
+                MAX_GRAPH_DEPTH
+            )));
+        }
+
+        let reference = reference.get_reference();
+        let mut parameters = QueryParameters::new();
+        spec.append_parameters(&mut parameters);
+
+        let url_path = format!(
+            "{}{}",
+            self.build_targeted_url(&reference.entity_name, reference.entity_id),
+            parameters
+        );
+
+        let mut graph: Value = self
+            .request(Method::GET, &url_path, Ok, handle_json_response)
+            .await?;
+
+        loop {
+            let mut next_links = Vec::new();
+            collect_next_links(&graph, String::new(), &mut next_links);
+
+            if next_links.is_empty() {
+                break;
+            }
+
+            for next_link in next_links {
+                self.complete_collection(&mut graph, next_link).await?;
+            }
+        }
+
+        serde_json::from_value(graph).into_dataverse_result()
+    }
+
+    async fn complete_collection(&self, graph: &mut Value, next_link: NextLink) -> Result<()> {
+        if let Some(parent) = graph.pointer_mut(&next_link.parent).and_then(Value::as_object_mut) {
+            parent.remove(&next_link.annotation);
+        }
+
+        let mut link = Some(next_link.link);
+
+        while let Some(url) = link {
+            let mut page: Value = self
+                .request(Method::GET, &url, Ok, handle_json_response)
+                .await?;
+
+            link = page
+                .get(NEXT_LINK_ANNOTATION)
+                .and_then(Value::as_str)
+                .map(String::from);
+
+            let records = match page.get_mut("value") {
+                Some(Value::Array(records)) => std::mem::take(records),
+                _ => Vec::new(),
+            };
+
+            match graph.pointer_mut(&next_link.collection) {
+                Some(Value::Array(collection)) => collection.extend(records),
+                _ => {
+                    return Err(DataverseError::new(format!(
+                        "expanded collection {} is missing in the response",
+                        next_link.collection
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// next link annotation of a partially returned collection
+#[derive(Debug, PartialEq, Eq)]
+struct NextLink {
+    /// JSON pointer to the object containing the collection
+    parent: String,
+    /// JSON pointer to the collection
+    collection: String,
+    /// name of the annotation in the parent object
+    annotation: String,
+    link: String,
+}
+
+fn collect_next_links(value: &Value, path: String, next_links: &mut Vec<NextLink>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter() {
+                match (key.strip_suffix(NEXT_LINK_ANNOTATION), value) {
+                    (Some(collection), Value::String(link)) if !collection.is_empty() => {
+                        next_links.push(NextLink {
+                            parent: path.clone(),
+                            collection: format!("{}/{}", path, escape_pointer(collection)),
+                            annotation: key.clone(),
+                            link: link.clone(),
+                        });
+                    }
+                    _ => collect_next_links(
+                        value,
+                        format!("{}/{}", path, escape_pointer(key)),
+                        next_links,
+                    ),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_next_links(value, format!("{}/{}", path, index), next_links);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::query::parameters::QueryParameters;
+
+    use super::{collect_next_links, GraphSpec};
+
+    #[test]
+    fn nested_expand_options() {
+        let spec = GraphSpec::new(&["accountid", "name"])
+            .expand("primarycontactid", GraphSpec::new(&["fullname"]))
+            .expand(
+                "contact_customer_accounts",
+                GraphSpec::new(&["fullname"]).expand("owninguser", GraphSpec::new(&["fullname"])),
+            );
+
+        let mut parameters = QueryParameters::new();
+        spec.append_parameters(&mut parameters);
+
+        assert_eq!(spec.get_depth(), 2);
+        assert_eq!(
+            parameters.to_string(),
+            "?$select=accountid,name&$expand=primarycontactid($select=fullname),contact_customer_accounts($select=fullname;$expand=owninguser($select=fullname))"
+        );
+    }
+
+    #[test]
+    fn finds_nested_next_links() {
+        let graph = json!({
+            "name": "Contoso",
+            "contact_customer_accounts": [
+                {
+                    "fullname": "Testy",
+                    "Contact_Tasks": [],
+                    "Contact_Tasks@odata.nextLink": "https://instance/tasks?$skiptoken=1"
+                }
+            ]
+        });
+
+        let mut next_links = Vec::new();
+        collect_next_links(&graph, String::new(), &mut next_links);
+
+        assert_eq!(next_links.len(), 1);
+        assert_eq!(next_links[0].parent, "/contact_customer_accounts/0");
+        assert_eq!(next_links[0].collection, "/contact_customer_accounts/0/Contact_Tasks");
+        assert_eq!(next_links[0].link, "https://instance/tasks?$skiptoken=1");
+    }
+}
@@ -0,0 +1,122 @@
+/*!
+Module for hooks that modify the payloads of write requests
+
+A write hook configured on the client receives the JSON object of every record that
+is created, updated or upserted before it is serialized into the request. This allows
+stamping columns uniformly, e.g. `overriddencreatedon` during a migration or a custom
+correlation column, without touching every entity struct
+
+The hook applies to the direct writes of the client, to the bulk functions and to
+batches and transactions created with `Client::new_batch()` and `Client::new_transaction()`
+
+# Examples
+```rust
+use serde_json::Value;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    hook::WriteOperation
+};
+
+let client = Client::new_dummy().with_write_hook(|context, payload| {
+    if context.operation == WriteOperation::Create && context.entity_name == "contacts" {
+        payload.insert(
+            String::from("overriddencreatedon"),
+            Value::String(String::from("2019-03-01T00:00:00Z"))
+        );
+    }
+});
+```
+*/
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::result::{IntoDataverseResult, Result};
+
+/// The kind of write request a payload belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WriteOperation {
+    Create,
+    Update,
+    Upsert,
+}
+
+/// Describes the write request whose payload is passed to a write hook
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteContext<'a> {
+    pub operation: WriteOperation,
+    /// the entity set name of the written record
+    pub entity_name: &'a str,
+}
+
+/// callback that modifies the payloads of write requests
+pub type WriteHook = Arc<dyn Fn(&WriteContext, &mut Map<String, Value>) + Send + Sync>;
+
+/**
+serializes the entity into a JSON value and applies the hook if there is one
+
+Payloads that don't serialize into a JSON object are not passed to the hook
+*/
+pub(crate) fn to_hooked_value(
+    hook: Option<&WriteHook>,
+    context: &WriteContext,
+    entity: &impl Serialize,
+) -> Result<Value> {
+    let mut value = serde_json::to_value(entity).into_dataverse_result()?;
+
+    if let (Some(hook), Some(payload)) = (hook, value.as_object_mut()) {
+        hook(context, payload);
+    }
+
+    Ok(value)
+}
+
+/// serializes the entity into a JSON string and applies the hook if there is one
+pub(crate) fn to_hooked_string(
+    hook: Option<&WriteHook>,
+    context: &WriteContext,
+    entity: &impl Serialize,
+) -> Result<String> {
+    match hook {
+        Some(_) => Ok(to_hooked_value(hook, context, entity)?.to_string()),
+        None => serde_json::to_string(entity).into_dataverse_result(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{json, Value};
+
+    use super::{to_hooked_string, WriteContext, WriteHook, WriteOperation};
+
+    #[test]
+    fn hook_modifies_objects_only() {
+        let hook: WriteHook = Arc::new(|context, payload| {
+            if context.operation == WriteOperation::Create {
+                payload.insert(String::from("new_correlation"), Value::from("abc"));
+            }
+        });
+        let create = WriteContext {
+            operation: WriteOperation::Create,
+            entity_name: "contacts",
+        };
+        let update = WriteContext {
+            operation: WriteOperation::Update,
+            ..create
+        };
+
+        assert_eq!(
+            to_hooked_string(Some(&hook), &create, &json!({ "firstname": "Testy" })).unwrap(),
+            r#"{"firstname":"Testy","new_correlation":"abc"}"#
+        );
+        assert_eq!(
+            to_hooked_string(Some(&hook), &update, &json!({ "firstname": "Testy" })).unwrap(),
+            r#"{"firstname":"Testy"}"#
+        );
+        assert_eq!(to_hooked_string(Some(&hook), &create, &json!([1])).unwrap(), "[1]");
+    }
+}
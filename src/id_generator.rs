@@ -0,0 +1,203 @@
+/*!
+Module for generating the primary keys of created records on the client
+
+By default Dataverse generates the primary key of a created record unless the payload
+contains one. With an `IdGenerator` configured via `Client::with_id_generator(...)`,
+`Client::create(...)`, `Client::bulk_create(...)` and the batches, batch sets and transactions
+of `Client::new_batch()`, `Client::new_batch_set()` and `Client::new_transaction()` always
+send a client-side generated key. This makes retried creates idempotent, because a repeated
+request fails with a duplicate key instead of creating a second record, and the key can be
+correlated with downstream systems before the record exists
+
+Requests are added to batches synchronously, so the primary keys of their entities have to
+be resolved with `Client::prepare_creates(...)` before creates are added. Batches created
+with `Batch::new(...)` don't know the client and don't get generated keys
+
+These strategies are built in
+- `RandomIds` generates random UUIDs like Dataverse itself
+- `UuidV7` generates time-ordered UUIDs as specified in RFC 9562
+- `SequentialIds` generates UUIDs that increase in the order SQL Server sorts them,
+  which keeps the clustered index of the table from fragmenting
+
+Custom strategies implement the `IdGenerator` trait
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    id_generator::UuidV7
+};
+
+let client = Client::new_dummy() // Please replace this with your preferred authentication method
+    .with_id_generator(UuidV7);
+```
+*/
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::Utc;
+use serde_json::{Map, Value};
+use uuid::{Builder, Uuid};
+
+use crate::{error::DataverseError, result::Result};
+
+/// The strategy that generates the primary keys of created records
+pub trait IdGenerator: Send + Sync {
+    /// returns a new primary key for a record of the entity with the given entity set name
+    fn generate(&self, entity_name: &str) -> Uuid;
+}
+
+impl<F: Fn(&str) -> Uuid + Send + Sync> IdGenerator for F {
+    fn generate(&self, entity_name: &str) -> Uuid {
+        self(entity_name)
+    }
+}
+
+/// Generates random version 4 UUIDs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn generate(&self, _entity_name: &str) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/**
+Generates version 7 UUIDs that start with the current unix timestamp in milliseconds
+
+The keys sort by their creation time in their textual and binary representation.
+Keys generated within the same millisecond are ordered randomly
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UuidV7;
+
+impl IdGenerator for UuidV7 {
+    fn generate(&self, _entity_name: &str) -> Uuid {
+        let random = Uuid::new_v4().into_bytes();
+        let mut bytes = [0; 10];
+        bytes.copy_from_slice(&random[..10]);
+
+        Builder::from_unix_timestamp_millis(Utc::now().timestamp_millis() as u64, &bytes)
+            .into_uuid()
+    }
+}
+
+/**
+Generates UUIDs that increase in the order SQL Server sorts `uniqueidentifier` columns
+
+SQL Server compares the last six bytes of a UUID first, so these bytes contain a counter
+that starts at the current time and strictly increases with every key of this generator.
+The remaining bytes are random. The keys are only ordered per generator, so a client
+should share one generator across all of its creates
+*/
+#[derive(Debug)]
+pub struct SequentialIds {
+    last: AtomicU64,
+}
+
+impl SequentialIds {
+    /// the counter fills the last 48 bits of a key
+    const MAX_COUNTER: u64 = (1 << 48) - 1;
+
+    pub fn new() -> Self {
+        Self {
+            last: AtomicU64::new(0),
+        }
+    }
+
+    /// returns the next value of the counter, 64 values per millisecond keep it close to the current time
+    fn next_counter(&self) -> u64 {
+        let now = (Utc::now().timestamp_millis() as u64) << 6;
+
+        let previous = self
+            .last
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| {
+                Some(now.max(last + 1) & Self::MAX_COUNTER)
+            })
+            .unwrap_or_default();
+
+        now.max(previous + 1) & Self::MAX_COUNTER
+    }
+}
+
+impl Default for SequentialIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn generate(&self, _entity_name: &str) -> Uuid {
+        let mut bytes = Uuid::new_v4().into_bytes();
+        bytes[10..].copy_from_slice(&self.next_counter().to_be_bytes()[2..]);
+        Uuid::from_bytes(bytes)
+    }
+}
+
+/**
+inserts a generated key into the payload unless it already contains a key
+
+A missing, `null` or nil key is replaced. A key that is no UUID fails instead of
+being replaced, so a malformed key of the caller is never silently discarded
+*/
+pub(crate) fn apply_generated_id(payload: &mut Map<String, Value>, primary_key: &str, id: Uuid) -> Result<()> {
+    let existing = match payload.get(primary_key) {
+        None | Some(Value::Null) => Uuid::nil(),
+        Some(value) => value
+            .as_str()
+            .and_then(|value| Uuid::parse_str(value).ok())
+            .ok_or_else(|| {
+                DataverseError::new(format!(
+                    "the primary key {} of the created record is not a UUID: {}",
+                    primary_key, value
+                ))
+            })?,
+    };
+
+    if existing.is_nil() {
+        payload.insert(primary_key.to_string(), Value::String(id.to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::{Uuid, Version};
+
+    use super::{apply_generated_id, IdGenerator, SequentialIds, UuidV7};
+
+    #[test]
+    fn generates_ordered_ids() {
+        let first = UuidV7.generate("contacts");
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = UuidV7.generate("contacts");
+        assert_eq!(first.get_version(), Some(Version::SortRand));
+        assert!(first < second);
+
+        let generator = SequentialIds::new();
+        let ids: Vec<Uuid> = (0..200).map(|_| generator.generate("contacts")).collect();
+        assert!(ids
+            .windows(2)
+            .all(|pair| pair[0].as_bytes()[10..] < pair[1].as_bytes()[10..]));
+    }
+
+    #[test]
+    fn keeps_existing_ids() {
+        let id = Uuid::from_u128(1);
+
+        let mut payload = json!({"contactid": Uuid::nil(), "lastname": "McTestface"});
+        apply_generated_id(payload.as_object_mut().unwrap(), "contactid", id).unwrap();
+        assert_eq!(payload["contactid"], json!(id));
+
+        let mut payload = json!({"contactid": Uuid::from_u128(2)});
+        apply_generated_id(payload.as_object_mut().unwrap(), "contactid", id).unwrap();
+        assert_eq!(payload["contactid"], json!(Uuid::from_u128(2)));
+
+        let mut payload = json!({"contactid": "legacy-42"});
+        assert!(apply_generated_id(payload.as_object_mut().unwrap(), "contactid", id).is_err());
+        assert_eq!(payload["contactid"], json!("legacy-42"));
+    }
+}
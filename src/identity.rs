@@ -0,0 +1,158 @@
+/*!
+Module for the identity of the connection and the ownership of created records
+
+`Client::who_am_i()` returns the user, business unit and organization of the
+connection. A `DefaultOwner` configured on the client is assigned to every record
+created with `Client::create(...)`, `Client::bulk_create(...)` or in a batch of
+`Client::new_batch()` that doesn't set an owner itself
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    identity::DefaultOwner,
+    result::{IntoDataverseResult, Result}
+};
+
+fn test() -> Result<()> {
+    let team_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+
+    // every record created by this client is owned by the team unless it sets an owner
+    let client = Client::new_dummy().with_default_owner(DefaultOwner::Team(team_id));
+    Ok(())
+}
+```
+*/
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    reference::EntityReference,
+    result::Result,
+};
+
+/// name of the owner lookup of user or team owned entities
+pub static OWNER_ID: &str = "ownerid";
+
+/// The identity of the connection as returned by the `WhoAmI` function
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct WhoAmI {
+    #[serde(rename = "UserId")]
+    pub user_id: Uuid,
+    #[serde(rename = "BusinessUnitId")]
+    pub business_unit_id: Uuid,
+    #[serde(rename = "OrganizationId")]
+    pub organization_id: Uuid,
+}
+
+/// The owner that is assigned to created records which don't set an owner themselves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DefaultOwner {
+    /// the user of the connection as returned by `WhoAmI`
+    CurrentUser,
+
+    /// the user with the given id
+    User(Uuid),
+
+    /// the team with the given id
+    Team(Uuid),
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    returns the identity of the connection
+
+    The identity is retrieved once with the `WhoAmI` function and then cached in the client
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn who_am_i(&self) -> Result<WhoAmI> {
+        let identity = self
+            .identity
+            .get_or_try_init(|| async {
+                let url_path = self.build_simple_url("WhoAmI");
+                self.request(Method::GET, &url_path, Ok, handle_json_response)
+                    .await
+            })
+            .await?;
+
+        Ok(*identity)
+    }
+
+    /// returns the reference to the default owner of created records if there is one
+    pub(crate) async fn get_default_owner(&self) -> Result<Option<EntityReference>> {
+        let owner = match self.default_owner {
+            Some(DefaultOwner::CurrentUser) => {
+                EntityReference::new("systemusers", self.who_am_i().await?.user_id)
+            }
+            Some(DefaultOwner::User(user_id)) => EntityReference::new("systemusers", user_id),
+            Some(DefaultOwner::Team(team_id)) => EntityReference::new("teams", team_id),
+            None => return Ok(None),
+        };
+
+        Ok(Some(owner))
+    }
+
+    /// returns the reference to the default owner without resolving the current user
+    #[cfg(feature = "batch")]
+    pub(crate) fn get_known_default_owner(&self) -> Option<EntityReference> {
+        match self.default_owner? {
+            DefaultOwner::CurrentUser => self
+                .identity
+                .get()
+                .map(|identity| EntityReference::new("systemusers", identity.user_id)),
+            DefaultOwner::User(user_id) => Some(EntityReference::new("systemusers", user_id)),
+            DefaultOwner::Team(team_id) => Some(EntityReference::new("teams", team_id)),
+        }
+    }
+}
+
+/// binds the owner in the payload unless the payload already sets an owner
+pub(crate) fn apply_default_owner(payload: &mut Map<String, Value>, owner: &EntityReference) {
+    let bind = format!("{}@odata.bind", OWNER_ID);
+
+    if !payload.contains_key(OWNER_ID) && !payload.contains_key(&bind) {
+        payload.insert(bind, Value::String(owner.to_bind()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use crate::reference::EntityReference;
+
+    use super::apply_default_owner;
+
+    #[test]
+    fn binds_missing_owner() {
+        let mut payload = json!({ "firstname": "Testy" });
+        let owner = EntityReference::new("teams", Uuid::nil());
+        apply_default_owner(payload.as_object_mut().unwrap(), &owner);
+        assert_eq!(
+            payload["ownerid@odata.bind"],
+            Value::from("/teams(00000000-0000-0000-0000-000000000000)")
+        );
+    }
+
+    #[test]
+    fn keeps_existing_owner() {
+        let mut payload = json!({ "ownerid@odata.bind": "/systemusers(00000000-0000-0000-0000-000000000001)" });
+        let owner = EntityReference::new("teams", Uuid::nil());
+        apply_default_owner(payload.as_object_mut().unwrap(), &owner);
+        assert_eq!(
+            payload["ownerid@odata.bind"],
+            Value::from("/systemusers(00000000-0000-0000-0000-000000000001)")
+        );
+    }
+}
@@ -0,0 +1,176 @@
+/*!
+Module for downloading and uploading image columns of records
+
+Image columns like the primary image `entityimage` of a record are not part of its
+JSON representation. They are read and written as raw bytes through their own endpoints
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    image::{ImageSize, ENTITY_IMAGE},
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let reference = ReferenceStruct::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let photo = std::fs::read("photo.png").into_dataverse_result()?;
+    client.upload_image(&reference, ENTITY_IMAGE, photo).await?;
+
+    if let Some(thumbnail) = client.download_image(&reference, ENTITY_IMAGE, ImageSize::Thumbnail).await? {
+        std::fs::write("thumbnail.png", thumbnail).into_dataverse_result()?;
+    }
+
+    Ok(())
+}
+```
+*/
+
+use reqwest::{Method, Response, StatusCode};
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_empty_response, Client},
+    error::DataverseError,
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+/// name of the primary image column of entities
+pub static ENTITY_IMAGE: &str = "entityimage";
+
+/// maximum size of images accepted by Dataverse image columns (30 MB)
+pub const MAX_IMAGE_SIZE: usize = 30 * 1024 * 1024;
+
+/// The size in which an image is downloaded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageSize {
+    /// the thumbnail of 144x144 pixels that Dataverse generates for each image
+    Thumbnail,
+
+    /// the full sized image if the column is configured to store full images
+    Full,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    downloads the image stored in the given image column of the referenced record
+
+    Returns `None` if the record has no image in this column
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The entity record referenced doesn't exist
+    - A full image is requested but the column stores only thumbnails
+    */
+    pub async fn download_image(
+        &self,
+        reference: &impl Reference,
+        column: &str,
+        size: ImageSize,
+    ) -> Result<Option<Vec<u8>>> {
+        let reference = reference.get_reference();
+        let mut url_path = format!(
+            "{}/{}/$value",
+            self.build_targeted_url(&reference.entity_name, reference.entity_id),
+            column
+        );
+
+        if size == ImageSize::Full {
+            url_path.push_str("?size=full");
+        }
+
+        async fn handle_response(response: Response) -> Result<Option<Vec<u8>>> {
+            if response.status() == StatusCode::NO_CONTENT {
+                return Ok(None);
+            }
+
+            if response.status().is_client_error() || response.status().is_server_error() {
+                let error_message = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("no error details provided from server"));
+                return Err(DataverseError::new(error_message));
+            }
+
+            let content = response.bytes().await.into_dataverse_result()?;
+            Ok(Some(content.to_vec()))
+        }
+
+        self.request(Method::GET, &url_path, Ok, handle_response).await
+    }
+
+    /**
+    uploads the image into the given image column of the referenced record
+
+    Images larger than `MAX_IMAGE_SIZE` are rejected before they are sent. Please note
+    that the maximum size of a column may be configured lower than that in Dataverse
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The entity record referenced doesn't exist
+    - The image is too large
+    */
+    pub async fn upload_image(
+        &self,
+        reference: &impl Reference,
+        column: &str,
+        image: Vec<u8>,
+    ) -> Result<()> {
+        if image.len() > MAX_IMAGE_SIZE {
+            return Err(DataverseError::new(format!(
+                "image has {} bytes but at most {} bytes are allowed",
+                image.len(),
+                MAX_IMAGE_SIZE
+            )));
+        }
+
+        let reference = reference.get_reference();
+        let url_path = format!(
+            "{}/{}",
+            self.build_targeted_url(&reference.entity_name, reference.entity_id),
+            column
+        );
+
+        self.request(
+            Method::PUT,
+            &url_path,
+            move |request| {
+                Ok(request
+                    .header("Content-Type", "application/octet-stream")
+                    .body(image))
+            },
+            handle_empty_response,
+        )
+        .await
+    }
+
+    /**
+    removes the image from the given image column of the referenced record
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The entity record referenced doesn't exist
+    */
+    pub async fn delete_image(&self, reference: &impl Reference, column: &str) -> Result<()> {
+        let reference = reference.get_reference();
+        let url_path = format!(
+            "{}/{}",
+            self.build_targeted_url(&reference.entity_name, reference.entity_id),
+            column
+        );
+
+        self.request(Method::DELETE, &url_path, Ok, handle_empty_response)
+            .await
+    }
+}
@@ -0,0 +1,260 @@
+/*!
+Module for monitoring system jobs
+
+Long running operations like bulk deletes, duplicate detection jobs, asynchronous
+workflows or solution imports are executed as system jobs (`asyncoperation`).
+These functions allow to query their state, cancel them or wait for their completion
+
+# Examples
+```rust
+use std::time::Duration;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    job::JobStatus,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let job_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+
+    let job = client
+        .wait_for_system_job(job_id, Duration::from_secs(5), Some(Duration::from_secs(600)))
+        .await?;
+
+    if job.get_status() == JobStatus::Failed {
+        println!("job failed: {:?}", job.message);
+    }
+    Ok(())
+}
+```
+*/
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::{ReadEntity, WriteEntity},
+    error::DataverseError,
+    progress::Operation,
+    query::{attribute::Attribute, filter::Filter, order::Order, Query},
+    reference::{EntityReference, Reference, ReferenceStruct},
+    result::Result,
+    select::Select,
+};
+
+/// The status of a system job derived from its `statuscode`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JobStatus {
+    WaitingForResources,
+    Waiting,
+    InProgress,
+    Pausing,
+    Canceling,
+    Succeeded,
+    Failed,
+    Canceled,
+    Unknown(i32),
+}
+
+impl JobStatus {
+    /// converts the `statuscode` of a system job
+    pub fn from_status_code(status_code: i32) -> Self {
+        match status_code {
+            0 => Self::WaitingForResources,
+            10 => Self::Waiting,
+            20 => Self::InProgress,
+            21 => Self::Pausing,
+            22 => Self::Canceling,
+            30 => Self::Succeeded,
+            31 => Self::Failed,
+            32 => Self::Canceled,
+            other => Self::Unknown(other),
+        }
+    }
+
+    /// Indicates if the job finished and will not change anymore
+    pub fn is_completed(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Canceled)
+    }
+}
+
+/// A system job (`asyncoperation`)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct SystemJob {
+    pub asyncoperationid: Uuid,
+    pub name: Option<String>,
+    pub operationtype: Option<i32>,
+    pub statecode: i32,
+    pub statuscode: i32,
+    pub correlationid: Option<Uuid>,
+    pub message: Option<String>,
+    pub friendlymessage: Option<String>,
+    pub createdon: Option<String>,
+    pub completedon: Option<String>,
+}
+
+impl SystemJob {
+    /// returns the status of this job
+    pub fn get_status(&self) -> JobStatus {
+        JobStatus::from_status_code(self.statuscode)
+    }
+
+    /// Indicates if this job finished and will not change anymore
+    pub fn is_completed(&self) -> bool {
+        self.get_status().is_completed()
+    }
+}
+
+impl ReadEntity for SystemJob {}
+
+impl Select for SystemJob {
+    fn get_columns() -> &'static [&'static str] {
+        &[
+            "asyncoperationid",
+            "name",
+            "operationtype",
+            "statecode",
+            "statuscode",
+            "correlationid",
+            "message",
+            "friendlymessage",
+            "createdon",
+            "completedon",
+        ]
+    }
+}
+
+#[derive(Serialize)]
+struct CancelJob {
+    #[serde(skip)]
+    asyncoperationid: Uuid,
+    statecode: i32,
+    statuscode: i32,
+}
+
+impl WriteEntity for CancelJob {}
+
+impl Reference for CancelJob {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("asyncoperations", self.asyncoperationid)
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the system job with the given id
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no system job with this id
+    */
+    pub async fn retrieve_system_job(&self, job_id: Uuid) -> Result<SystemJob> {
+        self.retrieve(&EntityReference::new("asyncoperations", job_id))
+            .await
+    }
+
+    /**
+    retrieves all system jobs with the given correlation id ordered by their creation
+
+    The correlation id links all jobs started by the same request, e.g. a
+    workflow and the jobs it triggered
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_system_jobs_by_correlation(
+        &self,
+        correlation_id: Uuid,
+    ) -> Result<Vec<SystemJob>> {
+        let query = Query::new("asyncoperations")
+            .filter(Filter::Equal("correlationid", Attribute::Uuid(correlation_id)))
+            .order(vec![Order::Ascending("createdon")]);
+
+        self.retrieve_all(&query).await
+    }
+
+    /**
+    requests the cancellation of the system job with the given id
+
+    Jobs that are already completed cannot be canceled
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - There is no system job with this id
+    */
+    pub async fn cancel_system_job(&self, job_id: Uuid) -> Result<()> {
+        self.update(&CancelJob {
+            asyncoperationid: job_id,
+            statecode: 3,
+            statuscode: 32,
+        })
+        .await
+    }
+
+    /**
+    polls the system job with the given id until it is completed and returns it
+
+    The job is retrieved every `poll_interval`. If a timeout is given and the job
+    did not complete within it, an error is returned. Please note that a failed or
+    canceled job is returned as well; check its status
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The job did not complete within the timeout
+    */
+    pub async fn wait_for_system_job(
+        &self,
+        job_id: Uuid,
+        poll_interval: Duration,
+        timeout: Option<Duration>,
+    ) -> Result<SystemJob> {
+        let started = Instant::now();
+        let tracker = self.track_progress(Operation::SystemJob, Some(1));
+
+        loop {
+            let job = self.retrieve_system_job(job_id).await?;
+
+            if job.is_completed() {
+                tracker.report(1);
+                return Ok(job);
+            }
+
+            tracker.report(0);
+
+            if timeout.is_some_and(|timeout| started.elapsed() + poll_interval > timeout) {
+                return Err(DataverseError::new(format!(
+                    "system job {} did not complete within {:?}",
+                    job_id, timeout.unwrap_or_default()
+                )));
+            }
+
+            self.sleep(poll_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JobStatus;
+
+    #[test]
+    fn status_codes() {
+        assert_eq!(JobStatus::from_status_code(20), JobStatus::InProgress);
+        assert!(JobStatus::from_status_code(31).is_completed());
+        assert!(!JobStatus::from_status_code(10).is_completed());
+        assert_eq!(JobStatus::from_status_code(99), JobStatus::Unknown(99));
+    }
+}
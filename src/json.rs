@@ -0,0 +1,152 @@
+/*!
+Deserialization of Dataverse responses with error context
+
+Deserialization errors of serde only point to a line and column in the payload.
+For pages with thousands of records this is not helpful, so failed pages are
+deserialized a second time record by record to find the index, the primary key
+and the path of the field that caused the error
+
+Successful payloads are parsed without tracking the path. With the `simd-json`
+feature they are parsed by `simd-json` instead of `serde_json`, which is
+considerably faster for large pages. Only failed payloads are parsed again with
+`serde_json` to build the error message
+*/
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{error::DataverseError, result::Result};
+
+/// A page of records as returned by queries
+#[derive(Deserialize)]
+pub(crate) struct RetrieveMultipleResult<E> {
+    #[serde(rename = "value")]
+    pub entities: Vec<E>,
+    #[serde(rename = "@odata.nextLink")]
+    pub next_link: Option<String>,
+    /// the link to the changes since this query, only on the last page of queries that track changes
+    #[serde(rename = "@odata.deltaLink")]
+    pub delta_link: Option<String>,
+}
+
+/// deserializes a value with the parser selected by the crate features
+#[cfg(not(feature = "simd-json"))]
+fn parse<E: DeserializeOwned>(content: &[u8]) -> std::result::Result<E, String> {
+    serde_json::from_slice(content).map_err(|error| error.to_string())
+}
+
+/// deserializes a value with the parser selected by the crate features
+#[cfg(feature = "simd-json")]
+fn parse<E: DeserializeOwned>(content: &[u8]) -> std::result::Result<E, String> {
+    // simd-json parses in place, so it needs its own copy of the payload
+    let mut content = content.to_vec();
+    simd_json::serde::from_slice(&mut content).map_err(|error| error.to_string())
+}
+
+/// deserializes a single value and adds the path of the offending field to errors
+pub(crate) fn from_slice<E: DeserializeOwned>(content: &[u8]) -> Result<E> {
+    if let Ok(value) = parse(content) {
+        return Ok(value);
+    }
+
+    let deserializer = &mut serde_json::Deserializer::from_slice(content);
+
+    serde_path_to_error::deserialize(deserializer).map_err(|error| {
+        DataverseError::new(format!(
+            "could not deserialize field '{}': {}",
+            error.path(),
+            error.inner()
+        ))
+    })
+}
+
+/// deserializes a page of records and adds the record causing errors to them
+pub(crate) fn page_from_slice<E: DeserializeOwned>(
+    content: &[u8],
+) -> Result<RetrieveMultipleResult<E>> {
+    match parse(content) {
+        Ok(page) => Ok(page),
+        Err(error) => Err(find_page_error::<E>(content).unwrap_or_else(|| {
+            DataverseError::new(format!("could not deserialize page: {}", error))
+        })),
+    }
+}
+
+fn find_page_error<E: DeserializeOwned>(content: &[u8]) -> Option<DataverseError> {
+    let page: RetrieveMultipleResult<Value> = serde_json::from_slice(content).ok()?;
+
+    page.entities
+        .into_iter()
+        .enumerate()
+        .find_map(|(index, record)| {
+            let primary_key = find_primary_key(&record);
+            let error = serde_path_to_error::deserialize::<_, E>(&record).err()?;
+
+            Some(DataverseError::new(format!(
+                "could not deserialize record {} ({}) at field '{}': {}",
+                index,
+                primary_key.unwrap_or_else(|| String::from("unknown primary key")),
+                error.path(),
+                error.inner()
+            )))
+        })
+}
+
+/// guesses the primary key of a record as the first `<name>id` column holding a uuid
+fn find_primary_key(record: &Value) -> Option<String> {
+    record
+        .as_object()?
+        .iter()
+        .filter(|(name, _)| !name.starts_with('_') && !name.contains('@') && name.ends_with("id"))
+        .find_map(|(name, value)| {
+            let value = value.as_str()?;
+            uuid::Uuid::parse_str(value)
+                .ok()
+                .map(|_| format!("{}={}", name, value))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::{from_slice, page_from_slice};
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Contact {
+        firstname: String,
+        address: Address,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[test]
+    fn page_error_names_record_and_field() {
+        let content = br#"{"value":[
+            {"contactid":"00000000-0000-0000-0000-000000000001","firstname":"Testy","address":{"city":"Berlin"}},
+            {"contactid":"00000000-0000-0000-0000-000000000002","firstname":"Marianne","address":{"city":5}}
+        ]}"#;
+
+        let error = page_from_slice::<Contact>(content).err().unwrap();
+        assert_eq!(
+            error.message,
+            "could not deserialize record 1 (contactid=00000000-0000-0000-0000-000000000002) at field 'address.city': invalid type: integer `5`, expected a string"
+        );
+    }
+
+    #[test]
+    fn single_error_names_field() {
+        let content = br#"{"firstname":"Testy","address":{}}"#;
+        let error = from_slice::<Contact>(content).err().unwrap();
+        assert_eq!(
+            error.message,
+            "could not deserialize field 'address': missing field `city` at line 1 column 33"
+        );
+    }
+}
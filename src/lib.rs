@@ -166,15 +166,98 @@ impl Reference for Contact {
     }
 }
 ```
+
+## Optional features
+
+- `batch` (default) executes batches, transactions and bulk operations
+- `rustls` uses rustls instead of the native TLS implementation
+- `simd-json` parses responses with `simd-json`, which speeds up large exports
+- `fault-injection` allows to inject artificial throttling, outages and latency for testing
+- `testkit` provides an in-process mock of the Dataverse Web API for tests
+- `arrow` retrieves query results as Apache Arrow record batches
+- `recording` records http interactions to disk and replays them for tests
+- `emulator` runs the client against an in-memory emulation of Dataverse
+- `blocking` provides a client for synchronous code that runs its own tokio runtime
 */
 
+pub mod access_team;
 pub mod action;
+pub mod activity;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod audit;
+pub mod audit_trail;
 pub mod auth;
+#[cfg(feature = "batch")]
 pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "batch")]
+pub mod bulk;
+pub mod cache;
 pub mod client;
+pub mod coercion;
+pub mod consistency;
+pub mod count;
+pub mod currency;
+pub mod dedupe;
+pub mod diagnostics;
+pub mod distinct;
+pub mod duplicate;
+#[cfg(feature = "emulator")]
+pub mod emulator;
 pub mod entity;
+pub mod environment;
 pub mod error;
+pub mod explain;
+pub mod export;
+mod failover;
+#[cfg(feature = "fault-injection")]
+pub mod fault;
+pub mod formatted;
+pub mod graph;
+pub mod hook;
+pub mod id_generator;
+pub mod identity;
+pub mod image;
+pub mod job;
+mod json;
+pub mod metadata;
+pub mod metrics;
+pub mod migration;
+pub mod naming;
+pub mod navigation;
+#[cfg(feature = "batch")]
+pub mod planner;
+pub mod privilege;
+pub mod progress;
 pub mod query;
+pub mod queue;
+pub mod rate_limit;
+#[cfg(feature = "recording")]
+pub mod recording;
 pub mod reference;
+#[cfg(feature = "batch")]
+pub mod report;
 pub mod result;
+pub mod rollup;
+pub mod runtime;
+pub mod schedule;
 pub mod select;
+pub mod serde_helpers;
+pub mod service;
+pub mod sharing;
+pub mod solution;
+#[cfg(feature = "batch")]
+pub mod state;
+pub mod subscriptions;
+pub mod sync;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+#[cfg(feature = "batch")]
+pub mod transaction;
+pub mod transport;
+pub mod unmapped;
+pub mod update;
+pub mod web_resource;
+pub mod workflow;
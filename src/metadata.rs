@@ -0,0 +1,467 @@
+/*!
+Module for reading entity metadata from Microsoft Dataverse
+
+## Resolving entity names
+
+Users often mix up the logical name of an entity (e.g. `contact`) and the name of
+its entity set (e.g. `contacts`) which is used in urls and binds. The client can
+resolve either form into both names with the metadata of the environment.
+Resolved names are cached in the client
+
+For entities known at compile time the `EntityName` trait in the `entity` module
+avoids the metadata lookup entirely
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{client::Client, result::Result};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+
+    // both calls resolve to the same names
+    let names = client.resolve_entity_names("contact").await?;
+    let names = client.resolve_entity_names("contacts").await?;
+
+    assert_eq!(names.logical_name, "contact");
+    assert_eq!(names.collection_name, "contacts");
+    Ok(())
+}
+```
+
+## Keeping a local metadata cache in sync
+
+`MetadataCache` keeps a local copy of entity metadata and only downloads the
+changes since its last refresh with the `RetrieveMetadataChanges` function
+
+```rust
+use serde::Deserialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    metadata::{EntityQueryExpression, MetadataCache, MetadataRecord},
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let query = EntityQueryExpression::new(&["LogicalName", "DisplayName"])
+        .logical_names(&["account", "contact"]);
+
+    let mut cache: MetadataCache<Entity> = MetadataCache::new(query);
+    cache.refresh(&client).await?;
+
+    // later on only the changes are downloaded
+    cache.refresh(&client).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Entity {
+    #[serde(rename = "MetadataId")]
+    metadata_id: Uuid,
+    #[serde(rename = "LogicalName")]
+    logical_name: String,
+}
+
+impl MetadataRecord for Entity {
+    fn get_metadata_id(&self) -> Uuid {
+        self.metadata_id
+    }
+}
+```
+*/
+
+use std::{collections::HashMap, sync::Mutex};
+
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_json_response, Client},
+    entity::EntityName,
+    error::DataverseError,
+    query::{attribute::Attribute, filter::Filter, parameters::QueryParameters},
+    reference::{EntityReference, Reference},
+    result::{IntoDataverseResult, Result},
+};
+
+/// The logical name and the entity set name of an entity
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct EntityNames {
+    #[serde(rename = "LogicalName")]
+    pub logical_name: String,
+    #[serde(rename = "EntitySetName")]
+    pub collection_name: String,
+}
+
+impl EntityNames {
+    /// returns the names of an entity known at compile time
+    pub fn of<E: EntityName>() -> Self {
+        Self {
+            logical_name: E::LOGICAL.to_string(),
+            collection_name: E::COLLECTION.to_string(),
+        }
+    }
+}
+
+/// cache of resolved entity names keyed by both of their forms
+#[derive(Debug, Default)]
+pub(crate) struct EntityNameCache {
+    names: Mutex<HashMap<String, EntityNames>>,
+}
+
+impl EntityNameCache {
+    pub(crate) fn get(&self, name: &str) -> Option<EntityNames> {
+        self.names.lock().ok()?.get(name).cloned()
+    }
+
+    fn insert(&self, names: &EntityNames) {
+        if let Ok(mut cache) = self.names.lock() {
+            cache.insert(names.logical_name.clone(), names.clone());
+            cache.insert(names.collection_name.clone(), names.clone());
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EntityDefinitions {
+    value: Vec<EntityNames>,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    resolves the logical name and the entity set name of an entity from either form
+
+    The names are looked up in the entity metadata of the environment and cached
+    in this client, so subsequent calls for the same entity don't cause any requests
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no entity with the given name
+    */
+    pub async fn resolve_entity_names(&self, name: &str) -> Result<EntityNames> {
+        if let Some(names) = self.entity_names.get(name) {
+            return Ok(names);
+        }
+
+        let mut parameters = QueryParameters::new();
+        parameters.push_select(&["LogicalName", "EntitySetName"]);
+        parameters.push(
+            "$filter",
+            Filter::Equal("LogicalName", Attribute::String(name.replace('\'', "''")))
+                .or(Filter::Equal("EntitySetName", Attribute::String(name.replace('\'', "''")))),
+        );
+
+        let url_path = format!("{}{}", self.build_simple_url("EntityDefinitions"), parameters);
+
+        let definitions: EntityDefinitions = self
+            .request(Method::GET, &url_path, Ok, handle_json_response)
+            .await?;
+
+        let names = definitions.value.into_iter().next().ok_or_else(|| {
+            DataverseError::new(format!("There is no entity with the name {}", name))
+        })?;
+
+        self.entity_names.insert(&names);
+        Ok(names)
+    }
+
+    /**
+    returns a copy of the reference that uses the entity set name of its entity
+
+    This allows creating references with the logical name of an entity and still
+    generate correct urls and binds from them
+
+    see `resolve_entity_names(...)` for the failure reasons
+    */
+    pub async fn resolve_reference(&self, reference: &impl Reference) -> Result<EntityReference> {
+        let reference = reference.get_reference();
+        let names = self.resolve_entity_names(&reference.entity_name).await?;
+        Ok(EntityReference::new(names.collection_name, reference.entity_id))
+    }
+}
+
+/// error code dataverse returns when a version stamp is too old to compute changes
+static EXPIRED_VERSION_STAMP: &str = "0x80044352";
+
+/**
+A query for entity metadata used by `RetrieveMetadataChanges`
+
+It selects the entity properties to retrieve and optionally restricts the
+entities to the given logical names
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityQueryExpression {
+    pub properties: Vec<String>,
+    pub logical_names: Vec<String>,
+}
+
+impl EntityQueryExpression {
+    /// creates a query that retrieves the given properties of all entities
+    pub fn new(properties: &[&str]) -> Self {
+        Self {
+            properties: properties.iter().map(|property| property.to_string()).collect(),
+            logical_names: Vec::new(),
+        }
+    }
+
+    /// restricts the query to the entities with the given logical names
+    pub fn logical_names(mut self, logical_names: &[&str]) -> Self {
+        self.logical_names = logical_names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let conditions: Vec<Value> = self
+            .logical_names
+            .iter()
+            .map(|name| {
+                json!({
+                    "PropertyName": "LogicalName",
+                    "ConditionOperator": "Equals",
+                    "Value": { "Type": "System.String", "Value": name }
+                })
+            })
+            .collect();
+
+        json!({
+            "Properties": {
+                "AllProperties": false,
+                "PropertyNames": self.properties
+            },
+            "Criteria": {
+                "FilterOperator": "Or",
+                "Conditions": conditions
+            }
+        })
+    }
+}
+
+/// trait for metadata records that can be identified by their `MetadataId`
+pub trait MetadataRecord {
+    /// returns the `MetadataId` of this record
+    fn get_metadata_id(&self) -> Uuid;
+}
+
+/// The changes of entity metadata since a version stamp
+#[derive(Debug)]
+pub struct MetadataChanges<E> {
+    /// entities that were created or changed
+    pub entities: Vec<E>,
+
+    /// `MetadataId`s of entities and other metadata that were deleted
+    pub deleted: Vec<Uuid>,
+
+    /// the version stamp to use for the next call
+    pub server_version_stamp: String,
+}
+
+#[derive(Deserialize)]
+struct RetrieveMetadataChangesResult<E> {
+    #[serde(rename = "EntityMetadata")]
+    entities: Vec<E>,
+    #[serde(rename = "DeletedMetadata", default)]
+    deleted: Option<DeletedMetadata>,
+    #[serde(rename = "ServerVersionStamp")]
+    server_version_stamp: String,
+}
+
+#[derive(Deserialize)]
+struct DeletedMetadata {
+    #[serde(rename = "Values", default)]
+    values: Vec<Vec<Uuid>>,
+}
+
+/**
+A local copy of entity metadata that is kept in sync with `RetrieveMetadataChanges`
+
+The cache remembers the version stamp of its last refresh and only applies the changes
+since then on the next refresh. If the version stamp expired, the cache is reloaded
+completely
+*/
+#[derive(Debug)]
+pub struct MetadataCache<E> {
+    query: EntityQueryExpression,
+    entities: HashMap<Uuid, E>,
+    version_stamp: Option<String>,
+}
+
+impl<E: MetadataRecord + DeserializeOwned> MetadataCache<E> {
+    /// creates a new empty cache for the entities selected by the query
+    pub fn new(query: EntityQueryExpression) -> Self {
+        Self {
+            query,
+            entities: HashMap::new(),
+            version_stamp: None,
+        }
+    }
+
+    /// returns the version stamp of the last refresh
+    pub fn get_version_stamp(&self) -> Option<&str> {
+        self.version_stamp.as_deref()
+    }
+
+    /// returns the cached entity metadata
+    pub fn get_entities(&self) -> impl Iterator<Item = &E> {
+        self.entities.values()
+    }
+
+    /// returns the cached metadata of the entity with the given `MetadataId`
+    pub fn get(&self, metadata_id: &Uuid) -> Option<&E> {
+        self.entities.get(metadata_id)
+    }
+
+    /**
+    applies the changes since the last refresh to this cache
+
+    see `Client::retrieve_metadata_changes(...)` for the failure reasons
+    */
+    pub async fn refresh<A: Authenticate>(&mut self, client: &Client<'_, A>) -> Result<()> {
+        let changes = match client
+            .retrieve_metadata_changes(&self.query, self.version_stamp.as_deref())
+            .await
+        {
+            Err(error) if error.message.contains(EXPIRED_VERSION_STAMP) => {
+                self.entities.clear();
+                client.retrieve_metadata_changes(&self.query, None).await?
+            }
+            result => result?,
+        };
+
+        self.apply(changes);
+        Ok(())
+    }
+
+    fn apply(&mut self, changes: MetadataChanges<E>) {
+        for deleted in changes.deleted.iter() {
+            self.entities.remove(deleted);
+        }
+
+        for entity in changes.entities {
+            self.entities.insert(entity.get_metadata_id(), entity);
+        }
+
+        self.version_stamp = Some(changes.server_version_stamp);
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the entity metadata selected by the query that changed since the given version stamp
+
+    Without a version stamp all selected entity metadata is retrieved. The returned
+    changes contain the version stamp to use for the next call
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    - The version stamp expired (error code `0x80044352`)
+    */
+    pub async fn retrieve_metadata_changes<E: DeserializeOwned>(
+        &self,
+        query: &EntityQueryExpression,
+        version_stamp: Option<&str>,
+    ) -> Result<MetadataChanges<E>> {
+        let query = serde_json::to_string(&query.to_json()).into_dataverse_result()?;
+        let mut parameters = QueryParameters::new();
+        parameters.push("@q", query);
+
+        let function = match version_stamp {
+            Some(version_stamp) => {
+                parameters.push("@v", format_args!("'{}'", version_stamp));
+                "RetrieveMetadataChanges(Query=@q,ClientVersionStamp=@v)"
+            }
+            None => "RetrieveMetadataChanges(Query=@q)",
+        };
+
+        let url_path = format!("{}{}", self.build_simple_url(function), parameters);
+        let result: RetrieveMetadataChangesResult<E> = self
+            .request(Method::GET, &url_path, Ok, handle_json_response)
+            .await?;
+
+        Ok(MetadataChanges {
+            entities: result.entities,
+            deleted: result
+                .deleted
+                .map(|deleted| deleted.values.into_iter().flatten().collect())
+                .unwrap_or_default(),
+            server_version_stamp: result.server_version_stamp,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{
+        EntityNameCache, EntityNames, EntityQueryExpression, MetadataCache, MetadataChanges,
+        MetadataRecord,
+    };
+
+    #[test]
+    fn cache_resolves_both_forms() {
+        let cache = EntityNameCache::default();
+        let names = EntityNames {
+            logical_name: String::from("contact"),
+            collection_name: String::from("contacts"),
+        };
+
+        cache.insert(&names);
+        assert_eq!(cache.get("contact"), Some(names.clone()));
+        assert_eq!(cache.get("contacts"), Some(names));
+        assert_eq!(cache.get("account"), None);
+    }
+
+    #[test]
+    fn query_expression_json() {
+        let query = EntityQueryExpression::new(&["LogicalName"]).logical_names(&["account"]);
+        assert_eq!(
+            query.to_json().to_string(),
+            r#"{"Criteria":{"Conditions":[{"ConditionOperator":"Equals","PropertyName":"LogicalName","Value":{"Type":"System.String","Value":"account"}}],"FilterOperator":"Or"},"Properties":{"AllProperties":false,"PropertyNames":["LogicalName"]}}"#
+        );
+    }
+
+    #[test]
+    fn cache_applies_changes() {
+        struct Entity(Uuid, &'static str);
+
+        impl MetadataRecord for Entity {
+            fn get_metadata_id(&self) -> Uuid {
+                self.0
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for Entity {
+            fn deserialize<D: serde::Deserializer<'de>>(_: D) -> Result<Self, D::Error> {
+                Err(serde::de::Error::custom("the test entity is not deserialized"))
+            }
+        }
+
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let mut cache = MetadataCache::new(EntityQueryExpression::new(&[]));
+
+        cache.apply(MetadataChanges {
+            entities: vec![Entity(first, "account"), Entity(second, "contact")],
+            deleted: vec![],
+            server_version_stamp: String::from("1"),
+        });
+
+        cache.apply(MetadataChanges {
+            entities: vec![Entity(first, "account2")],
+            deleted: vec![second],
+            server_version_stamp: String::from("2"),
+        });
+
+        assert_eq!(cache.get_version_stamp(), Some("2"));
+        assert_eq!(cache.get(&first).map(|entity| entity.1), Some("account2"));
+        assert!(cache.get(&second).is_none());
+    }
+}
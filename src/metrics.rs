@@ -0,0 +1,112 @@
+/*!
+Module for recording client metrics
+
+A `Metrics` implementation configured on the client is notified about every
+request, every retry of a throttled batch and every executed batch. This allows
+exporting counters and histograms to any metrics system, e.g. OpenTelemetry or
+Prometheus, without tying this crate to one of them
+
+All functions have empty default implementations, so only the relevant ones
+need to be implemented
+
+# Examples
+```rust
+use std::sync::atomic::{AtomicU64, Ordering};
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    metrics::{Metrics, RequestMetric}
+};
+
+#[derive(Default)]
+struct ErrorCounter {
+    errors: AtomicU64,
+}
+
+impl Metrics for ErrorCounter {
+    fn record_request(&self, request: &RequestMetric) {
+        if request.status.map_or(true, |status| status >= 400) {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+let client = Client::new_dummy().with_metrics(ErrorCounter::default());
+```
+*/
+
+use std::time::Duration;
+
+use reqwest::Method;
+
+/// A request that was sent to dataverse
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestMetric<'a> {
+    pub method: &'a Method,
+    pub url: &'a str,
+    /// http status of the response or `None` if no response was received
+    pub status: Option<u16>,
+    /// time until the response headers were received
+    pub duration: Duration,
+}
+
+/// A throttled batch that is retried
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryMetric {
+    /// number of the retry, starting with 1
+    pub attempt: usize,
+    /// period that is waited before the retry
+    pub delay: Duration,
+}
+
+/// receiver of the metrics of a client
+pub trait Metrics: Send + Sync {
+    /// called after every request with its outcome
+    fn record_request(&self, _request: &RequestMetric) {}
+
+    /// called before a throttled batch is retried
+    fn record_retry(&self, _retry: &RetryMetric) {}
+
+    /// called before a batch is executed with the number of requests in it
+    fn record_batch(&self, _size: usize) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+
+    use crate::{auth::Authenticate, client::Client, result::Result};
+
+    use super::{Metrics, RequestMetric};
+
+    struct StaticToken;
+
+    #[async_trait]
+    impl Authenticate for StaticToken {
+        async fn get_valid_token(&self) -> Result<Arc<String>> {
+            Ok(Arc::new(String::from("token")))
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        statuses: Mutex<Vec<Option<u16>>>,
+    }
+
+    impl Metrics for Arc<Recorder> {
+        fn record_request(&self, request: &RequestMetric) {
+            self.statuses.lock().unwrap().push(request.status);
+        }
+    }
+
+    #[tokio::test]
+    async fn records_failed_requests() {
+        let recorder = Arc::new(Recorder::default());
+        let client = Client::new("", reqwest::Client::new(), StaticToken).with_metrics(recorder.clone());
+
+        // the client has no url, so the request fails before a response is received
+        assert!(client.who_am_i().await.is_err());
+        assert_eq!(*recorder.statuses.lock().unwrap(), vec![None]);
+    }
+}
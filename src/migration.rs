@@ -0,0 +1,298 @@
+/*!
+Module for migrating records with their original creation date and creator
+
+Dataverse sets `createdon` and `createdby` of new records itself. For data migrations
+the original values can be preserved:
+- the `overriddencreatedon` column replaces `createdon` with the given date
+- impersonating the original creator (`MSCRMCallerID`) sets `createdby` to this user,
+  while `createdonbehalfby` records the user of the connection that did the migration
+
+This requires the `prvOverrideCreatedOnCreatedBy` privilege ("Override Created on or
+Created by for Records during Data Import") and a table with an `overriddencreatedon`
+column. `Client::check_migration_support(...)` validates both up front and fails with
+`ErrorKind::MigrationNotSupported` otherwise, instead of failing every single write
+
+# Examples
+```rust
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+# #[cfg(feature = "batch")]
+use powerplatform_dataverse_service_client::bulk::BulkOptions;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::WriteEntity,
+    migration::Migrated,
+    reference::{Reference, ReferenceStruct},
+    result::{IntoDataverseResult, Result}
+};
+
+# #[cfg(feature = "batch")]
+async fn test() -> Result<()> {
+    let legacy_user = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+    let records = vec![
+        Migrated::new(
+            Contact { contactid: Uuid::new_v4(), lastname: String::from("McTestface") },
+            Utc.with_ymd_and_hms(2009, 3, 14, 9, 30, 0).unwrap(),
+        )
+        .created_by(legacy_user),
+    ];
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let result = client.bulk_create_migrated(&records, &BulkOptions::new()).await?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    lastname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+#[cfg(feature = "batch")]
+use std::collections::HashSet;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{ser::Error, Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[cfg(feature = "batch")]
+use crate::bulk::{BulkOptions, BulkResult};
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::WriteEntity,
+    error::{DataverseError, ErrorKind},
+    reference::{EntityReference, Reference},
+    result::Result,
+};
+
+/// column that overrides the `createdon` date of created records
+pub static OVERRIDDEN_CREATED_ON: &str = "overriddencreatedon";
+
+/// privilege that allows setting `overriddencreatedon` and impersonating the creator
+pub static OVERRIDE_PRIVILEGE: &str = "prvOverrideCreatedOnCreatedBy";
+
+/// header that impersonates the system user with the given id
+static CALLER_ID_HEADER: &str = "MSCRMCallerID";
+
+/**
+A record that is created with its original creation date and optionally its original creator
+
+The entity is serialized with an additional `overriddencreatedon` column. The creator is
+impersonated by `Client::create_migrated(...)` and `Client::bulk_create_migrated(...)`
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migrated<E> {
+    pub entity: E,
+    pub created_on: DateTime<Utc>,
+    /// the system user that becomes `createdby` of the record
+    pub created_by: Option<Uuid>,
+}
+
+impl<E> Migrated<E> {
+    /// wraps the entity to be created with the given creation date
+    pub fn new(entity: E, created_on: DateTime<Utc>) -> Self {
+        Self {
+            entity,
+            created_on,
+            created_by: None,
+        }
+    }
+
+    /// creates the record on behalf of the system user with the given id
+    pub fn created_by(mut self, user_id: Uuid) -> Self {
+        self.created_by = Some(user_id);
+        self
+    }
+
+    fn get_headers(&self) -> Vec<(&'static str, String)> {
+        self.created_by
+            .iter()
+            .map(|user_id| (CALLER_ID_HEADER, user_id.as_hyphenated().to_string()))
+            .collect()
+    }
+}
+
+impl<E: Serialize> Serialize for Migrated<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(&self.entity).map_err(S::Error::custom)?;
+        let payload = value
+            .as_object_mut()
+            .ok_or_else(|| S::Error::custom("a migrated entity has to serialize into a JSON object"))?;
+
+        payload.insert(
+            OVERRIDDEN_CREATED_ON.to_string(),
+            Value::String(self.created_on.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        );
+
+        value.serialize(serializer)
+    }
+}
+
+impl<E: Reference> Reference for Migrated<E> {
+    fn get_reference(&self) -> EntityReference {
+        self.entity.get_reference()
+    }
+}
+
+impl<E: WriteEntity> WriteEntity for Migrated<E> {}
+
+#[derive(Deserialize)]
+struct AttributeDefinitions {
+    value: Vec<AttributeDefinition>,
+}
+
+#[derive(Deserialize)]
+struct AttributeDefinition {
+    #[serde(rename = "IsValidForCreate")]
+    is_valid_for_create: bool,
+}
+
+fn not_supported(message: String) -> DataverseError {
+    DataverseError::with_kind(ErrorKind::MigrationNotSupported, message)
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    checks that records of the entity (logical or entity set name) can be created with
+    an overridden creation date and creator by the user of this client
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The entity has no `overriddencreatedon` column that is valid for create
+    - The user lacks the `prvOverrideCreatedOnCreatedBy` privilege
+    */
+    pub async fn check_migration_support(&self, entity_name: &str) -> Result<()> {
+        let names = self.resolve_entity_names(entity_name).await?;
+
+        let attributes: AttributeDefinitions = self
+            .execute_function(&format!(
+                "EntityDefinitions(LogicalName='{}')/Attributes?$select=IsValidForCreate&$filter=LogicalName eq '{}'",
+                names.logical_name, OVERRIDDEN_CREATED_ON
+            ))
+            .await?;
+
+        if !attributes.value.iter().any(|attribute| attribute.is_valid_for_create) {
+            return Err(not_supported(format!(
+                "the entity {} has no {} column that can be set on create",
+                names.logical_name, OVERRIDDEN_CREATED_ON
+            )));
+        }
+
+        let user_id = self.who_am_i().await?.user_id;
+        if !self.has_privilege(user_id, OVERRIDE_PRIVILEGE).await? {
+            return Err(not_supported(format!(
+                "the user {} lacks the privilege {} to override the creation of records",
+                user_id.as_hyphenated(),
+                OVERRIDE_PRIVILEGE
+            )));
+        }
+
+        Ok(())
+    }
+
+    /**
+    creates the record with its original creation date and creator and returns its id
+
+    Use `check_migration_support(...)` to get a clear error before migrating, as
+    Dataverse silently ignores `overriddencreatedon` for some configurations
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization error
+    - Any http client or server error
+    - The creator doesn't exist or cannot be impersonated
+    */
+    pub async fn create_migrated<E: WriteEntity>(&self, record: &Migrated<E>) -> Result<Uuid> {
+        self.create_with_headers(record, &record.get_headers()).await
+    }
+
+    /**
+    creates all given records with their original creation date and creator in parallel batches
+
+    The migration support of every table in the records is checked before any record is
+    written. Failed records are reported in the returned `BulkResult` instead of failing
+    the whole operation
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error during the check
+    - A table of the records doesn't support migration (`ErrorKind::MigrationNotSupported`)
+    */
+    #[cfg(feature = "batch")]
+    pub async fn bulk_create_migrated<E: WriteEntity + Sync>(
+        &self,
+        records: &[Migrated<E>],
+        options: &BulkOptions,
+    ) -> Result<BulkResult> {
+        let tables: HashSet<String> = records
+            .iter()
+            .map(|record| record.get_reference().entity_name.to_string())
+            .collect();
+
+        for table in tables.iter() {
+            self.check_migration_support(table).await?;
+        }
+
+        let entity_name = |index: usize| records[index].get_reference().entity_name.to_string();
+
+        self.execute_bulk(records.len(), options, entity_name, |batch, index| {
+            let record = &records[index];
+            let headers = record.get_headers();
+            let headers: Vec<(&str, &str)> = headers
+                .iter()
+                .map(|(name, value)| (*name, value.as_str()))
+                .collect();
+
+            batch.create_with_headers(record, &headers)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::Migrated;
+
+    #[test]
+    fn serializes_overridden_created_on() {
+        let record = Migrated::new(
+            json!({ "lastname": "McTestface" }),
+            Utc.with_ymd_and_hms(2009, 3, 14, 9, 30, 0).unwrap(),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"lastname":"McTestface","overriddencreatedon":"2009-03-14T09:30:00Z"}"#
+        );
+        assert!(record.get_headers().is_empty());
+        assert_eq!(
+            record.created_by(Uuid::nil()).get_headers(),
+            vec![("MSCRMCallerID", String::from("00000000-0000-0000-0000-000000000000"))]
+        );
+    }
+
+    #[test]
+    fn rejects_non_object_entities() {
+        let record = Migrated::new(json!([1, 2]), Utc::now());
+        assert!(serde_json::to_string(&record).is_err());
+    }
+}
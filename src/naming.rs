@@ -0,0 +1,189 @@
+/*!
+Module for detecting suspicious column names before requests are sent
+
+Dataverse expects logical names (e.g. `firstname`), which are always lowercase.
+Schema names (e.g. `FirstName`), typos with invalid characters or lookup value
+columns (`_parentcustomerid_value`) in write payloads are common mistakes that
+only surface as cryptic `400 Bad Request` responses
+
+With name validation enabled the client inspects the keys of create, update and
+upsert payloads and the selected, filtered and ordered columns of queries, and
+passes a `NameWarning` to the handler for every suspicious name. The requests
+are sent nonetheless, so this is meant for development builds
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::client::Client;
+
+let client = Client::new_dummy().with_name_validation(|warning| {
+    eprintln!("suspicious column {} in {:?}: {:?}", warning.name, warning.location, warning.issue);
+});
+```
+*/
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::query::Query;
+
+/// What is suspicious about a column name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameIssue {
+    /// The name contains uppercase letters, which indicates a schema name instead of a logical name
+    Uppercase,
+
+    /// The name contains characters that are not allowed in logical names
+    InvalidCharacter,
+
+    /// The name is a read-only lookup value column which has to be written with `<navigation>@odata.bind`
+    LookupValue,
+}
+
+/// Where a suspicious column name was found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameLocation {
+    Payload,
+    Select,
+    Filter,
+    Order,
+}
+
+/// A suspicious column name in an outgoing request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameWarning {
+    pub name: String,
+    pub location: NameLocation,
+    pub issue: NameIssue,
+}
+
+/// callback that receives suspicious column names
+pub type NameWarningHandler = Arc<dyn Fn(&NameWarning) + Send + Sync>;
+
+/**
+checks a logical column name and returns what is suspicious about it
+
+Navigation paths (e.g. `parentcustomerid_account/name`) are checked by their last
+segment, because navigation properties of custom lookups use the casing of the schema name
+*/
+pub fn check_name(name: &str) -> Option<NameIssue> {
+    let column = name.rsplit('/').next().unwrap_or(name);
+
+    if column.is_empty()
+        || !column
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+    {
+        return Some(NameIssue::InvalidCharacter);
+    }
+
+    if column.chars().any(|character| character.is_ascii_uppercase()) {
+        return Some(NameIssue::Uppercase);
+    }
+
+    None
+}
+
+/// checks the keys of a JSON write payload, skipping annotations like `@odata.bind`
+pub(crate) fn check_payload(payload: &[u8]) -> Vec<NameWarning> {
+    let Ok(Value::Object(payload)) = serde_json::from_slice::<Value>(payload) else {
+        return Vec::new();
+    };
+
+    payload
+        .keys()
+        .filter(|key| !key.contains('@'))
+        .filter_map(|key| {
+            let issue = if key.starts_with('_') && key.ends_with("_value") {
+                Some(NameIssue::LookupValue)
+            } else {
+                check_name(key)
+            };
+
+            issue.map(|issue| NameWarning {
+                name: key.clone(),
+                location: NameLocation::Payload,
+                issue,
+            })
+        })
+        .collect()
+}
+
+/// checks the selected columns and the columns used in the filter and order of a query
+pub(crate) fn check_query(columns: &[&str], query: Option<&Query>) -> Vec<NameWarning> {
+    let mut names: Vec<(&str, NameLocation)> = columns
+        .iter()
+        .map(|column| (*column, NameLocation::Select))
+        .collect();
+
+    if let Some(query) = query {
+        let mut filtered = Vec::new();
+
+        if let Some(filter) = query.filter.as_ref() {
+            filter.collect_names(&mut filtered);
+        }
+
+        names.extend(filtered.into_iter().map(|name| (name, NameLocation::Filter)));
+        names.extend(
+            query
+                .order
+                .iter()
+                .flatten()
+                .map(|order| (order.get_name(), NameLocation::Order)),
+        );
+    }
+
+    names
+        .into_iter()
+        .filter_map(|(name, location)| {
+            check_name(name).map(|issue| NameWarning {
+                name: name.to_string(),
+                location,
+                issue,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::{filter::Filter, order::Order, Query};
+
+    use super::{check_name, check_payload, check_query, NameIssue, NameLocation};
+
+    #[test]
+    fn suspicious_names() {
+        assert_eq!(check_name("firstname"), None);
+        assert_eq!(check_name("new_ParentAccount/name"), None);
+        assert_eq!(check_name("FirstName"), Some(NameIssue::Uppercase));
+        assert_eq!(check_name("first name"), Some(NameIssue::InvalidCharacter));
+    }
+
+    #[test]
+    fn payload_keys() {
+        let warnings = check_payload(
+            br#"{"firstname":"Testy","LastName":"McTestface","_parentcustomerid_value":"x","parentcustomerid_account@odata.bind":"/accounts(x)"}"#,
+        );
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].name, "LastName");
+        assert_eq!(warnings[0].issue, NameIssue::Uppercase);
+        assert_eq!(warnings[1].issue, NameIssue::LookupValue);
+    }
+
+    #[test]
+    fn query_columns() {
+        let query = Query::new("contacts")
+            .filter(Filter::equal("firstname", "Testy").and(Filter::equal("StateCode", 0)))
+            .order(vec![Order::Ascending("Last Name")]);
+
+        let warnings = check_query(&["contactid", "FullName"], Some(&query));
+        let locations: Vec<NameLocation> = warnings.iter().map(|warning| warning.location).collect();
+
+        assert_eq!(
+            locations,
+            vec![NameLocation::Select, NameLocation::Filter, NameLocation::Order]
+        );
+        assert_eq!(warnings[2].issue, NameIssue::InvalidCharacter);
+    }
+}
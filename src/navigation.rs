@@ -0,0 +1,120 @@
+/*!
+Module for writing collection-valued navigation properties
+
+Records are added to or removed from a collection-valued navigation property of
+another record by the name of the navigation property (e.g. `contact_customer_accounts`
+of an account). This works for one-to-many as well as many-to-many relationships
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let account = EntityReference::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+    let contact = EntityReference::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?
+    );
+
+    client.add_to_collection(&account, "contact_customer_accounts", &contact).await?;
+    client.remove_from_collection(&account, "contact_customer_accounts", &contact).await?;
+    Ok(())
+}
+```
+*/
+
+use reqwest::Method;
+use serde::Serialize;
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_empty_response, Client},
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+#[derive(Serialize)]
+struct ReferenceBody {
+    #[serde(rename = "@odata.id")]
+    odata_id: String,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    adds the related record to the collection-valued navigation property of the record
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization error
+    - Any http client or server error
+    - One of the records or the navigation property doesn't exist
+    */
+    pub async fn add_to_collection(
+        &self,
+        reference: &impl Reference,
+        navigation_property: &str,
+        related: &impl Reference,
+    ) -> Result<()> {
+        let reference = reference.get_reference();
+        let related = related.get_reference();
+        let url_path = format!(
+            "{}/{}/$ref",
+            self.build_targeted_url(&reference.entity_name, reference.entity_id),
+            navigation_property
+        );
+        let body = ReferenceBody {
+            odata_id: self.build_targeted_url(&related.entity_name, related.entity_id),
+        };
+        let body = serde_json::to_vec(&body).into_dataverse_result()?;
+
+        self.request(
+            Method::POST,
+            &url_path,
+            move |request| {
+                Ok(request
+                    .header("Content-Type", "application/json")
+                    .body(body))
+            },
+            handle_empty_response,
+        )
+        .await
+    }
+
+    /**
+    removes the related record from the collection-valued navigation property of the record
+
+    Only the relationship is removed, both records are kept
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - One of the records or the navigation property doesn't exist
+    */
+    pub async fn remove_from_collection(
+        &self,
+        reference: &impl Reference,
+        navigation_property: &str,
+        related: &impl Reference,
+    ) -> Result<()> {
+        let reference = reference.get_reference();
+        let related = related.get_reference();
+        let url_path = format!(
+            "{}/{}({})/$ref",
+            self.build_targeted_url(&reference.entity_name, reference.entity_id),
+            navigation_property,
+            related.entity_id.as_hyphenated()
+        );
+
+        self.request(Method::DELETE, &url_path, Ok, handle_empty_response)
+            .await
+    }
+}
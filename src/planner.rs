@@ -0,0 +1,179 @@
+/*!
+Module for sizing batches by the measured execution time of their requests
+
+Dataverse cancels batches that take longer than 2 minutes, and how many requests fit
+into this time depends on the complexity of the entity (plugins, workflows, columns).
+The `BatchPlanner` of a client keeps a moving average of the execution time per request
+for every entity, measured from the batches of the bulk functions, and derives batch
+sizes that stay within a time budget well below the limit
+
+Bulk functions use the planner if the options are time boxed with `BulkOptions::time_boxed()`.
+Until an entity was measured, the batch size of the options is used
+
+This is only available with the `batch` feature, which is enabled by default
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    bulk::BulkOptions,
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let contacts: Vec<Contact> = (0..10_000)
+        .map(|number| Contact {
+            contactid: Uuid::new_v4(),
+            lastname: format!("Contact {}", number),
+        })
+        .collect();
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let options = BulkOptions::new().batch_size(20).time_boxed();
+    client.bulk_create(&contacts, &options).await?;
+
+    println!(
+        "the next batch of contacts would contain {} records",
+        client.get_batch_planner().plan_batch_size("contacts", 20)
+    );
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    lastname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use crate::bulk::MAX_BATCH_SIZE;
+
+/// maximum execution time of a batch before dataverse cancels it
+pub static MAX_EXECUTION_TIME: Duration = Duration::from_secs(120);
+
+/// execution time that planned batches aim for, leaving room for slower requests
+pub static DEFAULT_TIME_BUDGET: Duration = Duration::from_secs(60);
+
+/// weight of a new measurement in the moving average
+static SMOOTHING: f64 = 0.3;
+
+/// Plans the size of batches from the measured execution time of their requests
+#[derive(Debug)]
+pub struct BatchPlanner {
+    budget: Duration,
+    averages: Mutex<HashMap<String, Duration>>,
+}
+
+impl BatchPlanner {
+    /// creates a planner without measurements that aims for the default time budget
+    pub fn new() -> Self {
+        Self {
+            budget: DEFAULT_TIME_BUDGET,
+            averages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// sets the execution time that planned batches aim for, at most the maximum execution time
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = budget.min(MAX_EXECUTION_TIME);
+        self
+    }
+
+    pub fn get_budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// adds the execution time of a batch with the given number of requests of the entity to its average
+    pub fn observe(&self, entity_name: &str, requests: usize, elapsed: Duration) {
+        if requests == 0 {
+            return;
+        }
+
+        let per_request = elapsed / requests as u32;
+
+        if let Ok(mut averages) = self.averages.lock() {
+            averages
+                .entry(entity_name.to_string())
+                .and_modify(|average| {
+                    *average = average.mul_f64(1.0 - SMOOTHING) + per_request.mul_f64(SMOOTHING)
+                })
+                .or_insert(per_request);
+        }
+    }
+
+    /// returns the average execution time of a request of the entity if it was measured
+    pub fn get_average(&self, entity_name: &str) -> Option<Duration> {
+        self.averages.lock().ok()?.get(entity_name).copied()
+    }
+
+    /**
+    returns the number of requests of the entity that fit into the time budget
+
+    Returns the given fallback if the entity was not measured yet
+    */
+    pub fn plan_batch_size(&self, entity_name: &str, fallback: u16) -> u16 {
+        let Some(average) = self.get_average(entity_name) else {
+            return fallback;
+        };
+
+        if average.is_zero() {
+            return MAX_BATCH_SIZE;
+        }
+
+        let size = self.budget.as_secs_f64() / average.as_secs_f64();
+        (size.floor() as u64).clamp(1, MAX_BATCH_SIZE as u64) as u16
+    }
+}
+
+impl Default for BatchPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::BatchPlanner;
+
+    #[test]
+    fn plans_batch_size_by_average() {
+        let planner = BatchPlanner::new().with_budget(Duration::from_secs(10));
+        assert_eq!(planner.plan_batch_size("contacts", 50), 50);
+
+        planner.observe("contacts", 50, Duration::from_secs(5));
+        assert_eq!(
+            planner.get_average("contacts"),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(planner.plan_batch_size("contacts", 50), 100);
+
+        planner.observe("contacts", 10, Duration::from_secs(8));
+        assert_eq!(
+            planner.get_average("contacts"),
+            Some(Duration::from_millis(310))
+        );
+        assert_eq!(planner.plan_batch_size("contacts", 50), 32);
+
+        planner.observe("accounts", 1, Duration::from_secs(60));
+        assert_eq!(planner.plan_batch_size("accounts", 50), 1);
+        planner.observe("tasks", 100, Duration::from_millis(10));
+        assert_eq!(planner.plan_batch_size("tasks", 50), 1000);
+    }
+}
@@ -0,0 +1,293 @@
+/*!
+Module for checking the privileges of the user of a client
+
+Long running operations like bulk deletes or assignments fail halfway if the user
+lacks the required privileges. `Client::check_privilege(...)` allows a pre-flight
+check of the table privileges before starting such an operation
+
+`Client::retrieve_user_security()` returns the names of all roles of the user (including
+the roles of their teams) and their effective privileges, e.g. to tailor a user interface
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result,
+    sharing::AccessRights
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    client
+        .check_privilege("contact", AccessRights::DELETE | AccessRights::ASSIGN)
+        .await?;
+
+    // start the bulk operation here
+    Ok(())
+}
+```
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    error::{DataverseError, ErrorKind},
+    result::Result,
+    sharing::AccessRights,
+};
+
+/// prefixes of the table privileges for each single access right
+static PRIVILEGE_PREFIXES: &[(AccessRights, &str)] = &[
+    (AccessRights::READ, "prvRead"),
+    (AccessRights::WRITE, "prvWrite"),
+    (AccessRights::APPEND, "prvAppend"),
+    (AccessRights::APPEND_TO, "prvAppendTo"),
+    (AccessRights::CREATE, "prvCreate"),
+    (AccessRights::DELETE, "prvDelete"),
+    (AccessRights::SHARE, "prvShare"),
+    (AccessRights::ASSIGN, "prvAssign"),
+];
+
+#[derive(Deserialize)]
+struct EntityDefinition {
+    #[serde(rename = "SchemaName")]
+    schema_name: String,
+}
+
+#[derive(Deserialize)]
+struct UserPrivileges {
+    #[serde(rename = "RolePrivileges")]
+    role_privileges: Vec<Value>,
+}
+
+/// The depth of a privilege, ordered from the most restricted to the widest access
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+pub enum PrivilegeDepth {
+    /// records owned by the user or shared with them
+    Basic,
+    /// records of the business unit of the user
+    Local,
+    /// records of the business unit of the user and its child business units
+    Deep,
+    /// all records of the organization
+    Global,
+}
+
+/// The roles and effective privileges of a user
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserSecurity {
+    /// names of the roles of the user and of their teams
+    pub roles: BTreeSet<String>,
+    /// names of the privileges (e.g. `prvReadAccount`) with their widest depth
+    pub privileges: BTreeMap<String, PrivilegeDepth>,
+}
+
+impl UserSecurity {
+    /// Indicates if the user or one of their teams has the role with the given name
+    pub fn has_role(&self, name: &str) -> bool {
+        self.roles.contains(name)
+    }
+
+    /// Indicates if the user holds the privilege with the given name in any depth
+    pub fn has_privilege(&self, name: &str) -> bool {
+        self.privileges.contains_key(name)
+    }
+
+    /// returns the widest depth the user holds the privilege with the given name in
+    pub fn get_privilege_depth(&self, name: &str) -> Option<PrivilegeDepth> {
+        self.privileges.get(name).copied()
+    }
+
+    fn add_privileges(&mut self, privileges: Vec<RolePrivilege>) {
+        for privilege in privileges {
+            let depth = self
+                .privileges
+                .entry(privilege.name)
+                .or_insert(privilege.depth);
+            *depth = (*depth).max(privilege.depth);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RolePrivilege {
+    #[serde(rename = "PrivilegeName")]
+    name: String,
+    #[serde(rename = "Depth")]
+    depth: PrivilegeDepth,
+}
+
+#[derive(Deserialize)]
+struct TypedUserPrivileges {
+    #[serde(rename = "RolePrivileges")]
+    role_privileges: Vec<RolePrivilege>,
+}
+
+#[derive(Deserialize)]
+struct Roles {
+    value: Vec<Role>,
+}
+
+#[derive(Deserialize)]
+struct Role {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Teams {
+    value: Vec<Team>,
+}
+
+#[derive(Deserialize)]
+struct Team {
+    #[serde(default)]
+    teamroles_association: Vec<Role>,
+}
+
+/// returns the names of the table privileges for the given access rights, e.g. `prvDeleteContact`
+fn get_privilege_names(schema_name: &str, rights: AccessRights) -> Vec<String> {
+    PRIVILEGE_PREFIXES
+        .iter()
+        .filter(|(right, _)| rights.contains(*right))
+        .map(|(_, prefix)| format!("{}{}", prefix, schema_name))
+        .collect()
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    checks that the user of this client holds the table privileges for all given access rights
+    on the entity (logical or entity set name)
+
+    Privileges granted by the security roles of the user and of its teams are considered.
+    Please note that the depth of the privilege (user, business unit, organization) is not
+    checked, so single records may still be inaccessible
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no entity with the given name
+    - The user lacks one of the privileges (`ErrorKind::PrivilegeMissing`)
+    */
+    pub async fn check_privilege(&self, entity_name: &str, rights: AccessRights) -> Result<()> {
+        let names = self.resolve_entity_names(entity_name).await?;
+        let entity: EntityDefinition = self
+            .execute_function(&format!(
+                "EntityDefinitions(LogicalName='{}')?$select=SchemaName",
+                names.logical_name
+            ))
+            .await?;
+
+        let user_id = self.who_am_i().await?.user_id;
+
+        for privilege in get_privilege_names(&entity.schema_name, rights) {
+            if !self.has_privilege(user_id, &privilege).await? {
+                return Err(DataverseError::with_kind(
+                    ErrorKind::PrivilegeMissing,
+                    format!(
+                        "the user {} lacks the privilege {} on the entity {}",
+                        user_id.as_hyphenated(),
+                        privilege,
+                        names.logical_name
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+    returns the roles of the user of this client, including the roles of their teams,
+    and their effective privileges
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_user_security(&self) -> Result<UserSecurity> {
+        let user_id = self.who_am_i().await?.user_id.as_hyphenated().to_string();
+
+        let roles: Roles = self
+            .execute_function(&format!(
+                "systemusers({})/systemuserroles_association?$select=name",
+                user_id
+            ))
+            .await?;
+        let teams: Teams = self
+            .execute_function(&format!(
+                "systemusers({})/teammembership_association?$select=teamid&$expand=teamroles_association($select=name)",
+                user_id
+            ))
+            .await?;
+        let privileges: TypedUserPrivileges = self
+            .execute_function(&format!(
+                "systemusers({})/Microsoft.Dynamics.CRM.RetrieveUserPrivileges()",
+                user_id
+            ))
+            .await?;
+
+        let mut security = UserSecurity {
+            roles: roles
+                .value
+                .into_iter()
+                .chain(teams.value.into_iter().flat_map(|team| team.teamroles_association))
+                .map(|role| role.name)
+                .collect(),
+            ..UserSecurity::default()
+        };
+        security.add_privileges(privileges.role_privileges);
+        Ok(security)
+    }
+
+    /// Indicates if the system user holds the privilege with the given name in any of its roles
+    pub(crate) async fn has_privilege(&self, user_id: Uuid, privilege: &str) -> Result<bool> {
+        let privileges: UserPrivileges = self
+            .execute_function(&format!(
+                "systemusers({})/Microsoft.Dynamics.CRM.RetrieveUserPrivilegeByPrivilegeName(PrivilegeName='{}')",
+                user_id.as_hyphenated(),
+                privilege
+            ))
+            .await?;
+
+        Ok(!privileges.role_privileges.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sharing::AccessRights;
+
+    use super::{get_privilege_names, PrivilegeDepth, TypedUserPrivileges, UserSecurity};
+
+    #[test]
+    fn keeps_widest_depth() {
+        let privileges: TypedUserPrivileges = serde_json::from_str(
+            r#"{"RolePrivileges":[{"Depth":"Basic","PrivilegeId":"00000000-0000-0000-0000-000000000001","BusinessUnitId":"00000000-0000-0000-0000-000000000002","PrivilegeName":"prvReadAccount"},{"Depth":"Global","PrivilegeId":"00000000-0000-0000-0000-000000000001","BusinessUnitId":"00000000-0000-0000-0000-000000000002","PrivilegeName":"prvReadAccount"},{"Depth":"Local","PrivilegeId":"00000000-0000-0000-0000-000000000003","BusinessUnitId":"00000000-0000-0000-0000-000000000002","PrivilegeName":"prvWriteAccount"}]}"#,
+        )
+        .unwrap();
+
+        let mut security = UserSecurity::default();
+        security.add_privileges(privileges.role_privileges);
+
+        assert_eq!(security.get_privilege_depth("prvReadAccount"), Some(PrivilegeDepth::Global));
+        assert_eq!(security.get_privilege_depth("prvWriteAccount"), Some(PrivilegeDepth::Local));
+        assert!(!security.has_privilege("prvDeleteAccount"));
+    }
+
+    #[test]
+    fn builds_privilege_names() {
+        assert_eq!(
+            get_privilege_names("Contact", AccessRights::DELETE | AccessRights::APPEND_TO),
+            vec![String::from("prvAppendToContact"), String::from("prvDeleteContact")]
+        );
+        assert!(get_privilege_names("Contact", AccessRights::NONE).is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+/*!
+Module for reporting the progress of long running operations
+
+A `Progress` implementation configured on the client is notified while bulk
+functions, exports with `retrieve_all(...)` and system job pollers are running.
+Each update carries the number of completed items, the total if it is known and
+the elapsed time, from which an estimated time of arrival is derived. This allows
+rendering progress bars without wrapping every call
+
+Closures implement `Progress`, and `ChannelProgress` forwards the updates into
+a channel to consume them in another task
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    progress::ProgressUpdate
+};
+
+let client = Client::new_dummy().with_progress(|update: &ProgressUpdate| {
+    match (update.total, update.get_eta()) {
+        (Some(total), Some(eta)) => println!("{}/{} ({:?} left)", update.completed, total, eta),
+        _ => println!("{} done", update.completed),
+    }
+});
+```
+*/
+
+use std::time::{Duration, Instant};
+
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+
+/// The kind of operation a progress update belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// a bulk function, counting records
+    Bulk,
+    /// a retrieve of all pages of a query, counting records
+    Retrieve,
+    /// a poller waiting for a system job, counting completed jobs
+    SystemJob,
+    /// a deduplication, counting merges
+    Merge,
+}
+
+/// The progress of a long running operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgressUpdate {
+    pub operation: Operation,
+    /// number of items that were completed so far
+    pub completed: usize,
+    /// number of all items or `None` if it is not known in advance
+    pub total: Option<usize>,
+    /// time since the operation started
+    pub elapsed: Duration,
+}
+
+impl ProgressUpdate {
+    /// Indicates if all items were completed
+    pub fn is_finished(&self) -> bool {
+        self.total.is_some_and(|total| self.completed >= total)
+    }
+
+    /// estimates the remaining time from the rate of the completed items so far
+    pub fn get_eta(&self) -> Option<Duration> {
+        let total = self.total?;
+
+        if self.completed == 0 {
+            return None;
+        }
+
+        let remaining = total.saturating_sub(self.completed) as u32;
+        Some(self.elapsed.mul_f64(1.0 / self.completed as f64) * remaining)
+    }
+}
+
+/// receiver of the progress updates of a client
+pub trait Progress: Send + Sync {
+    /// called whenever an operation made progress
+    fn report(&self, update: &ProgressUpdate);
+}
+
+impl<F: Fn(&ProgressUpdate) + Send + Sync> Progress for F {
+    fn report(&self, update: &ProgressUpdate) {
+        self(update)
+    }
+}
+
+/// Forwards progress updates into an unbounded channel
+#[derive(Clone, Debug)]
+pub struct ChannelProgress {
+    sender: UnboundedSender<ProgressUpdate>,
+}
+
+impl ChannelProgress {
+    /// creates a reporter and the receiver of its updates
+    pub fn new() -> (Self, UnboundedReceiver<ProgressUpdate>) {
+        let (sender, receiver) = unbounded();
+        (Self { sender }, receiver)
+    }
+}
+
+impl Progress for ChannelProgress {
+    fn report(&self, update: &ProgressUpdate) {
+        // a dropped receiver only means nobody is interested anymore
+        let _ = self.sender.unbounded_send(*update);
+    }
+}
+
+/// measures an operation and reports its progress to an optional receiver
+pub(crate) struct ProgressTracker<'a> {
+    progress: Option<&'a dyn Progress>,
+    operation: Operation,
+    total: Option<usize>,
+    started: Instant,
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub(crate) fn new(progress: Option<&'a dyn Progress>, operation: Operation, total: Option<usize>) -> Self {
+        Self {
+            progress,
+            operation,
+            total,
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn report(&self, completed: usize) {
+        if let Some(progress) = self.progress {
+            progress.report(&ProgressUpdate {
+                operation: self.operation,
+                completed,
+                total: self.total,
+                elapsed: self.started.elapsed(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ChannelProgress, Operation, ProgressTracker, ProgressUpdate};
+
+    #[test]
+    fn estimates_remaining_time() {
+        let update = ProgressUpdate {
+            operation: Operation::Bulk,
+            completed: 25,
+            total: Some(100),
+            elapsed: Duration::from_secs(10),
+        };
+
+        assert_eq!(update.get_eta(), Some(Duration::from_secs(30)));
+        assert!(!update.is_finished());
+        assert_eq!(ProgressUpdate { completed: 0, ..update }.get_eta(), None);
+        assert_eq!(ProgressUpdate { total: None, ..update }.get_eta(), None);
+    }
+
+    #[test]
+    fn channel_receives_updates() {
+        let (progress, mut receiver) = ChannelProgress::new();
+        let tracker = ProgressTracker::new(Some(&progress), Operation::Retrieve, None);
+        tracker.report(5000);
+
+        let update = receiver.try_recv().unwrap();
+        assert_eq!(update.operation, Operation::Retrieve);
+        assert_eq!(update.completed, 5000);
+        assert_eq!(update.total, None);
+    }
+}
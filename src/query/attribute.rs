@@ -1,12 +1,14 @@
 use std::fmt::Display;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::Value;
 use uuid::Uuid;
 
 /**
-A Dataverse AttributeValue for use in query filters
+A Dataverse AttributeValue for use in query filters and ad-hoc updates
 
-Please note that this enum is for use in queries only as it is not serializable
+Please note that this enum is not serializable itself. `Client::update_builder(...)`
+converts the values into their JSON representation
 */
 #[derive(Clone, Debug)]
 pub enum Attribute {
@@ -32,6 +34,21 @@ pub enum Attribute {
     Uuid(Uuid),
 }
 
+impl Attribute {
+    /// converts the value into its JSON representation in request payloads
+    pub(crate) fn to_json_value(&self) -> Value {
+        match self {
+            Attribute::Null => Value::Null,
+            Attribute::Boolean(value) => Value::Bool(*value),
+            Attribute::Integer(value) => Value::from(*value),
+            Attribute::Decimal(value) => Value::from(*value),
+            Attribute::String(value) => Value::String(value.clone()),
+            Attribute::DateTime(value) => Value::String(value.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            Attribute::Uuid(value) => Value::String(value.as_hyphenated().to_string()),
+        }
+    }
+}
+
 impl Display for Attribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -45,3 +62,61 @@ impl Display for Attribute {
         }
     }
 }
+
+impl From<&str> for Attribute {
+    fn from(value: &str) -> Self {
+        Attribute::String(value.to_string())
+    }
+}
+
+impl From<String> for Attribute {
+    fn from(value: String) -> Self {
+        Attribute::String(value)
+    }
+}
+
+impl From<i32> for Attribute {
+    fn from(value: i32) -> Self {
+        Attribute::Integer(value as i64)
+    }
+}
+
+impl From<i64> for Attribute {
+    fn from(value: i64) -> Self {
+        Attribute::Integer(value)
+    }
+}
+
+impl From<f64> for Attribute {
+    fn from(value: f64) -> Self {
+        Attribute::Decimal(value)
+    }
+}
+
+impl From<bool> for Attribute {
+    fn from(value: bool) -> Self {
+        Attribute::Boolean(value)
+    }
+}
+
+impl From<Uuid> for Attribute {
+    fn from(value: Uuid) -> Self {
+        Attribute::Uuid(value)
+    }
+}
+
+impl From<DateTime<Utc>> for Attribute {
+    fn from(value: DateTime<Utc>) -> Self {
+        Attribute::DateTime(value)
+    }
+}
+
+/// `None` is converted to `Attribute::Null`
+impl<T: Into<Attribute>> From<Option<T>> for Attribute {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Attribute::Null,
+        }
+    }
+}
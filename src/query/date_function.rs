@@ -0,0 +1,256 @@
+use std::fmt::Display;
+
+/**
+A Dataverse query function for date and time columns
+
+The periods are evaluated by Dataverse in the time zone of the calling user and
+fiscal periods follow the fiscal year settings of the organization
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::query::{date_function::DateFunction, filter::Filter};
+
+let filter = Filter::date("createdon", DateFunction::LastFiscalYear)
+    .or(Filter::date("createdon", DateFunction::InFiscalPeriodAndYear(3, 2024)));
+
+assert_eq!(
+    filter.to_string(),
+    "Microsoft.Dynamics.CRM.LastFiscalYear(PropertyName='createdon') or Microsoft.Dynamics.CRM.InFiscalPeriodAndYear(PropertyName='createdon',PropertyValue1=3,PropertyValue2=2024)"
+);
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateFunction {
+    /// Indicates the current day
+    Today,
+
+    /// Indicates the previous day
+    Yesterday,
+
+    /// Indicates the next day
+    Tomorrow,
+
+    /// Indicates the current week
+    ThisWeek,
+
+    /// Indicates the previous week
+    LastWeek,
+
+    /// Indicates the next week
+    NextWeek,
+
+    /// Indicates the current month
+    ThisMonth,
+
+    /// Indicates the previous month
+    LastMonth,
+
+    /// Indicates the next month
+    NextMonth,
+
+    /// Indicates the current year
+    ThisYear,
+
+    /// Indicates the previous year
+    LastYear,
+
+    /// Indicates the next year
+    NextYear,
+
+    /// Indicates the given number of hours before now
+    LastXHours(i64),
+
+    /// Indicates the given number of hours after now
+    NextXHours(i64),
+
+    /// Indicates the given number of days before today
+    LastXDays(i64),
+
+    /// Indicates the given number of days after today
+    NextXDays(i64),
+
+    /// Indicates the given number of weeks before this week
+    LastXWeeks(i64),
+
+    /// Indicates the given number of weeks after this week
+    NextXWeeks(i64),
+
+    /// Indicates the given number of months before this month
+    LastXMonths(i64),
+
+    /// Indicates the given number of months after this month
+    NextXMonths(i64),
+
+    /// Indicates the given number of years before this year
+    LastXYears(i64),
+
+    /// Indicates the given number of years after this year
+    NextXYears(i64),
+
+    /// Indicates dates older than the given number of days
+    OlderThanXDays(i64),
+
+    /// Indicates dates older than the given number of months
+    OlderThanXMonths(i64),
+
+    /// Indicates dates older than the given number of years
+    OlderThanXYears(i64),
+
+    /// Indicates the current fiscal period
+    ThisFiscalPeriod,
+
+    /// Indicates the previous fiscal period
+    LastFiscalPeriod,
+
+    /// Indicates the next fiscal period
+    NextFiscalPeriod,
+
+    /// Indicates the current fiscal year
+    ThisFiscalYear,
+
+    /// Indicates the previous fiscal year
+    LastFiscalYear,
+
+    /// Indicates the next fiscal year
+    NextFiscalYear,
+
+    /// Indicates the given number of fiscal periods before this fiscal period
+    LastXFiscalPeriods(i64),
+
+    /// Indicates the given number of fiscal periods after this fiscal period
+    NextXFiscalPeriods(i64),
+
+    /// Indicates the given number of fiscal years before this fiscal year
+    LastXFiscalYears(i64),
+
+    /// Indicates the given number of fiscal years after this fiscal year
+    NextXFiscalYears(i64),
+
+    /// Indicates the given fiscal period of any fiscal year
+    InFiscalPeriod(i64),
+
+    /// Indicates the given fiscal year
+    InFiscalYear(i64),
+
+    /// Indicates the given fiscal period (first value) of the given fiscal year (second value)
+    InFiscalPeriodAndYear(i64, i64),
+
+    /// Indicates the given fiscal period (first value) of the given fiscal year (second value) or earlier
+    InOrBeforeFiscalPeriodAndYear(i64, i64),
+
+    /// Indicates the given fiscal period (first value) of the given fiscal year (second value) or later
+    InOrAfterFiscalPeriodAndYear(i64, i64),
+}
+
+impl DateFunction {
+    /// returns the name of the query function in dataverse
+    pub fn get_name(&self) -> &'static str {
+        use DateFunction::*;
+        match self {
+            Today => "Today",
+            Yesterday => "Yesterday",
+            Tomorrow => "Tomorrow",
+            ThisWeek => "ThisWeek",
+            LastWeek => "LastWeek",
+            NextWeek => "NextWeek",
+            ThisMonth => "ThisMonth",
+            LastMonth => "LastMonth",
+            NextMonth => "NextMonth",
+            ThisYear => "ThisYear",
+            LastYear => "LastYear",
+            NextYear => "NextYear",
+            LastXHours(_) => "LastXHours",
+            NextXHours(_) => "NextXHours",
+            LastXDays(_) => "LastXDays",
+            NextXDays(_) => "NextXDays",
+            LastXWeeks(_) => "LastXWeeks",
+            NextXWeeks(_) => "NextXWeeks",
+            LastXMonths(_) => "LastXMonths",
+            NextXMonths(_) => "NextXMonths",
+            LastXYears(_) => "LastXYears",
+            NextXYears(_) => "NextXYears",
+            OlderThanXDays(_) => "OlderThanXDays",
+            OlderThanXMonths(_) => "OlderThanXMonths",
+            OlderThanXYears(_) => "OlderThanXYears",
+            ThisFiscalPeriod => "ThisFiscalPeriod",
+            LastFiscalPeriod => "LastFiscalPeriod",
+            NextFiscalPeriod => "NextFiscalPeriod",
+            ThisFiscalYear => "ThisFiscalYear",
+            LastFiscalYear => "LastFiscalYear",
+            NextFiscalYear => "NextFiscalYear",
+            LastXFiscalPeriods(_) => "LastXFiscalPeriods",
+            NextXFiscalPeriods(_) => "NextXFiscalPeriods",
+            LastXFiscalYears(_) => "LastXFiscalYears",
+            NextXFiscalYears(_) => "NextXFiscalYears",
+            InFiscalPeriod(_) => "InFiscalPeriod",
+            InFiscalYear(_) => "InFiscalYear",
+            InFiscalPeriodAndYear(..) => "InFiscalPeriodAndYear",
+            InOrBeforeFiscalPeriodAndYear(..) => "InOrBeforeFiscalPeriodAndYear",
+            InOrAfterFiscalPeriodAndYear(..) => "InOrAfterFiscalPeriodAndYear",
+        }
+    }
+
+    fn get_values(&self) -> (Option<i64>, Option<i64>) {
+        use DateFunction::*;
+        match *self {
+            LastXHours(value) | NextXHours(value) | LastXDays(value) | NextXDays(value)
+            | LastXWeeks(value) | NextXWeeks(value) | LastXMonths(value)
+            | NextXMonths(value) | LastXYears(value) | NextXYears(value)
+            | OlderThanXDays(value) | OlderThanXMonths(value) | OlderThanXYears(value)
+            | LastXFiscalPeriods(value) | NextXFiscalPeriods(value)
+            | LastXFiscalYears(value) | NextXFiscalYears(value) | InFiscalPeriod(value)
+            | InFiscalYear(value) => (Some(value), None),
+            InFiscalPeriodAndYear(period, year)
+            | InOrBeforeFiscalPeriodAndYear(period, year)
+            | InOrAfterFiscalPeriodAndYear(period, year) => (Some(period), Some(year)),
+            _ => (None, None),
+        }
+    }
+}
+
+/// renders a date function applied to a column
+pub(crate) struct AppliedDateFunction<'a>(pub &'a str, pub &'a DateFunction);
+
+impl Display for AppliedDateFunction<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let AppliedDateFunction(name, function) = self;
+
+        f.write_fmt(format_args!(
+            "Microsoft.Dynamics.CRM.{}(PropertyName='{}'",
+            function.get_name(),
+            name
+        ))?;
+
+        match function.get_values() {
+            (Some(value), None) => f.write_fmt(format_args!(",PropertyValue={}", value))?,
+            (Some(first), Some(second)) => f.write_fmt(format_args!(
+                ",PropertyValue1={},PropertyValue2={}",
+                first, second
+            ))?,
+            _ => {}
+        }
+
+        f.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppliedDateFunction, DateFunction};
+
+    #[test]
+    fn function_arguments() {
+        assert_eq!(
+            AppliedDateFunction("createdon", &DateFunction::ThisFiscalPeriod).to_string(),
+            "Microsoft.Dynamics.CRM.ThisFiscalPeriod(PropertyName='createdon')"
+        );
+        assert_eq!(
+            AppliedDateFunction("createdon", &DateFunction::LastXFiscalYears(2)).to_string(),
+            "Microsoft.Dynamics.CRM.LastXFiscalYears(PropertyName='createdon',PropertyValue=2)"
+        );
+        assert_eq!(
+            AppliedDateFunction("createdon", &DateFunction::InOrAfterFiscalPeriodAndYear(4, 2023)).to_string(),
+            "Microsoft.Dynamics.CRM.InOrAfterFiscalPeriodAndYear(PropertyName='createdon',PropertyValue1=4,PropertyValue2=2023)"
+        );
+    }
+}
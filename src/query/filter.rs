@@ -1,6 +1,10 @@
 use std::fmt::Display;
 
-use super::attribute::Attribute;
+use super::{
+    attribute::Attribute,
+    date_function::{AppliedDateFunction, DateFunction},
+    user_function::UserFunction,
+};
 
 /**
 Represents a filter for Microsoft Dataverse queries
@@ -15,6 +19,12 @@ use powerplatform_dataverse_service_client::query::{attribute::Attribute, filter
 // example filter for attributes "firstname" and "lastname"
 let filter = Filter::Equal("firstname", Attribute::String(String::from("Testy")))
     .and(Filter::EndsWith("lastname", Attribute::String(String::from("face"))));
+
+// the same filter with the generic constructors
+let filter = Filter::equal("firstname", "Testy").and(Filter::ends_with("lastname", "face"));
+
+// contacts of the calling user that were modified in the last week
+let filter = Filter::equal_current_user("ownerid").and(Filter::last_n_days("modifiedon", 7));
 ```
 */
 #[derive(Clone, Debug)]
@@ -46,6 +56,24 @@ pub enum Filter {
     /// Indicates an "ends with" expression as in a string ends with the content of another string
     EndsWith(&'static str, Attribute),
 
+    /// Indicates a hierarchy expression for records above the given record (excluding it)
+    Above(&'static str, Attribute),
+
+    /// Indicates a hierarchy expression for records above the given record (including it)
+    AboveOrEqual(&'static str, Attribute),
+
+    /// Indicates a hierarchy expression for records under the given record (excluding it)
+    Under(&'static str, Attribute),
+
+    /// Indicates a hierarchy expression for records under the given record (including it)
+    UnderOrEqual(&'static str, Attribute),
+
+    /// Indicates a date or fiscal period expression for a date and time column
+    Date(&'static str, DateFunction),
+
+    /// Indicates an expression that compares a column with the calling user
+    User(&'static str, UserFunction),
+
     /// Indicates a logical and `&` expression
     And(Box<Filter>, Box<Filter>),
 
@@ -57,6 +85,131 @@ pub enum Filter {
 }
 
 impl Filter {
+    /// creates an equal `==` expression from any value convertible into an `Attribute`
+    pub fn equal(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::Equal(name, value.into())
+    }
+
+    /// creates a not equal `!=` expression from any value convertible into an `Attribute`
+    pub fn not_equal(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::NotEqual(name, value.into())
+    }
+
+    /// creates a greater than `>` expression from any value convertible into an `Attribute`
+    pub fn greater_than(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::GreaterThan(name, value.into())
+    }
+
+    /// creates a greater than or equal `>=` expression from any value convertible into an `Attribute`
+    pub fn greater_or_equal(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::GreaterOrEqual(name, value.into())
+    }
+
+    /// creates a less than `<` expression from any value convertible into an `Attribute`
+    pub fn less_than(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::LessThan(name, value.into())
+    }
+
+    /// creates a less than or equal `<=` expression from any value convertible into an `Attribute`
+    pub fn less_or_equal(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::LessOrEqual(name, value.into())
+    }
+
+    /// creates a contains expression from any value convertible into an `Attribute`
+    pub fn contains(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::Contains(name, value.into())
+    }
+
+    /// creates a starts with expression from any value convertible into an `Attribute`
+    pub fn starts_with(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::StartsWith(name, value.into())
+    }
+
+    /// creates an "ends with" expression from any value convertible into an `Attribute`
+    pub fn ends_with(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::EndsWith(name, value.into())
+    }
+
+    /// creates a hierarchy expression for records above the record with the given key
+    pub fn above(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::Above(name, value.into())
+    }
+
+    /// creates a hierarchy expression for the record with the given key and the records above it
+    pub fn above_or_equal(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::AboveOrEqual(name, value.into())
+    }
+
+    /// creates a hierarchy expression for records under the record with the given key
+    pub fn under(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::Under(name, value.into())
+    }
+
+    /// creates a hierarchy expression for the record with the given key and the records under it
+    pub fn under_or_equal(name: &'static str, value: impl Into<Attribute>) -> Self {
+        Filter::UnderOrEqual(name, value.into())
+    }
+
+    /// creates a date or fiscal period expression for the given date and time column
+    pub fn date(name: &'static str, function: DateFunction) -> Self {
+        Filter::Date(name, function)
+    }
+
+    /// creates an expression that compares the given column with the calling user
+    pub fn user(name: &'static str, function: UserFunction) -> Self {
+        Filter::User(name, function)
+    }
+
+    /// creates an expression for dates on the current day
+    pub fn today(name: &'static str) -> Self {
+        Filter::Date(name, DateFunction::Today)
+    }
+
+    /// creates an expression for dates on the previous day
+    pub fn yesterday(name: &'static str) -> Self {
+        Filter::Date(name, DateFunction::Yesterday)
+    }
+
+    /// creates an expression for dates on the next day
+    pub fn tomorrow(name: &'static str) -> Self {
+        Filter::Date(name, DateFunction::Tomorrow)
+    }
+
+    /// creates an expression for dates within the given number of days before today
+    pub fn last_n_days(name: &'static str, days: i64) -> Self {
+        Filter::Date(name, DateFunction::LastXDays(days))
+    }
+
+    /// creates an expression for dates within the given number of days after today
+    pub fn next_n_days(name: &'static str, days: i64) -> Self {
+        Filter::Date(name, DateFunction::NextXDays(days))
+    }
+
+    /// creates an expression for dates older than the given number of days
+    pub fn older_than_n_days(name: &'static str, days: i64) -> Self {
+        Filter::Date(name, DateFunction::OlderThanXDays(days))
+    }
+
+    /// creates an expression for lookups to the calling user, e.g. `ownerid`
+    pub fn equal_current_user(name: &'static str) -> Self {
+        Filter::User(name, UserFunction::EqualUserId)
+    }
+
+    /// creates an expression for lookups to the calling user or one of their teams
+    pub fn equal_current_user_or_teams(name: &'static str) -> Self {
+        Filter::User(name, UserFunction::EqualUserOrUserTeams)
+    }
+
+    /// creates an expression for lookups to the business unit of the calling user
+    pub fn equal_current_business_unit(name: &'static str) -> Self {
+        Filter::User(name, UserFunction::EqualBusinessId)
+    }
+
+    /// creates an expression for language columns with the language of the calling user
+    pub fn equal_user_language(name: &'static str) -> Self {
+        Filter::User(name, UserFunction::EqualUserLanguage)
+    }
+
     /// Logically combines this filter and the given filter with an `&` expression
     pub fn and(self, other: Filter) -> Self {
         Filter::And(Box::new(self), Box::new(other))
@@ -80,6 +233,47 @@ impl Filter {
     }
 }
 
+impl Filter {
+    /// collects the names of all columns used in this filter
+    pub(crate) fn collect_names(&self, names: &mut Vec<&'static str>) {
+        use Filter::*;
+        match self {
+            Equal(name, _) | NotEqual(name, _) | GreaterThan(name, _) | GreaterOrEqual(name, _)
+            | LessThan(name, _) | LessOrEqual(name, _) | Contains(name, _) | StartsWith(name, _)
+            | EndsWith(name, _) | Above(name, _) | AboveOrEqual(name, _) | Under(name, _)
+            | UnderOrEqual(name, _) | Date(name, _) | User(name, _) => names.push(name),
+            And(left, right) | Or(left, right) => {
+                left.collect_names(names);
+                right.collect_names(names);
+            }
+            Not(subfilter) => subfilter.collect_names(names),
+        }
+    }
+
+    fn is_or(&self) -> bool {
+        matches!(self, Filter::Or(..))
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(self, Filter::And(..) | Filter::Or(..))
+    }
+}
+
+/// renders a sub filter in parentheses if required by operator precedence
+struct Grouped<'a>(&'a Filter, fn(&Filter) -> bool);
+
+impl Display for Grouped<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Grouped(filter, requires_group) = self;
+
+        if requires_group(filter) {
+            f.write_fmt(format_args!("({})", filter))
+        } else {
+            f.write_fmt(format_args!("{}", filter))
+        }
+    }
+}
+
 impl Display for Filter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Filter::*;
@@ -101,9 +295,97 @@ impl Display for Filter {
             EndsWith(name, attribute) => {
                 f.write_fmt(format_args!("endswith({},{})", name, attribute))
             }
-            And(left, right) => f.write_fmt(format_args!("{} and {}", left, right)),
+            Above(name, attribute) => write_query_function(f, "Above", name, attribute),
+            AboveOrEqual(name, attribute) => {
+                write_query_function(f, "AboveOrEqual", name, attribute)
+            }
+            Under(name, attribute) => write_query_function(f, "Under", name, attribute),
+            UnderOrEqual(name, attribute) => {
+                write_query_function(f, "UnderOrEqual", name, attribute)
+            }
+            Date(name, function) => f.write_fmt(format_args!(
+                "{}",
+                AppliedDateFunction(name, function)
+            )),
+            User(name, function) => f.write_fmt(format_args!(
+                "Microsoft.Dynamics.CRM.{}(PropertyName='{}')",
+                function.get_name(),
+                name
+            )),
+            And(left, right) => f.write_fmt(format_args!(
+                "{} and {}",
+                Grouped(left, Filter::is_or),
+                Grouped(right, Filter::is_or)
+            )),
             Or(left, right) => f.write_fmt(format_args!("{} or {}", left, right)),
-            Not(subfilter) => f.write_fmt(format_args!("not {}", subfilter)),
+            Not(subfilter) => f.write_fmt(format_args!(
+                "not {}",
+                Grouped(subfilter, Filter::is_compound)
+            )),
         }
     }
 }
+
+/// renders a dataverse query function with a property name and a property value
+fn write_query_function(
+    f: &mut std::fmt::Formatter<'_>,
+    function: &str,
+    name: &str,
+    attribute: &Attribute,
+) -> std::fmt::Result {
+    f.write_fmt(format_args!(
+        "Microsoft.Dynamics.CRM.{}(PropertyName='{}',PropertyValue={})",
+        function, name, attribute
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::Filter;
+
+    #[test]
+    fn generic_constructors() {
+        let filter = Filter::equal("firstname", "Testy")
+            .and(Filter::greater_than("age", 30))
+            .and(Filter::not_equal("parentcustomerid", None::<Uuid>))
+            .and(Filter::equal("donotemail", false));
+
+        assert_eq!(
+            filter.to_string(),
+            "firstname eq 'Testy' and age gt 30 and parentcustomerid ne null and donotemail eq false"
+        );
+    }
+
+    #[test]
+    fn hierarchy_functions() {
+        let filter = Filter::under("accountid", Uuid::nil())
+            .and(Filter::not_equal("statecode", 1));
+
+        assert_eq!(
+            filter.to_string(),
+            "Microsoft.Dynamics.CRM.Under(PropertyName='accountid',PropertyValue='00000000-0000-0000-0000-000000000000') and statecode ne 1"
+        );
+        assert_eq!(
+            Filter::above_or_equal("accountid", Uuid::nil()).to_string(),
+            "Microsoft.Dynamics.CRM.AboveOrEqual(PropertyName='accountid',PropertyValue='00000000-0000-0000-0000-000000000000')"
+        );
+    }
+
+    #[test]
+    fn semantic_helpers() {
+        let filter = Filter::today("createdon")
+            .or(Filter::last_n_days("modifiedon", 7))
+            .and(Filter::equal_current_user("ownerid"));
+
+        assert_eq!(
+            filter.to_string(),
+            "(Microsoft.Dynamics.CRM.Today(PropertyName='createdon') or Microsoft.Dynamics.CRM.LastXDays(PropertyName='modifiedon',PropertyValue=7)) and Microsoft.Dynamics.CRM.EqualUserId(PropertyName='ownerid')"
+        );
+        assert_eq!(
+            Filter::equal_user_language("languagecode").to_string(),
+            "Microsoft.Dynamics.CRM.EqualUserLanguage(PropertyName='languagecode')"
+        );
+    }
+}
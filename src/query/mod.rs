@@ -50,13 +50,19 @@ impl Select for Contact {
 ```
 */
 
-use std::fmt::Display;
+use std::fmt::{Display, Write};
 
-use self::{filter::Filter, order::Order};
+use crate::{auth::Authenticate, client::Client};
+
+use self::{attribute::Attribute, filter::Filter, order::Order, parameters::QueryParameters};
 
 pub mod attribute;
+pub mod date_function;
 pub mod filter;
 pub mod order;
+pub mod parameters;
+pub mod template;
+pub mod user_function;
 
 /**
 Represents a Microsoft Dataverse query
@@ -115,6 +121,12 @@ pub struct Query {
     pub limit: Option<u32>,
     pub filter: Option<Filter>,
     pub order: Option<Vec<Order>>,
+    /// Indicates if `Client::retrieve_all(...)` retrieves the following pages
+    pub follow_next_link: bool,
+    /// Indicates if only records with `statecode eq 0` are retrieved
+    pub active_only: bool,
+    /// Indicates if the default filter of the retrieved entity is applied, see `Select::get_default_filter()`
+    pub use_default_filter: bool,
 }
 
 impl Query {
@@ -133,6 +145,9 @@ impl Query {
             limit: None,
             filter: None,
             order: None,
+            follow_next_link: true,
+            active_only: false,
+            use_default_filter: true,
         }
     }
 
@@ -154,63 +169,178 @@ impl Query {
         self.order = Some(order);
         self
     }
+
+    /**
+    stops `Client::retrieve_all(...)` after the first page even if there are more records
+
+    This is useful for previews of large tables, e.g. the first 5000 records
+    */
+    pub fn no_follow_next_link(mut self) -> Self {
+        self.follow_next_link = false;
+        self
+    }
+
+    /**
+    only retrieves active records, i.e. records with `statecode eq 0`
+
+    The condition is combined with the filter of the query, so it doesn't matter
+    if this is called before or after `filter(...)`
+    */
+    pub fn active_only(mut self) -> Self {
+        self.active_only = true;
+        self
+    }
+
+    /// doesn't apply the default filter of the retrieved entity, see `Select::get_default_filter()`
+    pub fn without_default_filter(mut self) -> Self {
+        self.use_default_filter = false;
+        self
+    }
 }
 
-impl Display for Query {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut first_item = true;
-        f.write_str(self.logical_name)?;
+impl Query {
+    /**
+    splits this query into the two queries that emulate the `null` placement of its first order
+
+    Returns `None` if the first order uses the server behavior. Otherwise the records of
+    the first returned query are followed by the records of the second one, where one
+    query retrieves the records with a value and the other the records without a value
+    in the ordered column
+    */
+    pub fn split_by_nulls(&self) -> Option<(Query, Query)> {
+        let first_order = self.order.as_ref()?.first()?;
 
+        if !first_order.has_reversed_nulls() {
+            return None;
+        }
+
+        let name = first_order.get_name();
+        let with_value = self.with_additional_filter(Filter::NotEqual(name, Attribute::Null));
+        let without_value = self.with_additional_filter(Filter::Equal(name, Attribute::Null));
+
+        match first_order {
+            Order::AscendingNullsLast(_) => Some((with_value, without_value)),
+            _ => Some((without_value, with_value)),
+        }
+    }
+
+    /**
+    returns this query with the given default filter of an entity added to its filter
+
+    Returns the query unchanged if there is no default filter or the query opts out of it
+    */
+    pub fn with_default_filter(&self, default_filter: Option<Filter>) -> Query {
+        match default_filter {
+            Some(filter) if self.use_default_filter => self.with_additional_filter(filter),
+            _ => self.clone(),
+        }
+    }
+
+    /// returns the filter of this query combined with its implicit conditions
+    pub fn get_filter(&self) -> Option<Filter> {
+        let active = self
+            .active_only
+            .then_some(Filter::Equal("statecode", Attribute::Integer(0)));
+
+        match (self.filter.clone(), active) {
+            (Some(filter), Some(active)) => Some(filter.and(active)),
+            (filter, active) => filter.or(active),
+        }
+    }
+
+    fn with_additional_filter(&self, filter: Filter) -> Query {
+        let mut query = self.clone();
+        query.filter = Some(match query.filter.take() {
+            Some(existing) => existing.and(filter),
+            None => filter,
+        });
+        query
+    }
+
+    /// adds the query options of this query to the given list
+    pub fn append_parameters(&self, parameters: &mut QueryParameters) {
+        self.append_ordered_parameters(parameters, None);
+    }
+
+    /**
+    adds the query options of this query to the given list and orders by the primary key last
+
+    Dataverse can skip or repeat records between pages if the order is not unique.
+    Ordering by the primary key as the last column makes the order of the pages stable
+    */
+    pub fn append_stable_parameters(&self, parameters: &mut QueryParameters, primary_key: &str) {
+        self.append_ordered_parameters(parameters, Some(primary_key));
+    }
+
+    fn append_ordered_parameters(&self, parameters: &mut QueryParameters, primary_key: Option<&str>) {
         if let Some(limit) = self.limit {
-            if first_item {
-                f.write_str("?")?;
-                first_item = false;
-            } else {
-                f.write_str("&")?;
-            }
+            parameters.push("$top", limit);
+        }
 
-            f.write_fmt(format_args!("$top={}", limit))?;
+        if let Some(filter) = self.get_filter() {
+            parameters.push("$filter", filter);
         }
 
-        if let Some(filter) = &self.filter {
-            if first_item {
-                f.write_str("?")?;
-                first_item = false;
-            } else {
-                f.write_str("&")?;
+        let mut order = String::new();
+
+        for column in self.order.iter().flatten() {
+            if !order.is_empty() {
+                order.push(',');
             }
 
-            f.write_fmt(format_args!("$filter={}", filter))?;
+            // writing into a String cannot fail
+            let _ = write!(order, "{}", column);
         }
 
-        if let Some(order) = &self.order {
-            if first_item {
-                f.write_str("?")?;
-            } else {
-                f.write_str("&")?;
+        let is_ordered_by_key = self
+            .order
+            .iter()
+            .flatten()
+            .any(|column| Some(column.get_name()) == primary_key);
+
+        if let (Some(primary_key), false) = (primary_key, is_ordered_by_key) {
+            if !order.is_empty() {
+                order.push(',');
             }
 
-            f.write_str("$orderby=")?;
+            order.push_str(primary_key);
+            order.push_str(" asc");
+        }
 
-            let mut first_column = true;
+        if !order.is_empty() {
+            parameters.push_string("$orderby", order);
+        }
+    }
 
-            for column in order.iter() {
-                if first_column {
-                    first_column = false;
-                } else {
-                    f.write_str(",")?;
-                }
+    /// returns the query options of this query
+    pub fn get_parameters(&self) -> QueryParameters {
+        let mut parameters = QueryParameters::new();
+        self.append_parameters(&mut parameters);
+        parameters
+    }
 
-                f.write_fmt(format_args!("{}", column))?;
-            }
-        }
+    /**
+    returns the url that retrieves the given columns with this query from the environment of the client
 
-        Ok(())
+    Please note that the client may order by the primary key (stable paging) or send the
+    query options in the body (long urls). Use `Client::explain_query(...)` for the exact request
+    */
+    pub fn to_url<A: Authenticate>(&self, client: &Client<'_, A>, columns: &[&str]) -> String {
+        client.build_query_url(columns, self, None)
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.logical_name)?;
+        f.write_fmt(format_args!("{}", self.get_parameters()))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::auth::no_auth::NoAuth;
+    use crate::client::Client;
     use crate::query::{attribute::Attribute, Filter, Order, Query};
 
     #[test]
@@ -236,6 +366,16 @@ mod tests {
         assert_eq!(query.to_string(), "testy?$filter=name eq 'Testface'");
     }
 
+    #[test]
+    fn query_url() {
+        let client = Client::new("https://instance.crm.dynamics.com/", reqwest::Client::new(), NoAuth {});
+        let query = Query::new("contacts").limit(3);
+        assert_eq!(
+            query.to_url(&client, &["firstname"]),
+            "https://instance.crm.dynamics.com/api/data/v9.2/contacts?$select=firstname&$top=3"
+        );
+    }
+
     #[test]
     fn orderby_query() {
         let mut query: Query = Query::new("testy");
@@ -257,4 +397,48 @@ mod tests {
             "testy?$top=5&$filter=name eq 'Testface'&$orderby=name asc,rank desc"
         );
     }
+
+    #[test]
+    fn split_by_nulls() {
+        let query = Query::new("contacts")
+            .filter(Filter::Equal("statecode", Attribute::Integer(0)))
+            .order(vec![Order::AscendingNullsLast("birthdate")]);
+
+        let (first, second) = query.split_by_nulls().unwrap();
+        assert_eq!(
+            first.to_string(),
+            "contacts?$filter=statecode eq 0 and birthdate ne null&$orderby=birthdate asc"
+        );
+        assert_eq!(
+            second.to_string(),
+            "contacts?$filter=statecode eq 0 and birthdate eq null&$orderby=birthdate asc"
+        );
+
+        let query = Query::new("contacts").order(vec![Order::Descending("birthdate")]);
+        assert!(query.split_by_nulls().is_none());
+    }
+
+    #[test]
+    fn active_only_and_default_filter() {
+        let query = Query::new("contacts")
+            .active_only()
+            .filter(Filter::equal("firstname", "Testy").or(Filter::equal("lastname", "McTestface")));
+        assert_eq!(
+            query.to_string(),
+            "contacts?$filter=(firstname eq 'Testy' or lastname eq 'McTestface') and statecode eq 0"
+        );
+
+        let default_filter = Some(Filter::equal("customertypecode", 1));
+        assert_eq!(
+            Query::new("contacts").with_default_filter(default_filter.clone()).to_string(),
+            "contacts?$filter=customertypecode eq 1"
+        );
+        assert_eq!(
+            Query::new("contacts")
+                .without_default_filter()
+                .with_default_filter(default_filter)
+                .to_string(),
+            "contacts"
+        );
+    }
 }
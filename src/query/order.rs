@@ -5,6 +5,12 @@ Represents a single ordering statement used in `Query` Structures
 
 These can be combined in those queries to build more complex orderings
 
+Dataverse sorts `null` values first in ascending and last in descending order and
+OData offers no option to change this. `AscendingNullsLast` and `DescendingNullsFirst`
+are sent as plain ascending and descending orders, so single pages keep the server
+behavior. `Client::retrieve_all(...)` emulates the requested placement for the first
+order of a query by retrieving the records with and without a value separately
+
 # Examples
 Using `Order` statements is as simple as this:
 ```rust
@@ -21,14 +27,39 @@ pub enum Order {
 
     /// Indicates a descending order
     Descending(&'static str),
+
+    /// Indicates an ascending order with `null` values last
+    AscendingNullsLast(&'static str),
+
+    /// Indicates a descending order with `null` values first
+    DescendingNullsFirst(&'static str),
+}
+
+impl Order {
+    /// returns the name of the ordered attribute
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Order::Ascending(name)
+            | Order::Descending(name)
+            | Order::AscendingNullsLast(name)
+            | Order::DescendingNullsFirst(name) => name,
+        }
+    }
+
+    /// Indicates if the `null` values shall be placed opposite to the server behavior
+    pub fn has_reversed_nulls(&self) -> bool {
+        matches!(self, Order::AscendingNullsLast(_) | Order::DescendingNullsFirst(_))
+    }
 }
 
 impl Display for Order {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Order::*;
         match self {
-            Ascending(name) => f.write_fmt(format_args!("{} asc", name)),
-            Descending(name) => f.write_fmt(format_args!("{} desc", name)),
+            Ascending(name) | AscendingNullsLast(name) => f.write_fmt(format_args!("{} asc", name)),
+            Descending(name) | DescendingNullsFirst(name) => {
+                f.write_fmt(format_args!("{} desc", name))
+            }
         }
     }
 }
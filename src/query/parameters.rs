@@ -0,0 +1,146 @@
+use std::fmt::{Display, Write};
+
+/**
+A list of query options that is rendered as the query string of an url
+
+Options are rendered in the order they were added. An empty list renders as
+an empty string, otherwise the query string starts with `?`
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::query::parameters::QueryParameters;
+
+let mut parameters = QueryParameters::new();
+assert_eq!(parameters.to_string(), "");
+
+parameters.push("$select", "firstname,lastname");
+parameters.push("$top", 5);
+assert_eq!(parameters.to_string(), "?$select=firstname,lastname&$top=5");
+```
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryParameters {
+    parameters: Vec<(&'static str, String)>,
+}
+
+impl QueryParameters {
+    /// creates a new empty list of query options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds the query option with the given name and value
+    pub fn push(&mut self, name: &'static str, value: impl Display) {
+        self.parameters.push((name, value.to_string()));
+    }
+
+    /// adds the query option with the given name and an already rendered value without copying it
+    pub fn push_string(&mut self, name: &'static str, value: String) {
+        self.parameters.push((name, value));
+    }
+
+    /// adds a `$select` option for the given columns if there are any
+    pub fn push_select(&mut self, columns: &[&str]) {
+        if !columns.is_empty() {
+            self.parameters.push(("$select", columns.join(",")));
+        }
+    }
+
+    /// appends the rendered query string to the given buffer
+    pub fn write_to(&self, buffer: &mut String) {
+        buffer.reserve(self.get_rendered_length());
+        let mut separator = '?';
+
+        for (name, value) in self.parameters.iter() {
+            buffer.push(separator);
+            buffer.push_str(name);
+            buffer.push('=');
+            buffer.push_str(value);
+            separator = '&';
+        }
+    }
+
+    /// returns the length of the rendered query string
+    pub fn get_rendered_length(&self) -> usize {
+        self.parameters
+            .iter()
+            .map(|(name, value)| name.len() + value.len() + 2)
+            .sum()
+    }
+
+    /// Indicates if there are no query options in this list
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// returns the value of the first query option with the given name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter, _)| *parameter == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Display for QueryParameters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut separator = '?';
+
+        for (name, value) in self.parameters.iter() {
+            f.write_char(separator)?;
+            f.write_str(name)?;
+            f.write_char('=')?;
+            f.write_str(value)?;
+            separator = '&';
+        }
+
+        Ok(())
+    }
+}
+
+/// appends the `$select` query string for the given columns to the buffer if there are any
+pub(crate) fn write_select(buffer: &mut String, columns: &[&str]) {
+    if columns.is_empty() {
+        return;
+    }
+
+    buffer.reserve(columns.iter().map(|column| column.len() + 1).sum::<usize>() + 8);
+    buffer.push_str("?$select=");
+
+    for (index, column) in columns.iter().enumerate() {
+        if index > 0 {
+            buffer.push(',');
+        }
+
+        buffer.push_str(column);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_select, QueryParameters};
+
+    #[test]
+    fn write_to_matches_display() {
+        let mut parameters = QueryParameters::new();
+        parameters.push_select(&["firstname", "lastname"]);
+        parameters.push("$top", 5);
+        parameters.push_string("$orderby", String::from("lastname asc"));
+
+        let mut buffer = String::from("contacts");
+        parameters.write_to(&mut buffer);
+
+        assert_eq!(buffer, format!("contacts{}", parameters));
+        assert_eq!(parameters.get_rendered_length(), parameters.to_string().len());
+    }
+
+    #[test]
+    fn select_is_written_directly() {
+        let mut buffer = String::from("contacts");
+        write_select(&mut buffer, &["firstname", "lastname"]);
+        assert_eq!(buffer, "contacts?$select=firstname,lastname");
+
+        write_select(&mut buffer, &[]);
+        assert_eq!(buffer, "contacts?$select=firstname,lastname");
+    }
+}
@@ -0,0 +1,349 @@
+/*!
+Module for named, reusable queries with placeholders
+
+A `QueryTemplate` defines the table, filter, order and page size of a query once.
+Its filter may contain placeholders like `@since` that are filled with `Attribute`
+values when the template is executed with `Client::retrieve_template(...)`. Templates
+can be declared in code or deserialized from configuration, so services can share
+the same queries across environments. `QueryTemplates` collects templates by name
+
+Placeholders inside string literals of the filter are left untouched and string
+values are escaped, so values from user input cannot change the filter expression
+
+# Examples
+```rust
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::{Client, Page},
+    entity::ReadEntity,
+    query::{attribute::Attribute, template::QueryTemplates},
+    result::Result,
+    select::Select
+};
+
+async fn test() -> Result<()> {
+    let templates = QueryTemplates::from_json(r#"[{
+        "name": "recent_contacts",
+        "table": "contacts",
+        "filter": "lastname eq @lastname and modifiedon ge @since",
+        "order": "modifiedon desc",
+        "limit": 50
+    }]"#)?;
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let contacts: Page<Contact> = client
+        .retrieve_template(
+            templates.get("recent_contacts").unwrap(),
+            &[
+                ("lastname", Attribute::from("McTestface")),
+                ("since", Attribute::from(Utc::now() - Duration::days(7))),
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    lastname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "lastname"]
+    }
+}
+```
+*/
+
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::Authenticate,
+    client::{handle_page_response, prefer_annotations, Client, Page},
+    entity::ReadEntity,
+    error::DataverseError,
+    result::{IntoDataverseResult, Result},
+};
+
+use super::{attribute::Attribute, parameters::QueryParameters};
+
+/// A named query whose filter may contain `@name` placeholders
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryTemplate {
+    pub name: String,
+    /// the entity set name of the queried table, e.g. `contacts`
+    pub table: String,
+    /// the `$filter` expression with placeholders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// the `$orderby` expression, e.g. `lastname asc,firstname asc`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    /// the maximum number of records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl QueryTemplate {
+    /// creates a template that queries all records of the table (entity set name)
+    pub fn new(name: impl Into<String>, table: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            filter: None,
+            order: None,
+            limit: None,
+        }
+    }
+
+    /// sets the `$filter` expression, which may contain `@name` placeholders
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// sets the `$orderby` expression
+    pub fn order(mut self, order: impl Into<String>) -> Self {
+        self.order = Some(order.into());
+        self
+    }
+
+    /// limits the query result to at most `n` records
+    pub fn limit(mut self, count: u32) -> Self {
+        self.limit = Some(count);
+        self
+    }
+
+    /// returns the names of the placeholders in the filter in order of appearance
+    pub fn get_placeholders(&self) -> Vec<&str> {
+        let mut placeholders = Vec::new();
+
+        if let Some(filter) = self.filter.as_deref() {
+            for_each_placeholder(filter, |_, name| {
+                if !placeholders.contains(&name) {
+                    placeholders.push(name);
+                }
+            });
+        }
+
+        placeholders
+    }
+
+    /**
+    returns the query options of this template with the placeholders replaced by the given values
+
+    This may fail for any of these reasons
+    - A placeholder of the filter has no value
+    */
+    pub fn bind(&self, columns: &[&str], values: &[(&str, Attribute)]) -> Result<QueryParameters> {
+        let mut parameters = QueryParameters::new();
+        parameters.push_select(columns);
+
+        if let Some(limit) = self.limit {
+            parameters.push("$top", limit);
+        }
+
+        if let Some(filter) = self.filter.as_deref() {
+            parameters.push_string("$filter", self.bind_filter(filter, values)?);
+        }
+
+        if let Some(order) = self.order.as_deref() {
+            parameters.push("$orderby", order);
+        }
+
+        Ok(parameters)
+    }
+
+    fn bind_filter(&self, filter: &str, values: &[(&str, Attribute)]) -> Result<String> {
+        let mut bound = String::with_capacity(filter.len());
+        let mut rest = 0;
+        let mut missing = None;
+
+        for_each_placeholder(filter, |position, name| {
+            bound.push_str(&filter[rest..position]);
+            rest = position + name.len() + 1;
+
+            match values.iter().find(|(value_name, _)| *value_name == name) {
+                Some((_, value)) => bound.push_str(&render_value(value)),
+                None => {
+                    missing.get_or_insert_with(|| name.to_string());
+                }
+            }
+        });
+
+        if let Some(name) = missing {
+            return Err(DataverseError::new(format!(
+                "the query template {} has no value for the placeholder @{}",
+                self.name, name
+            )));
+        }
+
+        bound.push_str(&filter[rest..]);
+        Ok(bound)
+    }
+}
+
+/// calls the function with the position and name of every placeholder outside of string literals
+fn for_each_placeholder<'a>(filter: &'a str, mut function: impl FnMut(usize, &'a str)) {
+    let bytes = filter.as_bytes();
+    let mut in_literal = false;
+    let mut position = 0;
+
+    while position < bytes.len() {
+        match bytes[position] {
+            b'\'' => in_literal = !in_literal,
+            b'@' if !in_literal => {
+                let length = filter[position + 1..]
+                    .find(|character: char| !character.is_ascii_alphanumeric() && character != '_')
+                    .unwrap_or(filter.len() - position - 1);
+
+                if length > 0 {
+                    function(position, &filter[position + 1..position + 1 + length]);
+                    position += length;
+                }
+            }
+            _ => {}
+        }
+
+        position += 1;
+    }
+}
+
+/// renders the value for a filter expression with single quotes of strings escaped
+fn render_value(value: &Attribute) -> String {
+    match value {
+        Attribute::String(value) => format!("'{}'", value.replace('\'', "''")),
+        value => value.to_string(),
+    }
+}
+
+/// A collection of query templates by their name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryTemplates {
+    templates: HashMap<String, QueryTemplate>,
+}
+
+impl QueryTemplates {
+    /// creates an empty collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    reads the templates from a JSON array of templates
+
+    Please note that this function can fail if a serde deserialization error occurs
+    */
+    pub fn from_json(json: &str) -> Result<Self> {
+        let templates: Vec<QueryTemplate> = serde_json::from_str(json).into_dataverse_result()?;
+        let mut collection = Self::new();
+
+        for template in templates {
+            collection.add(template);
+        }
+
+        Ok(collection)
+    }
+
+    /// adds the template and replaces any template with the same name
+    pub fn add(&mut self, template: QueryTemplate) {
+        self.templates.insert(template.name.clone(), template);
+    }
+
+    /// returns the template with the given name
+    pub fn get(&self, name: &str) -> Option<&QueryTemplate> {
+        self.templates.get(name)
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves the first page of the records that match the template with the given values
+
+    The following pages can be retrieved with `retrieve_next_page(...)` like for
+    `retrieve_multiple(...)`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - A placeholder of the filter has no value
+    */
+    pub async fn retrieve_template<E: ReadEntity>(
+        &self,
+        template: &QueryTemplate,
+        values: &[(&str, Attribute)],
+    ) -> Result<Page<E>> {
+        let parameters = template.bind(E::get_columns(), values)?;
+        let mut url_path = self.build_simple_url(&template.table);
+        parameters.write_to(&mut url_path);
+
+        if self.is_url_too_long(&url_path) {
+            return self.retrieve_multiple_via_post(&template.table, &parameters).await;
+        }
+
+        self.request(
+            Method::GET,
+            &url_path,
+            |request| Ok(prefer_annotations::<E>(request)),
+            handle_page_response,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::query::attribute::Attribute;
+
+    use super::{QueryTemplate, QueryTemplates};
+
+    #[test]
+    fn binds_placeholders() {
+        let template = QueryTemplate::new("by_name", "contacts")
+            .filter("lastname eq @name and emailaddress1 ne 'a@b.com' and parentcustomerid eq @parent")
+            .order("lastname asc")
+            .limit(10);
+
+        assert_eq!(template.get_placeholders(), vec!["name", "parent"]);
+
+        let parameters = template
+            .bind(
+                &["lastname"],
+                &[
+                    ("name", Attribute::from("O'Neil")),
+                    ("parent", Attribute::from(Uuid::nil())),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            parameters.to_string(),
+            "?$select=lastname&$top=10&$filter=lastname eq 'O''Neil' and emailaddress1 ne 'a@b.com' and parentcustomerid eq '00000000-0000-0000-0000-000000000000'&$orderby=lastname asc"
+        );
+
+        let error = template.bind(&[], &[("name", Attribute::from("Testy"))]).unwrap_err();
+        assert_eq!(error.message, "the query template by_name has no value for the placeholder @parent");
+    }
+
+    #[test]
+    fn reads_templates_from_json() {
+        let templates = QueryTemplates::from_json(r#"[{"name":"active","table":"accounts","filter":"statecode eq 0"}]"#).unwrap();
+
+        let template = templates.get("active").unwrap();
+        assert_eq!(template.table, "accounts");
+        assert_eq!(template.limit, None);
+        assert!(templates.get("inactive").is_none());
+    }
+}
@@ -0,0 +1,57 @@
+/**
+A Dataverse query function that compares a column with the calling user
+
+The user, business unit, teams and language are resolved by Dataverse, so the
+same filter works for every user without looking up their ids first
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::query::{filter::Filter, user_function::UserFunction};
+
+let filter = Filter::user("ownerid", UserFunction::EqualUserOrUserTeams);
+
+assert_eq!(
+    filter.to_string(),
+    "Microsoft.Dynamics.CRM.EqualUserOrUserTeams(PropertyName='ownerid')"
+);
+```
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserFunction {
+    /// Indicates the id of the calling user
+    EqualUserId,
+
+    /// Indicates any other id than the one of the calling user
+    NotEqualUserId,
+
+    /// Indicates the id of the business unit of the calling user
+    EqualBusinessId,
+
+    /// Indicates any other id than the one of the business unit of the calling user
+    NotEqualBusinessId,
+
+    /// Indicates the ids of the teams of the calling user
+    EqualUserTeams,
+
+    /// Indicates the id of the calling user or of one of their teams
+    EqualUserOrUserTeams,
+
+    /// Indicates the language code of the calling user
+    EqualUserLanguage,
+}
+
+impl UserFunction {
+    /// returns the name of the query function in dataverse
+    pub fn get_name(&self) -> &'static str {
+        use UserFunction::*;
+        match self {
+            EqualUserId => "EqualUserId",
+            NotEqualUserId => "NotEqualUserId",
+            EqualBusinessId => "EqualBusinessId",
+            NotEqualBusinessId => "NotEqualBusinessId",
+            EqualUserTeams => "EqualUserTeams",
+            EqualUserOrUserTeams => "EqualUserOrUserTeams",
+            EqualUserLanguage => "EqualUserLanguage",
+        }
+    }
+}
@@ -0,0 +1,215 @@
+/*!
+Module for routing queue items between queues and workers
+
+These functions wrap the `AddToQueue`, `PickFromQueue`, `ReleaseToQueue` and `RouteTo`
+actions. References passed to them use the logical names of their entities
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let queue_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+    let worker_id = Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?;
+    let case = EntityReference::new(
+        "incident",
+        Uuid::parse_str("12345678-1234-1234-1234-1234567890ab").into_dataverse_result()?
+    );
+
+    // put the case into the queue and let the worker pick it up
+    let queue_item_id = client.add_to_queue(queue_id, &case, None).await?;
+    client.pick_from_queue(queue_item_id, worker_id, false).await?;
+    Ok(())
+}
+```
+*/
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    action::serialize_action_reference, auth::Authenticate, client::Client,
+    reference::EntityReference, result::Result,
+};
+
+#[derive(Serialize)]
+struct AddToQueueRequest {
+    #[serde(rename = "Target", serialize_with = "serialize_action_reference")]
+    target: EntityReference,
+    #[serde(
+        rename = "SourceQueue",
+        serialize_with = "serialize_optional_reference",
+        skip_serializing_if = "Option::is_none"
+    )]
+    source_queue: Option<EntityReference>,
+}
+
+#[derive(Deserialize)]
+struct AddToQueueResponse {
+    #[serde(rename = "QueueItemId")]
+    queue_item_id: Uuid,
+}
+
+#[derive(Serialize)]
+struct PickFromQueueRequest {
+    #[serde(rename = "QueueItemId")]
+    queue_item_id: Uuid,
+    #[serde(rename = "WorkerId")]
+    worker_id: Uuid,
+    #[serde(rename = "RemoveQueueItem")]
+    remove_queue_item: bool,
+}
+
+#[derive(Serialize)]
+struct ReleaseToQueueRequest {
+    #[serde(rename = "QueueItemId")]
+    queue_item_id: Uuid,
+}
+
+#[derive(Serialize)]
+struct RouteToRequest {
+    #[serde(rename = "Target", serialize_with = "serialize_action_reference")]
+    target: EntityReference,
+    #[serde(rename = "QueueItem", serialize_with = "serialize_action_reference")]
+    queue_item: EntityReference,
+}
+
+fn serialize_optional_reference<S: serde::Serializer>(
+    reference: &Option<EntityReference>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match reference {
+        Some(reference) => serialize_action_reference(reference, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    adds the target record to the queue and returns the id of the created queue item
+
+    If the record is currently in another queue, that queue can be given as source
+    queue to move the record instead of copying it
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The queue or the target record doesn't exist
+    */
+    pub async fn add_to_queue(
+        &self,
+        queue_id: Uuid,
+        target: &EntityReference,
+        source_queue_id: Option<Uuid>,
+    ) -> Result<Uuid> {
+        let request = AddToQueueRequest {
+            target: target.clone(),
+            source_queue: source_queue_id.map(|id| EntityReference::new("queue", id)),
+        };
+
+        let response: AddToQueueResponse = self
+            .execute_action(
+                &format!("queues({})/Microsoft.Dynamics.CRM.AddToQueue", queue_id.as_hyphenated()),
+                &request,
+            )
+            .await?;
+
+        Ok(response.queue_item_id)
+    }
+
+    /**
+    assigns the queue item to the worker (user or team)
+
+    If `remove_queue_item` is set, the queue item is removed from the queue
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The queue item or the worker doesn't exist
+    */
+    pub async fn pick_from_queue(
+        &self,
+        queue_item_id: Uuid,
+        worker_id: Uuid,
+        remove_queue_item: bool,
+    ) -> Result<()> {
+        let request = PickFromQueueRequest {
+            queue_item_id,
+            worker_id,
+            remove_queue_item,
+        };
+
+        self.execute_action("PickFromQueue", &request).await
+    }
+
+    /**
+    releases the queue item from its worker back to the queue
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The queue item doesn't exist
+    */
+    pub async fn release_to_queue(&self, queue_item_id: Uuid) -> Result<()> {
+        self.execute_action("ReleaseToQueue", &ReleaseToQueueRequest { queue_item_id })
+            .await
+    }
+
+    /**
+    routes the queue item to the target which is a `queue`, `systemuser` or `team`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The queue item or the target doesn't exist
+    */
+    pub async fn route_to(&self, queue_item_id: Uuid, target: &EntityReference) -> Result<()> {
+        let request = RouteToRequest {
+            target: target.clone(),
+            queue_item: EntityReference::new("queueitem", queue_item_id),
+        };
+
+        self.execute_action("RouteTo", &request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::reference::EntityReference;
+
+    use super::{AddToQueueRequest, RouteToRequest};
+
+    #[test]
+    fn add_to_queue_request() {
+        let request = AddToQueueRequest {
+            target: EntityReference::new("email", Uuid::nil()),
+            source_queue: None,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"Target":{"@odata.type":"Microsoft.Dynamics.CRM.email","activityid":"00000000-0000-0000-0000-000000000000"}}"#
+        );
+    }
+
+    #[test]
+    fn route_to_request() {
+        let request = RouteToRequest {
+            target: EntityReference::new("systemuser", Uuid::nil()),
+            queue_item: EntityReference::new("queueitem", Uuid::nil()),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"Target":{"@odata.type":"Microsoft.Dynamics.CRM.systemuser","systemuserid":"00000000-0000-0000-0000-000000000000"},"QueueItem":{"@odata.type":"Microsoft.Dynamics.CRM.queueitem","queueitemid":"00000000-0000-0000-0000-000000000000"}}"#
+        );
+    }
+}
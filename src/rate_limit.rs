@@ -0,0 +1,153 @@
+/*!
+Module for the service protection limits of Dataverse
+
+Dataverse reports the remaining number of requests and the remaining execution time
+of the current 5 minute window with every response. `Client::get_quota()` returns them
+for the last response as `QuotaInfo`, so schedulers can plan their work by the remaining
+quota instead of reacting to throttled requests
+
+# Examples
+```rust
+use std::time::Duration;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let identity = client.who_am_i().await?;
+
+    if let Some(quota) = client.get_quota() {
+        if quota.get_time_remaining_ratio().unwrap_or(1.0) < 0.1 {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    }
+    Ok(())
+}
+```
+*/
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+#[cfg(feature = "batch")]
+use reqwest::StatusCode;
+
+/// header with the remaining number of requests in the current window
+pub(crate) static BURST_REMAINING: &str = "x-ms-ratelimit-burst-remaining-xrm-requests";
+
+/// header with the remaining combined execution time in milliseconds in the current window
+pub(crate) static TIME_REMAINING: &str = "x-ms-ratelimit-time-remaining-xrm-requests";
+
+/// combined execution time in milliseconds that dataverse allows per 5 minute window
+pub static EXECUTION_TIME_LIMIT: f64 = 1_200_000.0;
+
+/// The remaining service protection quota reported with a response
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuotaInfo {
+    /// the remaining number of requests in the current window
+    pub burst_remaining: Option<u64>,
+    /// the remaining combined execution time in the current window
+    pub time_remaining: Option<Duration>,
+    /// the time to wait before the next request if the request was throttled
+    pub retry_after: Option<Duration>,
+}
+
+impl QuotaInfo {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            burst_remaining: parse_header(headers, BURST_REMAINING).map(|value| value as u64),
+            time_remaining: parse_header(headers, TIME_REMAINING)
+                .map(|milliseconds| Duration::from_secs_f64(milliseconds / 1000.0)),
+            retry_after: parse_header(headers, "Retry-After").map(Duration::from_secs_f64),
+        }
+    }
+
+    /// returns the share of the execution time that is left in the current window
+    pub fn get_time_remaining_ratio(&self) -> Option<f64> {
+        self.time_remaining
+            .map(|remaining| remaining.as_secs_f64() * 1000.0 / EXECUTION_TIME_LIMIT)
+    }
+}
+
+/// The service protection state reported with a response
+#[cfg(feature = "batch")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct RateLimit {
+    pub throttled: bool,
+    pub retry_after: Option<Duration>,
+    pub burst_remaining: Option<u64>,
+    pub time_remaining: Option<f64>,
+}
+
+#[cfg(feature = "batch")]
+impl RateLimit {
+    pub fn from_response(status: StatusCode, headers: &HeaderMap) -> Self {
+        Self {
+            throttled: status == StatusCode::TOO_MANY_REQUESTS,
+            retry_after: parse_header(headers, "Retry-After").map(Duration::from_secs_f64),
+            burst_remaining: parse_header(headers, BURST_REMAINING).map(|value| value as u64),
+            time_remaining: parse_header(headers, TIME_REMAINING),
+        }
+    }
+
+    /// returns the share of the execution time that is left in the current window
+    pub fn get_time_remaining_ratio(&self) -> Option<f64> {
+        self.time_remaining
+            .map(|remaining| remaining / EXECUTION_TIME_LIMIT)
+    }
+}
+
+/// parses numeric header values which dataverse formats with thousands separators
+fn parse_header(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .replace(',', "")
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::header::HeaderMap;
+
+    use super::QuotaInfo;
+
+    #[test]
+    fn parse_quota_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ms-ratelimit-time-remaining-xrm-requests", "600,000.00".parse().unwrap());
+        headers.insert("x-ms-ratelimit-burst-remaining-xrm-requests", "5999".parse().unwrap());
+
+        let quota = QuotaInfo::from_headers(&headers);
+        assert_eq!(quota.burst_remaining, Some(5999));
+        assert_eq!(quota.time_remaining, Some(Duration::from_secs(600)));
+        assert_eq!(quota.retry_after, None);
+        assert_eq!(quota.get_time_remaining_ratio(), Some(0.5));
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn parse_rate_limit_headers() {
+        use reqwest::StatusCode;
+
+        use super::RateLimit;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Retry-After", "5".parse().unwrap());
+        headers.insert("x-ms-ratelimit-time-remaining-xrm-requests", "1,199,500.00".parse().unwrap());
+        headers.insert("x-ms-ratelimit-burst-remaining-xrm-requests", "5999".parse().unwrap());
+
+        let rate_limit = RateLimit::from_response(StatusCode::TOO_MANY_REQUESTS, &headers);
+        assert!(rate_limit.throttled);
+        assert_eq!(rate_limit.retry_after, Some(Duration::from_secs(5)));
+        assert_eq!(rate_limit.burst_remaining, Some(5999));
+        assert_eq!(rate_limit.time_remaining, Some(1_199_500.0));
+    }
+}
@@ -0,0 +1,339 @@
+/*!
+Module for recording http interactions to disk and replaying them later
+
+This is only available with the `recording` feature. A `RecordingTransport` sends
+requests with another transport and writes every request/response pair into a
+JSON file (a "cassette"). A `ReplayTransport` answers requests from such a file
+without any network access, which makes tests and demos deterministic and allows
+debugging the OData behavior of an environment that is not accessible anymore
+
+Recordings never contain request headers, so tokens are not written to disk.
+Secrets and personal data in urls and payloads can be scrubbed by replacing
+literal texts (e.g. the organization url) and by redacting the values of named
+JSON fields
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    recording::{RecordingTransport, ReplayTransport},
+    result::Result
+};
+
+fn test() -> Result<()> {
+    // records the interactions of a real environment
+    let recorder = RecordingTransport::new("fixtures/contacts.json", reqwest::Client::new())
+        .replace("https://contoso.crm4.dynamics.com/", "https://instance/")
+        .redact(&["emailaddress1", "telephone1"]);
+    let client = Client::new_dummy().with_transport(recorder);
+
+    // replays them in a test
+    let replay = ReplayTransport::load("fixtures/contacts.json")?
+        .replace("https://contoso.crm4.dynamics.com/", "https://instance/");
+    let client = Client::new_dummy().with_transport(replay);
+    Ok(())
+}
+```
+*/
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use reqwest::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diagnostics::Redactor,
+    error::DataverseError,
+    result::{IntoDataverseResult, Result},
+    transport::HttpTransport,
+};
+
+/// response headers that are kept in recordings
+static RECORDED_HEADERS: &[&str] = &[
+    "content-type",
+    "odata-entityid",
+    "odata-version",
+    "etag",
+    "preference-applied",
+    "retry-after",
+    "x-ms-ratelimit-burst-remaining-xrm-requests",
+    "x-ms-ratelimit-time-remaining-xrm-requests",
+];
+
+/// A recorded request and the response of the server
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    /// the payload of the request, `None` for requests without a body or streamed bodies
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Interaction {
+    /// converts the recorded response into a response of the http client
+    fn to_response(&self) -> Result<Response> {
+        let mut builder = http::Response::builder().status(self.status);
+
+        for (name, value) in self.headers.iter() {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.body(self.body.clone()).into_dataverse_result()?;
+        Ok(Response::from(response))
+    }
+}
+
+/// replaces literal texts in urls and payloads
+#[derive(Clone, Debug, Default)]
+struct Replacements {
+    replacements: Vec<(String, String)>,
+}
+
+impl Replacements {
+    fn apply(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |text, (from, to)| text.replace(from, to))
+    }
+}
+
+/// Sends requests with another transport and records them into a JSON file
+pub struct RecordingTransport {
+    inner: Box<dyn HttpTransport>,
+    path: PathBuf,
+    replacements: Replacements,
+    redactor: Redactor,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl RecordingTransport {
+    /// records the requests sent by the given transport into the file at the path
+    pub fn new(path: impl Into<PathBuf>, inner: impl HttpTransport + 'static) -> Self {
+        Self {
+            inner: Box::new(inner),
+            path: path.into(),
+            replacements: Replacements::default(),
+            redactor: Redactor::default(),
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// replaces the given text in recorded urls and payloads, e.g. the organization url
+    pub fn replace(mut self, from: &str, to: &str) -> Self {
+        self.replacements
+            .replacements
+            .push((from.to_string(), to.to_string()));
+        self
+    }
+
+    /// redacts the values of the given JSON fields in recorded payloads
+    pub fn redact(mut self, fields: &[&str]) -> Self {
+        self.redactor.add_fields(fields);
+        self
+    }
+
+    /// returns the interactions recorded so far
+    pub fn get_interactions(&self) -> Vec<Interaction> {
+        self.interactions
+            .lock()
+            .map(|interactions| interactions.clone())
+            .unwrap_or_default()
+    }
+
+    fn scrub(&self, text: &[u8]) -> String {
+        self.replacements.apply(&self.redactor.redact_body(text))
+    }
+
+    /// appends the interaction and rewrites the file, so a crash keeps all previous interactions
+    fn record(&self, interaction: Interaction) -> Result<()> {
+        let mut interactions = self
+            .interactions
+            .lock()
+            .map_err(|_| DataverseError::new(String::from("the recording was poisoned by a panic")))?;
+
+        interactions.push(interaction);
+        let content = serde_json::to_vec_pretty(&*interactions).into_dataverse_result()?;
+        std::fs::write(&self.path, content).into_dataverse_result()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for RecordingTransport {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        let method = request.method().to_string();
+        let url = self.replacements.apply(request.url().as_str());
+        let request_body = request
+            .body()
+            .and_then(Body::as_bytes)
+            .map(|body| self.scrub(body));
+
+        let response = self.inner.execute(request).await?;
+        let status = response.status().as_u16();
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| RECORDED_HEADERS.contains(&name.as_str()))
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    self.replacements.apply(&String::from_utf8_lossy(value.as_bytes())),
+                )
+            })
+            .collect();
+        let body = response.bytes().await.into_dataverse_result()?;
+
+        let interaction = Interaction {
+            method,
+            url,
+            request_body,
+            status,
+            headers,
+            body: self.scrub(&body),
+        };
+
+        let response = interaction.to_response()?;
+        self.record(interaction)?;
+        Ok(response)
+    }
+}
+
+/**
+Answers requests from recorded interactions without network access
+
+Each request is answered by the first unused interaction with the same method and url,
+so repeated requests are answered in the order they were recorded
+*/
+pub struct ReplayTransport {
+    replacements: Replacements,
+    interactions: Mutex<Vec<Option<Interaction>>>,
+}
+
+impl ReplayTransport {
+    /// replays the given interactions
+    pub fn new(interactions: Vec<Interaction>) -> Self {
+        Self {
+            replacements: Replacements::default(),
+            interactions: Mutex::new(interactions.into_iter().map(Some).collect()),
+        }
+    }
+
+    /**
+    loads the interactions recorded into the file at the path
+
+    This may fail if the file cannot be read or contains no recording
+    */
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read(path).into_dataverse_result()?;
+        let interactions: Vec<Interaction> = serde_json::from_slice(&content).into_dataverse_result()?;
+        Ok(Self::new(interactions))
+    }
+
+    /// replaces the given text in the urls of requests before they are matched
+    pub fn replace(mut self, from: &str, to: &str) -> Self {
+        self.replacements
+            .replacements
+            .push((from.to_string(), to.to_string()));
+        self
+    }
+
+    /// returns the number of interactions that were not replayed yet
+    pub fn get_remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .map(|interactions| interactions.iter().flatten().count())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReplayTransport {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        let method = request.method().to_string();
+        let url = self.replacements.apply(request.url().as_str());
+
+        let mut interactions = self
+            .interactions
+            .lock()
+            .map_err(|_| DataverseError::new(String::from("the replay was poisoned by a panic")))?;
+
+        let interaction = interactions
+            .iter_mut()
+            .find(|interaction| {
+                interaction
+                    .as_ref()
+                    .is_some_and(|interaction| interaction.method == method && interaction.url == url)
+            })
+            .and_then(Option::take)
+            .ok_or_else(|| DataverseError::new(format!("there is no recorded interaction for {} {}", method, url)))?;
+
+        interaction.to_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpTransport, Interaction, RecordingTransport, ReplayTransport};
+
+    fn interaction(url: &str, body: &str) -> Interaction {
+        Interaction {
+            method: String::from("GET"),
+            url: url.to_string(),
+            request_body: None,
+            status: 200,
+            headers: vec![(String::from("content-type"), String::from("application/json"))],
+            body: body.to_string(),
+        }
+    }
+
+    fn request(url: &str) -> reqwest::Request {
+        reqwest::Client::new().get(url).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn replays_in_recorded_order() {
+        let replay = ReplayTransport::new(vec![
+            interaction("https://instance/api/data/v9.2/contacts", "[1]"),
+            interaction("https://instance/api/data/v9.2/contacts", "[2]"),
+        ]);
+
+        let first = replay.execute(request("https://instance/api/data/v9.2/contacts")).await.unwrap();
+        assert_eq!(first.text().await.unwrap(), "[1]");
+        let second = replay.execute(request("https://instance/api/data/v9.2/contacts")).await.unwrap();
+        assert_eq!(second.text().await.unwrap(), "[2]");
+        assert!(replay.execute(request("https://instance/api/data/v9.2/contacts")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn records_scrubbed_interactions() {
+        let path = std::env::temp_dir().join(format!("dataverse_recording_{}.json", uuid::Uuid::new_v4().as_simple()));
+        let inner = ReplayTransport::new(vec![interaction(
+            "https://contoso.crm4.dynamics.com/api/data/v9.2/contacts",
+            r#"{"value":[{"emailaddress1":"testy@example.com","lastname":"McTestface"}]}"#,
+        )]);
+        let recorder = RecordingTransport::new(&path, inner)
+            .replace("https://contoso.crm4.dynamics.com/", "https://instance/")
+            .redact(&["emailaddress1"]);
+
+        let response = recorder
+            .execute(request("https://contoso.crm4.dynamics.com/api/data/v9.2/contacts"))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+
+        let replay = ReplayTransport::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let response = replay.execute(request("https://instance/api/data/v9.2/contacts")).await.unwrap();
+        assert_eq!(
+            response.text().await.unwrap(),
+            r#"{"value":[{"emailaddress1":"***","lastname":"McTestface"}]}"#
+        );
+    }
+}
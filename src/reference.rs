@@ -1,36 +1,91 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::entity::EntityName;
+
+/// suffix of the annotations that contain the logical name of the entity referenced by a lookup
+pub static LOOKUP_LOGICAL_NAME_ANNOTATION: &str = "@Microsoft.Dynamics.CRM.lookuplogicalname";
+
+/// value of the `Prefer` header that requests the logical names of referenced entities
+pub(crate) static LOOKUP_LOGICAL_NAMES_PREFERENCE: &str =
+    "odata.include-annotations=\"Microsoft.Dynamics.CRM.lookuplogicalname\"";
+
 /**
 trait for getting a reference to an entity record from a struct
 */
 pub trait Reference {
     /// creates a Reference structure for association purposes in Microsoft Dataverse
-    fn get_reference(&self) -> ReferenceStruct;
+    fn get_reference(&self) -> EntityReference;
 }
 
 /**
-default implementation for the `Reference` trait
+A reference to an entity record in Microsoft Dataverse
+
+This is the single reference type of this crate. It is used for retrieving,
+writing and deleting records, in batches, in action requests and for binding
+lookups
+
+Which name of the entity is expected depends on where the reference is used
+- Requests that address a record by its url (retrieve, write, delete, batches) and
+  `to_bind()` expect the name of the entity set (e.g. `contacts`)
+- Action parameters like `MergeRequest` and `action::serialize_action_reference`
+  expect the logical name (e.g. `contact`), which `MergeRequest::validate()` checks
+- References read from lookups like `activity::Regarding` contain the logical name,
+  `Client::resolve_reference(...)` turns them into references with the entity set name
+
+The entity name can be either a static string or an owned string for names that are
+only known at runtime. Because of the owned names the reference is `Clone` but no
+longer `Copy` like the former `ReferenceStruct`
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::reference::EntityReference;
+
+let static_reference = EntityReference::new("contacts", Uuid::nil());
+let runtime_reference = EntityReference::new(String::from("contacts"), Uuid::nil());
+
+assert_eq!(static_reference, runtime_reference);
+assert_eq!(static_reference.to_bind(), "/contacts(00000000-0000-0000-0000-000000000000)");
+```
 */
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ReferenceStruct {
-    pub entity_name: &'static str,
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntityReference {
+    pub entity_name: Cow<'static, str>,
     pub entity_id: Uuid,
 }
 
-impl ReferenceStruct {
+/**
+former name of `EntityReference` that is kept for compatibility
+
+Please note that it isn't `Copy` anymore, references that are used more than once
+have to be cloned
+*/
+pub type ReferenceStruct = EntityReference;
+
+impl EntityReference {
     /// creates a new Reference struct
-    pub fn new(entity_name: &'static str, entity_id: Uuid) -> Self {
+    pub fn new(entity_name: impl Into<Cow<'static, str>>, entity_id: Uuid) -> Self {
         Self {
-            entity_name,
+            entity_name: entity_name.into(),
             entity_id,
         }
     }
+
+    /// creates a reference to a record of an entity whose names are known at compile time
+    pub fn of<E: EntityName>(entity_id: Uuid) -> Self {
+        Self::new(E::COLLECTION, entity_id)
+    }
+
+    /// creates the value for an `@odata.bind` annotation pointing to the referenced record
+    pub fn to_bind(&self) -> String {
+        format!("/{}({})", self.entity_name, self.entity_id.as_hyphenated())
+    }
 }
 
-impl Display for ReferenceStruct {
+impl Display for EntityReference {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
             "{}:({})",
@@ -40,8 +95,14 @@ impl Display for ReferenceStruct {
     }
 }
 
-impl Reference for ReferenceStruct {
-    fn get_reference(&self) -> ReferenceStruct {
-        *self
+impl Reference for EntityReference {
+    fn get_reference(&self) -> EntityReference {
+        self.clone()
+    }
+}
+
+impl<N: Into<Cow<'static, str>>> From<(N, Uuid)> for EntityReference {
+    fn from((entity_name, entity_id): (N, Uuid)) -> Self {
+        Self::new(entity_name, entity_id)
     }
 }
@@ -0,0 +1,226 @@
+/*!
+Module for reports of the records that failed in a bulk function
+
+A `FailureReport` lists every failed record of a `BulkResult` with its index, http
+status, Dataverse error code and message. When the written records are given, their
+serialized payload is echoed in the report, so the failed rows can be fixed and
+loaded again. The report can be serialized with serde or written as CSV
+
+# Examples
+```rust
+use std::fs::File;
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    bulk::BulkOptions,
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    report::FailureReport,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let contacts = vec![Contact {
+        contactid: Uuid::new_v4(),
+        lastname: String::from("McTestface"),
+    }];
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let result = client.bulk_create(&contacts, &BulkOptions::new()).await?;
+
+    let report = FailureReport::with_payloads(&result, &contacts)?;
+    report.write_csv(File::create("failures.csv").into_dataverse_result()?)?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    lastname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::{
+    bulk::{BulkFailure, BulkResult},
+    result::{IntoDataverseResult, Result},
+};
+
+/// column names of the CSV output
+static CSV_HEADER: [&str; 5] = ["index", "status", "code", "message", "payload"];
+
+/// A record that failed in a bulk function
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FailureRecord {
+    /// index of the record in the slice given to the bulk function
+    pub index: usize,
+    /// http status of the failed request if the server responded
+    pub status: Option<u16>,
+    /// error code of Dataverse (e.g. `0x80040237`) if the server sent one
+    pub code: Option<String>,
+    pub message: String,
+    /// the serialized record if the records were given to the report
+    pub payload: Option<String>,
+}
+
+impl FailureRecord {
+    fn new(failure: &BulkFailure, payload: Option<String>) -> Self {
+        Self {
+            index: failure.index,
+            status: failure.status,
+            code: failure.code.clone(),
+            message: failure.message.clone(),
+            payload,
+        }
+    }
+}
+
+/// The failed records of a bulk function, ordered by their index
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FailureReport {
+    pub records: Vec<FailureRecord>,
+}
+
+impl FailureReport {
+    /// creates a report of the failed records of the result without their payloads
+    pub fn from_result(result: &BulkResult) -> Self {
+        Self {
+            records: result
+                .failures
+                .iter()
+                .map(|failure| FailureRecord::new(failure, None))
+                .collect(),
+        }
+    }
+
+    /**
+    creates a report of the failed records of the result that echoes their payloads
+
+    The records have to be the ones given to the bulk function, so the indices of
+    the failures point to them
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn with_payloads<E: Serialize>(result: &BulkResult, records: &[E]) -> Result<Self> {
+        let records = result
+            .failures
+            .iter()
+            .map(|failure| {
+                let payload = records
+                    .get(failure.index)
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .into_dataverse_result()?;
+                Ok(FailureRecord::new(failure, payload))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { records })
+    }
+
+    /// Indicates if the report contains no failed records
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /**
+    writes the report as CSV with a header row to the writer
+
+    Fields are quoted if they contain commas, quotes or line breaks
+
+    Please note that this function can fail if writing to the writer fails
+    */
+    pub fn write_csv(&self, mut writer: impl Write) -> Result<()> {
+        writer
+            .write_all(self.to_csv().as_bytes())
+            .into_dataverse_result()
+    }
+
+    /// returns the report as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        write_csv_row(&mut csv, CSV_HEADER.iter().copied());
+
+        for record in self.records.iter() {
+            let index = record.index.to_string();
+            let status = record.status.map(|status| status.to_string());
+
+            write_csv_row(
+                &mut csv,
+                [
+                    index.as_str(),
+                    status.as_deref().unwrap_or_default(),
+                    record.code.as_deref().unwrap_or_default(),
+                    record.message.as_str(),
+                    record.payload.as_deref().unwrap_or_default(),
+                ],
+            );
+        }
+
+        csv
+    }
+}
+
+/// appends the fields as one CSV row terminated by CRLF
+fn write_csv_row<'a>(csv: &mut String, fields: impl IntoIterator<Item = &'a str>) {
+    for (position, field) in fields.into_iter().enumerate() {
+        if position > 0 {
+            csv.push(',');
+        }
+
+        if field.contains([',', '"', '\r', '\n']) {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(field);
+        }
+    }
+
+    csv.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::bulk::{BulkFailure, BulkResult};
+
+    use super::FailureReport;
+
+    #[test]
+    fn writes_csv_with_payloads() {
+        let result = BulkResult {
+            failures: vec![BulkFailure {
+                index: 1,
+                status: Some(400),
+                code: Some(String::from("0x80040237")),
+                message: String::from("A record with matching key values already exists."),
+            }],
+            ..BulkResult::default()
+        };
+        let records = vec![json!({ "lastname": "A" }), json!({ "lastname": "B" })];
+
+        let report = FailureReport::with_payloads(&result, &records).unwrap();
+        assert_eq!(
+            report.to_csv(),
+            "index,status,code,message,payload\r\n\
+             1,400,0x80040237,A record with matching key values already exists.,\"{\"\"lastname\"\":\"\"B\"\"}\"\r\n"
+        );
+
+        assert_eq!(FailureReport::from_result(&result).records[0].payload, None);
+    }
+}
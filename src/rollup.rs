@@ -0,0 +1,93 @@
+/*!
+Module for refreshing rollup columns
+
+Dataverse recalculates rollup columns with a system job every hour by default, so
+their values can lag behind the related records. `Client::calculate_rollup(...)`
+recalculates a rollup column of a single record right away with the
+`CalculateRollupField` function. Calculated columns don't need to be refreshed,
+because they are calculated whenever they are retrieved
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let account = ReferenceStruct::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let total: Option<f64> = client.calculate_rollup_value(&account, "new_opportunitytotal").await?;
+    println!("the refreshed total is {:?}", total);
+    Ok(())
+}
+```
+*/
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    recalculates the rollup column with the given logical name of the referenced record
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The column is not a rollup column of the entity
+    */
+    pub async fn calculate_rollup(&self, reference: &impl Reference, field_name: &str) -> Result<()> {
+        let reference = reference.get_reference();
+        let function = format!(
+            "CalculateRollupField(Target=@target,FieldName=@field)?@target={{\"@odata.id\":\"{}({})\"}}&@field='{}'",
+            reference.entity_name,
+            reference.entity_id.as_hyphenated(),
+            field_name
+        );
+
+        let _: Value = self.execute_function(&function).await?;
+        Ok(())
+    }
+
+    /**
+    recalculates the rollup column like `calculate_rollup(...)` and retrieves its refreshed value
+
+    The value is `None` if the rollup column is empty, e.g. because there are no related records
+
+    see `calculate_rollup(...)` for the failure reasons
+    */
+    pub async fn calculate_rollup_value<T: DeserializeOwned>(
+        &self,
+        reference: &impl Reference,
+        field_name: &str,
+    ) -> Result<Option<T>> {
+        self.calculate_rollup(reference, field_name).await?;
+
+        let reference = reference.get_reference();
+        let mut record: Map<String, Value> = self
+            .execute_function(&format!(
+                "{}({})?$select={}",
+                reference.entity_name,
+                reference.entity_id.as_hyphenated(),
+                field_name
+            ))
+            .await?;
+
+        let value = record.remove(field_name).unwrap_or(Value::Null);
+        serde_json::from_value(value).into_dataverse_result()
+    }
+}
@@ -0,0 +1,57 @@
+/*!
+Module for the runtime specific parts of the client
+
+The client itself only uses runtime-agnostic primitives for locking and channels.
+Waiting (for throttled batches, system job pollers, subscriptions or injected latency)
+is done by a `Timer`, which uses the timers of tokio by default. Embedders on another
+runtime can provide their own timer with `Client::with_timer(...)`
+
+Please note that the http client `reqwest` still requires a tokio reactor to send requests.
+On other runtimes the futures of the client can be wrapped with a compatibility layer
+like `async-compat`
+
+# Examples
+```rust
+use std::{future::Future, pin::Pin, time::Duration};
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    runtime::Timer
+};
+
+struct ThreadTimer;
+
+impl Timer for ThreadTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let (sender, receiver) = futures_channel::oneshot::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let _ = sender.send(());
+        });
+
+        Box::pin(async move {
+            let _ = receiver.await;
+        })
+    }
+}
+
+let client = Client::new_dummy().with_timer(ThreadTimer);
+```
+*/
+
+use std::{future::Future, pin::Pin, time::Duration};
+
+/// waits for periods of time on the runtime of the embedder
+pub trait Timer: Send + Sync {
+    /// returns a future that completes after the given period
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+/// Implements the `Timer` trait with the timers of tokio, which is the default of the client
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioTimer;
+
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
@@ -0,0 +1,354 @@
+/*!
+Module for scheduling appointments and expanding calendars
+
+`Client::book(...)` and `Client::reschedule(...)` validate the availability of the
+resources of an appointment before it is saved. The returned `ValidationResult`
+lists the scheduling conflicts if the validation failed
+
+`Client::calculate_business_time(...)` expands a calendar (e.g. the business hours
+of a customer service schedule) and sums its working time within a period, which
+is the basis of SLA durations. `Client::calculate_total_time_incident(...)` returns
+the time spent on the activities of a case
+
+# Examples
+```rust
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::Result
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let appointment = Appointment {
+        activityid: Uuid::new_v4(),
+        subject: String::from("Maintenance"),
+        scheduledstart: Utc::now().to_rfc3339(),
+        scheduledend: (Utc::now() + Duration::hours(1)).to_rfc3339(),
+    };
+
+    let result = client.book("appointment", &appointment).await?;
+
+    if !result.validation_success {
+        for error in result.trace_info.error_info_list {
+            println!("conflict: {}", error.error_code);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Appointment {
+    activityid: Uuid,
+    subject: String,
+    scheduledstart: String,
+    scheduledend: String,
+}
+
+impl WriteEntity for Appointment {}
+
+impl Reference for Appointment {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("appointments", self.activityid)
+    }
+}
+```
+*/
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    action::to_typed_entity, auth::Authenticate, client::Client, entity::WriteEntity,
+    result::Result,
+};
+
+/// The result of validating the schedule of an appointment
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ValidationResult {
+    pub validation_success: bool,
+    pub activity_id: Option<Uuid>,
+    #[serde(default)]
+    pub trace_info: TraceInfo,
+}
+
+/// The scheduling errors found during the validation
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TraceInfo {
+    #[serde(default)]
+    pub error_info_list: Vec<ErrorInfo>,
+}
+
+/// A scheduling error and the resources it affects
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ErrorInfo {
+    pub error_code: String,
+    #[serde(default)]
+    pub resource_list: Vec<ResourceInfo>,
+}
+
+/// A resource that is affected by a scheduling error
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResourceInfo {
+    pub id: Uuid,
+    pub entity_name: Option<String>,
+    pub display_name: Option<String>,
+}
+
+/// A block of time of an expanded calendar
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimeInfo {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub time_code: String,
+    pub sub_code: String,
+    pub source_id: Option<Uuid>,
+    pub calendar_id: Option<Uuid>,
+    #[serde(default)]
+    pub is_activity: bool,
+    pub effort: Option<f64>,
+    pub display_text: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ScheduleRequest {
+    #[serde(rename = "Target")]
+    target: Value,
+    #[serde(rename = "ReturnDynamicEntities")]
+    return_dynamic_entities: bool,
+}
+
+#[derive(Deserialize)]
+struct ScheduleResponse {
+    #[serde(rename = "ValidationResult")]
+    validation_result: ValidationResult,
+}
+
+#[derive(Deserialize)]
+struct ExpandCalendarResponse {
+    result: Vec<TimeInfo>,
+}
+
+#[derive(Deserialize)]
+struct CalculateTotalTimeIncidentResponse {
+    #[serde(rename = "TotalTime")]
+    total_time: i64,
+}
+
+/// `TimeCode` of working time in expanded calendars
+static AVAILABLE_TIME_CODE: &str = "Available";
+
+/// `SubCode` of breaks within working time in expanded calendars
+static BREAK_SUB_CODE: &str = "Break";
+
+/**
+sums the working time of the blocks between start and end
+
+Blocks are clipped to the period and overlapping blocks are only counted once
+*/
+fn sum_working_time(blocks: &[TimeInfo], start: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+    let mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> = blocks
+        .iter()
+        .filter(|block| block.time_code == AVAILABLE_TIME_CODE && block.sub_code != BREAK_SUB_CODE)
+        .map(|block| (block.start.max(start), block.end.min(end)))
+        .filter(|(block_start, block_end)| block_start < block_end)
+        .collect();
+    intervals.sort();
+
+    let mut total = Duration::zero();
+    let mut counted_until = start;
+
+    for (block_start, block_end) in intervals {
+        let block_start = block_start.max(counted_until);
+
+        if block_start < block_end {
+            total += block_end - block_start;
+            counted_until = block_end;
+        }
+    }
+
+    total
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    validates the schedule of the appointment and creates it if its resources are available
+
+    The logical name is the name of the appointment entity, e.g. `appointment`
+    or `serviceappointment`. A failed validation is not an error; it is reported
+    in the returned `ValidationResult`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    */
+    pub async fn book(&self, logical_name: &str, appointment: &impl WriteEntity) -> Result<ValidationResult> {
+        self.validate_schedule("Book", logical_name, appointment).await
+    }
+
+    /**
+    validates the new schedule of the existing appointment and updates it if its
+    resources are available
+
+    The appointment has to contain its id. A failed validation is not an error;
+    it is reported in the returned `ValidationResult`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    */
+    pub async fn reschedule(
+        &self,
+        logical_name: &str,
+        appointment: &impl WriteEntity,
+    ) -> Result<ValidationResult> {
+        self.validate_schedule("Reschedule", logical_name, appointment).await
+    }
+
+    /**
+    expands the calendar into the blocks of time between start and end
+
+    This is used to check the availability of a resource, for example the calendar
+    of a user or the occurrences of a recurring appointment
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn expand_calendar(
+        &self,
+        calendar_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<TimeInfo>> {
+        let function = format!(
+            "calendars({})/Microsoft.Dynamics.CRM.ExpandCalendar(Start=@start,End=@end)?@start={}&@end={}",
+            calendar_id.as_hyphenated(),
+            start.to_rfc3339_opts(SecondsFormat::Secs, true),
+            end.to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+
+        let response: ExpandCalendarResponse = self.execute_function(&function).await?;
+        Ok(response.result)
+    }
+
+    /**
+    returns the working time of the calendar between start and end
+
+    The calendar is expanded with `expand_calendar(...)` and its available blocks
+    without breaks are summed. Holidays and other unavailable times of the calendar
+    are therefore not counted. This is the business time an SLA measures for a case
+    with the business hours of the given calendar
+
+    see `expand_calendar(...)` for the failure reasons
+    */
+    pub async fn calculate_business_time(
+        &self,
+        calendar_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Duration> {
+        let blocks = self.expand_calendar(calendar_id, start, end).await?;
+        Ok(sum_working_time(&blocks, start, end))
+    }
+
+    /**
+    returns the time spent on the closed activities of the case with the given id
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn calculate_total_time_incident(&self, incident_id: Uuid) -> Result<Duration> {
+        let response: CalculateTotalTimeIncidentResponse = self
+            .execute_function(&format!(
+                "incidents({})/Microsoft.Dynamics.CRM.CalculateTotalTimeIncident()",
+                incident_id.as_hyphenated()
+            ))
+            .await?;
+
+        Ok(Duration::minutes(response.total_time))
+    }
+
+    async fn validate_schedule(
+        &self,
+        action: &str,
+        logical_name: &str,
+        appointment: &impl WriteEntity,
+    ) -> Result<ValidationResult> {
+        let request = ScheduleRequest {
+            target: to_typed_entity(logical_name, appointment)?,
+            return_dynamic_entities: false,
+        };
+
+        let response: ScheduleResponse = self.execute_action(action, &request).await?;
+        Ok(response.validation_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::{sum_working_time, ScheduleResponse, TimeInfo};
+
+    fn block(start_hour: u32, end_hour: u32, time_code: &str, sub_code: &str) -> TimeInfo {
+        TimeInfo {
+            start: Utc.with_ymd_and_hms(2024, 3, 4, start_hour, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 3, 4, end_hour, 0, 0).unwrap(),
+            time_code: time_code.to_string(),
+            sub_code: sub_code.to_string(),
+            source_id: None,
+            calendar_id: None,
+            is_activity: false,
+            effort: None,
+            display_text: None,
+        }
+    }
+
+    #[test]
+    fn sums_working_time() {
+        let blocks = vec![
+            block(8, 12, "Available", "Schedulable"),
+            block(12, 13, "Available", "Break"),
+            block(11, 17, "Available", "Schedulable"),
+            block(17, 20, "Unavailable", "Holiday"),
+        ];
+
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 3, 4, 18, 0, 0).unwrap();
+        assert_eq!(sum_working_time(&blocks, start, end), Duration::hours(8));
+    }
+
+    #[test]
+    fn deserialize_conflicts() {
+        let response: ScheduleResponse = serde_json::from_str(
+            r#"{"ValidationResult":{"ValidationSuccess":false,"ActivityId":null,"TraceInfo":{"ErrorInfoList":[{"ErrorCode":"ResourceBusy","ResourceList":[{"Id":"00000000-0000-0000-0000-000000000001","EntityName":"systemuser","DisplayName":"Testy"}]}]}},"Notifications":[]}"#,
+        )
+        .unwrap();
+
+        let result = response.validation_result;
+        assert!(!result.validation_success);
+        assert_eq!(result.trace_info.error_info_list.len(), 1);
+        assert_eq!(result.trace_info.error_info_list[0].error_code, "ResourceBusy");
+        assert_eq!(
+            result.trace_info.error_info_list[0].resource_list[0].display_name.as_deref(),
+            Some("Testy")
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::query::filter::Filter;
+
 /**
 trait for acquiring the relevant attribute names for queries
 */
@@ -5,4 +7,15 @@ pub trait Select {
     /// gets a vector of attribute names that shall be included in
     /// the query select statement
     fn get_columns() -> &'static [&'static str];
+
+    /**
+    gets a predicate that is added to every query of this entity, e.g. to only
+    retrieve records of a certain type
+
+    The predicate is combined with the filter of the query unless the query
+    opts out with `Query::without_default_filter()`
+    */
+    fn get_default_filter() -> Option<Filter> {
+        None
+    }
 }
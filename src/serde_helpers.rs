@@ -0,0 +1,273 @@
+/*!
+Module with serde adapters for Dataverse specific formats
+
+These adapters are used with `#[serde(with = "...")]` on the fields of entity structs
+
+- `date_only` reads and writes `Date Only` columns (`2024-01-31`) as `NaiveDate`
+- `option_set` reads and writes choice columns as enums that convert from and into `i32`
+- `int64_string` reads `Edm.Int64` values that are given as numbers or as strings
+- `empty_string_as_none` treats empty strings like `null`
+
+The `option` submodules handle the same formats for optional fields
+
+# Examples
+```rust
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use powerplatform_dataverse_service_client::serde_helpers;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+enum Priority {
+    Low = 1,
+    High = 2,
+}
+
+impl From<Priority> for i32 {
+    fn from(priority: Priority) -> Self {
+        priority as i32
+    }
+}
+
+impl TryFrom<i32> for Priority {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Priority::Low),
+            2 => Ok(Priority::High),
+            other => Err(format!("invalid priority {}", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Task {
+    #[serde(with = "serde_helpers::date_only")]
+    new_duedate: NaiveDate,
+    #[serde(with = "serde_helpers::option_set")]
+    new_priority: Priority,
+    #[serde(with = "serde_helpers::int64_string")]
+    versionnumber: i64,
+    #[serde(with = "serde_helpers::empty_string_as_none")]
+    description: Option<String>,
+}
+
+let task: Task = serde_json::from_str(
+    r#"{"new_duedate":"2024-01-31","new_priority":2,"versionnumber":"123456789","description":""}"#
+).unwrap();
+
+assert_eq!(task.new_duedate, NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+assert_eq!(task.new_priority, Priority::High);
+assert_eq!(task.versionnumber, 123456789);
+assert_eq!(task.description, None);
+```
+*/
+
+/// adapter for `Date Only` columns in the format `YYYY-MM-DD`
+pub mod date_only {
+    use chrono::NaiveDate;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    static FORMAT: &str = "%Y-%m-%d";
+
+    pub fn serialize<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format(FORMAT))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse(&value).map_err(D::Error::custom)
+    }
+
+    /// parses the date and ignores a time part that may be appended to it
+    pub(crate) fn parse(value: &str) -> Result<NaiveDate, chrono::ParseError> {
+        NaiveDate::parse_from_str(value.get(..10).unwrap_or(value), FORMAT)
+    }
+
+    /// adapter for optional `Date Only` columns
+    pub mod option {
+        use chrono::NaiveDate;
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(date: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error> {
+            match date {
+                Some(date) => super::serialize(date, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|value| super::parse(&value).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+/**
+adapter for choice columns as enums
+
+The enum has to implement `From<E> for i32` and `TryFrom<i32>`, which is
+straightforward for enums with `#[repr(i32)]`
+*/
+pub mod option_set {
+    use std::fmt::Display;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<E, S>(value: &E, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        E: Copy + Into<i32>,
+        S: Serializer,
+    {
+        serializer.serialize_i32((*value).into())
+    }
+
+    pub fn deserialize<'de, E, D>(deserializer: D) -> Result<E, D::Error>
+    where
+        E: TryFrom<i32>,
+        E::Error: Display,
+        D: Deserializer<'de>,
+    {
+        E::try_from(i32::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    /// adapter for optional choice columns
+    pub mod option {
+        use std::fmt::Display;
+
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<E, S>(value: &Option<E>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            E: Copy + Into<i32>,
+            S: Serializer,
+        {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, E, D>(deserializer: D) -> Result<Option<E>, D::Error>
+        where
+            E: TryFrom<i32>,
+            E::Error: Display,
+            D: Deserializer<'de>,
+        {
+            Option::<i32>::deserialize(deserializer)?
+                .map(|value| E::try_from(value).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+/**
+adapter for `Edm.Int64` values
+
+Values are accepted as JSON numbers or strings and written as strings, so no
+precision is lost in clients that parse numbers as floating point values
+*/
+pub mod int64_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(crate) enum Int64 {
+        Number(i64),
+        String(String),
+    }
+
+    impl Int64 {
+        pub(crate) fn into_i64<E: Error>(self) -> Result<i64, E> {
+            match self {
+                Int64::Number(value) => Ok(value),
+                Int64::String(value) => value.parse().map_err(E::custom),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        Int64::deserialize(deserializer)?.into_i64()
+    }
+
+    /// adapter for optional `Edm.Int64` values
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use super::Int64;
+
+        pub fn serialize<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+            Option::<Int64>::deserialize(deserializer)?
+                .map(Int64::into_i64)
+                .transpose()
+        }
+    }
+}
+
+/// adapter that reads empty strings as `None` and writes `None` as `null`
+pub mod empty_string_as_none {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+        let value = Option::<String>::deserialize(deserializer)?;
+        Ok(value.filter(|value| !value.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "super::date_only::option")]
+        birthdate: Option<NaiveDate>,
+        #[serde(with = "super::int64_string::option")]
+        versionnumber: Option<i64>,
+    }
+
+    #[test]
+    fn optional_round_trip() {
+        let record = Record {
+            birthdate: NaiveDate::from_ymd_opt(1990, 5, 17),
+            versionnumber: Some(42),
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"birthdate":"1990-05-17","versionnumber":"42"}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+
+        let empty: Record = serde_json::from_str(r#"{"birthdate":null,"versionnumber":null}"#).unwrap();
+        assert_eq!(empty.birthdate, None);
+        assert_eq!(empty.versionnumber, None);
+    }
+
+    #[test]
+    fn date_only_ignores_time() {
+        let record: Record =
+            serde_json::from_str(r#"{"birthdate":"1990-05-17T00:00:00Z","versionnumber":7}"#).unwrap();
+        assert_eq!(record.birthdate, NaiveDate::from_ymd_opt(1990, 5, 17));
+        assert_eq!(record.versionnumber, Some(7));
+    }
+}
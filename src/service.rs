@@ -0,0 +1,219 @@
+/*!
+Module for the `DataverseService` trait
+
+The trait covers the core operations of the client, so application code can depend
+on the trait instead of the concrete generic `Client`. This allows injecting mocks
+(e.g. generated by `mockall`) in tests or decorating the client with caching, metrics
+or other cross-cutting concerns
+
+# Examples
+```rust
+use serde::Serialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::Result,
+    service::DataverseService
+};
+
+async fn register(service: &impl DataverseService, firstname: &str) -> Result<Uuid> {
+    let contact = Contact {
+        contactid: Uuid::new_v4(),
+        firstname: firstname.to_string(),
+    };
+
+    service.create(&contact).await
+}
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    register(&client, "Testy").await?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[cfg(feature = "batch")]
+use crate::batch::{response::BatchResponse, Batch};
+use crate::{
+    auth::Authenticate,
+    client::{Client, Page},
+    entity::{ReadEntity, WriteEntity},
+    query::Query,
+    reference::Reference,
+    result::Result,
+};
+
+/**
+trait for the core operations on a Dataverse environment
+
+`Client` implements this trait by delegating to its methods of the same name.
+Please see their documentation for details on the individual operations
+*/
+#[async_trait]
+pub trait DataverseService: Send + Sync {
+    /// writes the given entity into the current dataverse instance and returns its generated Uuid
+    async fn create<E: WriteEntity + Sync>(&self, entity: &E) -> Result<Uuid>;
+
+    /// retrieves the entity record that is referenced by the given reference
+    async fn retrieve<E: ReadEntity + Send, R: Reference + Sync>(&self, reference: &R) -> Result<E>;
+
+    /// updates the attributes of the record that is referenced by the given entity
+    async fn update<E: WriteEntity + Sync>(&self, entity: &E) -> Result<()>;
+
+    /// updates or creates the record that is referenced by the given entity
+    async fn upsert<E: WriteEntity + Sync>(&self, entity: &E) -> Result<()>;
+
+    /// deletes the record that is referenced by the given reference
+    async fn delete<R: Reference + Sync>(&self, reference: &R) -> Result<()>;
+
+    /// retrieves a single page of the records that match the given query
+    async fn retrieve_multiple<E: ReadEntity + Send>(&self, query: &Query) -> Result<Page<E>>;
+}
+
+/**
+trait for services that execute batches in addition to the core operations
+
+It is a separate trait, so enabling the `batch` feature doesn't add a required
+method to existing implementations of `DataverseService`
+*/
+#[cfg(feature = "batch")]
+#[async_trait]
+pub trait DataverseBatchService: DataverseService {
+    /// executes the given batch and returns the response for each of its requests
+    async fn execute(&self, batch: &Batch) -> Result<BatchResponse>;
+}
+
+#[async_trait]
+impl<'url, A: Authenticate + Send + Sync> DataverseService for Client<'url, A> {
+    async fn create<E: WriteEntity + Sync>(&self, entity: &E) -> Result<Uuid> {
+        Client::create(self, entity).await
+    }
+
+    async fn retrieve<E: ReadEntity + Send, R: Reference + Sync>(&self, reference: &R) -> Result<E> {
+        Client::retrieve(self, reference).await
+    }
+
+    async fn update<E: WriteEntity + Sync>(&self, entity: &E) -> Result<()> {
+        Client::update(self, entity).await
+    }
+
+    async fn upsert<E: WriteEntity + Sync>(&self, entity: &E) -> Result<()> {
+        Client::upsert(self, entity).await
+    }
+
+    async fn delete<R: Reference + Sync>(&self, reference: &R) -> Result<()> {
+        Client::delete(self, reference).await
+    }
+
+    async fn retrieve_multiple<E: ReadEntity + Send>(&self, query: &Query) -> Result<Page<E>> {
+        Client::retrieve_multiple(self, query).await
+    }
+}
+
+#[cfg(feature = "batch")]
+#[async_trait]
+impl<'url, A: Authenticate + Send + Sync> DataverseBatchService for Client<'url, A> {
+    async fn execute(&self, batch: &Batch) -> Result<BatchResponse> {
+        Client::execute(self, batch).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use uuid::Uuid;
+
+    #[cfg(feature = "batch")]
+    use crate::batch::{response::BatchResponse, Batch};
+    use crate::{
+        auth::no_auth::NoAuth,
+        client::{Client, Page},
+        entity::{ReadEntity, WriteEntity},
+        query::Query,
+        reference::{Reference, ReferenceStruct},
+        result::Result,
+    };
+
+    #[cfg(feature = "batch")]
+    use super::DataverseBatchService;
+    use super::DataverseService;
+
+    /// decorates a service by counting the deletions
+    struct CountingService<S> {
+        inner: S,
+        deletions: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl<S: DataverseService> DataverseService for CountingService<S> {
+        async fn create<E: WriteEntity + Sync>(&self, entity: &E) -> Result<Uuid> {
+            self.inner.create(entity).await
+        }
+
+        async fn retrieve<E: ReadEntity + Send, R: Reference + Sync>(&self, reference: &R) -> Result<E> {
+            self.inner.retrieve(reference).await
+        }
+
+        async fn update<E: WriteEntity + Sync>(&self, entity: &E) -> Result<()> {
+            self.inner.update(entity).await
+        }
+
+        async fn upsert<E: WriteEntity + Sync>(&self, entity: &E) -> Result<()> {
+            self.inner.upsert(entity).await
+        }
+
+        async fn delete<R: Reference + Sync>(&self, reference: &R) -> Result<()> {
+            self.deletions.fetch_add(1, Ordering::SeqCst);
+            self.inner.delete(reference).await
+        }
+
+        async fn retrieve_multiple<E: ReadEntity + Send>(&self, query: &Query) -> Result<Page<E>> {
+            self.inner.retrieve_multiple(query).await
+        }
+    }
+
+    #[cfg(feature = "batch")]
+    #[async_trait]
+    impl<S: DataverseBatchService> DataverseBatchService for CountingService<S> {
+        async fn execute(&self, batch: &Batch) -> Result<BatchResponse> {
+            self.inner.execute(batch).await
+        }
+    }
+
+    #[tokio::test]
+    async fn decorates_client() {
+        let service = CountingService {
+            inner: Client::<NoAuth>::new_dummy(),
+            deletions: AtomicUsize::new(0),
+        };
+
+        let result = service
+            .delete(&ReferenceStruct::new("contacts", Uuid::new_v4()))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(service.deletions.load(Ordering::SeqCst), 1);
+    }
+}
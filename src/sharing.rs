@@ -0,0 +1,238 @@
+/*!
+Module for sharing records with users and teams
+
+Records are shared with the `GrantAccess` action, which grants a principal
+(`systemuser` or `team`) the given access rights on a single record. Sharing a
+large number of records one by one is very slow, so `Client::bulk_grant_access(...)`
+executes the actions in parallel batches like the other bulk functions
+
+The records and the principal are referenced with the logical name of their
+entity (e.g. `account`)
+
+# Examples
+```rust
+use uuid::Uuid;
+# #[cfg(feature = "batch")]
+use powerplatform_dataverse_service_client::bulk::BulkOptions;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    progress::ProgressUpdate,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result},
+    sharing::AccessRights
+};
+
+# #[cfg(feature = "batch")]
+async fn test() -> Result<()> {
+    let client = Client::new_dummy() // Please replace this with your preferred authentication method
+        .with_progress(|update: &ProgressUpdate| println!("shared {} accounts", update.completed));
+    let team = EntityReference::new(
+        "team",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+    let accounts: Vec<EntityReference> = (0..5_000)
+        .map(|_| EntityReference::new("account", Uuid::new_v4()))
+        .collect();
+
+    let result = client
+        .bulk_grant_access(
+            &accounts,
+            &team,
+            AccessRights::READ | AccessRights::WRITE,
+            &BulkOptions::new().batch_size(100),
+        )
+        .await?;
+
+    for failure in result.failures.iter() {
+        println!("account {} could not be shared: {}", accounts[failure.index], failure.message);
+    }
+    Ok(())
+}
+```
+*/
+
+use std::ops::BitOr;
+
+use serde::{Serialize, Serializer};
+
+#[cfg(feature = "batch")]
+use crate::bulk::{BulkOptions, BulkResult};
+use crate::{
+    action::serialize_action_reference,
+    auth::Authenticate,
+    client::Client,
+    reference::EntityReference,
+    result::Result,
+};
+
+/// names of the single access rights in the order of their bits
+static ACCESS_RIGHT_NAMES: &[(u32, &str)] = &[
+    (0x1, "ReadAccess"),
+    (0x2, "WriteAccess"),
+    (0x4, "AppendAccess"),
+    (0x10, "AppendToAccess"),
+    (0x20, "CreateAccess"),
+    (0x10000, "DeleteAccess"),
+    (0x40000, "ShareAccess"),
+    (0x80000, "AssignAccess"),
+];
+
+/**
+The access rights granted on a shared record
+
+Rights are combined with `|`, e.g. `AccessRights::READ | AccessRights::WRITE`
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AccessRights(u32);
+
+impl AccessRights {
+    pub const NONE: AccessRights = AccessRights(0);
+    pub const READ: AccessRights = AccessRights(0x1);
+    pub const WRITE: AccessRights = AccessRights(0x2);
+    pub const APPEND: AccessRights = AccessRights(0x4);
+    pub const APPEND_TO: AccessRights = AccessRights(0x10);
+    pub const CREATE: AccessRights = AccessRights(0x20);
+    pub const DELETE: AccessRights = AccessRights(0x10000);
+    pub const SHARE: AccessRights = AccessRights(0x40000);
+    pub const ASSIGN: AccessRights = AccessRights(0x80000);
+
+    /// returns the bit mask of these access rights
+    pub fn get_mask(&self) -> u32 {
+        self.0
+    }
+
+    /// Indicates if all of the given access rights are contained in these
+    pub fn contains(&self, other: AccessRights) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// returns the value of the `AccessMask` parameter, e.g. `ReadAccess,WriteAccess`
+    pub fn get_access_mask(&self) -> String {
+        let names: Vec<&str> = ACCESS_RIGHT_NAMES
+            .iter()
+            .filter(|(bit, _)| self.0 & bit != 0)
+            .map(|(_, name)| *name)
+            .collect();
+
+        match names.is_empty() {
+            true => String::from("None"),
+            false => names.join(","),
+        }
+    }
+}
+
+impl BitOr for AccessRights {
+    type Output = AccessRights;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        AccessRights(self.0 | rhs.0)
+    }
+}
+
+impl Serialize for AccessRights {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.get_access_mask())
+    }
+}
+
+#[derive(Serialize)]
+struct PrincipalAccess<'a> {
+    #[serde(rename = "Principal", serialize_with = "serialize_action_reference")]
+    principal: &'a EntityReference,
+    #[serde(rename = "AccessMask")]
+    access_mask: AccessRights,
+}
+
+#[derive(Serialize)]
+struct GrantAccessRequest<'a> {
+    #[serde(rename = "Target", serialize_with = "serialize_action_reference")]
+    target: &'a EntityReference,
+    #[serde(rename = "PrincipalAccess")]
+    principal_access: PrincipalAccess<'a>,
+}
+
+impl<'a> GrantAccessRequest<'a> {
+    fn new(target: &'a EntityReference, principal: &'a EntityReference, rights: AccessRights) -> Self {
+        Self {
+            target,
+            principal_access: PrincipalAccess {
+                principal,
+                access_mask: rights,
+            },
+        }
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    shares the record with the principal (`systemuser` or `team`) by granting the given access rights
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The record or the principal doesn't exist
+    */
+    pub async fn grant_access(
+        &self,
+        record: &EntityReference,
+        principal: &EntityReference,
+        rights: AccessRights,
+    ) -> Result<()> {
+        self.execute_action("GrantAccess", &GrantAccessRequest::new(record, principal, rights))
+            .await
+    }
+
+    /**
+    shares all given records with the principal (`systemuser` or `team`) in parallel batches
+
+    Failed records are reported in the returned `BulkResult` instead of failing the whole
+    operation. The progress is reported to the receiver configured with `Client::with_progress(...)`
+
+    This may fail for any of these reasons
+    - An authentication failure
+    */
+    #[cfg(feature = "batch")]
+    pub async fn bulk_grant_access(
+        &self,
+        records: &[EntityReference],
+        principal: &EntityReference,
+        rights: AccessRights,
+        options: &BulkOptions,
+    ) -> Result<BulkResult> {
+        self.execute_bulk(records.len(), options, |_| String::from("GrantAccess"), |batch, index| {
+            batch.action("GrantAccess", &GrantAccessRequest::new(&records[index], principal, rights))
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::reference::EntityReference;
+
+    use super::{AccessRights, GrantAccessRequest};
+
+    #[test]
+    fn access_mask() {
+        assert_eq!(AccessRights::NONE.get_access_mask(), "None");
+        assert_eq!(
+            (AccessRights::READ | AccessRights::APPEND_TO | AccessRights::SHARE).get_access_mask(),
+            "ReadAccess,AppendToAccess,ShareAccess"
+        );
+        assert!((AccessRights::READ | AccessRights::WRITE).contains(AccessRights::WRITE));
+    }
+
+    #[test]
+    fn grant_access_request() {
+        let target = EntityReference::new("account", Uuid::nil());
+        let principal = EntityReference::new("team", Uuid::nil());
+        let request = GrantAccessRequest::new(&target, &principal, AccessRights::READ | AccessRights::WRITE);
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"Target":{"@odata.type":"Microsoft.Dynamics.CRM.account","accountid":"00000000-0000-0000-0000-000000000000"},"PrincipalAccess":{"Principal":{"@odata.type":"Microsoft.Dynamics.CRM.team","teamid":"00000000-0000-0000-0000-000000000000"},"AccessMask":"ReadAccess,WriteAccess"}}"#
+        );
+    }
+}
@@ -0,0 +1,249 @@
+/*!
+Module for managing the components of solutions
+
+These functions wrap the `AddSolutionComponent` action and the
+`RetrieveDependenciesForDelete` function and list the components of a solution,
+which are the building blocks of ALM pipelines
+
+Components are identified by their object id and their component type
+(e.g. `1` for entities or `61` for web resources)
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::{IntoDataverseResult, Result},
+    solution::COMPONENT_TYPE_WEB_RESOURCE
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let web_resource_id = Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?;
+
+    client
+        .add_solution_component("contoso_core", web_resource_id, COMPONENT_TYPE_WEB_RESOURCE, false)
+        .await?;
+
+    let dependencies = client
+        .retrieve_dependencies_for_delete(web_resource_id, COMPONENT_TYPE_WEB_RESOURCE)
+        .await?;
+
+    if dependencies.is_empty() {
+        println!("the web resource can be deleted safely");
+    }
+    Ok(())
+}
+```
+*/
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::ReadEntity,
+    query::{attribute::Attribute, filter::Filter, Query},
+    result::Result,
+    select::Select,
+};
+
+/// component type of entities
+pub static COMPONENT_TYPE_ENTITY: i32 = 1;
+
+/// component type of attributes
+pub static COMPONENT_TYPE_ATTRIBUTE: i32 = 2;
+
+/// component type of global option sets
+pub static COMPONENT_TYPE_OPTION_SET: i32 = 9;
+
+/// component type of security roles
+pub static COMPONENT_TYPE_ROLE: i32 = 20;
+
+/// component type of forms
+pub static COMPONENT_TYPE_FORM: i32 = 24;
+
+/// component type of processes (workflows and cloud flows)
+pub static COMPONENT_TYPE_WORKFLOW: i32 = 29;
+
+/// component type of web resources
+pub static COMPONENT_TYPE_WEB_RESOURCE: i32 = 61;
+
+/// component type of plugin assemblies
+pub static COMPONENT_TYPE_PLUGIN_ASSEMBLY: i32 = 91;
+
+/// component type of environment variable definitions
+pub static COMPONENT_TYPE_ENVIRONMENT_VARIABLE: i32 = 380;
+
+/// A component of a solution
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct SolutionComponent {
+    pub solutioncomponentid: Uuid,
+    pub objectid: Uuid,
+    pub componenttype: i32,
+    pub rootcomponentbehavior: Option<i32>,
+    #[serde(default)]
+    pub ismetadata: bool,
+}
+
+impl ReadEntity for SolutionComponent {}
+
+impl Select for SolutionComponent {
+    fn get_columns() -> &'static [&'static str] {
+        &[
+            "solutioncomponentid",
+            "objectid",
+            "componenttype",
+            "rootcomponentbehavior",
+            "ismetadata",
+        ]
+    }
+}
+
+/// A dependency of a component that requires another component
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Dependency {
+    pub dependencyid: Uuid,
+    pub dependencytype: i32,
+    pub dependentcomponentobjectid: Uuid,
+    pub dependentcomponenttype: i32,
+    pub requiredcomponentobjectid: Uuid,
+    pub requiredcomponenttype: i32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct AddSolutionComponentRequest<'a> {
+    component_id: Uuid,
+    component_type: i32,
+    solution_unique_name: &'a str,
+    add_required_components: bool,
+    do_not_include_subcomponents: bool,
+}
+
+#[derive(Deserialize)]
+struct AddSolutionComponentResponse {
+    id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct DependencyCollection {
+    #[serde(rename = "EntityCollection")]
+    entity_collection: Vec<Dependency>,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    adds the component to the unmanaged solution with the given unique name and
+    returns the id of the created solution component
+
+    If `add_required_components` is set, all components the component depends on
+    are added to the solution as well
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The solution or the component doesn't exist
+    */
+    pub async fn add_solution_component(
+        &self,
+        solution_unique_name: &str,
+        component_id: Uuid,
+        component_type: i32,
+        add_required_components: bool,
+    ) -> Result<Uuid> {
+        let request = AddSolutionComponentRequest {
+            component_id,
+            component_type,
+            solution_unique_name,
+            add_required_components,
+            do_not_include_subcomponents: false,
+        };
+
+        let response: AddSolutionComponentResponse =
+            self.execute_action("AddSolutionComponent", &request).await?;
+        Ok(response.id)
+    }
+
+    /**
+    retrieves all components of the solution with the given unique name
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_solution_components(
+        &self,
+        solution_unique_name: &str,
+    ) -> Result<Vec<SolutionComponent>> {
+        let query = Query::new("solutioncomponents").filter(Filter::Equal(
+            "solutionid/uniquename",
+            Attribute::String(solution_unique_name.to_string()),
+        ));
+
+        self.retrieve_all(&query).await
+    }
+
+    /**
+    retrieves the dependencies that prevent the component from being deleted
+
+    An empty result means that the component can be deleted
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_dependencies_for_delete(
+        &self,
+        object_id: Uuid,
+        component_type: i32,
+    ) -> Result<Vec<Dependency>> {
+        let function = format!(
+            "RetrieveDependenciesForDelete(ObjectId=@id,ComponentType=@type)?@id={}&@type={}",
+            object_id.as_hyphenated(),
+            component_type
+        );
+
+        let response: DependencyCollection = self.execute_function(&function).await?;
+        Ok(response.entity_collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{AddSolutionComponentRequest, DependencyCollection};
+
+    #[test]
+    fn add_solution_component_request() {
+        let request = AddSolutionComponentRequest {
+            component_id: Uuid::nil(),
+            component_type: 61,
+            solution_unique_name: "contoso_core",
+            add_required_components: true,
+            do_not_include_subcomponents: false,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"ComponentId":"00000000-0000-0000-0000-000000000000","ComponentType":61,"SolutionUniqueName":"contoso_core","AddRequiredComponents":true,"DoNotIncludeSubcomponents":false}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_dependencies() {
+        let response: DependencyCollection = serde_json::from_str(
+            r#"{"EntityCollection":[{"dependencyid":"00000000-0000-0000-0000-000000000001","dependencytype":2,"dependentcomponentobjectid":"00000000-0000-0000-0000-000000000002","dependentcomponenttype":60,"requiredcomponentobjectid":"00000000-0000-0000-0000-000000000003","requiredcomponenttype":61}]}"#,
+        )
+        .unwrap();
+
+        let dependencies = response.entity_collection;
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].dependentcomponenttype, 60);
+    }
+}
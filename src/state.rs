@@ -0,0 +1,242 @@
+/*!
+Module for writing records regardless of their state
+
+Some tables reject updates of inactive records, e.g. when a user deactivated a row
+that a synchronization still writes into. With `WriteOptions` the client detects such
+failures by their error code (`ErrorCode::RecordInactive`), looks up the state of the
+record and repeats the write in a transaction that reactivates the record first and
+optionally restores its previous state afterwards. Because all steps are executed in
+one changeset, the record never stays reactivated when the write fails
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::{IntoDataverseResult, Result},
+    state::{InactiveRecordPolicy, WriteOptions}
+};
+
+async fn test() -> Result<()> {
+    let contact = Contact {
+        contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+        firstname: String::from("Testy"),
+    };
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let options = WriteOptions::new().inactive_records(InactiveRecordPolicy::RestoreState);
+    client.upsert_with_options(&contact, &options).await
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::WriteEntity,
+    error::{DataverseError, ErrorCode},
+    reference::ReferenceStruct,
+    result::Result,
+};
+
+/// How writes into inactive records are handled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InactiveRecordPolicy {
+    /// returns the error of the failed write
+    #[default]
+    Fail,
+
+    /// reactivates the record and repeats the write, the record stays active
+    Reactivate,
+
+    /// reactivates the record, repeats the write and restores the previous state and status
+    RestoreState,
+}
+
+/// Options for the writes of `Client::update_with_options(...)` and `Client::upsert_with_options(...)`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    inactive_records: InactiveRecordPolicy,
+}
+
+impl WriteOptions {
+    /// creates options that fail on inactive records like `update(...)` and `upsert(...)`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets how writes into inactive records are handled
+    pub fn inactive_records(mut self, policy: InactiveRecordPolicy) -> Self {
+        self.inactive_records = policy;
+        self
+    }
+
+    /// returns how writes into inactive records are handled
+    pub fn get_inactive_records(&self) -> InactiveRecordPolicy {
+        self.inactive_records
+    }
+}
+
+/// The state and status reason of a record
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+struct RecordState {
+    statecode: Option<i32>,
+    statuscode: Option<i32>,
+}
+
+impl RecordState {
+    /// Indicates if the record has a state other than active
+    fn is_inactive(&self) -> bool {
+        self.statecode.is_some_and(|statecode| statecode != 0)
+    }
+
+    /// returns the payload that restores this state and status reason
+    fn to_restore_payload(self) -> String {
+        json!({ "statecode": self.statecode, "statuscode": self.statuscode }).to_string()
+    }
+}
+
+/// payload that reactivates a record with the default status reason of the active state
+static ACTIVATE_PAYLOAD: &str = r#"{"statecode":0}"#;
+
+/// Indicates if the error was caused by the state of the record
+fn may_be_inactive(error: &DataverseError) -> bool {
+    error.get_error_code() == Some(ErrorCode::RecordInactive)
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    updates the given entity like `update(...)` and handles inactive records as configured
+    in the options
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization error
+    - Any http client or server error
+    - The payload exceeds the maximum payload size of this client
+    - The reactivation, the update or the restoration of the state failed
+    */
+    pub async fn update_with_options(&self, entity: &impl WriteEntity, options: &WriteOptions) -> Result<()> {
+        let error = match self.update(entity).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        self.write_reactivated(entity, options, error, false).await
+    }
+
+    /**
+    updates or creates the given entity like `upsert(...)` and handles inactive records
+    as configured in the options
+
+    see `update_with_options(...)` for the failure reasons
+    */
+    pub async fn upsert_with_options(&self, entity: &impl WriteEntity, options: &WriteOptions) -> Result<()> {
+        let error = match self.upsert(entity).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        self.write_reactivated(entity, options, error, true).await
+    }
+
+    /// repeats a failed write in a transaction that reactivates the record if it is inactive
+    async fn write_reactivated(
+        &self,
+        entity: &impl WriteEntity,
+        options: &WriteOptions,
+        error: DataverseError,
+        upsert: bool,
+    ) -> Result<()> {
+        if options.inactive_records == InactiveRecordPolicy::Fail || !may_be_inactive(&error) {
+            return Err(error);
+        }
+
+        let reference = entity.get_reference();
+        let state = match self.get_record_state(&reference).await {
+            Ok(state) if state.is_inactive() => state,
+            _ => return Err(error),
+        };
+
+        let mut transaction = self.new_transaction();
+        transaction.update_raw(&reference, ACTIVATE_PAYLOAD)?;
+
+        if upsert {
+            transaction.upsert(entity)?;
+        } else {
+            transaction.update(entity)?;
+        }
+
+        if options.inactive_records == InactiveRecordPolicy::RestoreState {
+            transaction.update_raw(&reference, &state.to_restore_payload())?;
+        }
+
+        transaction.commit(self).await?;
+        Ok(())
+    }
+
+    /// retrieves the state and status reason of the referenced record
+    async fn get_record_state(&self, reference: &ReferenceStruct) -> Result<RecordState> {
+        self.execute_function(&format!(
+            "{}({})?$select=statecode,statuscode",
+            reference.entity_name,
+            reference.entity_id.as_hyphenated()
+        ))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DataverseError;
+
+    use super::{may_be_inactive, RecordState};
+
+    #[test]
+    fn detects_possibly_inactive_records() {
+        let error = |code: &str| {
+            DataverseError::new(format!(
+                r#"{{"error":{{"code":"{}","message":"The record is inactive"}}}}"#,
+                code
+            ))
+        };
+
+        assert!(may_be_inactive(&error("0x80043b09")));
+        assert!(!may_be_inactive(&error("0x80040203")));
+        assert!(!may_be_inactive(&error("0x80040217")));
+        assert!(!may_be_inactive(&error("0x80072322")));
+        assert!(!may_be_inactive(&DataverseError::new(String::from("timeout"))));
+    }
+
+    #[test]
+    fn restores_state() {
+        let state = RecordState {
+            statecode: Some(1),
+            statuscode: Some(2),
+        };
+
+        assert!(state.is_inactive());
+        assert_eq!(state.to_restore_payload(), r#"{"statecode":1,"statuscode":2}"#);
+        assert!(!RecordState { statecode: None, statuscode: None }.is_inactive());
+    }
+}
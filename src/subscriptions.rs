@@ -0,0 +1,314 @@
+/*!
+Module for subscribing to record changes by polling their `versionnumber`
+
+A `Subscription` repeatedly retrieves the changes of a query with a `VersionSync`
+and emits every changed record once. Failed polls are reported and retried with an
+exponential backoff, so a consumer only needs to handle the emitted records
+
+Transactions that commit after a record with a higher `versionnumber` was already
+retrieved would be missed by a plain high water mark. A lookback re-queries the given
+number of versions below the high water mark; records that were already emitted
+are skipped by their `versionnumber`
+
+# Examples
+```rust
+use std::time::Duration;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    query::Query,
+    result::Result,
+    select::Select,
+    sync::Versioned
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let changes = client
+        .subscribe::<Contact>(Query::new("contacts"))
+        .with_interval(Duration::from_secs(10))
+        .with_lookback(1000)
+        .into_stream();
+
+    futures_util::pin_mut!(changes);
+
+    while let Some(change) = changes.next().await {
+        match change {
+            Ok(contact) => println!("{} changed", contact.contactid),
+            Err(error) => eprintln!("polling failed: {}", error),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    versionnumber: i64,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "versionnumber"]
+    }
+}
+
+impl Versioned for Contact {
+    fn get_version_number(&self) -> i64 {
+        self.versionnumber
+    }
+}
+```
+*/
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    time::Duration,
+};
+
+use futures_util::{stream, Stream};
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::ReadEntity,
+    query::Query,
+    result::Result,
+    sync::{VersionSync, Versioned},
+};
+
+/// default period between two polls
+pub static DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// default upper bound of the backoff after failed polls
+pub static DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/**
+Polls the changes of a query and emits each changed record once
+
+A subscription is created with `Client::subscribe`. The first poll happens immediately,
+every following poll waits for the interval or, after a failed poll, for the backoff
+*/
+pub struct Subscription<'client, 'url, A: Authenticate, E> {
+    client: &'client Client<'url, A>,
+    query: Query,
+    sync: VersionSync,
+    interval: Duration,
+    max_backoff: Duration,
+    lookback: i64,
+    emitted: BTreeSet<i64>,
+    failures: u32,
+    polled: bool,
+    _entity: std::marker::PhantomData<E>,
+}
+
+impl<'client, 'url, A: Authenticate, E: ReadEntity + Versioned> Subscription<'client, 'url, A, E> {
+    /// sets the period between two successful polls
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// sets the upper bound of the exponential backoff after failed polls
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// re-queries the given number of versions below the high water mark to catch late commits
+    pub fn with_lookback(mut self, versions: i64) -> Self {
+        self.lookback = versions.max(0);
+        self
+    }
+
+    /// continues a synchronization that was persisted earlier instead of emitting all records
+    pub fn starting_from(mut self, sync: VersionSync) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    /// returns the state of the synchronization, which can be persisted to resume later on
+    pub fn get_sync(&self) -> VersionSync {
+        self.sync
+    }
+
+    /// returns the delay before the next poll
+    pub fn get_delay(&self) -> Duration {
+        if !self.polled {
+            return Duration::ZERO;
+        }
+
+        if self.failures == 0 {
+            return self.interval;
+        }
+
+        self.interval
+            .saturating_mul(2u32.saturating_pow(self.failures.min(16)))
+            .min(self.max_backoff)
+    }
+
+    /**
+    waits for the next poll and returns the records that changed since the last one
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn next_changes(&mut self) -> Result<Vec<E>> {
+        self.client.sleep(self.get_delay()).await;
+        self.polled = true;
+
+        let mut sync = match self.sync.get_high_water_mark() {
+            Some(high_water_mark) if self.lookback > 0 => {
+                VersionSync::from_high_water_mark(high_water_mark.saturating_sub(self.lookback))
+            }
+            _ => self.sync,
+        };
+
+        match self.client.retrieve_changes::<E>(&self.query, &mut sync).await {
+            Ok(changes) => {
+                self.failures = 0;
+                Ok(self.accept(changes))
+            }
+            Err(error) => {
+                self.failures = self.failures.saturating_add(1);
+                Err(error)
+            }
+        }
+    }
+
+    /// converts the subscription into an endless stream of changed records and poll errors
+    pub fn into_stream(self) -> impl Stream<Item = Result<E>> + 'client
+    where
+        'url: 'client,
+        E: 'client,
+    {
+        stream::unfold((self, VecDeque::new()), |(mut subscription, mut pending)| async move {
+            loop {
+                if let Some(change) = pending.pop_front() {
+                    return Some((Ok(change), (subscription, pending)));
+                }
+
+                match subscription.next_changes().await {
+                    Ok(changes) => pending.extend(changes),
+                    Err(error) => return Some((Err(error), (subscription, pending))),
+                }
+            }
+        })
+    }
+
+    /// drops the records that were already emitted and advances the high water mark
+    fn accept(&mut self, changes: Vec<E>) -> Vec<E> {
+        let changes: Vec<E> = changes
+            .into_iter()
+            .filter(|change| self.emitted.insert(change.get_version_number()))
+            .collect();
+
+        for change in changes.iter() {
+            self.sync.observe(change.get_version_number());
+        }
+
+        match self.sync.get_high_water_mark() {
+            Some(high_water_mark) if self.lookback > 0 => {
+                self.emitted = self
+                    .emitted
+                    .split_off(&high_water_mark.saturating_sub(self.lookback));
+            }
+            _ => self.emitted.clear(),
+        }
+
+        changes
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    creates a subscription that polls the changes of the given query
+
+    The `versionnumber` column has to be part of the columns returned by the `Select`
+    implementation of the entity. No request is sent until the subscription is polled
+    */
+    pub fn subscribe<E: ReadEntity + Versioned>(&self, query: Query) -> Subscription<'_, 'url, A, E> {
+        Subscription {
+            client: self,
+            query,
+            sync: VersionSync::new(),
+            interval: DEFAULT_INTERVAL,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            lookback: 0,
+            emitted: BTreeSet::new(),
+            failures: 0,
+            polled: false,
+            _entity: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde::Deserialize;
+
+    use crate::{client::Client, entity::ReadEntity, query::Query, select::Select, sync::Versioned};
+
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    struct Change {
+        versionnumber: i64,
+    }
+
+    impl ReadEntity for Change {}
+
+    impl Select for Change {
+        fn get_columns() -> &'static [&'static str] {
+            &["versionnumber"]
+        }
+    }
+
+    impl Versioned for Change {
+        fn get_version_number(&self) -> i64 {
+            self.versionnumber
+        }
+    }
+
+    fn changes(versions: &[i64]) -> Vec<Change> {
+        versions.iter().map(|versionnumber| Change { versionnumber: *versionnumber }).collect()
+    }
+
+    #[test]
+    fn lookback_skips_emitted_records() {
+        let client = Client::new_dummy();
+        let mut subscription = client.subscribe::<Change>(Query::new("contacts")).with_lookback(10);
+
+        assert_eq!(subscription.accept(changes(&[3, 5, 8])), changes(&[3, 5, 8]));
+        assert_eq!(subscription.accept(changes(&[5, 7, 8, 12])), changes(&[7, 12]));
+        assert_eq!(subscription.get_sync().get_high_water_mark(), Some(12));
+        assert_eq!(subscription.emitted.iter().copied().collect::<Vec<_>>(), vec![3, 5, 7, 8, 12]);
+
+        subscription.accept(changes(&[20]));
+        assert_eq!(subscription.emitted.iter().copied().collect::<Vec<_>>(), vec![12, 20]);
+    }
+
+    #[test]
+    fn backoff_after_failures() {
+        let client = Client::new_dummy();
+        let mut subscription = client
+            .subscribe::<Change>(Query::new("contacts"))
+            .with_interval(Duration::from_secs(10))
+            .with_max_backoff(Duration::from_secs(60));
+
+        assert_eq!(subscription.get_delay(), Duration::ZERO);
+        subscription.polled = true;
+        assert_eq!(subscription.get_delay(), Duration::from_secs(10));
+        subscription.failures = 1;
+        assert_eq!(subscription.get_delay(), Duration::from_secs(20));
+        subscription.failures = 5;
+        assert_eq!(subscription.get_delay(), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,212 @@
+/*!
+Module for incrementally synchronizing records based on their `versionnumber`
+
+Every Dataverse record carries a `versionnumber` (row version) that increases with
+each change. Querying for records with a version number greater than the highest one
+seen so far returns all records changed since the last synchronization. This is a
+simpler alternative to change tracking for tables where delta tokens are not enabled
+
+Please note that deleted records cannot be detected this way
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    query::Query,
+    result::Result,
+    select::Select,
+    sync::{Versioned, VersionSync}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let mut sync = VersionSync::new();
+
+    let changed: Vec<Contact> = client.retrieve_changes(&Query::new("contacts"), &mut sync).await?;
+
+    // store this value to continue the synchronization later on
+    let high_water_mark = sync.get_high_water_mark();
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+    versionnumber: i64,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname", "versionnumber"]
+    }
+}
+
+impl Versioned for Contact {
+    fn get_version_number(&self) -> i64 {
+        self.versionnumber
+    }
+}
+```
+*/
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::ReadEntity,
+    query::{attribute::Attribute, filter::Filter, order::Order, Query},
+    result::Result,
+};
+
+/// name of the row version column of Dataverse tables
+pub static VERSION_NUMBER: &str = "versionnumber";
+
+/**
+trait for entities that expose their row version
+
+The `versionnumber` column has to be part of the columns returned by
+the `Select` implementation of the entity
+*/
+pub trait Versioned {
+    /// returns the `versionnumber` of this record
+    fn get_version_number(&self) -> i64;
+}
+
+/**
+Tracks the highest `versionnumber` seen by an incremental synchronization
+
+The state is serializable so it can be persisted between runs
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionSync {
+    high_water_mark: Option<i64>,
+}
+
+impl VersionSync {
+    /// creates a new state that synchronizes all records on its first run
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// creates a state that continues a synchronization after the given `versionnumber`
+    pub fn from_high_water_mark(high_water_mark: i64) -> Self {
+        Self {
+            high_water_mark: Some(high_water_mark),
+        }
+    }
+
+    /// returns the highest `versionnumber` seen so far
+    pub fn get_high_water_mark(&self) -> Option<i64> {
+        self.high_water_mark
+    }
+
+    /**
+    builds the query for the changes since the last synchronization
+
+    The given query is restricted to records with a greater `versionnumber` than the
+    high water mark and ordered ascending by `versionnumber`. A limit of the given query
+    is kept and applies to each synchronization run
+    */
+    pub fn build_query(&self, query: &Query) -> Query {
+        let mut query = query.clone();
+
+        if let Some(high_water_mark) = self.high_water_mark {
+            let version_filter =
+                Filter::GreaterThan(VERSION_NUMBER, Attribute::Integer(high_water_mark));
+
+            query.filter = Some(match query.filter.take() {
+                Some(filter) => filter.and(version_filter),
+                None => version_filter,
+            });
+        }
+
+        query.order = Some(vec![Order::Ascending(VERSION_NUMBER)]);
+        query
+    }
+
+    pub(crate) fn observe(&mut self, version_number: i64) {
+        if self.high_water_mark < Some(version_number) {
+            self.high_water_mark = Some(version_number);
+        }
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    retrieves all records matching the query that changed since the last synchronization
+
+    All pages of the result are retrieved. The high water mark of the given state is only
+    advanced when all pages were retrieved successfully, so a failed run can simply be repeated
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    */
+    pub async fn retrieve_changes<E: ReadEntity + Versioned>(
+        &self,
+        query: &Query,
+        sync: &mut VersionSync,
+    ) -> Result<Vec<E>> {
+        let query = sync.build_query(query);
+        let changes = self.retrieve_all::<E>(&query).await?;
+
+        for change in changes.iter() {
+            sync.observe(change.get_version_number());
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::{attribute::Attribute, filter::Filter, Query};
+
+    use super::VersionSync;
+
+    #[test]
+    fn first_run_query() {
+        let query = VersionSync::new().build_query(&Query::new("contacts"));
+        assert_eq!(query.to_string(), "contacts?$orderby=versionnumber asc");
+    }
+
+    #[test]
+    fn continued_run_query() {
+        let query = Query::new("contacts")
+            .filter(Filter::Equal("statecode", Attribute::Integer(0)));
+        let query = VersionSync::from_high_water_mark(42).build_query(&query);
+        assert_eq!(
+            query.to_string(),
+            "contacts?$filter=statecode eq 0 and versionnumber gt 42&$orderby=versionnumber asc"
+        );
+    }
+
+    #[test]
+    fn continued_run_query_keeps_precedence() {
+        let query = Query::new("contacts").filter(
+            Filter::Equal("statecode", Attribute::Integer(0))
+                .or(Filter::Equal("statecode", Attribute::Integer(1))),
+        );
+        let query = VersionSync::from_high_water_mark(42).build_query(&query);
+        assert_eq!(
+            query.to_string(),
+            "contacts?$filter=(statecode eq 0 or statecode eq 1) and versionnumber gt 42&$orderby=versionnumber asc"
+        );
+    }
+
+    #[test]
+    fn observe_keeps_highest_version() {
+        let mut sync = VersionSync::new();
+        sync.observe(5);
+        sync.observe(3);
+        assert_eq!(sync.get_high_water_mark(), Some(5));
+    }
+}
@@ -0,0 +1,816 @@
+/*!
+Module for testing code against an in-process mock of the Dataverse Web API
+
+This is only available with the `testkit` feature. `MockDataverse` starts a local
+http server (based on `wiremock`) and creates clients that talk to it. Canned
+behaviors for the common OData patterns can be mounted with a single call:
+
+- `mock_records(...)` serves the records of a table in pages with next links
+- `mock_record(...)` serves a single record by its id
+- `mock_create(...)` answers creates with the id of the created record
+- `mock_batch(...)` answers batches with the given status per request
+- `mock_throttling(...)` answers the next requests with `429 Too Many Requests`
+
+Other behaviors can be mounted on the underlying `wiremock::MockServer` directly
+
+# Examples
+```rust
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    entity::ReadEntity,
+    query::Query,
+    result::Result,
+    select::Select,
+    testkit::MockDataverse
+};
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &["contactid", "firstname"]
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let dataverse = MockDataverse::start().await;
+    let records = (0..5)
+        .map(|index| json!({ "contactid": Uuid::new_v4(), "firstname": format!("Testy {}", index) }))
+        .collect();
+    dataverse.mock_records("contacts", records, 2).await;
+
+    let client = dataverse.client();
+    let contacts: Vec<Contact> = client.retrieve_all(&Query::new("contacts")).await?;
+    assert_eq!(contacts.len(), 5);
+    Ok(())
+}
+```
+*/
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use wiremock::{
+    matchers::{method, path, query_param, query_param_is_missing},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::{auth::Authenticate, client::{Client, VERSION}, result::Result};
+
+/// priority of throttling mocks, so they take precedence over the other behaviors
+static THROTTLING_PRIORITY: u8 = 1;
+
+/// priority of mocks that override the regular behaviors for specific requests
+static OVERRIDE_PRIORITY: u8 = 2;
+
+/// boundary of the batch responses of the mock server
+static BATCH_BOUNDARY: &str = "batchresponse_mock";
+
+/// Implements the `Authenticate` trait with a constant token accepted by `MockDataverse`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockAuth;
+
+#[async_trait]
+impl Authenticate for MockAuth {
+    async fn get_valid_token(&self) -> Result<Arc<String>> {
+        Ok(Arc::new(String::from("mock-token")))
+    }
+}
+
+/// An in-process mock of the Dataverse Web API
+pub struct MockDataverse {
+    server: MockServer,
+}
+
+impl MockDataverse {
+    /// starts a new mock server on a random local port
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// returns the organization url of the mock server (with a trailing slash)
+    pub fn get_url(&self) -> String {
+        format!("{}/", self.server.uri())
+    }
+
+    /// returns the underlying server to mount custom behaviors
+    pub fn get_server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// creates a client that sends its requests to this mock server
+    pub fn client(&self) -> Client<'static, MockAuth> {
+        Client::new(self.get_url(), reqwest::Client::new(), MockAuth)
+    }
+
+    /**
+    serves the given records for queries of the table (entity set name)
+
+    The records are split into pages of the given size which are linked with
+    `@odata.nextLink` like Dataverse does it. Query options are not evaluated
+    */
+    pub async fn mock_records(&self, table: &str, records: Vec<Value>, page_size: usize) {
+        let pages: Vec<&[Value]> = records.chunks(page_size.max(1)).collect();
+        let page_count = pages.len().max(1);
+
+        for index in 0..page_count {
+            let mut page = json!({ "value": pages.get(index).copied().unwrap_or_default() });
+
+            if index + 1 < page_count {
+                page["@odata.nextLink"] = Value::String(format!(
+                    "{}?$skiptoken={}",
+                    self.get_api_url(table),
+                    index + 1
+                ));
+            }
+
+            let mock = Mock::given(method("GET")).and(path(self.get_api_path(table)));
+
+            let mock = match index {
+                0 => mock.and(query_param_is_missing("$skiptoken")),
+                _ => mock.and(query_param("$skiptoken", index.to_string())),
+            };
+
+            mock.respond_with(ResponseTemplate::new(200).set_body_json(page))
+                .mount(&self.server)
+                .await;
+        }
+    }
+
+    /// serves the given record for retrieves of the record with the id in the table (entity set name)
+    pub async fn mock_record(&self, table: &str, id: Uuid, record: Value) {
+        Mock::given(method("GET"))
+            .and(path(self.get_api_path(&format!("{}({})", table, id.as_hyphenated()))))
+            .respond_with(ResponseTemplate::new(200).set_body_json(record))
+            .mount(&self.server)
+            .await;
+    }
+
+    /**
+    answers the next retrieves of the record with the id in the table (entity set name)
+    with `404 Not Found`, like a replica that didn't receive the record yet
+
+    After the given number of requests the other behaviors apply again
+    */
+    pub async fn mock_not_found(&self, table: &str, id: Uuid, times: u64) {
+        Mock::given(method("GET"))
+            .and(path(self.get_api_path(&format!("{}({})", table, id.as_hyphenated()))))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "error": {
+                    "code": "0x80040217",
+                    "message": format!("Entity with id = {} Does Not Exist", id.as_hyphenated())
+                }
+            })))
+            .up_to_n_times(times)
+            .with_priority(OVERRIDE_PRIORITY)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// answers creates in the table (entity set name) with the given id of the created record
+    pub async fn mock_create(&self, table: &str, id: Uuid) {
+        Mock::given(method("POST"))
+            .and(path(self.get_api_path(table)))
+            .respond_with(ResponseTemplate::new(204).insert_header(
+                "OData-EntityId",
+                format!("{}({})", self.get_api_url(table), id.as_hyphenated()).as_str(),
+            ))
+            .mount(&self.server)
+            .await;
+    }
+
+    /**
+    answers batches with one response per given status and optional JSON body
+
+    The responses get the Content-Ids 1 to n in the given order. Failed requests
+    without a body get a Dataverse error body
+    */
+    pub async fn mock_batch(&self, responses: &[(u16, Option<Value>)]) {
+        Mock::given(method("POST"))
+            .and(path(self.get_api_path("$batch")))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(
+                    build_batch_response(responses),
+                    &format!("multipart/mixed; boundary={}", BATCH_BOUNDARY),
+                ),
+            )
+            .mount(&self.server)
+            .await;
+    }
+
+    /**
+    answers the next requests with the method and relative path (e.g. `$batch`) with
+    `429 Too Many Requests` and the given `Retry-After` seconds
+
+    After the given number of requests the other behaviors apply again
+    */
+    pub async fn mock_throttling(&self, http_method: &str, relative_path: &str, times: u64, retry_after: u64) {
+        Mock::given(method(http_method))
+            .and(path(self.get_api_path(relative_path)))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", retry_after.to_string().as_str())
+                    .set_body_json(json!({
+                        "error": {
+                            "code": "0x80072321",
+                            "message": "Number of requests exceeded the limit of 6000 over time window of 300 seconds."
+                        }
+                    })),
+            )
+            .up_to_n_times(times)
+            .with_priority(THROTTLING_PRIORITY)
+            .mount(&self.server)
+            .await;
+    }
+
+    fn get_api_path(&self, relative_path: &str) -> String {
+        format!("/api/data/v{}/{}", VERSION, relative_path)
+    }
+
+    fn get_api_url(&self, relative_path: &str) -> String {
+        format!("{}{}", self.server.uri(), self.get_api_path(relative_path))
+    }
+}
+
+fn build_batch_response(responses: &[(u16, Option<Value>)]) -> String {
+    let mut body = String::new();
+
+    for (index, (status, content)) in responses.iter().enumerate() {
+        let content = match (content, (200..300).contains(status)) {
+            (Some(content), _) => content.to_string(),
+            (None, true) => String::new(),
+            (None, false) => json!({
+                "error": { "code": "0x80040203", "message": "mock failure" }
+            })
+            .to_string(),
+        };
+
+        body.push_str(&format!(
+            "--{}\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: {}\r\n\r\nHTTP/1.1 {} Mock\r\nContent-Type: application/json\r\n\r\n{}\r\n",
+            BATCH_BOUNDARY,
+            index + 1,
+            status,
+            content
+        ));
+    }
+
+    body.push_str(&format!("--{}--\r\n", BATCH_BOUNDARY));
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use uuid::Uuid;
+    use wiremock::{
+        matchers::{header, method, path, query_param},
+        Mock, ResponseTemplate,
+    };
+
+    #[cfg(feature = "batch")]
+    use crate::{batch::Batch, bulk::BulkOptions, identity::DefaultOwner};
+    use crate::{
+        cache::MemoryCacheStore,
+        client::Client,
+        consistency::ReadYourWrites,
+        count::{CountAccuracy, CountMode, RecordCount},
+        entity::{ReadEntity, WriteEntity},
+        error::DataverseError,
+        export::{ExportJob, MemoryCheckpointStore},
+        query::{attribute::Attribute, filter::Filter, Query},
+        reference::{Reference, ReferenceStruct},
+        select::Select,
+    };
+
+    use super::{MockAuth, MockDataverse, OVERRIDE_PRIORITY};
+
+    #[derive(Deserialize)]
+    struct Contact {
+        firstname: String,
+    }
+
+    impl ReadEntity for Contact {}
+
+    impl Select for Contact {
+        fn get_columns() -> &'static [&'static str] {
+            &["firstname"]
+        }
+    }
+
+    #[tokio::test]
+    async fn follows_pages() {
+        let dataverse = MockDataverse::start().await;
+        let records = (0..5)
+            .map(|index| json!({ "firstname": format!("Testy {}", index) }))
+            .collect();
+        dataverse.mock_records("contacts", records, 2).await;
+
+        let contacts: Vec<Contact> = dataverse
+            .client()
+            .retrieve_all(&Query::new("contacts"))
+            .await
+            .unwrap();
+
+        let names: Vec<String> = contacts.into_iter().map(|contact| contact.firstname).collect();
+        assert_eq!(names, vec!["Testy 0", "Testy 1", "Testy 2", "Testy 3", "Testy 4"]);
+    }
+
+    #[tokio::test]
+    async fn stops_after_first_page() {
+        let dataverse = MockDataverse::start().await;
+        let records = (0..5)
+            .map(|index| json!({ "firstname": format!("Testy {}", index) }))
+            .collect();
+        dataverse.mock_records("contacts", records, 2).await;
+
+        let contacts: Vec<Contact> = dataverse
+            .client()
+            .retrieve_all(&Query::new("contacts").no_follow_next_link())
+            .await
+            .unwrap();
+
+        assert_eq!(contacts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn resumes_export_after_failed_page() {
+        let dataverse = MockDataverse::start().await;
+        let records = (0..5)
+            .map(|index| json!({ "firstname": format!("Testy {}", index) }))
+            .collect();
+        dataverse.mock_records("contacts", records, 2).await;
+
+        let client = dataverse.client();
+        let job = ExportJob::new("contacts", Query::new("contacts"), MemoryCheckpointStore::new());
+        let mut exported = Vec::new();
+
+        let result = client
+            .run_export(&job, |contacts: Vec<Contact>, checkpoint| {
+                if checkpoint.get_pages() == 2 {
+                    return Err(DataverseError::new(String::from("the target is unavailable")));
+                }
+
+                exported.extend(contacts.into_iter().map(|contact| contact.firstname));
+                Ok(())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(job.load_checkpoint().unwrap().get_exported(), 2);
+
+        let checkpoint = client
+            .run_export(&job, |contacts: Vec<Contact>, _| {
+                exported.extend(contacts.into_iter().map(|contact| contact.firstname));
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert!(checkpoint.is_completed());
+        assert_eq!(checkpoint.get_pages(), 3);
+        assert_eq!(checkpoint.get_exported(), 5);
+        assert_eq!(exported, (0..5).map(|index| format!("Testy {}", index)).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn saves_delta_link_of_tracked_export() {
+        let dataverse = MockDataverse::start().await;
+        let delta_link = format!("{}?$deltatoken=919042%2108%2f22%2f2017", dataverse.get_api_url("contacts"));
+
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("contacts")))
+            .and(header("Prefer", "odata.track-changes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "value": [{ "firstname": "Testy" }],
+                "@odata.deltaLink": delta_link
+            })))
+            .mount(dataverse.get_server())
+            .await;
+
+        let job = ExportJob::new("contacts", Query::new("contacts"), MemoryCheckpointStore::new())
+            .track_changes();
+        let checkpoint = dataverse
+            .client()
+            .run_export(&job, |_: Vec<Contact>, _| Ok(()))
+            .await
+            .unwrap();
+
+        assert!(checkpoint.is_completed());
+        assert_eq!(checkpoint.get_exported(), 1);
+        assert_eq!(checkpoint.get_delta_link(), Some(delta_link.as_str()));
+        assert_eq!(job.load_checkpoint().unwrap(), checkpoint);
+    }
+
+    #[tokio::test]
+    async fn counts_records_exactly_or_fast() {
+        let dataverse = MockDataverse::start().await;
+        let definitions = vec![json!({ "LogicalName": "contact", "EntitySetName": "contacts" })];
+        dataverse.mock_records("EntityDefinitions", definitions, 10).await;
+        let records = (0..5).map(|_| json!({ "contactid": Uuid::new_v4() })).collect();
+        dataverse.mock_records("contacts", records, 2).await;
+
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("contacts")))
+            .and(header("ConsistencyLevel", "eventual"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "@odata.count": 5000,
+                "@Microsoft.Dynamics.CRM.totalrecordcountlimitexceeded": true,
+                "value": [{ "contactid": Uuid::new_v4() }]
+            })))
+            .with_priority(OVERRIDE_PRIORITY)
+            .mount(dataverse.get_server())
+            .await;
+
+        let client = dataverse.client();
+        let query = Query::new("contacts").filter(Filter::Equal("statecode", Attribute::Integer(0)));
+
+        let count = client.count_records(&query, CountMode::Exact).await.unwrap();
+        assert_eq!(count, RecordCount::new(5, CountAccuracy::Exact));
+
+        let count = client.count_records(&query, CountMode::Fast).await.unwrap();
+        assert_eq!(count, RecordCount::new(5000, CountAccuracy::AtLeast));
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_url() {
+        let dataverse = MockDataverse::start().await;
+        let id = Uuid::new_v4();
+        dataverse.mock_record("contacts", id, json!({ "firstname": "Testy" })).await;
+
+        let client = Client::new("http://127.0.0.1:1/", reqwest::Client::new(), MockAuth)
+            .with_failover_urls(&[&dataverse.get_url()]);
+
+        let contact: Contact = client
+            .retrieve(&ReferenceStruct::new("contacts", id))
+            .await
+            .unwrap();
+        assert_eq!(contact.firstname, "Testy");
+        assert_eq!(client.get_active_url(), dataverse.get_url());
+    }
+
+    #[derive(Serialize)]
+    struct NewContact {
+        contactid: Uuid,
+        firstname: &'static str,
+    }
+
+    impl WriteEntity for NewContact {}
+
+    impl Reference for NewContact {
+        fn get_reference(&self) -> ReferenceStruct {
+            ReferenceStruct::new("contacts", self.contactid)
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_retrieve_of_created_record() {
+        let dataverse = MockDataverse::start().await;
+        let id = Uuid::new_v4();
+        dataverse.mock_create("contacts", id).await;
+        dataverse.mock_record("contacts", id, json!({ "firstname": "Testy" })).await;
+        dataverse.mock_not_found("contacts", id, 2).await;
+
+        let client = dataverse
+            .client()
+            .with_read_your_writes(ReadYourWrites::new().with_delay(Duration::from_millis(1), Duration::from_millis(5)));
+        let contact = NewContact {
+            contactid: id,
+            firstname: "Testy",
+        };
+
+        client.create(&contact).await.unwrap();
+        let contact: Contact = client.retrieve(&contact).await.unwrap();
+        assert_eq!(contact.firstname, "Testy");
+
+        let other_id = Uuid::new_v4();
+        dataverse.mock_not_found("contacts", other_id, 1).await;
+        let result = client.retrieve::<Contact>(&ReferenceStruct::new("contacts", other_id)).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn retries_cached_retrieve_of_created_record() {
+        let dataverse = MockDataverse::start().await;
+        let id = Uuid::new_v4();
+        dataverse.mock_create("contacts", id).await;
+        dataverse.mock_record("contacts", id, json!({ "firstname": "Testy" })).await;
+        dataverse.mock_not_found("contacts", id, 2).await;
+
+        let client = dataverse
+            .client()
+            .with_cache(MemoryCacheStore::new())
+            .with_read_your_writes(ReadYourWrites::new().with_delay(Duration::from_millis(1), Duration::from_millis(5)));
+        let contact = NewContact {
+            contactid: id,
+            firstname: "Testy",
+        };
+
+        client.create(&contact).await.unwrap();
+        let contact: Contact = client.retrieve(&contact).await.unwrap();
+        assert_eq!(contact.firstname, "Testy");
+    }
+
+    #[tokio::test]
+    async fn deduplicates_only_active_records() {
+        use crate::dedupe::{DedupeOptions, Survivor, SurvivorshipPolicy};
+
+        let dataverse = MockDataverse::start().await;
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("EntityDefinitions")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "value": [{ "LogicalName": "contact", "EntitySetName": "contacts" }]
+            })))
+            .mount(dataverse.get_server())
+            .await;
+
+        // the first run merged the second contact, which deactivated it
+        let survivor = json!({
+            "contactid": Uuid::from_u128(1),
+            "createdon": "2024-03-01T00:00:00Z",
+            "emailaddress1": "testy@example.com"
+        });
+        let merged = json!({
+            "contactid": Uuid::from_u128(2),
+            "createdon": "2024-03-02T00:00:00Z",
+            "emailaddress1": "testy@example.com"
+        });
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("contacts")))
+            .and(query_param("$filter", "statecode eq 0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "value": [survivor] })))
+            .mount(dataverse.get_server())
+            .await;
+        dataverse.mock_records("contacts", vec![survivor, merged], 5000).await;
+
+        let report = dataverse
+            .client()
+            .deduplicate(
+                &Query::new("contacts"),
+                &["emailaddress1"],
+                &DedupeOptions::new(SurvivorshipPolicy::new(Survivor::Oldest)),
+            )
+            .await
+            .unwrap();
+
+        assert!(report.merges.is_empty());
+        assert_eq!(report.merged, 0);
+    }
+
+    #[tokio::test]
+    async fn creates_and_retrieves() {
+        let dataverse = MockDataverse::start().await;
+        let id = Uuid::new_v4();
+        dataverse.mock_record("contacts", id, json!({ "firstname": "Testy" })).await;
+
+        let contact: Contact = dataverse
+            .client()
+            .retrieve(&ReferenceStruct::new("contacts", id))
+            .await
+            .unwrap();
+        assert_eq!(contact.firstname, "Testy");
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn bulk_retries_throttled_batches() {
+        let dataverse = MockDataverse::start().await;
+        dataverse.mock_throttling("POST", "$batch", 1, 0).await;
+        dataverse.mock_batch(&[(204, None), (404, None)]).await;
+
+        let references = vec![
+            ReferenceStruct::new("contacts", Uuid::new_v4()),
+            ReferenceStruct::new("contacts", Uuid::new_v4()),
+        ];
+
+        let result = dataverse
+            .client()
+            .bulk_delete(&references, &BulkOptions::new())
+            .await
+            .unwrap();
+
+        assert_eq!(result.throttled, 1);
+        assert_eq!(result.failures.len(), 2);
+        assert_eq!(result.failures[1].status, Some(404));
+        assert_eq!(result.failures[1].message, "mock failure");
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn bulk_records_transport_failures_per_item() {
+        let dataverse = MockDataverse::start().await;
+        Mock::given(method("POST"))
+            .and(path(dataverse.get_api_path("$batch")))
+            .respond_with(ResponseTemplate::new(204).set_delay(Duration::from_secs(5)))
+            .up_to_n_times(1)
+            .mount(dataverse.get_server())
+            .await;
+        dataverse.mock_batch(&[(204, None), (204, None)]).await;
+
+        let backend = reqwest::Client::builder()
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+        let client = Client::new(dataverse.get_url(), backend, MockAuth);
+
+        let references: Vec<ReferenceStruct> = (0..4)
+            .map(|_| ReferenceStruct::new("contacts", Uuid::new_v4()))
+            .collect();
+
+        // the first batch times out, the second one is still executed
+        let options = BulkOptions::new().batch_size(2).concurrency(1);
+        let result = client.bulk_delete(&references, &options).await.unwrap();
+
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.failures.len(), 2);
+        assert_eq!(result.failures[0].index, 0);
+        assert_eq!(result.failures[1].index, 1);
+        assert_eq!(result.failures[1].status, None);
+
+        let indices: Vec<usize> = result.items.iter().map(|item| item.index).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert!(!result.items[1].is_success());
+        assert!(result.items[2].is_success());
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn does_not_reactivate_on_validation_errors() {
+        use crate::state::{InactiveRecordPolicy, WriteOptions};
+
+        let dataverse = MockDataverse::start().await;
+        let contact = NewContact { contactid: Uuid::new_v4(), firstname: "Testy" };
+        Mock::given(method("PATCH"))
+            .and(path(dataverse.get_api_path(&format!("contacts({})", contact.contactid))))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": { "code": "0x80040203", "message": "Invalid value for firstname" }
+            })))
+            .mount(dataverse.get_server())
+            .await;
+
+        let options = WriteOptions::new().inactive_records(InactiveRecordPolicy::Reactivate);
+        let error = dataverse
+            .client()
+            .update_with_options(&contact, &options)
+            .await
+            .unwrap_err();
+
+        assert!(error.message.contains("0x80040203"));
+        let requests = dataverse.get_server().received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn bulk_creates_with_generated_ids() {
+        let dataverse = MockDataverse::start().await;
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("EntityDefinitions")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "value": [{ "LogicalName": "contact", "EntitySetName": "contacts" }]
+            })))
+            .mount(dataverse.get_server())
+            .await;
+        dataverse.mock_batch(&[(204, None), (204, None)]).await;
+
+        let client = dataverse.client().with_id_generator(|_: &str| Uuid::from_u128(42));
+        let contacts = [
+            NewContact { contactid: Uuid::nil(), firstname: "Testy" },
+            NewContact { contactid: Uuid::from_u128(7), firstname: "Tester" },
+        ];
+
+        let result = client.bulk_create(&contacts, &BulkOptions::new()).await.unwrap();
+        assert_eq!(result.succeeded, 2);
+
+        let requests = dataverse.get_server().received_requests().await.unwrap();
+        let body = String::from_utf8_lossy(&requests.last().unwrap().body).to_string();
+        assert!(body.contains(&Uuid::from_u128(42).to_string()));
+        assert!(body.contains(&Uuid::from_u128(7).to_string()));
+        assert!(!body.contains(&Uuid::nil().to_string()));
+
+        // the entity was prepared by the bulk create, so batches of the client get keys as well
+        let mut batch = client.new_batch();
+        batch.create(&contacts[0]).unwrap();
+        let body = batch.into_body().unwrap();
+        assert!(String::from_utf8_lossy(&body).contains(&Uuid::from_u128(42).to_string()));
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn binds_default_owner_in_batches() {
+        let dataverse = MockDataverse::start().await;
+        dataverse.mock_batch(&[(204, None)]).await;
+
+        let team = format!("\"ownerid@odata.bind\":\"/teams({})\"", Uuid::from_u128(5));
+        let client = dataverse.client().with_default_owner(DefaultOwner::Team(Uuid::from_u128(5)));
+        let contacts = [NewContact { contactid: Uuid::new_v4(), firstname: "Testy" }];
+
+        let mut transaction = client.new_transaction();
+        transaction.create(&contacts[0]).unwrap();
+        transaction.commit(&client).await.unwrap();
+
+        let result = client.bulk_create(&contacts, &BulkOptions::new()).await.unwrap();
+        assert_eq!(result.succeeded, 1);
+
+        let requests = dataverse.get_server().received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|request| String::from_utf8_lossy(&request.body).contains(&team)));
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn binds_default_currency_in_prepared_batches() {
+        let dataverse = MockDataverse::start().await;
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("EntityDefinitions")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "value": [{ "LogicalName": "contact", "EntitySetName": "contacts" }]
+            })))
+            .mount(dataverse.get_server())
+            .await;
+        Mock::given(method("GET"))
+            .and(path(dataverse.get_api_path("EntityDefinitions(LogicalName='contact')/Attributes")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "value": [{ "LogicalName": "transactioncurrencyid" }]
+            })))
+            .mount(dataverse.get_server())
+            .await;
+        dataverse.mock_batch(&[(204, None)]).await;
+
+        let currency = format!("\"transactioncurrencyid@odata.bind\":\"/transactioncurrencies({})\"", Uuid::from_u128(9));
+        let client = dataverse.client().with_default_currency(Uuid::from_u128(9));
+        let contacts = [NewContact { contactid: Uuid::new_v4(), firstname: "Testy" }];
+
+        let mut batch = client.new_batch();
+        assert!(batch.create(&contacts[0]).is_err());
+
+        client.prepare_creates(&["contacts"]).await.unwrap();
+        batch.create(&contacts[0]).unwrap();
+        assert!(String::from_utf8_lossy(&batch.into_body().unwrap()).contains(&currency));
+
+        let result = client.bulk_create(&contacts, &BulkOptions::new()).await.unwrap();
+        assert_eq!(result.succeeded, 1);
+
+        let requests = dataverse.get_server().received_requests().await.unwrap();
+        assert!(String::from_utf8_lossy(&requests.last().unwrap().body).contains(&currency));
+    }
+
+    #[cfg(feature = "batch")]
+    #[tokio::test]
+    async fn audits_spooled_and_throttled_batches_once() {
+        use std::sync::{Arc, Mutex};
+
+        let dataverse = MockDataverse::start().await;
+        dataverse.mock_throttling("POST", "$batch", 1, 0).await;
+        dataverse.mock_batch(&[(204, None), (204, None)]).await;
+
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&entries);
+        let client = dataverse.client().with_audit_trail(move |entry| {
+            sink.lock().unwrap().push(entry.clone());
+        });
+
+        let contacts = [
+            NewContact { contactid: Uuid::new_v4(), firstname: "Testy" },
+            NewContact { contactid: Uuid::new_v4(), firstname: "Tester" },
+        ];
+
+        let mut batch = Batch::spooled(dataverse.get_url()).unwrap();
+        for contact in contacts.iter() {
+            batch.create(contact).unwrap();
+        }
+
+        // the first attempt is throttled, which the batch doesn't retry
+        assert!(client.execute(&batch).await.is_err());
+        assert_eq!(entries.lock().unwrap().len(), 2);
+        assert_eq!(entries.lock().unwrap()[0].entity_name, "contacts");
+        assert_eq!(entries.lock().unwrap()[1].columns, vec!["contactid", "firstname"]);
+
+        entries.lock().unwrap().clear();
+        dataverse.mock_throttling("POST", "$batch", 1, 0).await;
+
+        let result = client.bulk_create(&contacts, &BulkOptions::new()).await.unwrap();
+        assert_eq!(result.throttled, 1);
+        assert_eq!(entries.lock().unwrap().len(), 2);
+
+        entries.lock().unwrap().clear();
+        let boundary = format!("batch_{}", batch.get_batch_id().as_simple());
+        let body = std::io::Cursor::new(batch.into_body().unwrap().to_vec());
+        client.execute_reader(&boundary, body).await.unwrap();
+        assert_eq!(entries.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,320 @@
+/*!
+Module for executing several operations as one transaction
+
+A `Transaction` is a batch with a single changeset. Dataverse executes all
+operations of a changeset in one database transaction: either every operation
+succeeds or none of them is applied. When one operation fails, all operations
+executed before it are rolled back and the remaining operations are not executed
+at all. In this case `Transaction::commit(...)` returns the error of the failed
+operation
+
+Every operation returns a `ContentId` that extracts its typed result from the
+`TransactionResult` after the commit
+
+Please note that plugins with side effects outside of Dataverse (e.g. calls to
+external services) are not rolled back
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Serialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::WriteEntity,
+    reference::{Reference, ReferenceStruct},
+    result::{IntoDataverseResult, Result},
+    transaction::Transaction
+};
+
+async fn test() -> Result<()> {
+    let account = Account {
+        accountid: Uuid::new_v4(),
+        name: String::from("Contoso"),
+    };
+
+    let contact = Contact {
+        contactid: Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?,
+        firstname: String::from("Testy"),
+    };
+
+    let mut tx = Transaction::new("https://instance.crm.dynamics.com/");
+    let created = tx.create(&account)?;
+    tx.update(&contact)?;
+
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let result = tx.commit(&client).await?;
+    let account_id = result.get_created_id(created)?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Account {
+    accountid: Uuid,
+    name: String,
+}
+
+impl WriteEntity for Account {}
+
+impl Reference for Account {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("accounts", self.accountid)
+    }
+}
+
+#[derive(Serialize)]
+struct Contact {
+    contactid: Uuid,
+    firstname: String,
+}
+
+impl WriteEntity for Contact {}
+
+impl Reference for Contact {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("contacts", self.contactid)
+    }
+}
+```
+*/
+
+use std::borrow::Cow;
+
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    batch::{
+        response::{BatchResponse, BatchResponseItem, ContentId},
+        Batch,
+    },
+    bulk::MAX_BATCH_SIZE,
+    client::{Client, UUID_REGEX},
+    entity::{ReadEntity, WriteEntity},
+    error::DataverseError,
+    reference::Reference,
+    result::{IntoDataverseResult, Result},
+};
+
+/// Represents operations that are executed all together or not at all
+pub struct Transaction {
+    batch: Batch,
+}
+
+impl Transaction {
+    /// creates a new empty transaction for the dataverse environment with the given url
+    pub fn new(url: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            batch: Batch::new(url),
+        }
+    }
+
+    /// creates a new empty transaction whose operations are written into the given batch
+    pub(crate) fn from_batch(batch: Batch) -> Self {
+        Self { batch }
+    }
+
+    /// returns the current count of operations in this transaction
+    pub fn get_count(&self) -> u16 {
+        self.batch.get_count()
+    }
+
+    /**
+    adds the creation of the given entity to this transaction
+
+    The returned `ContentId` extracts the id of the created record from the result
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn create(&mut self, entity: &impl WriteEntity) -> Result<ContentId<Uuid>> {
+        let content_id = self.next_content_id();
+        self.batch.create(entity)?;
+        Ok(content_id)
+    }
+
+    /**
+    adds the creation of the given entity to this transaction and asks dataverse
+    to return the created record with the columns selected by `R`
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn create_returning<R: ReadEntity>(
+        &mut self,
+        entity: &impl WriteEntity,
+    ) -> Result<ContentId<R>> {
+        self.batch.create_returning(entity)
+    }
+
+    /**
+    adds the update of the given entity to this transaction
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn update(&mut self, entity: &impl WriteEntity) -> Result<ContentId<()>> {
+        let content_id = self.next_content_id();
+        self.batch.update(entity)?;
+        Ok(content_id)
+    }
+
+    /**
+    adds the upsert of the given entity to this transaction
+
+    Please note that this function can fail if a serde serialization error occurs
+    */
+    pub fn upsert(&mut self, entity: &impl WriteEntity) -> Result<ContentId<()>> {
+        let content_id = self.next_content_id();
+        self.batch.upsert(entity)?;
+        Ok(content_id)
+    }
+
+    /// adds the update of the referenced record with a pre-serialized JSON payload to this transaction
+    pub fn update_raw(&mut self, reference: &impl Reference, json: &str) -> Result<ContentId<()>> {
+        let content_id = self.next_content_id();
+        self.batch.update_raw(reference, json)?;
+        Ok(content_id)
+    }
+
+    /// adds the deletion of the referenced record to this transaction
+    pub fn delete(&mut self, reference: &impl Reference) -> Result<ContentId<()>> {
+        let content_id = self.next_content_id();
+        self.batch.delete(reference)?;
+        Ok(content_id)
+    }
+
+    /**
+    executes all operations of this transaction
+
+    When any operation fails, no operation is applied and the error of the failed
+    operation is returned
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - Any operation of the transaction failed
+    - The transaction contains more than 1000 operations
+    */
+    pub async fn commit<A: Authenticate>(self, client: &Client<'_, A>) -> Result<TransactionResult> {
+        if self.get_count() > MAX_BATCH_SIZE {
+            return Err(DataverseError::new(format!(
+                "a transaction may contain at most {} operations, but it contains {}",
+                MAX_BATCH_SIZE,
+                self.get_count()
+            )));
+        }
+
+        if self.get_count() == 0 {
+            return Ok(TransactionResult {
+                response: BatchResponse::default(),
+            });
+        }
+
+        let count = self.get_count();
+        let response = client.execute_owned(self.batch).await?;
+
+        if let Some(failure) = response.get_failure() {
+            let mut error = failure.into_error();
+            error.message = format!("transaction was rolled back because {}", error.message);
+            return Err(error);
+        }
+
+        if response.items.len() < count as usize {
+            return Err(DataverseError::new(format!(
+                "transaction was rolled back: dataverse returned {} responses for {} operations",
+                response.items.len(),
+                count
+            )));
+        }
+
+        Ok(TransactionResult { response })
+    }
+
+    fn next_content_id<T>(&self) -> ContentId<T> {
+        ContentId::new(self.batch.get_count() + 1)
+    }
+}
+
+/// The results of the operations of a committed `Transaction`
+#[derive(Clone, Debug, Default)]
+pub struct TransactionResult {
+    response: BatchResponse,
+}
+
+impl TransactionResult {
+    /// returns the id of the record created by the given operation
+    pub fn get_created_id(&self, content_id: ContentId<Uuid>) -> Result<Uuid> {
+        let item = self.get_item(content_id.get_id())?;
+
+        let entity_id = item
+            .get_header("OData-EntityId")
+            .and_then(|header| UUID_REGEX.find(header))
+            .ok_or_else(|| DataverseError::new("Dataverse provided no Uuid".to_string()))?;
+
+        Uuid::parse_str(entity_id.as_str()).into_dataverse_result()
+    }
+
+    /// deserializes the record returned by the given operation
+    pub fn get_entity<E: DeserializeOwned>(&self, content_id: ContentId<E>) -> Result<E> {
+        self.response.get_entity(content_id)
+    }
+
+    /// returns the raw response of the operation with the given Content-Id
+    pub fn get_item(&self, content_id: u16) -> Result<&BatchResponseItem> {
+        self.response.get(content_id).ok_or_else(|| {
+            DataverseError::new(format!(
+                "transaction result contains no item for Content-Id {}",
+                content_id
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        batch::response::{BatchResponse, ContentId},
+        reference::ReferenceStruct,
+    };
+
+    use super::{Transaction, TransactionResult};
+
+    static RESPONSE: &str = "--batchresponse_1\r\nContent-Type: multipart/mixed; boundary=changesetresponse_2\r\n\r\n--changesetresponse_2\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: 1\r\n\r\nHTTP/1.1 204 No Content\r\nOData-EntityId: https://instance/api/data/v9.2/accounts(12345678-1234-1234-1234-123456789012)\r\n\r\n\r\n--changesetresponse_2\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: 2\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n\r\n--changesetresponse_2--\r\n--batchresponse_1--\r\n";
+
+    static FAILED_RESPONSE: &str = "--batchresponse_1\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\nContent-ID: 2\r\n\r\nHTTP/1.1 400 Bad Request\r\n\r\n{\"error\":{\"message\":\"invalid\"}}\r\n--batchresponse_1--\r\n";
+
+    #[test]
+    fn content_ids_follow_operations() {
+        let mut tx = Transaction::new("https://instance/");
+        let first = tx.delete(&ReferenceStruct::new("accounts", Uuid::nil())).unwrap();
+        let second = tx.delete(&ReferenceStruct::new("contacts", Uuid::nil())).unwrap();
+
+        assert_eq!(first.get_id(), 1);
+        assert_eq!(second.get_id(), 2);
+        assert_eq!(tx.get_count(), 2);
+    }
+
+    #[test]
+    fn created_id_from_result() {
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", RESPONSE).unwrap();
+        assert!(response.get_failure().is_none());
+
+        let result = TransactionResult { response };
+        assert_eq!(
+            result.get_created_id(ContentId::new(1)).unwrap(),
+            Uuid::parse_str("12345678-1234-1234-1234-123456789012").unwrap()
+        );
+        assert!(result.get_created_id(ContentId::new(2)).is_err());
+    }
+
+    #[test]
+    fn failed_operation_is_reported() {
+        let response =
+            BatchResponse::parse("multipart/mixed; boundary=batchresponse_1", FAILED_RESPONSE).unwrap();
+        let failure = response.get_failure().unwrap();
+        assert_eq!(failure.content_id, Some(2));
+        assert_eq!(failure.index, Some(1));
+        assert_eq!(failure.get_message(), "invalid");
+    }
+}
@@ -0,0 +1,64 @@
+/*!
+Module for the transport that sends the http requests of the client
+
+All Dataverse logic of the client builds `reqwest::Request`s and consumes
+`reqwest::Response`s, but the requests are sent by an `HttpTransport`. By default
+this is the `reqwest::Client` given to `Client::new(...)`. Environments with their
+own http stack (corporate TLS stacks, custom proxies, hyper-only builds) can supply
+a transport with `Client::with_transport(...)` that converts the request for their
+stack and its answer back with `reqwest::Response::from(http::Response<_>)`
+
+# Examples
+```rust
+use async_trait::async_trait;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::{IntoDataverseResult, Result},
+    transport::HttpTransport
+};
+
+struct LoggingTransport {
+    inner: reqwest::Client,
+}
+
+#[async_trait]
+impl HttpTransport for LoggingTransport {
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        println!("{} {}", request.method(), request.url());
+        self.inner.execute(request).await.into_dataverse_result()
+    }
+}
+
+let client = Client::new_dummy().with_transport(LoggingTransport { inner: reqwest::Client::new() });
+```
+*/
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::result::{IntoDataverseResult, Result};
+
+/// sends http requests on behalf of the client
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    /// sends the request and returns the response of the server
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response>;
+}
+
+#[async_trait]
+impl HttpTransport for reqwest::Client {
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        reqwest::Client::execute(self, request)
+            .await
+            .into_dataverse_result()
+    }
+}
+
+/// allows sharing a transport between several clients
+#[async_trait]
+impl<T: HttpTransport> HttpTransport for Arc<T> {
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        self.as_ref().execute(request).await
+    }
+}
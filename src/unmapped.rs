@@ -0,0 +1,189 @@
+/*!
+Module for keeping the columns of a record that an entity doesn't model
+
+`WithUnmapped<E>` deserializes the entity `E` and collects every other column of the
+record in a map, so generic tools like synchronizations can preserve columns they
+don't know about. OData annotations like `@odata.etag` are not collected. Serializing
+a `WithUnmapped` writes the entity together with the collected columns that can be
+written. Lookup values (`_{name}_value`) and the read-only system columns `createdon`,
+`modifiedon` and `versionnumber` are left out, because Dataverse rejects them in writes
+
+Dataverse only returns the selected columns of a record. Select no columns in `E` to
+retrieve all columns of the record
+
+# Examples
+```rust
+use uuid::Uuid;
+use serde::Deserialize;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    entity::ReadEntity,
+    reference::ReferenceStruct,
+    result::{IntoDataverseResult, Result},
+    select::Select,
+    unmapped::WithUnmapped
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let reference = ReferenceStruct::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let contact: WithUnmapped<Contact> = client.retrieve(&reference).await?;
+    println!("{} has {} other columns", contact.entity.lastname, contact.unmapped.len());
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Contact {
+    contactid: Uuid,
+    lastname: String,
+}
+
+impl ReadEntity for Contact {}
+
+impl Select for Contact {
+    fn get_columns() -> &'static [&'static str] {
+        &[]
+    }
+}
+```
+*/
+
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{
+    entity::{ReadEntity, WriteEntity},
+    query::filter::Filter,
+    reference::{Reference, ReferenceStruct},
+    select::Select,
+};
+
+/// A record deserialized into an entity and the columns the entity doesn't contain
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WithUnmapped<E> {
+    #[serde(flatten)]
+    pub entity: E,
+    /// the columns of the record by their name that are not fields of the entity
+    #[serde(flatten, serialize_with = "serialize_writable")]
+    pub unmapped: HashMap<String, Value>,
+}
+
+/// system columns that are returned in reads but can't be written
+static READ_ONLY_COLUMNS: [&str; 3] = ["createdon", "modifiedon", "versionnumber"];
+
+/// Indicates if the column can be written, lookups are read as `_{name}_value` but written with a bind
+fn is_writable(column: &str) -> bool {
+    let is_lookup_value = column.starts_with('_') && column.ends_with("_value");
+    !is_lookup_value && !READ_ONLY_COLUMNS.contains(&column)
+}
+
+fn serialize_writable<S: Serializer>(
+    unmapped: &HashMap<String, Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(unmapped.iter().filter(|(column, _)| is_writable(column)))
+}
+
+/// deserializes the fields of the entity first, so the map only receives the remaining columns
+#[derive(Deserialize)]
+struct Record<E> {
+    #[serde(flatten)]
+    entity: E,
+    #[serde(flatten)]
+    unmapped: HashMap<String, Value>,
+}
+
+impl<'de, E: DeserializeOwned> Deserialize<'de> for WithUnmapped<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Record { entity, mut unmapped } = Record::<E>::deserialize(deserializer)?;
+        unmapped.retain(|column, _| !column.contains('@'));
+        Ok(Self { entity, unmapped })
+    }
+}
+
+impl<E: Select> Select for WithUnmapped<E> {
+    fn get_columns() -> &'static [&'static str] {
+        E::get_columns()
+    }
+
+    fn get_default_filter() -> Option<Filter> {
+        E::get_default_filter()
+    }
+}
+
+impl<E: ReadEntity> ReadEntity for WithUnmapped<E> {
+    fn include_formatted_values() -> bool {
+        E::include_formatted_values()
+    }
+
+    fn include_lookup_logical_names() -> bool {
+        E::include_lookup_logical_names()
+    }
+}
+
+impl<E: Reference> Reference for WithUnmapped<E> {
+    fn get_reference(&self) -> ReferenceStruct {
+        self.entity.get_reference()
+    }
+}
+
+impl<E: WriteEntity> WriteEntity for WithUnmapped<E> {}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    use super::WithUnmapped;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Contact {
+        #[serde(rename = "lastname")]
+        last_name: String,
+    }
+
+    #[test]
+    fn collects_unmapped_columns() {
+        let contact: WithUnmapped<Contact> = serde_json::from_str(
+            r#"{"@odata.etag":"W/\"1234\"","lastname":"McTestface","new_shoesize":44,"emailaddress1":null}"#,
+        )
+        .unwrap();
+
+        assert_eq!(contact.entity.last_name, "McTestface");
+        assert_eq!(contact.unmapped.len(), 2);
+        assert_eq!(contact.unmapped["new_shoesize"], json!(44));
+        assert_eq!(contact.unmapped["emailaddress1"], Value::Null);
+
+        assert_eq!(
+            serde_json::to_value(&contact).unwrap(),
+            json!({ "lastname": "McTestface", "new_shoesize": 44, "emailaddress1": null })
+        );
+    }
+
+    #[test]
+    fn writes_only_writable_columns() {
+        let record = json!({
+            "lastname": "McTestface",
+            "new_shoesize": 44,
+            "_parentcustomerid_value": "00000000-0000-0000-0000-000000000001",
+            "createdon": "2024-03-01T12:00:00Z",
+            "modifiedon": "2024-03-02T12:00:00Z",
+            "versionnumber": 1234
+        });
+
+        let contact: WithUnmapped<Contact> = serde_json::from_value(record).unwrap();
+        assert_eq!(contact.unmapped.len(), 5);
+
+        let written = serde_json::to_value(&contact).unwrap();
+        assert_eq!(written, json!({ "lastname": "McTestface", "new_shoesize": 44 }));
+
+        let contact: WithUnmapped<Contact> = serde_json::from_value(written.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&contact).unwrap(), written);
+    }
+}
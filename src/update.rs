@@ -0,0 +1,181 @@
+/*!
+Module for updating records without defining an entity struct
+
+`Client::update_builder(...)` collects the changed columns of a single record and
+writes them with `send()`. This is meant for one-off fixes and scripts where declaring
+a serde struct would be overkill. Values are given as `Attribute` like in query filters
+and lookups are bound with the `@odata.bind` annotation of `EntityReference::to_bind()`
+
+The collected `RecordChanges` implement `WriteEntity`, so they can be added to batches as well
+
+# Examples
+```rust
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let contact = EntityReference::new(
+        "contacts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+    let account = EntityReference::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789abc").into_dataverse_result()?
+    );
+
+    client
+        .update_builder(contact)
+        .set("firstname", "Testy")
+        .set("numberofchildren", 2)
+        .set_lookup("parentcustomerid_account", &account)
+        .send()
+        .await
+}
+```
+*/
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::WriteEntity,
+    query::attribute::Attribute,
+    reference::{EntityReference, Reference},
+    result::Result,
+};
+
+/// The changed columns of a single record
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordChanges {
+    reference: EntityReference,
+    columns: Map<String, Value>,
+}
+
+impl RecordChanges {
+    /// creates empty changes for the referenced record
+    pub fn new(reference: EntityReference) -> Self {
+        Self {
+            reference,
+            columns: Map::new(),
+        }
+    }
+
+    /// sets the column to the given value, e.g. `Attribute::Null` to clear it
+    pub fn set(mut self, column: &str, value: impl Into<Attribute>) -> Self {
+        self.columns
+            .insert(column.to_string(), value.into().to_json_value());
+        self
+    }
+
+    /**
+    binds the single-valued navigation property to the referenced record
+
+    The reference has to use the entity set name (e.g. `accounts`)
+    */
+    pub fn set_lookup(mut self, navigation_property: &str, reference: &EntityReference) -> Self {
+        self.columns.insert(
+            format!("{}@odata.bind", navigation_property),
+            Value::String(reference.to_bind()),
+        );
+        self
+    }
+
+    /// returns the changed columns as they are written
+    pub fn get_columns(&self) -> &Map<String, Value> {
+        &self.columns
+    }
+}
+
+impl Serialize for RecordChanges {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.columns.serialize(serializer)
+    }
+}
+
+impl Reference for RecordChanges {
+    fn get_reference(&self) -> EntityReference {
+        self.reference.clone()
+    }
+}
+
+impl WriteEntity for RecordChanges {}
+
+/// Collects the changes of a record and writes them with `send()`
+pub struct UpdateBuilder<'client, 'url, A: Authenticate> {
+    client: &'client Client<'url, A>,
+    changes: RecordChanges,
+}
+
+impl<'client, 'url, A: Authenticate> UpdateBuilder<'client, 'url, A> {
+    /// sets the column to the given value, e.g. `Attribute::Null` to clear it
+    pub fn set(mut self, column: &str, value: impl Into<Attribute>) -> Self {
+        self.changes = self.changes.set(column, value);
+        self
+    }
+
+    /// binds the single-valued navigation property to the referenced record
+    pub fn set_lookup(mut self, navigation_property: &str, reference: &EntityReference) -> Self {
+        self.changes = self.changes.set_lookup(navigation_property, reference);
+        self
+    }
+
+    /// returns the collected changes without writing them
+    pub fn into_changes(self) -> RecordChanges {
+        self.changes
+    }
+
+    /**
+    writes the collected changes into the record
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    - The record doesn't exist
+    - The payload exceeds the maximum payload size of this client
+    */
+    pub async fn send(self) -> Result<()> {
+        self.client.update(&self.changes).await
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /// starts an update of the referenced record without defining an entity struct
+    pub fn update_builder(&self, reference: EntityReference) -> UpdateBuilder<'_, 'url, A> {
+        UpdateBuilder {
+            client: self,
+            changes: RecordChanges::new(reference),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use crate::{query::attribute::Attribute, reference::EntityReference};
+
+    use super::RecordChanges;
+
+    #[test]
+    fn serializes_values_and_lookups() {
+        let changes = RecordChanges::new(EntityReference::new("contacts", Uuid::nil()))
+            .set("firstname", "Testy")
+            .set("numberofchildren", 2)
+            .set("birthdate", Utc.with_ymd_and_hms(1990, 5, 17, 0, 0, 0).unwrap())
+            .set("description", Attribute::Null)
+            .set_lookup("parentcustomerid_account", &EntityReference::new("accounts", Uuid::nil()));
+
+        assert_eq!(
+            serde_json::to_string(&changes).unwrap(),
+            r#"{"birthdate":"1990-05-17T00:00:00Z","description":null,"firstname":"Testy","numberofchildren":2,"parentcustomerid_account@odata.bind":"/accounts(00000000-0000-0000-0000-000000000000)"}"#
+        );
+    }
+}
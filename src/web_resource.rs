@@ -0,0 +1,244 @@
+/*!
+Module for deploying web resources
+
+Web resources are uploaded from their raw file bytes which are encoded for
+Dataverse automatically. Changed web resources have to be published before they
+are visible to users
+
+# Examples
+```rust
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    result::Result,
+    web_resource::WebResourceType
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let script = b"console.log('Hello Dataverse');".to_vec();
+
+    let id = client
+        .upload_web_resource("contoso_/scripts/main.js", &script, WebResourceType::Script)
+        .await?;
+    client.publish_web_resources(&[id]).await?;
+    Ok(())
+}
+```
+*/
+
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::{ReadEntity, WriteEntity},
+    query::{attribute::Attribute, filter::Filter, Query},
+    reference::{Reference, ReferenceStruct},
+    result::Result,
+    select::Select,
+};
+
+/// The type of the content of a web resource
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WebResourceType {
+    Html,
+    Css,
+    Script,
+    Xml,
+    Png,
+    Jpg,
+    Gif,
+    Xap,
+    Xsl,
+    Ico,
+    Svg,
+    Resx,
+}
+
+impl WebResourceType {
+    /// determines the web resource type from the extension of the given file path
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "htm" | "html" => Some(Self::Html),
+            "css" => Some(Self::Css),
+            "js" => Some(Self::Script),
+            "xml" => Some(Self::Xml),
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpg),
+            "gif" => Some(Self::Gif),
+            "xap" => Some(Self::Xap),
+            "xsl" | "xslt" => Some(Self::Xsl),
+            "ico" => Some(Self::Ico),
+            "svg" => Some(Self::Svg),
+            "resx" => Some(Self::Resx),
+            _ => None,
+        }
+    }
+
+    /// returns the option set value of this type in the `webresourcetype` column
+    pub fn get_value(&self) -> i32 {
+        match self {
+            Self::Html => 1,
+            Self::Css => 2,
+            Self::Script => 3,
+            Self::Xml => 4,
+            Self::Png => 5,
+            Self::Jpg => 6,
+            Self::Gif => 7,
+            Self::Xap => 8,
+            Self::Xsl => 9,
+            Self::Ico => 10,
+            Self::Svg => 11,
+            Self::Resx => 12,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WebResourceRecord {
+    webresourceid: Uuid,
+}
+
+impl ReadEntity for WebResourceRecord {}
+
+impl Select for WebResourceRecord {
+    fn get_columns() -> &'static [&'static str] {
+        &["webresourceid"]
+    }
+}
+
+#[derive(Serialize)]
+struct WebResourceWrite<'a> {
+    webresourceid: Uuid,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    displayname: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    webresourcetype: Option<i32>,
+}
+
+impl WriteEntity for WebResourceWrite<'_> {}
+
+impl Reference for WebResourceWrite<'_> {
+    fn get_reference(&self) -> ReferenceStruct {
+        ReferenceStruct::new("webresourceset", self.webresourceid)
+    }
+}
+
+#[derive(Serialize)]
+struct PublishXmlRequest {
+    #[serde(rename = "ParameterXml")]
+    parameter_xml: String,
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    uploads the content of the web resource with the given unique name and returns its id
+
+    The web resource is updated if it exists and created otherwise. The type is
+    only used when the web resource is created
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde serialization or deserialization error
+    - Any http client or server error
+    */
+    pub async fn upload_web_resource(
+        &self,
+        name: &str,
+        content: &[u8],
+        web_resource_type: WebResourceType,
+    ) -> Result<Uuid> {
+        let query = Query::new("webresourceset")
+            .limit(1)
+            .filter(Filter::Equal("name", Attribute::String(name.to_string())));
+
+        let existing = self
+            .retrieve_multiple::<WebResourceRecord>(&query)
+            .await?
+            .into_inner()
+            .into_iter()
+            .next();
+
+        match existing {
+            Some(existing) => {
+                self.update(&WebResourceWrite {
+                    webresourceid: existing.webresourceid,
+                    content: STANDARD.encode(content),
+                    name: None,
+                    displayname: None,
+                    webresourcetype: None,
+                })
+                .await?;
+
+                Ok(existing.webresourceid)
+            }
+            None => {
+                self.create(&WebResourceWrite {
+                    webresourceid: Uuid::new_v4(),
+                    content: STANDARD.encode(content),
+                    name: Some(name),
+                    displayname: Some(name),
+                    webresourcetype: Some(web_resource_type.get_value()),
+                })
+                .await
+            }
+        }
+    }
+
+    /**
+    publishes the web resources with the given ids
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - Any http client or server error
+    */
+    pub async fn publish_web_resources(&self, web_resource_ids: &[Uuid]) -> Result<()> {
+        let request = PublishXmlRequest {
+            parameter_xml: build_publish_xml(web_resource_ids),
+        };
+
+        self.execute_action("PublishXml", &request).await
+    }
+}
+
+fn build_publish_xml(web_resource_ids: &[Uuid]) -> String {
+    let mut xml = String::from("<importexportxml><webresources>");
+
+    for id in web_resource_ids {
+        xml.push_str(&format!("<webresource>{{{}}}</webresource>", id.as_hyphenated()));
+    }
+
+    xml.push_str("</webresources></importexportxml>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{build_publish_xml, WebResourceType};
+
+    #[test]
+    fn type_from_path() {
+        assert_eq!(WebResourceType::from_path("scripts/main.JS"), Some(WebResourceType::Script));
+        assert_eq!(WebResourceType::from_path("logo.svg"), Some(WebResourceType::Svg));
+        assert_eq!(WebResourceType::from_path("archive.zip"), None);
+    }
+
+    #[test]
+    fn publish_xml() {
+        assert_eq!(
+            build_publish_xml(&[Uuid::nil()]),
+            "<importexportxml><webresources><webresource>{00000000-0000-0000-0000-000000000000}</webresource></webresources></importexportxml>"
+        );
+    }
+}
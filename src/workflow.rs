@@ -0,0 +1,148 @@
+/*!
+Module for triggering on-demand workflows
+
+Classic workflows that are enabled to run on demand can be executed against a single
+record. The execution is asynchronous: the returned id is the id of the system job
+that runs the workflow and can be monitored with the functions of the `job` module
+
+Please note that cloud flows cannot be triggered through the `ExecuteWorkflow` action
+
+# Examples
+```rust
+use std::time::Duration;
+use uuid::Uuid;
+use powerplatform_dataverse_service_client::{
+    client::Client,
+    reference::EntityReference,
+    result::{IntoDataverseResult, Result}
+};
+
+async fn test() -> Result<()> {
+    let client = Client::new_dummy(); // Please replace this with your preferred authentication method
+    let account = EntityReference::new(
+        "accounts",
+        Uuid::parse_str("12345678-1234-1234-1234-123456789012").into_dataverse_result()?
+    );
+
+    let job_id = client.execute_workflow_by_name("Send Welcome Letter", &account).await?;
+    let job = client.wait_for_system_job(job_id, Duration::from_secs(5), None).await?;
+    Ok(())
+}
+```
+*/
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::Authenticate,
+    client::Client,
+    entity::ReadEntity,
+    error::DataverseError,
+    query::{attribute::Attribute, filter::Filter, Query},
+    reference::Reference,
+    result::Result,
+    select::Select,
+};
+
+/// `type` of workflow records that are process definitions (as opposed to activations)
+static WORKFLOW_TYPE_DEFINITION: i64 = 1;
+
+#[derive(Serialize)]
+struct ExecuteWorkflowRequest {
+    #[serde(rename = "EntityId")]
+    entity_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct ExecuteWorkflowResponse {
+    #[serde(rename = "Id")]
+    id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRecord {
+    workflowid: Uuid,
+}
+
+impl ReadEntity for WorkflowRecord {}
+
+impl Select for WorkflowRecord {
+    fn get_columns() -> &'static [&'static str] {
+        &["workflowid"]
+    }
+}
+
+impl<'url, A: Authenticate> Client<'url, A> {
+    /**
+    executes the on-demand workflow with the given id against the target record and
+    returns the id of the system job that runs it
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - The workflow doesn't exist or is not enabled to run on demand
+    */
+    pub async fn execute_workflow(&self, workflow_id: Uuid, target: &impl Reference) -> Result<Uuid> {
+        let request = ExecuteWorkflowRequest {
+            entity_id: target.get_reference().entity_id,
+        };
+
+        let response: ExecuteWorkflowResponse = self
+            .execute_action(
+                &format!(
+                    "workflows({})/Microsoft.Dynamics.CRM.ExecuteWorkflow",
+                    workflow_id.as_hyphenated()
+                ),
+                &request,
+            )
+            .await?;
+
+        Ok(response.id)
+    }
+
+    /**
+    executes the activated on-demand workflow with the given name against the target
+    record and returns the id of the system job that runs it
+
+    This may fail for any of these reasons
+    - An authentication failure
+    - A serde deserialization error
+    - Any http client or server error
+    - There is no activated workflow with this name
+    */
+    pub async fn execute_workflow_by_name(&self, name: &str, target: &impl Reference) -> Result<Uuid> {
+        let query = Query::new("workflows").limit(1).filter(
+            Filter::Equal("name", Attribute::String(name.to_string()))
+                .and(Filter::Equal("type", Attribute::Integer(WORKFLOW_TYPE_DEFINITION)))
+                .and(Filter::Equal("statecode", Attribute::Integer(1))),
+        );
+
+        let workflow = self
+            .retrieve_multiple::<WorkflowRecord>(&query)
+            .await?
+            .into_inner()
+            .into_iter()
+            .next()
+            .ok_or_else(|| DataverseError::new(format!("there is no activated workflow named {}", name)))?;
+
+        self.execute_workflow(workflow.workflowid, target).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::ExecuteWorkflowRequest;
+
+    #[test]
+    fn execute_workflow_request() {
+        let request = ExecuteWorkflowRequest { entity_id: Uuid::nil() };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"EntityId":"00000000-0000-0000-0000-000000000000"}"#
+        );
+    }
+}